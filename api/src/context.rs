@@ -12,7 +12,9 @@ use aptos_api_types::{AptosErrorCode, AsConverter, BcsBlock, LedgerInfo, Transac
 use aptos_config::config::{NodeConfig, RoleType};
 use aptos_crypto::HashValue;
 use aptos_gas::{AptosGasParameters, FromOnChainGasSchedule};
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolInspectionRequest, SubmissionStatus,
+};
 use aptos_state_view::StateView;
 use aptos_types::account_config::NewBlockEvent;
 use aptos_types::on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig};
@@ -120,6 +122,10 @@ impl Context {
         self.node_config.api.failpoints_enabled
     }
 
+    pub fn mempool_inspection_enabled(&self) -> bool {
+        self.node_config.api.mempool_inspection_enabled
+    }
+
     pub fn max_submit_transaction_batch_size(&self) -> usize {
         self.node_config.api.max_submit_transaction_batch_size
     }
@@ -528,6 +534,22 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    /// Sends a read-only inspection request to mempool, built from the callback to respond on
+    pub async fn inspect_mempool<T>(
+        &self,
+        request: impl FnOnce(oneshot::Sender<T>) -> MempoolInspectionRequest,
+    ) -> Result<T> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::Inspect(request(req_sender)))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
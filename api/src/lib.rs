@@ -15,6 +15,7 @@ mod events;
 mod failpoint;
 mod index;
 mod log;
+mod mempool_inspection;
 pub mod metrics;
 mod page;
 mod response;
@@ -0,0 +1,117 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only endpoints exposing the state of mempool, for operators debugging stuck
+//! transactions.  They are disabled unless `mempool_inspection_enabled` is set in the API
+//! config, and like `/set_failpoint` they are not part of the OpenAPI spec.
+
+use crate::context::Context;
+use aptos_api_types::{Address, AptosError, AptosErrorCode, HashValue};
+use aptos_mempool::{
+    GasPriceBucket, MempoolInspectionRequest, MempoolTransactionInfo, PeerBroadcastState,
+};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    IntoResponse,
+};
+use std::{fmt::Display, str::FromStr, sync::Arc};
+
+/// Pending transactions of a sender, in sequence number order, with their ready or parked status
+#[handler]
+pub async fn get_transactions_by_sender(
+    context: Data<&Arc<Context>>,
+    Path(address): Path<String>,
+) -> poem::Result<Json<Vec<MempoolTransactionInfo>>> {
+    check_enabled(&context)?;
+    let address = Address::from_str(&address)
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err, AptosErrorCode::InvalidInput))?;
+    let txns = context
+        .inspect_mempool(|callback| {
+            MempoolInspectionRequest::GetTransactionInfosBySender(address.into(), callback)
+        })
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(txns))
+}
+
+/// A pending transaction and its status, looked up by hash
+#[handler]
+pub async fn get_transaction_by_hash(
+    context: Data<&Arc<Context>>,
+    Path(txn_hash): Path<String>,
+) -> poem::Result<Json<MempoolTransactionInfo>> {
+    check_enabled(&context)?;
+    let hash = HashValue::from_str(&txn_hash)
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err, AptosErrorCode::InvalidInput))?;
+    context
+        .inspect_mempool(|callback| {
+            MempoolInspectionRequest::GetTransactionInfoByHash(hash.into(), callback)
+        })
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                format!("Transaction {} is not in mempool", txn_hash),
+                AptosErrorCode::TransactionNotFound,
+            )
+        })
+}
+
+/// Number of ready and parked transactions by gas unit price
+#[handler]
+pub async fn get_gas_price_buckets(
+    context: Data<&Arc<Context>>,
+) -> poem::Result<Json<Vec<GasPriceBucket>>> {
+    check_enabled(&context)?;
+    let buckets = context
+        .inspect_mempool(MempoolInspectionRequest::GetGasPriceBuckets)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(buckets))
+}
+
+/// Broadcast state of each peer mempool broadcasts transactions to
+#[handler]
+pub async fn get_peer_broadcast_states(
+    context: Data<&Arc<Context>>,
+) -> poem::Result<Json<Vec<PeerBroadcastState>>> {
+    check_enabled(&context)?;
+    let states = context
+        .inspect_mempool(MempoolInspectionRequest::GetPeerBroadcastStates)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(states))
+}
+
+fn check_enabled(context: &Context) -> poem::Result<()> {
+    if context.mempool_inspection_enabled() {
+        Ok(())
+    } else {
+        Err(error(
+            StatusCode::FORBIDDEN,
+            "Mempool inspection is disabled on this endpoint",
+            AptosErrorCode::ApiDisabled,
+        ))
+    }
+}
+
+fn internal_error(err: anyhow::Error) -> poem::Error {
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        err,
+        AptosErrorCode::InternalError,
+    )
+}
+
+/// Builds an error in the same JSON format as the rest of the API
+fn error<S: Display>(status: StatusCode, message: S, code: AptosErrorCode) -> poem::Error {
+    poem::Error::from_response(
+        poem_openapi::payload::Json(AptosError::new_with_error_code(message, code))
+            .with_status(status)
+            .into_response(),
+    )
+}
@@ -6,7 +6,8 @@ use std::{net::SocketAddr, sync::Arc};
 use crate::{
     accounts::AccountsApi, basic::BasicApi, blocks::BlocksApi, check_size::PostSizeLimit,
     context::Context, error_converter::convert_error, events::EventsApi, index::IndexApi,
-    log::middleware_log, set_failpoints, state::StateApi, transactions::TransactionsApi,
+    log::middleware_log, mempool_inspection, set_failpoints, state::StateApi,
+    transactions::TransactionsApi,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_config::config::NodeConfig;
@@ -188,6 +189,26 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+                    )
+                    // Read-only mempool inspection, gated by `mempool_inspection_enabled`
+                    .at(
+                        "/mempool/transactions/by_sender/:address",
+                        poem::get(mempool_inspection::get_transactions_by_sender)
+                            .data(context.clone()),
+                    )
+                    .at(
+                        "/mempool/transactions/by_hash/:txn_hash",
+                        poem::get(mempool_inspection::get_transaction_by_hash)
+                            .data(context.clone()),
+                    )
+                    .at(
+                        "/mempool/gas_price_buckets",
+                        poem::get(mempool_inspection::get_gas_price_buckets).data(context.clone()),
+                    )
+                    .at(
+                        "/mempool/peers",
+                        poem::get(mempool_inspection::get_peer_broadcast_states)
+                            .data(context.clone()),
                     ),
             )
             .with(cors)
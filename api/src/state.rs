@@ -13,9 +13,9 @@ use crate::{
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
-    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, TableItemRequest, VerifyInput,
-    VerifyInputWithRecursion, U64,
+    verify_module_identifier, Address, AptosErrorCode, AsConverter, HexEncodedBytes,
+    IdentifierWrapper, LedgerInfo, MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue,
+    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use aptos_state_view::StateView;
 use aptos_types::{
@@ -165,6 +165,46 @@ impl StateApi {
             ledger_version.0,
         )
     }
+
+    /// Get raw table item
+    ///
+    /// Get a table item at a specific ledger version from the table identified by {table_handle}
+    /// in the path and the BCS encoded "key" (RawTableItemRequest) provided in the request body.
+    ///
+    /// Unlike the get table item API, this doesn't require the key and value types of the
+    /// table to be known, and returns the BCS encoded value as is. This is mostly useful for
+    /// tools that mirror state from a node, such as the transaction replay in the CLI.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/tables/:table_handle/raw_item",
+        method = "post",
+        operation_id = "get_raw_table_item",
+        tag = "ApiTags::Tables"
+    )]
+    async fn get_raw_table_item(
+        &self,
+        accept_type: AcceptType,
+        /// Table handle hex encoded 32-byte string
+        table_handle: Path<Address>,
+        /// Table request with the BCS encoded key
+        table_item_request: Json<RawTableItemRequest>,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_raw_table_item")?;
+        self.context
+            .check_api_output_enabled("Get raw table item", &accept_type)?;
+        self.raw_table_item(
+            &accept_type,
+            table_handle.0,
+            table_item_request.0,
+            ledger_version.0,
+        )
+    }
 }
 
 impl StateApi {
@@ -385,4 +425,52 @@ impl StateApi {
             }
         }
     }
+
+    /// Retrieve raw table item for a specific ledger version
+    pub fn raw_table_item(
+        &self,
+        accept_type: &AcceptType,
+        table_handle: Address,
+        table_item_request: RawTableItemRequest,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        // Retrieve local state
+        let (ledger_info, ledger_version, state_view) =
+            self.preprocess_request(ledger_version.map(|inner| inner.0))?;
+
+        let key = table_item_request.key;
+        let state_key = StateKey::table_item(TableHandle(table_handle.into()), key.0.clone());
+        let bytes = state_view
+            .get_state_value(&state_key)
+            .context(format!(
+                "Failed when trying to retrieve table item from the DB with key: {}",
+                key
+            ))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?
+            .ok_or_else(|| {
+                table_item_not_found(
+                    table_handle,
+                    &serde_json::Value::String(key.to_string()),
+                    ledger_version,
+                    &ledger_info,
+                )
+            })?;
+
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                HexEncodedBytes::from(bytes),
+                &ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => {
+                BasicResponse::try_from_encoded((bytes, &ledger_info, BasicResponseStatus::Ok))
+            }
+        }
+    }
 }
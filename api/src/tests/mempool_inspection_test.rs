@@ -0,0 +1,129 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::{current_function_name, new_test_context_with_config, TestContext};
+use aptos_config::config::NodeConfig;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use serde_json::json;
+use std::str::FromStr;
+
+fn new_inspection_test_context(test_name: String) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.mempool_inspection_enabled = true;
+    new_test_context_with_config(test_name, node_config, false)
+}
+
+async fn submit_transaction(context: &mut TestContext) -> SignedTransaction {
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    txn
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_mempool_inspection_disabled_by_default() {
+    let context = new_test_context(current_function_name!());
+    for path in [
+        "/mempool/transactions/by_sender/0x1",
+        &format!("/mempool/transactions/by_hash/0x{}", "0".repeat(64)),
+        "/mempool/gas_price_buckets",
+        "/mempool/peers",
+    ] {
+        let resp = context.expect_status_code(403).get(path).await;
+        assert_eq!(resp["error_code"], "api_disabled");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_mempool_transactions_by_sender() {
+    let mut context = new_inspection_test_context(current_function_name!());
+    let txn = submit_transaction(&mut context).await;
+
+    let resp = context
+        .get(&format!(
+            "/mempool/transactions/by_sender/{}",
+            txn.sender().to_hex_literal()
+        ))
+        .await;
+    let txns = resp.as_array().unwrap();
+    assert_eq!(txns.len(), 1);
+    assert_eq!(
+        txns[0]["hash"],
+        json!(txn.clone().committed_hash().to_hex())
+    );
+    assert_eq!(txns[0]["sequence_number"], json!(txn.sequence_number()));
+    assert_eq!(txns[0]["gas_unit_price"], json!(txn.gas_unit_price()));
+    assert_eq!(txns[0]["status"], "ready");
+
+    // Senders without pending transactions have none
+    let resp = context.get("/mempool/transactions/by_sender/0x1").await;
+    assert_eq!(resp, json!([]));
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/mempool/transactions/by_sender/invalid")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_mempool_transaction_by_hash() {
+    let mut context = new_inspection_test_context(current_function_name!());
+    let txn = submit_transaction(&mut context).await;
+    let hash = txn.clone().committed_hash();
+
+    let resp = context
+        .get(&format!(
+            "/mempool/transactions/by_hash/0x{}",
+            hash.to_hex()
+        ))
+        .await;
+    assert_eq!(resp["hash"], json!(hash.to_hex()));
+    assert_eq!(
+        AccountAddress::from_str(resp["sender"].as_str().unwrap()).unwrap(),
+        txn.sender()
+    );
+    assert_eq!(resp["sequence_number"], json!(txn.sequence_number()));
+    assert_eq!(resp["status"], "ready");
+
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "/mempool/transactions/by_hash/0x{}",
+            "0".repeat(64)
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "transaction_not_found");
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/mempool/transactions/by_hash/0x1")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_mempool_gas_price_buckets() {
+    let mut context = new_inspection_test_context(current_function_name!());
+    assert_eq!(context.get("/mempool/gas_price_buckets").await, json!([]));
+
+    let txn = submit_transaction(&mut context).await;
+    let resp = context.get("/mempool/gas_price_buckets").await;
+    let buckets = resp.as_array().unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert!(buckets[0]["min_gas_unit_price"].as_u64().unwrap() <= txn.gas_unit_price());
+    assert!(buckets[0]["max_gas_unit_price"].as_u64().unwrap() >= txn.gas_unit_price());
+    assert_eq!(buckets[0]["ready"], 1);
+    assert_eq!(buckets[0]["parked"], 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_mempool_peers() {
+    let context = new_inspection_test_context(current_function_name!());
+    // The test mempool isn't connected to any peers
+    assert_eq!(context.get("/mempool/peers").await, json!([]));
+}
@@ -204,6 +204,50 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_raw_table_item() {
+    let mut context = new_test_context(current_function_name!());
+    // The total supply aggregator of the coin created at genesis
+    let handle: AccountAddress =
+        "0x1b854694ae746cdbd8d44186ca4929b2b337df21d1c74633be19b2710552fdca"
+            .parse()
+            .unwrap();
+    let resp = context
+        .post(
+            &get_raw_table_item(handle),
+            json!({
+                "key": "0x0619dc29a0aac8fa146714058e8dd6d2d0f3bdf5f6331907bf91f3acd81e6935",
+            }),
+        )
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_raw_table_item_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .post(
+            &get_raw_table_item(AccountAddress::ONE),
+            json!({ "key": "0x01" }),
+        )
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_raw_table_item_by_invalid_key() {
+    let mut context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .post(
+            &get_raw_table_item(AccountAddress::ONE),
+            json!({ "key": "0xzz" }),
+        )
+        .await;
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -223,6 +267,10 @@ fn get_table_item(handle: AccountAddress) -> String {
     format!("/tables/{}/item", handle)
 }
 
+fn get_raw_table_item(handle: AccountAddress) -> String {
+    format!("/tables/{}/raw_item", handle)
+}
+
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     let module = build_test_module(account.address()).await;
 
@@ -980,6 +980,18 @@ impl TransactionsApi {
                 mempool_status.message,
                 AptosErrorCode::InvalidTransactionUpdate,
             )),
+            MempoolStatusCode::ReplacementUnderpriced => Err(AptosError::new_with_error_code(
+                mempool_status.message,
+                AptosErrorCode::ReplacementUnderpriced,
+            )),
+            MempoolStatusCode::EvictionUnderpriced => Err(AptosError::new_with_error_code(
+                mempool_status.message,
+                AptosErrorCode::EvictionUnderpriced,
+            )),
+            MempoolStatusCode::AccountFairnessCapReached => Err(AptosError::new_with_error_code(
+                mempool_status.message,
+                AptosErrorCode::AccountFairnessCapReached,
+            )),
             MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_error_code(
                 format!("Transaction was rejected with status {}", mempool_status,),
                 AptosErrorCode::InternalError,
@@ -1040,10 +1052,13 @@ impl TransactionsApi {
                 ),
                 AptosErrorCode::VmError
                 | AptosErrorCode::SequenceNumberTooOld
-                | AptosErrorCode::InvalidTransactionUpdate => Err(
+                | AptosErrorCode::InvalidTransactionUpdate
+                | AptosErrorCode::ReplacementUnderpriced => Err(
                     SubmitTransactionError::bad_request_from_aptos_error(error, ledger_info),
                 ),
-                AptosErrorCode::MempoolIsFull => Err(
+                AptosErrorCode::MempoolIsFull
+                | AptosErrorCode::EvictionUnderpriced
+                | AptosErrorCode::AccountFairnessCapReached => Err(
                     SubmitTransactionError::insufficient_storage_from_aptos_error(
                         error,
                         ledger_info,
@@ -85,6 +85,15 @@ impl ApiSpecificConfig {
 }
 
 pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestContext {
+    new_test_context_with_config(test_name, NodeConfig::default(), use_db_with_indexer)
+}
+
+/// Creates a test context whose API is served with `node_config`
+pub fn new_test_context_with_config(
+    test_name: String,
+    node_config: NodeConfig,
+    use_db_with_indexer: bool,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -125,8 +134,6 @@ pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestCon
 
     let mempool = MockSharedMempool::new_in_runtime(&db_rw, VMValidator::new(db.clone()));
 
-    let node_config = NodeConfig::default();
-
     let context = Context::new(
         ChainId::test(),
         db.clone(),
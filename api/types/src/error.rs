@@ -93,11 +93,20 @@ pub enum AptosErrorCode {
     SequenceNumberTooOld = 402,
     /// The submitted transaction failed VM checks.
     VmError = 403,
+    /// The transaction would replace an already submitted transaction, but doesn't increase the
+    /// gas unit price enough.
+    ReplacementUnderpriced = 404,
 
     /// Health check failed.
     HealthCheckFailed = 500,
     /// The mempool is full, no new transactions can be submitted.
     MempoolIsFull = 501,
+    /// The mempool is full, and the transaction's gas unit price is too low to evict other
+    /// transactions.
+    EvictionUnderpriced = 502,
+    /// The mempool is full, and the sender has too many transactions in mempool to evict
+    /// transactions of other accounts.
+    AccountFairnessCapReached = 503,
 
     /// Internal server error
     InternalError = 600,
@@ -5,7 +5,7 @@
 //! parameters and traits to help manipulate them.
 
 use crate::{
-    algebra::Gas, instr::InstructionGasParameters, misc::MiscGasParameters,
+    algebra::Gas, instr::InstructionGasParameters, misc::MiscGasParameters, trace::ExecutionTrace,
     transaction::StorageGasParameters, transaction::TransactionGasParameters,
 };
 use aptos_types::{state_store::state_key::StateKey, write_set::WriteOp};
@@ -160,6 +160,7 @@ pub struct AptosGasMeter {
     gas_params: AptosGasParameters,
     storage_gas_params: Option<StorageGasParameters>,
     balance: InternalGas,
+    trace: Option<ExecutionTrace>,
 }
 
 impl AptosGasMeter {
@@ -182,9 +183,22 @@ impl AptosGasMeter {
             gas_params,
             storage_gas_params,
             balance,
+            trace: None,
         }
     }
 
+    /// Starts recording every charge made against this meter into an [`ExecutionTrace`].
+    ///
+    /// Tracing is meant for offline debugging only, as it allocates on every charge.
+    pub fn enable_tracing(&mut self) {
+        self.trace = Some(ExecutionTrace::new());
+    }
+
+    /// Takes the recorded trace out of the meter, if tracing was enabled.
+    pub fn take_trace(&mut self) -> Option<ExecutionTrace> {
+        self.trace.take()
+    }
+
     pub fn balance(&self) -> Gas {
         self.balance
             .to_unit_round_down_with_params(&self.gas_params.txn)
@@ -203,17 +217,34 @@ impl AptosGasMeter {
             }
         }
     }
+
+    #[inline]
+    fn charge_traced(&mut self, op: &'static str, amount: InternalGas) -> PartialVMResult<()> {
+        if let Some(trace) = &mut self.trace {
+            trace.record_instruction(op, amount);
+        }
+        self.charge(amount)
+    }
 }
 
 impl GasMeter for AptosGasMeter {
     #[inline]
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
         let cost = self.gas_params.instr.simple_instr_cost(instr)?;
+        if let Some(trace) = &mut self.trace {
+            match instr {
+                SimpleInstruction::Ret => trace.record_return(cost),
+                _ => trace.record_instruction(format!("{:?}", instr), cost),
+            }
+        }
         self.charge(cost)
     }
 
     #[inline]
     fn charge_native_function(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        if let Some(trace) = &mut self.trace {
+            trace.record_native_return(amount);
+        }
         self.charge(amount)
     }
 
@@ -241,40 +272,49 @@ impl GasMeter for AptosGasMeter {
             }
         };
 
-        self.charge(cost)
+        self.charge_traced("LoadResource", cost)
     }
 
     #[inline]
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
         let params = &self.gas_params.instr;
-        self.charge(params.call_base + params.call_per_arg * NumArgs::new(args.len() as u64))
+        let cost = params.call_base + params.call_per_arg * NumArgs::new(args.len() as u64);
+        if let Some(trace) = &mut self.trace {
+            trace.record_call(module_id, func_name, cost);
+        }
+        self.charge(cost)
     }
 
     #[inline]
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
         let params = &self.gas_params.instr;
-        self.charge(
-            params.call_generic_base
-                + params.call_generic_per_ty_arg * NumArgs::new(ty_args.len() as u64)
-                + params.call_generic_per_arg * NumArgs::new(args.len() as u64),
-        )
+        let cost = params.call_generic_base
+            + params.call_generic_per_ty_arg * NumArgs::new(ty_args.len() as u64)
+            + params.call_generic_per_arg * NumArgs::new(args.len() as u64);
+        if let Some(trace) = &mut self.trace {
+            trace.record_call(module_id, func_name, cost);
+        }
+        self.charge(cost)
     }
 
     #[inline]
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
         let params = &self.gas_params.instr;
-        self.charge(params.ld_const_base + params.ld_const_per_byte * size)
+        self.charge_traced(
+            "LdConst",
+            params.ld_const_base + params.ld_const_per_byte * size,
+        )
     }
 
     #[inline]
@@ -285,17 +325,17 @@ impl GasMeter for AptosGasMeter {
             + instr_params.copy_loc_per_abs_val_unit
                 * self.gas_params.misc.abs_val.abstract_value_size(val);
 
-        self.charge(cost)
+        self.charge_traced("CopyLoc", cost)
     }
 
     #[inline]
     fn charge_move_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.move_loc_base)
+        self.charge_traced("MoveLoc", self.gas_params.instr.move_loc_base)
     }
 
     #[inline]
     fn charge_store_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.st_loc_base)
+        self.charge_traced("StLoc", self.gas_params.instr.st_loc_base)
     }
 
     #[inline]
@@ -312,7 +352,7 @@ impl GasMeter for AptosGasMeter {
                     + params.pack_generic_per_field * NumArgs::new(args.len() as u64)
             }
         };
-        self.charge(cost)
+        self.charge_traced("Pack", cost)
     }
 
     #[inline]
@@ -329,7 +369,7 @@ impl GasMeter for AptosGasMeter {
                     + params.unpack_generic_per_field * NumArgs::new(args.len() as u64)
             }
         };
-        self.charge(cost)
+        self.charge_traced("Unpack", cost)
     }
 
     #[inline]
@@ -339,12 +379,12 @@ impl GasMeter for AptosGasMeter {
         let cost = instr_params.read_ref_base
             + instr_params.read_ref_per_abs_val_unit
                 * self.gas_params.misc.abs_val.abstract_value_size(val);
-        self.charge(cost)
+        self.charge_traced("ReadRef", cost)
     }
 
     #[inline]
     fn charge_write_ref(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.write_ref_base)
+        self.charge_traced("WriteRef", self.gas_params.instr.write_ref_base)
     }
 
     #[inline]
@@ -358,7 +398,7 @@ impl GasMeter for AptosGasMeter {
                 * (abs_val_params.abstract_value_size_dereferenced(lhs)
                     + abs_val_params.abstract_value_size_dereferenced(rhs));
 
-        self.charge(cost)
+        self.charge_traced("Eq", cost)
     }
 
     #[inline]
@@ -372,7 +412,7 @@ impl GasMeter for AptosGasMeter {
                 * (abs_val_params.abstract_value_size_dereferenced(lhs)
                     + abs_val_params.abstract_value_size_dereferenced(rhs));
 
-        self.charge(cost)
+        self.charge_traced("Neq", cost)
     }
 
     #[inline]
@@ -390,7 +430,7 @@ impl GasMeter for AptosGasMeter {
             (true, false) => params.mut_borrow_global_base,
             (true, true) => params.mut_borrow_global_generic_base,
         };
-        self.charge(cost)
+        self.charge_traced("BorrowGlobal", cost)
     }
 
     #[inline]
@@ -405,7 +445,7 @@ impl GasMeter for AptosGasMeter {
             false => params.exists_base,
             true => params.exists_generic_base,
         };
-        self.charge(cost)
+        self.charge_traced("Exists", cost)
     }
 
     #[inline]
@@ -420,7 +460,7 @@ impl GasMeter for AptosGasMeter {
             false => params.move_from_base,
             true => params.move_from_generic_base,
         };
-        self.charge(cost)
+        self.charge_traced("MoveFrom", cost)
     }
 
     #[inline]
@@ -436,7 +476,7 @@ impl GasMeter for AptosGasMeter {
             false => params.move_to_base,
             true => params.move_to_generic_base,
         };
-        self.charge(cost)
+        self.charge_traced("MoveTo", cost)
     }
 
     #[inline]
@@ -448,7 +488,7 @@ impl GasMeter for AptosGasMeter {
         let params = &self.gas_params.instr;
         let cost =
             params.vec_pack_base + params.vec_pack_per_elem * NumArgs::new(args.len() as u64);
-        self.charge(cost)
+        self.charge_traced("VecPack", cost)
     }
 
     #[inline]
@@ -460,12 +500,12 @@ impl GasMeter for AptosGasMeter {
         let params = &self.gas_params.instr;
         let cost =
             params.vec_unpack_base + params.vec_unpack_per_expected_elem * expect_num_elements;
-        self.charge(cost)
+        self.charge_traced("VecUnpack", cost)
     }
 
     #[inline]
     fn charge_vec_len(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_len_base)
+        self.charge_traced("VecLen", self.gas_params.instr.vec_len_base)
     }
 
     #[inline]
@@ -480,7 +520,7 @@ impl GasMeter for AptosGasMeter {
             false => params.vec_imm_borrow_base,
             true => params.vec_mut_borrow_base,
         };
-        self.charge(cost)
+        self.charge_traced("VecBorrow", cost)
     }
 
     #[inline]
@@ -489,7 +529,7 @@ impl GasMeter for AptosGasMeter {
         _ty: impl TypeView,
         _val: impl ValueView,
     ) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_push_back_base)
+        self.charge_traced("VecPushBack", self.gas_params.instr.vec_push_back_base)
     }
 
     #[inline]
@@ -498,18 +538,21 @@ impl GasMeter for AptosGasMeter {
         _ty: impl TypeView,
         _val: Option<impl ValueView>,
     ) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_pop_back_base)
+        self.charge_traced("VecPopBack", self.gas_params.instr.vec_pop_back_base)
     }
 
     #[inline]
     fn charge_vec_swap(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_swap_base)
+        self.charge_traced("VecSwap", self.gas_params.instr.vec_swap_base)
     }
 }
 
 impl AptosGasMeter {
     pub fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
+        if let Some(trace) = &mut self.trace {
+            trace.record_transaction("IntrinsicGas", cost);
+        }
         self.charge(cost).map_err(|e| e.finish(Location::Undefined))
     }
 
@@ -525,6 +568,9 @@ impl AptosGasMeter {
                 .unwrap()
                 .calculate_write_set_gas(ops),
         };
+        if let Some(trace) = &mut self.trace {
+            trace.record_transaction("WriteSet", cost);
+        }
         self.charge(cost).map_err(|e| e.finish(Location::Undefined))
     }
 }
@@ -0,0 +1,239 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines an optional execution trace that can be recorded by the gas meter.
+//!
+//! The trace is meant for offline debugging (e.g. replaying a transaction locally) and is never
+//! enabled on the validator execution path. It records every charge made against the meter,
+//! annotated with the Move call frame it happened in, so aborts and gas anomalies can be
+//! attributed to specific functions and instructions.
+
+use move_core_types::{gas_algebra::InternalGas, language_storage::ModuleId};
+use serde::Serialize;
+
+/// A single entry of the execution trace.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// A new Move call frame was entered.
+    Call {
+        depth: usize,
+        module: String,
+        function: String,
+        cost: u64,
+    },
+    /// The current Move call frame returned.
+    Return { depth: usize, cost: u64 },
+    /// A bytecode instruction (or native function call) was charged.
+    Instruction {
+        depth: usize,
+        name: String,
+        cost: u64,
+    },
+    /// A charge made outside of Move execution, e.g. intrinsic gas or write set storage fees.
+    Transaction { name: String, cost: u64 },
+}
+
+/// A function frame on the trace's call stack.
+#[derive(Clone, Debug, Serialize)]
+pub struct TraceFrame {
+    pub module: String,
+    pub function: String,
+}
+
+/// A record of everything the gas meter charged during execution, in order.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ExecutionTrace {
+    events: Vec<TraceEvent>,
+    #[serde(skip)]
+    stack: Vec<TraceFrame>,
+}
+
+impl ExecutionTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded events in execution order.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Returns the call stack at the point the trace stopped.
+    ///
+    /// For a transaction that aborted, this is the stack of the frames that were active when the
+    /// abort happened, innermost frame last.
+    pub fn stack(&self) -> &[TraceFrame] {
+        &self.stack
+    }
+
+    /// Returns the total amount of internal gas units charged while recording.
+    pub fn total_cost(&self) -> u64 {
+        self.events
+            .iter()
+            .map(|event| match event {
+                TraceEvent::Call { cost, .. }
+                | TraceEvent::Return { cost, .. }
+                | TraceEvent::Instruction { cost, .. }
+                | TraceEvent::Transaction { cost, .. } => *cost,
+            })
+            .sum()
+    }
+
+    pub(crate) fn record_call(&mut self, module_id: &ModuleId, func_name: &str, cost: InternalGas) {
+        self.stack.push(TraceFrame {
+            module: module_id.to_string(),
+            function: func_name.to_string(),
+        });
+        self.events.push(TraceEvent::Call {
+            depth: self.stack.len(),
+            module: module_id.to_string(),
+            function: func_name.to_string(),
+            cost: cost.into(),
+        });
+    }
+
+    pub(crate) fn record_return(&mut self, cost: InternalGas) {
+        self.events.push(TraceEvent::Return {
+            depth: self.stack.len(),
+            cost: cost.into(),
+        });
+        self.stack.pop();
+    }
+
+    /// Records the cost of the native function the current frame was entered for, and leaves the
+    /// frame. Natives never execute a `Ret`, so their frame is popped once they are charged.
+    pub(crate) fn record_native_return(&mut self, cost: InternalGas) {
+        self.record_instruction("NativeFunction", cost);
+        self.stack.pop();
+    }
+
+    pub(crate) fn record_instruction(&mut self, name: impl Into<String>, cost: InternalGas) {
+        self.events.push(TraceEvent::Instruction {
+            depth: self.stack.len(),
+            name: name.into(),
+            cost: cost.into(),
+        });
+    }
+
+    pub(crate) fn record_transaction(&mut self, name: &str, cost: InternalGas) {
+        self.events.push(TraceEvent::Transaction {
+            name: name.to_string(),
+            cost: cost.into(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    fn module_id(name: &str) -> ModuleId {
+        ModuleId::new(AccountAddress::ONE, Identifier::new(name).unwrap())
+    }
+
+    #[test]
+    fn nested_calls_track_depth_and_stack() {
+        let mut trace = ExecutionTrace::new();
+        trace.record_transaction("intrinsic", InternalGas::new(10));
+        trace.record_call(&module_id("coin"), "transfer", InternalGas::new(1));
+        trace.record_instruction("LdU64", InternalGas::new(2));
+        trace.record_call(&module_id("coin"), "deposit", InternalGas::new(3));
+        trace.record_instruction("MoveTo", InternalGas::new(4));
+
+        // Two frames are still active, e.g. because the inner one aborted.
+        let stack: Vec<_> = trace
+            .stack()
+            .iter()
+            .map(|frame| (frame.module.clone(), frame.function.as_str()))
+            .collect();
+        assert_eq!(
+            stack,
+            vec![
+                (module_id("coin").to_string(), "transfer"),
+                (module_id("coin").to_string(), "deposit")
+            ]
+        );
+
+        trace.record_return(InternalGas::new(5));
+        trace.record_return(InternalGas::new(6));
+        assert!(trace.stack().is_empty());
+
+        let depths: Vec<_> = trace
+            .events()
+            .iter()
+            .map(|event| match event {
+                TraceEvent::Call { depth, .. }
+                | TraceEvent::Return { depth, .. }
+                | TraceEvent::Instruction { depth, .. } => Some(*depth),
+                TraceEvent::Transaction { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            depths,
+            vec![None, Some(1), Some(1), Some(2), Some(2), Some(2), Some(1)]
+        );
+        assert_eq!(trace.total_cost(), 31);
+    }
+
+    #[test]
+    fn native_calls_leave_their_frame() {
+        let mut trace = ExecutionTrace::new();
+        trace.record_call(&module_id("coin"), "transfer", InternalGas::new(1));
+        trace.record_call(
+            &module_id("event"),
+            "write_to_event_store",
+            InternalGas::new(2),
+        );
+        trace.record_native_return(InternalGas::new(3));
+
+        // Back in the Move caller, which has no `Ret` of the native to wait for.
+        let stack: Vec<_> = trace
+            .stack()
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(stack, vec!["transfer"]);
+        trace.record_instruction("LdU64", InternalGas::new(4));
+        trace.record_return(InternalGas::new(5));
+        assert!(trace.stack().is_empty());
+
+        let depths: Vec<_> = trace
+            .events()
+            .iter()
+            .map(|event| match event {
+                TraceEvent::Call { depth, .. }
+                | TraceEvent::Return { depth, .. }
+                | TraceEvent::Instruction { depth, .. } => *depth,
+                TraceEvent::Transaction { .. } => unreachable!(),
+            })
+            .collect();
+        assert_eq!(depths, vec![1, 2, 2, 1, 1]);
+        assert_eq!(trace.total_cost(), 15);
+    }
+
+    #[test]
+    fn events_serialize_with_kind_tag() {
+        let mut trace = ExecutionTrace::new();
+        trace.record_call(&module_id("coin"), "transfer", InternalGas::new(1));
+        trace.record_return(InternalGas::new(2));
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "events": [
+                    {
+                        "kind": "call",
+                        "depth": 1,
+                        "module": module_id("coin").to_string(),
+                        "function": "transfer",
+                        "cost": 1,
+                    },
+                    { "kind": "return", "depth": 1, "cost": 2 },
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosValidatorInterface;
+use anyhow::{anyhow, Result};
+use aptos_rest_client::{error::RestError, Client};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    account_address::AccountAddress,
+    account_state::AccountState,
+    contract_event::EventWithVersion,
+    event::EventKey,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, Version},
+};
+use futures::executor::block_on;
+use move_deps::move_core_types::language_storage::{ModuleId, ResourceKey};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+
+/// Page size used for each request to a paginated endpoint, below the default limit of the API.
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// An [`AptosValidatorInterface`] backed by the REST API of a fullnode, so that state can be read
+/// without having a local copy of the DB.
+///
+/// All reads are blocking, so this must not be used from within an async task directly; wrap any
+/// usage in `tokio::task::spawn_blocking` instead.
+pub struct RestDebuggerInterface(Client);
+
+impl RestDebuggerInterface {
+    pub fn new(client: Client) -> Self {
+        Self(client)
+    }
+}
+
+/// Maps a 404 from the REST API to `None`, as the state doesn't exist at that version.
+fn not_found_to_none<T>(result: Result<T, RestError>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(RestError::Api(err)) if err.status_code == StatusCode::NOT_FOUND => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+impl AptosValidatorInterface for RestDebuggerInterface {
+    fn get_account_state_by_version(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountState>> {
+        let mut key_value_map = HashMap::new();
+        if let Some(resources) = not_found_to_none(block_on(
+            self.0
+                .get_account_resources_at_version_bcs(account, version),
+        ))? {
+            for (struct_tag, bytes) in resources.into_inner() {
+                let access_path =
+                    AccessPath::resource_access_path(ResourceKey::new(account, struct_tag));
+                key_value_map.insert(StateKey::AccessPath(access_path), StateValue::new(bytes));
+            }
+        }
+        if let Some(modules) = not_found_to_none(block_on(
+            self.0.get_account_modules_at_version_bcs(account, version),
+        ))? {
+            for (module_id, bytes) in modules.into_inner() {
+                let access_path =
+                    AccessPath::code_access_path(ModuleId::new(account, module_id.name.into()));
+                key_value_map.insert(StateKey::AccessPath(access_path), StateValue::new(bytes));
+            }
+        }
+        AccountState::from_access_paths_and_values(account, &key_value_map)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        let bytes = match state_key {
+            StateKey::AccessPath(access_path) => match access_path.get_path() {
+                Path::Code(module_id) => {
+                    not_found_to_none(block_on(self.0.get_account_module_bcs_at_version(
+                        *module_id.address(),
+                        module_id.name().as_str(),
+                        version,
+                    )))?
+                    .map(|response| response.into_inner().to_vec())
+                }
+                Path::Resource(struct_tag) => {
+                    not_found_to_none(block_on(self.0.get_account_resource_at_version_bytes(
+                        access_path.address,
+                        &struct_tag.to_string(),
+                        version,
+                    )))?
+                    .map(|response| response.into_inner())
+                }
+            },
+            StateKey::TableItem { handle, key } => not_found_to_none(block_on(
+                self.0.get_raw_table_item_at_version(handle.0, key, version),
+            ))?
+            .map(|response| response.into_inner()),
+            StateKey::Raw(_) => {
+                return Err(anyhow!(
+                    "Raw state keys can't be read through the REST API: {:?}",
+                    state_key
+                ))
+            }
+        };
+        Ok(bytes.map(StateValue::new))
+    }
+
+    fn get_events(
+        &self,
+        key: &EventKey,
+        start_seq: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        let mut events = vec![];
+        while (events.len() as u64) < limit {
+            let batch_size = std::cmp::min(limit - events.len() as u64, MAX_PAGE_SIZE);
+            let batch = block_on(self.0.get_account_events_by_creation_number_bcs(
+                key.get_creator_address(),
+                key.get_creation_number(),
+                Some(start_seq + events.len() as u64),
+                Some(batch_size as u16),
+            ))?
+            .into_inner();
+            let batch_len = batch.len() as u64;
+            events.extend(
+                batch
+                    .into_iter()
+                    .filter(|event| event.transaction_version <= ledger_version),
+            );
+            if batch_len < batch_size {
+                break;
+            }
+        }
+        Ok(events)
+    }
+
+    fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
+        let mut txns = Vec::with_capacity(limit as usize);
+        while (txns.len() as u64) < limit {
+            let batch_size = std::cmp::min(limit - txns.len() as u64, MAX_PAGE_SIZE);
+            let batch = block_on(
+                self.0
+                    .get_transactions_bcs(Some(start + txns.len() as u64), Some(batch_size as u16)),
+            )?
+            .into_inner();
+            if batch.is_empty() {
+                break;
+            }
+            txns.extend(batch.into_iter().map(|txn| txn.transaction));
+        }
+        Ok(txns)
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(block_on(self.0.get_ledger_information())?
+            .into_inner()
+            .version)
+    }
+
+    fn get_version_by_account_sequence(
+        &self,
+        account: AccountAddress,
+        seq: u64,
+    ) -> Result<Option<Version>> {
+        Ok(block_on(
+            self.0
+                .get_account_transactions_bcs(account, Some(seq), Some(1)),
+        )?
+        .into_inner()
+        .first()
+        .map(|txn| txn.version))
+    }
+}
@@ -25,7 +25,7 @@ use aptos_aggregator::{
     transaction::{ChangeSetExt, TransactionOutputExt},
 };
 use aptos_crypto::HashValue;
-use aptos_gas::AptosGasMeter;
+use aptos_gas::{AptosGasMeter, ExecutionTrace};
 use aptos_logger::prelude::*;
 use aptos_module_verifier::module_init::verify_module_init_function;
 use aptos_state_view::StateView;
@@ -610,27 +610,40 @@ impl AptosVM {
         txn: &SignatureCheckedTransaction,
         log_context: &AdapterLogSchema,
     ) -> (VMStatus, TransactionOutputExt) {
+        let (vm_status, output, _trace) =
+            self.execute_user_transaction_impl(storage, txn, log_context, false);
+        (vm_status, output)
+    }
+
+    fn execute_user_transaction_impl<S: MoveResolverExt + StateView>(
+        &self,
+        storage: &S,
+        txn: &SignatureCheckedTransaction,
+        log_context: &AdapterLogSchema,
+        enable_tracing: bool,
+    ) -> (VMStatus, TransactionOutputExt, Option<ExecutionTrace>) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
                 match $res {
                     Ok(s) => s,
-                    Err(e) => return discard_error_vm_status(e),
+                    Err(e) => {
+                        let (vm_status, output) = discard_error_vm_status(e);
+                        return (vm_status, output, None);
+                    }
                 }
             };
         }
 
         // Revalidate the transaction.
         let mut session = self.0.new_session(storage, SessionId::txn(txn));
-        if let Err(err) = validate_signature_checked_transaction::<S, Self>(
+        unwrap_or_discard!(validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
             storage,
             txn,
             false,
             log_context,
-        ) {
-            return discard_error_vm_status(err);
-        };
+        ));
 
         if self.0.get_gas_feature_version() >= 1 {
             // Create a new session so that the data cache is flushed.
@@ -650,6 +663,9 @@ impl AptosVM {
             storage_gas_params.cloned(),
             txn_data.max_gas_amount(),
         );
+        if enable_tracing {
+            gas_meter.enable_tracing();
+        }
 
         let result = match txn.payload() {
             payload @ TransactionPayload::Script(_)
@@ -673,7 +689,7 @@ impl AptosVM {
             .expect("Balance should always be less than or equal to max gas amount set");
         TXN_GAS_USAGE.observe(u64::from(gas_usage) as f64);
 
-        match result {
+        let (vm_status, output) = match result {
             Ok(output) => output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -689,7 +705,8 @@ impl AptosVM {
                     )
                 }
             }
-        }
+        };
+        (vm_status, output, gas_meter.take_trace())
     }
 
     fn execute_writeset<S: MoveResolverExt>(
@@ -874,6 +891,26 @@ impl AptosVM {
         Ok(res)
     }
 
+    /// Executes a single user transaction against `state_view` with gas tracing enabled, returning
+    /// the output together with the trace of every charge made during execution.
+    ///
+    /// This is meant for offline debugging, e.g. replaying a committed transaction locally.
+    pub fn execute_user_transaction_with_trace(
+        txn: SignedTransaction,
+        state_view: &impl StateView,
+    ) -> Result<(VMStatus, TransactionOutput, Option<ExecutionTrace>)> {
+        let txn = txn.check_signature()?;
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let (vm_status, output, trace) = vm.execute_user_transaction_impl(
+            &state_view.as_move_resolver(),
+            &txn,
+            &log_context,
+            true,
+        );
+        Ok((vm_status, output.into_transaction_output(state_view), trace))
+    }
+
     pub fn simulate_signed_transaction(
         txn: &SignedTransaction,
         state_view: &impl StateView,
@@ -14,7 +14,10 @@ use framework::natives::{
 use move_binary_format::errors::VMResult;
 use move_bytecode_verifier::VerifierConfig;
 use move_table_extension::NativeTableContext;
-use move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions};
+use move_vm_runtime::{
+    move_vm::MoveVM, native_extensions::NativeContextExtensions,
+    native_functions::NativeFunctionTable,
+};
 use std::ops::Deref;
 
 pub struct MoveVmExt {
@@ -26,10 +29,22 @@ impl MoveVmExt {
         native_gas_params: NativeGasParameters,
         abs_val_size_gas_params: AbstractValueSizeGasParameters,
         treat_friend_as_private: bool,
+    ) -> VMResult<Self> {
+        Self::new_with_natives(
+            aptos_natives(native_gas_params, abs_val_size_gas_params),
+            treat_friend_as_private,
+        )
+    }
+
+    /// Creates a VM with the given native functions instead of the default Aptos ones, e.g. to
+    /// include debug natives for local tooling.
+    pub fn new_with_natives(
+        natives: NativeFunctionTable,
+        treat_friend_as_private: bool,
     ) -> VMResult<Self> {
         Ok(Self {
             inner: MoveVM::new_with_verifier_config(
-                aptos_natives(native_gas_params, abs_val_size_gas_params),
+                natives,
                 VerifierConfig {
                     max_loop_depth: Some(5),
                     treat_friend_as_private,
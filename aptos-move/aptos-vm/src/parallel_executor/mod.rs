@@ -7,23 +7,33 @@ mod vm_wrapper;
 use crate::{
     adapter_common::{preprocess_transaction, PreprocessedTransaction},
     aptos_vm::AptosVM,
+    data_cache::AsMoveResolver,
     parallel_executor::vm_wrapper::AptosVMWrapper,
+    read_write_set_analysis::ReadWriteSetAnalysis,
 };
 use aptos_aggregator::{delta_change_set::DeltaOp, transaction::TransactionOutputExt};
 use aptos_parallel_executor::{
     errors::Error,
     executor::ParallelTransactionExecutor,
+    hints::ReadWriteHint,
     output_delta_resolver::ResolvedData,
     task::{Transaction as PTransaction, TransactionOutput as PTransactionOutput},
 };
 use aptos_state_view::StateView;
 use aptos_types::{
+    access_path::AccessPath,
+    account_config::{CoinInfoResource, CORE_CODE_ADDRESS},
     state_store::state_key::StateKey,
     transaction::{Transaction, TransactionOutput, TransactionStatus},
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
-use move_core_types::vm_status::{StatusCode, VMStatus};
+use move_core_types::{
+    language_storage::ResourceKey,
+    move_resource::MoveStructType,
+    vm_status::{StatusCode, VMStatus},
+};
 use rayon::prelude::*;
+use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
 use std::collections::HashMap;
 
 impl PTransaction for PreprocessedTransaction {
@@ -79,6 +89,39 @@ impl PTransactionOutput for AptosTransactionOutput {
     }
 }
 
+/// Predicts the keys accessed by the transactions from their read/write set analysis, for the
+/// parallel executor to schedule likely conflicts in order.
+fn read_write_hints<S: StateView>(
+    rw_analysis: &NormalizedReadWriteSetAnalysis,
+    state_view: &S,
+    transactions: &[PreprocessedTransaction],
+) -> Vec<ReadWriteHint<StateKey>> {
+    let resolver = state_view.as_move_resolver();
+    let analysis = ReadWriteSetAnalysis::new(rw_analysis, &resolver);
+    // The supply of AptosCoin is a parallelizable aggregator: the analysis reports the mints and
+    // the gas fee burns of every transaction as writes of CoinInfo<AptosCoin>, whereas their
+    // execution only produces deltas to the supply.
+    let aptos_coin_info = CoinInfoResource::struct_tag();
+    let into_state_keys = |keys: Vec<ResourceKey>| -> Vec<StateKey> {
+        keys.into_iter()
+            .filter(|key| key.address() != CORE_CODE_ADDRESS || key.type_() != &aptos_coin_info)
+            .map(|key| StateKey::AccessPath(AccessPath::resource_access_path(key)))
+            .collect()
+    };
+
+    transactions
+        .par_iter()
+        .map(|txn| match analysis.get_keys_transaction(txn, true) {
+            Ok((reads, writes)) => ReadWriteHint {
+                reads: into_state_keys(reads),
+                writes: into_state_keys(writes),
+            },
+            // Transactions the analysis does not support are scheduled optimistically.
+            Err(_) => ReadWriteHint::default(),
+        })
+        .collect()
+}
+
 pub struct ParallelAptosVM();
 
 impl ParallelAptosVM {
@@ -86,6 +129,32 @@ impl ParallelAptosVM {
         transactions: Vec<Transaction>,
         state_view: &S,
         concurrency_level: usize,
+    ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        Self::execute_block_impl(transactions, state_view, concurrency_level, None)
+    }
+
+    /// Same as execute_block, but the parallel executor defers the transactions predicted by
+    /// `rw_analysis` to conflict with lower transactions until those are executed, instead of
+    /// discovering the conflicts through aborts and re-executions.
+    pub fn execute_block_with_hints<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        rw_analysis: &NormalizedReadWriteSetAnalysis,
+    ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            concurrency_level,
+            Some(rw_analysis),
+        )
+    }
+
+    fn execute_block_impl<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        rw_analysis: Option<&NormalizedReadWriteSetAnalysis>,
     ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
@@ -95,11 +164,23 @@ impl ParallelAptosVM {
             .map(|txn| preprocess_transaction::<AptosVM>(txn.clone()))
             .collect();
 
-        match ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
-            concurrency_level,
-        )
-        .execute_transactions_parallel(state_view, signature_verified_block)
-        {
+        let executor =
+            ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
+                concurrency_level,
+            );
+        let result = match rw_analysis {
+            Some(rw_analysis) => {
+                let hints = read_write_hints(rw_analysis, state_view, &signature_verified_block);
+                executor.execute_transactions_parallel_with_hints(
+                    state_view,
+                    signature_verified_block,
+                    hints,
+                )
+            }
+            None => executor.execute_transactions_parallel(state_view, signature_verified_block),
+        };
+
+        match result {
             Ok((results, delta_resolver)) => {
                 // TODO: with more deltas, collect keys in parallel (in parallel executor).
                 let mut aggregator_keys: HashMap<StateKey, anyhow::Result<ResolvedData>> =
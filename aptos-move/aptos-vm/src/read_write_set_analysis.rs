@@ -12,6 +12,7 @@ use aptos_types::{
     transaction::{SignedTransaction, TransactionPayload},
 };
 
+use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::{
     ident_str,
@@ -20,7 +21,8 @@ use move_core_types::{
     resolver::ModuleResolver,
     value::{serialize_values, MoveValue},
 };
-use read_write_set_dynamic::{ConcretizedFormals, NormalizedReadWriteSetAnalysis};
+use read_write_set_dynamic::ConcretizedFormals;
+pub use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
 
 use once_cell::sync::Lazy;
 use std::ops::Deref;
@@ -42,6 +44,14 @@ static TRANSACTION_VALIDATION: Lazy<ModuleId> = Lazy::new(|| {
 const TRANSACTION_FEES_MODULE_NAME: &IdentStr = ident_str!("transaction_fee");
 const TRANSACTION_FEES_NAME: &IdentStr = ident_str!("TransactionFee");
 
+/// Analyzes `modules` and normalizes the result for all of their entry functions, as well as for
+/// the prologue and epilogue functions run with every transaction.
+pub fn analyze_modules<'a>(
+    modules: impl IntoIterator<Item = &'a CompiledModule>,
+) -> Result<NormalizedReadWriteSetAnalysis> {
+    Ok(read_write_set::analyze(modules)?.normalize_all_scripts(add_on_functions_list()))
+}
+
 pub fn add_on_functions_list() -> Vec<(ModuleId, Identifier)> {
     vec![
         (BLOCK_MODULE.clone(), BLOCK_PROLOGUE.to_owned()),
@@ -162,7 +172,6 @@ impl<'a, R: MoveResolverExt> ReadWriteSetAnalysis<'a, R> {
         }
     }
 
-    #[allow(dead_code)]
     /// Internal API to get the read/write set of `PreprocessedTransaction`.
     pub(crate) fn get_keys_transaction(
         &self,
@@ -45,6 +45,7 @@ use aptos_vm::{
     data_cache::{AsMoveResolver, StorageAdapter},
     move_vm_ext::{MoveVmExt, SessionId},
     parallel_executor::ParallelAptosVM,
+    read_write_set_analysis::NormalizedReadWriteSetAnalysis,
     AptosVM, VMExecutor, VMValidator,
 };
 use framework::ReleaseBundle;
@@ -383,6 +384,23 @@ impl FakeExecutor {
         Ok(result)
     }
 
+    /// Same as `execute_transaction_block_parallel`, but schedules the transactions with the
+    /// read/write sets predicted by `rw_analysis`.
+    pub fn execute_transaction_block_parallel_with_hints(
+        &self,
+        txn_block: Vec<Transaction>,
+        rw_analysis: &NormalizedReadWriteSetAnalysis,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let (result, _) = ParallelAptosVM::execute_block_with_hints(
+            txn_block,
+            &self.data_store,
+            num_cpus::get(),
+            rw_analysis,
+        )?;
+
+        Ok(result)
+    }
+
     pub fn execute_transaction_block(
         &self,
         txn_block: Vec<Transaction>,
@@ -1,10 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_parallel_executor::counters::{
+    PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_DEPENDENCY_WAITS,
+};
 use aptos_types::{
     account_config::{DepositEvent, WithdrawEvent},
-    transaction::{ExecutionStatus, SignedTransaction, TransactionOutput, TransactionStatus},
+    transaction::{
+        ExecutionStatus, SignedTransaction, Transaction, TransactionOutput, TransactionStatus,
+    },
 };
+use aptos_vm::read_write_set_analysis::analyze_modules;
 use language_e2e_tests::{
     account::Account, common_transactions::peer_to_peer_txn, executor::FakeExecutor,
 };
@@ -447,3 +453,54 @@ fn many_to_one_peer_to_peer() {
     println!("EXECUTION TIME: {}", execution_time);
     print_accounts(&executor, &accounts);
 }
+
+// Aborts, each re-incarnating a transaction, and dependency waits of the parallel executor. They
+// are counted for the whole process: run the test alone for the counts of a single block.
+fn parallel_execution_conflicts() -> (u64, u64) {
+    (
+        PARALLEL_EXECUTION_ABORTS.get(),
+        PARALLEL_EXECUTION_DEPENDENCY_WAITS.get(),
+    )
+}
+
+#[test]
+fn many_to_one_peer_to_peer_with_hints() {
+    let mut executor = FakeExecutor::from_head_genesis();
+    let accounts = executor.create_accounts(50, 1_000_000, 10);
+    let (_, txns) = create_many_to_one_transfers(&executor, &accounts, 1_000);
+    let txn_block: Vec<_> = txns.into_iter().map(Transaction::UserTransaction).collect();
+
+    let framework_modules = cached_packages::head_release_bundle().compiled_modules();
+    let rw_analysis = analyze_modules(framework_modules.iter()).unwrap();
+
+    // Every transfer deposits to the same account, so without hints each transaction reads the
+    // coin store the previous one writes.
+    let (aborts, waits) = parallel_execution_conflicts();
+    let output = executor
+        .execute_transaction_block_parallel(txn_block.clone())
+        .unwrap();
+    let (unhinted_aborts, unhinted_waits) = parallel_execution_conflicts();
+    let hinted_output = executor
+        .execute_transaction_block_parallel_with_hints(txn_block.clone(), &rw_analysis)
+        .unwrap();
+    let (hinted_aborts, hinted_waits) = parallel_execution_conflicts();
+    println!(
+        "Without hints: {} aborts, {} waits. With hints: {} aborts, {} waits.",
+        unhinted_aborts - aborts,
+        unhinted_waits - waits,
+        hinted_aborts - unhinted_aborts,
+        hinted_waits - unhinted_waits,
+    );
+
+    assert_eq!(hinted_output, output);
+    assert_eq!(
+        executor.execute_transaction_block(txn_block).unwrap(),
+        output
+    );
+    for txn_output in &output {
+        assert_eq!(
+            txn_output.status(),
+            &TransactionStatus::Keep(ExecutionStatus::Success)
+        );
+    }
+}
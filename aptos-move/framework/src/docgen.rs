@@ -0,0 +1,492 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module generates documentation for a package. It runs the Move docgen for markdown, and
+//! extends it with Aptos-specific information: entry functions together with the abort codes
+//! they reference, the abilities of each struct, and the event types a module emits.
+//!
+//! The same information is also produced in a structured form, which is written as JSON.
+
+use crate::built_package::BuildOptions;
+use crate::error_map::{generate_error_map_for_model, module_id_for_env};
+use anyhow::bail;
+use move_binary_format::file_format::{Bytecode, Visibility};
+use move_core_types::errmap::ErrorMapping;
+use move_core_types::value::MoveValue;
+use move_docgen::{Docgen, DocgenOptions};
+use move_model::model::{FunctionEnv, GlobalEnv, ModuleEnv, StructEnv};
+use move_model::ty::Type;
+use move_package::source_package::layout::SourcePackageLayout;
+use move_package::source_package::manifest_parser::{
+    parse_move_manifest_string, parse_source_manifest,
+};
+use move_package::{BuildConfig, ModelConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Structured documentation of a package.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PackageDocs {
+    pub name: String,
+    pub modules: Vec<ModuleDocs>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModuleDocs {
+    /// The fully qualified name of the module, e.g. `0x1::coin`.
+    pub name: String,
+    pub doc: String,
+    pub entry_functions: Vec<EntryFunctionDocs>,
+    pub structs: Vec<StructDocs>,
+    pub events: Vec<EventDocs>,
+    pub errors: Vec<ErrorDocs>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntryFunctionDocs {
+    pub name: String,
+    pub doc: String,
+    /// The visibility of the function, `public`, `friend` or `private`.
+    pub visibility: String,
+    pub is_entry: bool,
+    pub type_params: Vec<String>,
+    pub params: Vec<FieldDocs>,
+    /// Abort codes of this module referenced in the body of the function.
+    pub errors: Vec<ErrorDocs>,
+}
+
+impl EntryFunctionDocs {
+    /// Returns the Move declaration of the function, without its body.
+    pub fn signature(&self) -> String {
+        let visibility = match self.visibility.as_str() {
+            "public" => "public ",
+            "friend" => "public(friend) ",
+            _ => "",
+        };
+        let entry = if self.is_entry { "entry " } else { "" };
+        let type_params = if self.type_params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.type_params.join(", "))
+        };
+        let params = self
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.typ))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "{}{}fun {}{}({})",
+            visibility, entry, self.name, type_params, params
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StructDocs {
+    pub name: String,
+    pub doc: String,
+    pub abilities: Vec<String>,
+    pub fields: Vec<FieldDocs>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventDocs {
+    /// The type of the event, e.g. `0x1::coin::DepositEvent`.
+    pub event_type: String,
+    /// The struct field holding the `EventHandle` the event is emitted to, e.g.
+    /// `CoinStore.deposit_events`.
+    pub handle: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FieldDocs {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ErrorDocs {
+    pub code: u64,
+    pub name: String,
+    pub description: String,
+}
+
+/// Generates markdown and JSON documentation for the package at `package_path` into `doc_dir`.
+///
+/// Returns the structured documentation, alongside the list of files that were written.
+pub fn generate_docs(
+    package_path: &Path,
+    options: &BuildOptions,
+    doc_dir: &Path,
+) -> anyhow::Result<(PackageDocs, Vec<PathBuf>)> {
+    let build_config = BuildConfig {
+        dev_mode: false,
+        additional_named_addresses: options.named_addresses.clone(),
+        architecture: None,
+        generate_abis: false,
+        generate_docs: false,
+        install_dir: options.install_dir.clone(),
+        test_mode: false,
+        force_recompilation: false,
+        fetch_deps_only: false,
+        fetch_latest_git_deps: false,
+    };
+    let env = build_config.move_model_for_package(
+        package_path,
+        ModelConfig {
+            target_filter: None,
+            all_files_as_targets: false,
+        },
+    )?;
+    if env.has_errors() {
+        bail!("compilation of the package failed")
+    }
+
+    let error_map = generate_error_map_for_model(&env);
+    let modules: Vec<ModuleDocs> = env
+        .get_modules()
+        .filter(|module| module.is_target() && !module.is_script_module())
+        .map(|module| module_docs(&module, &error_map))
+        .collect();
+
+    let docgen_options = DocgenOptions {
+        output_directory: doc_dir.to_string_lossy().to_string(),
+        ..DocgenOptions::default()
+    };
+    let mut written = vec![];
+    for (file, mut content) in Docgen::new(&env, &docgen_options).gen() {
+        let path = PathBuf::from(file);
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+        if let Some(docs) = modules
+            .iter()
+            .find(|docs| docs.name.rsplit("::").next() == stem.as_deref())
+        {
+            content.push_str(&render_markdown(docs));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+
+    let manifest = parse_source_manifest(parse_move_manifest_string(fs::read_to_string(
+        package_path.join(SourcePackageLayout::Manifest.path()),
+    )?)?)?;
+    let docs = PackageDocs {
+        name: manifest.package.name.to_string(),
+        modules,
+    };
+    fs::create_dir_all(doc_dir)?;
+    let json_path = doc_dir.join(format!("{}.json", docs.name));
+    fs::write(&json_path, serde_json::to_string_pretty(&docs)?)?;
+    written.push(json_path);
+    Ok((docs, written))
+}
+
+fn module_docs(module: &ModuleEnv<'_>, error_map: &ErrorMapping) -> ModuleDocs {
+    let module_id = module_id_for_env(module);
+    let errors: Vec<ErrorDocs> = error_map
+        .module_error_maps
+        .get(&module_id)
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|(code, description)| ErrorDocs {
+                    code: *code,
+                    name: description.code_name.clone(),
+                    description: description.code_description.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let entry_functions = module
+        .get_functions()
+        .filter(|fun| fun.is_entry())
+        .map(|fun| entry_function_docs(&fun, &errors))
+        .collect();
+
+    let mut structs = vec![];
+    let mut events = vec![];
+    for struct_env in module.get_structs() {
+        events.extend(event_docs(&struct_env));
+        structs.push(struct_docs(&struct_env));
+    }
+
+    ModuleDocs {
+        name: module.get_full_name_str(),
+        doc: module.get_doc().trim().to_string(),
+        entry_functions,
+        structs,
+        events,
+        errors,
+    }
+}
+
+fn entry_function_docs(fun: &FunctionEnv<'_>, module_errors: &[ErrorDocs]) -> EntryFunctionDocs {
+    let symbols = fun.module_env.env.symbol_pool();
+    let type_ctx = fun.get_type_display_ctx();
+
+    // Abort codes can't be attributed precisely without data flow analysis, so this lists the
+    // error constants of the module which are loaded by the bytecode of the function.
+    let codes = referenced_u64_constants(fun);
+    let errors = module_errors
+        .iter()
+        .filter(|error| codes.contains(&error.code))
+        .cloned()
+        .collect();
+
+    EntryFunctionDocs {
+        name: fun.get_name_str(),
+        doc: fun.get_doc().trim().to_string(),
+        visibility: match fun.visibility() {
+            Visibility::Public => "public",
+            Visibility::Friend => "friend",
+            Visibility::Private => "private",
+        }
+        .to_string(),
+        is_entry: fun.is_entry(),
+        type_params: fun
+            .get_type_parameters()
+            .iter()
+            .map(|param| symbols.string(param.0).to_string())
+            .collect(),
+        params: fun
+            .get_parameters()
+            .iter()
+            .map(|param| FieldDocs {
+                name: symbols.string(param.0).to_string(),
+                typ: param.1.display(&type_ctx).to_string(),
+            })
+            .collect(),
+        errors,
+    }
+}
+
+/// Returns the values of the `u64` constants loaded from the constant pool by a function.
+fn referenced_u64_constants(fun: &FunctionEnv<'_>) -> BTreeSet<u64> {
+    let module = fun.module_env.get_verified_module();
+    fun.get_bytecode()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Bytecode::LdConst(idx) => match module.constant_at(*idx).deserialize_constant() {
+                Some(MoveValue::U64(value)) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn struct_docs(struct_env: &StructEnv<'_>) -> StructDocs {
+    let symbols = struct_env.symbol_pool();
+    let type_ctx = struct_env.get_type_display_ctx();
+    StructDocs {
+        name: symbols.string(struct_env.get_name()).to_string(),
+        doc: struct_env.get_doc().trim().to_string(),
+        abilities: struct_env
+            .get_abilities()
+            .into_iter()
+            .map(|ability| ability.to_string())
+            .collect(),
+        fields: struct_env
+            .get_fields()
+            .map(|field| FieldDocs {
+                name: symbols.string(field.get_name()).to_string(),
+                typ: field.get_type().display(&type_ctx).to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Finds the events emitted by a module, as the type arguments of `0x1::event::EventHandle`
+/// fields of its structs.
+fn event_docs(struct_env: &StructEnv<'_>) -> Vec<EventDocs> {
+    let env: &GlobalEnv = struct_env.module_env.env;
+    let symbols = env.symbol_pool();
+    let type_ctx = struct_env.get_type_display_ctx();
+    struct_env
+        .get_fields()
+        .filter_map(|field| match field.get_type() {
+            Type::Struct(module_id, struct_id, type_args) => {
+                let handle_env = env.get_module(module_id).into_struct(struct_id);
+                let is_event_handle = handle_env.module_env.get_full_name_str() == "0x1::event"
+                    && symbols.string(handle_env.get_name()).as_str() == "EventHandle";
+                match type_args.first() {
+                    Some(event_type) if is_event_handle => Some(EventDocs {
+                        event_type: event_type.display(&type_ctx).to_string(),
+                        handle: format!(
+                            "{}.{}",
+                            symbols.string(struct_env.get_name()),
+                            symbols.string(field.get_name())
+                        ),
+                    }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn render_markdown(docs: &ModuleDocs) -> String {
+    let mut out = String::new();
+    if !docs.entry_functions.is_empty() {
+        writeln!(out, "\n## Entry functions\n").unwrap();
+        for fun in &docs.entry_functions {
+            writeln!(out, "### `{}`\n", fun.name).unwrap();
+            writeln!(out, "```move\n{}\n```\n", fun.signature()).unwrap();
+            if !fun.errors.is_empty() {
+                writeln!(out, "| Abort code | Name | Description |").unwrap();
+                writeln!(out, "|---|---|---|").unwrap();
+                for error in &fun.errors {
+                    writeln!(
+                        out,
+                        "| {} | `{}` | {} |",
+                        error.code,
+                        error.name,
+                        error.description.replace('\n', " ")
+                    )
+                    .unwrap();
+                }
+                writeln!(out).unwrap();
+            }
+        }
+    }
+    if !docs.structs.is_empty() {
+        writeln!(out, "\n## Abilities\n").unwrap();
+        writeln!(out, "| Struct | copy | drop | store | key |").unwrap();
+        writeln!(out, "|---|---|---|---|---|").unwrap();
+        for struct_docs in &docs.structs {
+            let has = |ability: &str| {
+                if struct_docs.abilities.iter().any(|a| a == ability) {
+                    "✓"
+                } else {
+                    ""
+                }
+            };
+            writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} |",
+                struct_docs.name,
+                has("copy"),
+                has("drop"),
+                has("store"),
+                has("key")
+            )
+            .unwrap();
+        }
+    }
+    if !docs.events.is_empty() {
+        writeln!(out, "\n## Events\n").unwrap();
+        writeln!(out, "| Event type | Handle |").unwrap();
+        writeln!(out, "|---|---|").unwrap();
+        for event in &docs.events {
+            writeln!(out, "| `{}` | `{}` |", event.event_type, event.handle).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "Hello"
+version = "0.0.0"
+
+[addresses]
+hello = "0xcafe"
+"#;
+
+    const MODULE: &str = r#"
+module hello::greeting {
+    /// The message is empty
+    const EEMPTY: u64 = 1;
+    /// The message is too long
+    const ETOO_LONG: u64 = 2;
+
+    public entry fun set_message(_account: &signer, length: u64) {
+        assert!(length > 0, EEMPTY);
+    }
+
+    entry fun clear_message<T>(_account: &signer) {}
+}
+"#;
+
+    fn generate_test_docs() -> (tempfile::TempDir, PackageDocs) {
+        let package_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            package_dir
+                .path()
+                .join(SourcePackageLayout::Manifest.path()),
+            MANIFEST,
+        )
+        .unwrap();
+        let sources_dir = package_dir.path().join(SourcePackageLayout::Sources.path());
+        fs::create_dir_all(&sources_dir).unwrap();
+        fs::write(sources_dir.join("greeting.move"), MODULE).unwrap();
+
+        let doc_dir = package_dir.path().join("doc");
+        let (docs, _) =
+            generate_docs(package_dir.path(), &BuildOptions::default(), &doc_dir).unwrap();
+        (package_dir, docs)
+    }
+
+    #[test]
+    fn entry_functions_list_the_abort_codes_they_load() {
+        let (_package_dir, docs) = generate_test_docs();
+        let functions = &docs.modules[0].entry_functions;
+
+        let set_message = functions
+            .iter()
+            .find(|fun| fun.name == "set_message")
+            .unwrap();
+        assert_eq!(
+            set_message.errors,
+            vec![ErrorDocs {
+                code: 1,
+                name: "EEMPTY".to_string(),
+                description: "The message is empty".to_string(),
+            }]
+        );
+
+        let clear_message = functions
+            .iter()
+            .find(|fun| fun.name == "clear_message")
+            .unwrap();
+        assert!(clear_message.errors.is_empty());
+    }
+
+    #[test]
+    fn signatures_follow_the_declared_visibility() {
+        let (package_dir, docs) = generate_test_docs();
+        let functions = &docs.modules[0].entry_functions;
+        let signatures: BTreeSet<_> = functions.iter().map(|fun| fun.signature()).collect();
+        assert_eq!(
+            signatures,
+            [
+                "public entry fun set_message(_account: &signer, length: u64)".to_string(),
+                "entry fun clear_message<T>(_account: &signer)".to_string(),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        let markdown =
+            fs::read_to_string(package_dir.path().join("doc").join("greeting.md")).unwrap();
+        assert!(markdown.contains("```move\nentry fun clear_message<T>(_account: &signer)\n```"));
+        assert!(markdown.contains("| 1 | `EEMPTY` | The message is empty |"));
+        assert!(!markdown.contains("ETOO_LONG` |"));
+    }
+}
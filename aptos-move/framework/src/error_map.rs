@@ -44,14 +44,28 @@ pub(crate) fn generate_error_map(
             all_files_as_targets: true,
         },
     ) {
-        let mut gen = ErrorMapGenerator::new(&model);
-        gen.gen();
-        Some(gen.finish())
+        Some(generate_error_map_for_model(&model))
     } else {
         None
     }
 }
 
+/// Generates the error map for all modules of an already built model.
+pub(crate) fn generate_error_map_for_model(env: &GlobalEnv) -> ErrorMapping {
+    let mut gen = ErrorMapGenerator::new(env);
+    gen.gen();
+    gen.finish()
+}
+
+/// Returns the `ModuleId` of a module in the model.
+pub(crate) fn module_id_for_env(module: &ModuleEnv<'_>) -> ModuleId {
+    let env = module.env;
+    let name = module.get_name();
+    let addr = AccountAddress::from_hex_literal(&format!("0x{:x}", name.addr())).unwrap();
+    let name = Identifier::new(env.symbol_pool().string(name.name()).to_string()).unwrap();
+    ModuleId::new(addr, name)
+}
+
 struct ErrorMapGenerator<'env> {
     /// Input definitions
     env: &'env GlobalEnv,
@@ -137,10 +151,7 @@ impl<'env> ErrorMapGenerator<'env> {
     }
 
     fn get_module_id_for_name(&self, module: &ModuleEnv<'_>) -> ModuleId {
-        let name = module.get_name();
-        let addr = AccountAddress::from_hex_literal(&format!("0x{:x}", name.addr())).unwrap();
-        let name = Identifier::new(self.name_string(name.name()).to_string()).unwrap();
-        ModuleId::new(addr, name)
+        module_id_for_env(module)
     }
 
     fn name_string(&self, symbol: Symbol) -> Rc<String> {
@@ -14,6 +14,7 @@ pub use built_package::*;
 mod module_metadata;
 pub use module_metadata::*;
 
+pub mod docgen;
 mod error_map;
 pub mod natives;
 mod release_builder;
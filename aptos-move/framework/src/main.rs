@@ -6,6 +6,8 @@
 use clap::Parser;
 use framework::ReleaseOptions;
 use framework::ReleaseTarget;
+use framework::{BuildOptions, ReleaseSpec};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[clap(name = "aptos-framework", author, version, propagate_version = true)]
@@ -14,6 +16,8 @@ enum Commands {
     Release(StandardRelease),
     /// Allows to create a custom release package,
     Custom(CustomRelease),
+    /// Generates the governance proposal scripts for a release spec.
+    Proposals(ReleaseProposals),
 }
 
 fn main() {
@@ -21,6 +25,7 @@ fn main() {
     let result = match cmd {
         Commands::Release(release) => release.execute(),
         Commands::Custom(custom) => custom.execute(),
+        Commands::Proposals(proposals) => proposals.execute(),
     };
     if let Err(e) = result {
         eprintln!("error: {:#}", e);
@@ -60,3 +65,39 @@ impl StandardRelease {
         self.target.create_release(None)
     }
 }
+
+// ========================
+// Release Proposals
+
+#[derive(Debug, Parser)]
+struct ReleaseProposals {
+    /// The path to the YAML release spec.
+    #[clap(long, parse(from_os_str))]
+    spec: PathBuf,
+    /// The directory where to place the generated proposal scripts.
+    #[clap(long, default_value = "proposals", parse(from_os_str))]
+    output_dir: PathBuf,
+    /// Generate scripts which are executed by the core resources account of a test network
+    /// instead of resolving a proposal.
+    #[clap(long)]
+    testnet: bool,
+    #[clap(flatten)]
+    build_options: BuildOptions,
+}
+
+impl ReleaseProposals {
+    fn execute(self) -> anyhow::Result<()> {
+        let spec = ReleaseSpec::load(&self.spec)?;
+        let proposals =
+            spec.generate_proposals(&self.build_options, &self.output_dir, self.testnet)?;
+        for proposal in proposals {
+            println!(
+                "{}: {} (hash {})",
+                proposal.name,
+                proposal.script_path.display(),
+                proposal.script_hash
+            );
+        }
+        Ok(())
+    }
+}
@@ -2,13 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::built_package::{BuildOptions, BuiltPackage};
-use crate::path_relative_to_crate;
-use crate::release_bundle::{ReleaseBundle, ReleasePackage};
-use anyhow::anyhow;
+use crate::release_bundle::{
+    emit_proposal_script_epilogue, emit_proposal_script_prologue, generate_blob, ReleaseBundle,
+    ReleasePackage,
+};
+use crate::{path_in_crate, path_relative_to_crate};
+use anyhow::{anyhow, bail, Context};
+use aptos_crypto::HashValue;
 use aptos_sdk_builder::rust;
+use aptos_types::account_config::CORE_CODE_ADDRESS;
+use aptos_types::on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig, Version};
 use aptos_types::transaction::EntryABI;
 use clap::Parser;
+use move_model::code_writer::CodeWriter;
+use move_model::model::Loc;
+use move_model::{emit, emitln};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
 use std::path::{Path, PathBuf};
+use tempfile::TempDir;
 
 pub const RELEASE_BUNDLE_EXTENSION: &str = "mrb";
 
@@ -82,3 +95,262 @@ impl ReleaseOptions {
         Ok(())
     }
 }
+
+/// A declarative specification of a framework release, from which the ordered set of governance
+/// proposal scripts for the release is generated.
+///
+/// ```yaml
+/// name: v1.3
+/// packages:
+///   - ../move-stdlib
+///   - ../aptos-stdlib
+///   - ../aptos-framework
+/// config_updates:
+///   - Version:
+///       major: 4
+///   - FeatureFlags:
+///       enabled: [CODE_DEPENDENCY_CHECK]
+///       disabled: []
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseSpec {
+    pub name: String,
+    /// The paths to the Move packages to upgrade, in the order in which they are upgraded.
+    /// Relative paths are relative to the spec file.
+    #[serde(default)]
+    pub packages: Vec<PathBuf>,
+    /// The on-chain config updates, which are applied after the packages are upgraded.
+    #[serde(default)]
+    pub config_updates: Vec<ConfigUpdate>,
+}
+
+/// A typed update of an on-chain config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConfigUpdate {
+    Version(Version),
+    FeatureFlags {
+        #[serde(default)]
+        enabled: Vec<FeatureFlag>,
+        #[serde(default)]
+        disabled: Vec<FeatureFlag>,
+    },
+    ConsensusConfig(OnChainConsensusConfig),
+    GasSchedule(GasScheduleV2),
+}
+
+impl ConfigUpdate {
+    fn name(&self) -> &'static str {
+        match self {
+            ConfigUpdate::Version(_) => "version",
+            ConfigUpdate::FeatureFlags { .. } => "feature-flags",
+            ConfigUpdate::ConsensusConfig(_) => "consensus-config",
+            ConfigUpdate::GasSchedule(_) => "gas-schedule",
+        }
+    }
+
+    fn generate_script(&self, out: &Path, is_testnet: bool) -> anyhow::Result<()> {
+        let writer = CodeWriter::new(Loc::default());
+        emitln!(writer, "// Update of the on-chain {} config\n", self.name());
+        match self {
+            ConfigUpdate::Version(version) => {
+                emit_proposal_script_prologue(
+                    &writer,
+                    &["aptos_framework::version"],
+                    CORE_CODE_ADDRESS,
+                    is_testnet,
+                );
+                emitln!(
+                    writer,
+                    "version::set_version(&framework_signer, {});",
+                    version.major
+                );
+            }
+            ConfigUpdate::FeatureFlags { enabled, disabled } => {
+                emit_proposal_script_prologue(
+                    &writer,
+                    &["std::features"],
+                    CORE_CODE_ADDRESS,
+                    is_testnet,
+                );
+                let flags = |flags: &[FeatureFlag]| {
+                    flags
+                        .iter()
+                        .map(|flag| (*flag as u64).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                emitln!(
+                    writer,
+                    "features::change_feature_flags(&framework_signer, vector[{}], vector[{}]);",
+                    flags(enabled),
+                    flags(disabled)
+                );
+                emitln!(writer, "aptos_governance::reconfigure(&framework_signer);");
+            }
+            ConfigUpdate::ConsensusConfig(config) => {
+                emit_proposal_script_prologue(
+                    &writer,
+                    &["aptos_framework::consensus_config"],
+                    CORE_CODE_ADDRESS,
+                    is_testnet,
+                );
+                emit!(writer, "let config = ");
+                generate_blob(&writer, &bcs::to_bytes(config)?);
+                emitln!(writer, ";");
+                emitln!(writer, "consensus_config::set(&framework_signer, config);");
+            }
+            ConfigUpdate::GasSchedule(gas_schedule) => {
+                emit_proposal_script_prologue(
+                    &writer,
+                    &["aptos_framework::gas_schedule"],
+                    CORE_CODE_ADDRESS,
+                    is_testnet,
+                );
+                emit!(writer, "let gas_schedule_blob = ");
+                generate_blob(&writer, &bcs::to_bytes(gas_schedule)?);
+                emitln!(writer, ";");
+                emitln!(
+                    writer,
+                    "gas_schedule::set_gas_schedule(&framework_signer, gas_schedule_blob);"
+                );
+            }
+        }
+        emit_proposal_script_epilogue(&writer);
+        writer.process_result(|s| fs::write(out, s))?;
+        Ok(())
+    }
+}
+
+/// A proposal script generated from a [`ReleaseSpec`].
+#[derive(Debug, Clone)]
+pub struct GeneratedProposal {
+    pub name: String,
+    pub script_path: PathBuf,
+    pub script: Vec<u8>,
+    /// The hash of the compiled script, which is the execution hash of the proposal.
+    pub script_hash: HashValue,
+}
+
+impl ReleaseSpec {
+    /// Loads a release spec from a YAML file, resolving its package paths.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read release spec {}", path.display()))?;
+        let mut spec: ReleaseSpec = serde_yaml::from_str(&contents)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for package in &mut spec.packages {
+            if package.is_relative() {
+                *package = base_dir.join(&package);
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Generates the proposal scripts of the release into `output_dir`, in the order in which
+    /// they must be executed, and compiles them to obtain their hashes.
+    ///
+    /// With `is_testnet`, the scripts are executed by the core resources account instead of
+    /// resolving a proposal, which allows applying them to a test genesis.
+    pub fn generate_proposals(
+        &self,
+        build_options: &BuildOptions,
+        output_dir: &Path,
+        is_testnet: bool,
+    ) -> anyhow::Result<Vec<GeneratedProposal>> {
+        fs::create_dir_all(output_dir)?;
+
+        let mut script_paths = vec![];
+        let mut dependencies = vec![];
+        for package_path in &self.packages {
+            let built = BuiltPackage::build(package_path.clone(), build_options.clone())?;
+            let name = built.name().to_string();
+            let for_address = *built
+                .modules()
+                .next()
+                .ok_or_else(|| anyhow!("package `{}` has no modules", name))?
+                .self_id()
+                .address();
+            dependencies.push((name.clone(), fs::canonicalize(package_path)?));
+
+            let release = ReleasePackage::new(built)?;
+            let script_path = output_dir.join(format!(
+                "{:02}-upgrade-{}.move",
+                script_paths.len(),
+                name.to_lowercase()
+            ));
+            if is_testnet {
+                release.generate_script_proposal_testnet(for_address, script_path.clone())?;
+            } else {
+                release.generate_script_proposal(for_address, script_path.clone())?;
+            }
+            script_paths.push((format!("upgrade-{}", name), script_path));
+        }
+        for update in &self.config_updates {
+            let script_path =
+                output_dir.join(format!("{:02}-{}.move", script_paths.len(), update.name()));
+            update.generate_script(&script_path, is_testnet)?;
+            script_paths.push((update.name().to_string(), script_path));
+        }
+
+        // Scripts are compiled against the packages being released, so they can already use
+        // new functionality of the framework.
+        if !dependencies
+            .iter()
+            .any(|(name, _)| name == "AptosFramework")
+        {
+            dependencies.push((
+                "AptosFramework".to_string(),
+                fs::canonicalize(path_in_crate("aptos-framework"))?,
+            ));
+        }
+        script_paths
+            .into_iter()
+            .map(|(name, script_path)| {
+                let script = compile_script(&script_path, &dependencies)?;
+                Ok(GeneratedProposal {
+                    name,
+                    script_hash: HashValue::sha3_256_of(&script),
+                    script,
+                    script_path,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Compiles a single script in a temporary package which depends on `dependencies`.
+fn compile_script(
+    script_path: &Path,
+    dependencies: &[(String, PathBuf)],
+) -> anyhow::Result<Vec<u8>> {
+    let temp_dir = TempDir::new()?;
+    let package_dir = temp_dir.path();
+    let sources_dir = package_dir.join("sources");
+    fs::create_dir_all(&sources_dir)?;
+
+    let mut manifest =
+        String::from("[package]\nname = \"Proposal\"\nversion = \"1.0.0\"\n\n[dependencies]\n");
+    for (name, path) in dependencies {
+        writeln!(
+            manifest,
+            "{} = {{ local = {:?} }}",
+            name,
+            path.display().to_string()
+        )?;
+    }
+    fs::write(package_dir.join("Move.toml"), manifest)?;
+    fs::copy(script_path, sources_dir.join("proposal.move"))?;
+
+    let package = BuiltPackage::build(
+        package_dir.to_path_buf(),
+        BuildOptions {
+            with_error_map: false,
+            ..BuildOptions::default()
+        },
+    )
+    .with_context(|| format!("failed to compile {}", script_path.display()))?;
+    match package.extract_script_code().pop() {
+        Some(script) => Ok(script),
+        None => bail!("{} does not contain a script", script_path.display()),
+    }
+}
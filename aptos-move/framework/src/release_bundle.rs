@@ -192,6 +192,25 @@ impl ReleasePackage {
         &self,
         for_address: AccountAddress,
         out: PathBuf,
+    ) -> anyhow::Result<()> {
+        self.generate_script_proposal_impl(for_address, out, false)
+    }
+
+    /// Like `generate_script_proposal`, but generates a script which is executed directly by the
+    /// core resources account of a test network instead of going through governance.
+    pub fn generate_script_proposal_testnet(
+        &self,
+        for_address: AccountAddress,
+        out: PathBuf,
+    ) -> anyhow::Result<()> {
+        self.generate_script_proposal_impl(for_address, out, true)
+    }
+
+    fn generate_script_proposal_impl(
+        &self,
+        for_address: AccountAddress,
+        out: PathBuf,
+        is_testnet: bool,
     ) -> anyhow::Result<()> {
         let writer = CodeWriter::new(Loc::default());
         emitln!(
@@ -200,18 +219,11 @@ impl ReleasePackage {
             self.metadata.name
         );
         emitln!(writer, "// source digest: {}", self.metadata.source_digest);
-        emitln!(writer, "script {");
-        writer.indent();
-        emitln!(writer, "use std::vector;");
-        emitln!(writer, "use aptos_framework::aptos_governance;");
-        emitln!(writer, "use aptos_framework::code;\n");
-        emitln!(writer, "fun main(proposal_id: u64){");
-        writer.indent();
-
-        emitln!(
-            writer,
-            "let framework_signer = aptos_governance::resolve(proposal_id, @{});",
-            for_address
+        emit_proposal_script_prologue(
+            &writer,
+            &["std::vector", "aptos_framework::code"],
+            for_address,
+            is_testnet,
         );
         emit!(writer, "let code = ");
         Self::generate_blobs(&writer, &self.code);
@@ -227,7 +239,7 @@ impl ReleasePackage {
             let to_drain = if i == 3 { metadata.len() } else { chunk_size };
             let chunk = metadata.drain(0..to_drain).collect::<Vec<_>>();
             emit!(writer, "let chunk{} = ", i);
-            Self::generate_blob(&writer, &chunk);
+            generate_blob(&writer, &chunk);
             emitln!(writer, ";")
         }
         emitln!(writer, "vector::append(&mut chunk1, chunk2);");
@@ -236,10 +248,7 @@ impl ReleasePackage {
             writer,
             "code::publish_package_txn(&framework_signer, chunk1, code)"
         );
-        writer.unindent();
-        emitln!(writer, "}");
-        writer.unindent();
-        emitln!(writer, "}");
+        emit_proposal_script_epilogue(&writer);
         writer.process_result(|s| std::fs::write(&out, s))?;
         Ok(())
     }
@@ -248,24 +257,70 @@ impl ReleasePackage {
         emitln!(writer, "vector[");
         writer.indent();
         for blob in blobs {
-            Self::generate_blob(writer, blob);
+            generate_blob(writer, blob);
             emitln!(writer, ",")
         }
         writer.unindent();
         emit!(writer, "]");
     }
+}
 
-    fn generate_blob(writer: &CodeWriter, data: &[u8]) {
-        emitln!(writer, "vector[");
+/// Emits the start of a proposal script, up to the point where `framework_signer` holds the
+/// signer of `for_address`.
+///
+/// A proposal script is executed with its proposal id, and obtains the signer by resolving the
+/// proposal through governance. On test networks, the script can instead be executed by the
+/// core resources account, which is allowed to obtain the signer directly.
+pub(crate) fn emit_proposal_script_prologue(
+    writer: &CodeWriter,
+    uses: &[&str],
+    for_address: AccountAddress,
+    is_testnet: bool,
+) {
+    emitln!(writer, "script {");
+    writer.indent();
+    for module in uses {
+        emitln!(writer, "use {};", module);
+    }
+    emitln!(writer, "use aptos_framework::aptos_governance;\n");
+    if is_testnet {
+        emitln!(writer, "fun main(core_resources: &signer){");
         writer.indent();
-        for (i, b) in data.iter().enumerate() {
-            if (i + 1) % 20 == 0 {
-                emitln!(writer);
-            }
-            emit!(writer, "{}u8,", b);
+        emitln!(
+            writer,
+            "let framework_signer = aptos_governance::get_signer_testnet_only(core_resources, @{});",
+            for_address
+        );
+    } else {
+        emitln!(writer, "fun main(proposal_id: u64){");
+        writer.indent();
+        emitln!(
+            writer,
+            "let framework_signer = aptos_governance::resolve(proposal_id, @{});",
+            for_address
+        );
+    }
+}
+
+/// Emits the end of a proposal script started with `emit_proposal_script_prologue`.
+pub(crate) fn emit_proposal_script_epilogue(writer: &CodeWriter) {
+    writer.unindent();
+    emitln!(writer, "}");
+    writer.unindent();
+    emitln!(writer, "}");
+}
+
+/// Emits `data` as a Move `vector<u8>` literal.
+pub(crate) fn generate_blob(writer: &CodeWriter, data: &[u8]) {
+    emitln!(writer, "vector[");
+    writer.indent();
+    for (i, b) in data.iter().enumerate() {
+        if (i + 1) % 20 == 0 {
+            emitln!(writer);
         }
-        emitln!(writer);
-        writer.unindent();
-        emit!(writer, "]")
+        emit!(writer, "{}u8,", b);
     }
+    emitln!(writer);
+    writer.unindent();
+    emit!(writer, "]")
 }
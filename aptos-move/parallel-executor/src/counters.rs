@@ -0,0 +1,34 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{register_int_counter, IntCounter};
+use once_cell::sync::Lazy;
+
+/// Count the number of incarnations aborted by a failed validation, each of which leads to a
+/// re-execution of the transaction.
+pub static PARALLEL_EXECUTION_ABORTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_execution_aborts",
+        "Number of transaction incarnations aborted during parallel execution"
+    )
+    .unwrap()
+});
+
+/// Count the number of times an execution was suspended on a read of an estimated write.
+pub static PARALLEL_EXECUTION_DEPENDENCY_WAITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_execution_dependency_waits",
+        "Number of executions suspended on a read dependency during parallel execution"
+    )
+    .unwrap()
+});
+
+/// Count the number of transactions whose first incarnation was deferred until the execution of
+/// the transaction they are predicted to depend on.
+pub static PARALLEL_EXECUTION_DEFERRALS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_execution_deferrals",
+        "Number of transactions deferred on a predicted dependency during parallel execution"
+    )
+    .unwrap()
+});
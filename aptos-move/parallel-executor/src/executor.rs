@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::{PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_DEPENDENCY_WAITS},
     errors::*,
+    hints::{predicted_dependencies, ReadWriteHint},
     output_delta_resolver::OutputDeltaResolver,
     scheduler::{Scheduler, SchedulerTask, TaskGuard, TxnIndex, Version},
     task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
@@ -104,6 +106,7 @@ impl<
                     // `self.txn_idx` estimated to depend on a write from `dep_idx`.
                     match self.scheduler.wait_for_dependency(self.txn_idx, dep_idx) {
                         Some(dep_condition) => {
+                            PARALLEL_EXECUTION_DEPENDENCY_WAITS.inc();
                             // Wait on a condition variable correpsonding to the encountered
                             // read dependency. Once the dep_idx finishes re-execution, scheduler
                             // will mark the dependency as resolved, and then the txn_idx will be
@@ -291,6 +294,8 @@ where
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
 
         if aborted {
+            PARALLEL_EXECUTION_ABORTS.inc();
+
             // Not valid and successfully aborted, mark the latest write/delta sets as estimates.
             for k in last_input_output.modified_keys(idx_to_validate) {
                 versioned_data_cache.mark_estimate(&k, idx_to_validate);
@@ -363,6 +368,55 @@ where
             OutputDeltaResolver<<T as Transaction>::Key, <T as Transaction>::Value>,
         ),
         E::Error,
+    > {
+        let scheduler = Scheduler::new(signature_verified_block.len());
+        self.execute_transactions_parallel_with_scheduler(
+            executor_initial_arguments,
+            signature_verified_block,
+            scheduler,
+        )
+    }
+
+    /// Same as execute_transactions_parallel, but the first incarnation of every transaction
+    /// waits for the execution of the lower transactions predicted to write a key it accesses.
+    /// The caller needs to provide one hint per transaction of the block.
+    pub fn execute_transactions_parallel_with_hints(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        read_write_hints: Vec<ReadWriteHint<<T as Transaction>::Key>>,
+    ) -> Result<
+        (
+            Vec<E::Output>,
+            OutputDeltaResolver<<T as Transaction>::Key, <T as Transaction>::Value>,
+        ),
+        E::Error,
+    > {
+        assert_eq!(
+            read_write_hints.len(),
+            signature_verified_block.len(),
+            "Read/write hints must be provided for every transaction"
+        );
+        let scheduler =
+            Scheduler::new_with_predicted_dependencies(predicted_dependencies(&read_write_hints));
+        self.execute_transactions_parallel_with_scheduler(
+            executor_initial_arguments,
+            signature_verified_block,
+            scheduler,
+        )
+    }
+
+    fn execute_transactions_parallel_with_scheduler(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        scheduler: Scheduler,
+    ) -> Result<
+        (
+            Vec<E::Output>,
+            OutputDeltaResolver<<T as Transaction>::Key, <T as Transaction>::Value>,
+        ),
+        E::Error,
     > {
         let versioned_data_cache = MVHashMap::new();
 
@@ -372,7 +426,6 @@ where
 
         let num_txns = signature_verified_block.len();
         let last_input_output = TxnLastInputOutput::new(num_txns);
-        let scheduler = Scheduler::new(num_txns);
 
         RAYON_EXEC_POOL.scope(|s| {
             for _ in 0..self.concurrency_level {
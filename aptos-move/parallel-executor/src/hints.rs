@@ -0,0 +1,47 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::scheduler::TxnIndex;
+use std::{collections::HashMap, hash::Hash};
+
+/// Keys that a transaction is predicted to read and write, e.g. by a static analysis of the
+/// code it runs. Hints only change the order in which the transactions are scheduled, never the
+/// output of the block: a wrong prediction costs some parallelism or some re-executions.
+#[derive(Clone, Debug)]
+pub struct ReadWriteHint<K> {
+    pub reads: Vec<K>,
+    pub writes: Vec<K>,
+}
+
+impl<K> Default for ReadWriteHint<K> {
+    fn default() -> Self {
+        Self {
+            reads: vec![],
+            writes: vec![],
+        }
+    }
+}
+
+/// Returns, for every transaction, the highest lower transaction predicted to write a key that
+/// the transaction is predicted to read or write, if any.
+pub(crate) fn predicted_dependencies<K: Hash + Eq>(
+    hints: &[ReadWriteHint<K>],
+) -> Vec<Option<TxnIndex>> {
+    let mut last_writer: HashMap<&K, TxnIndex> = HashMap::new();
+    hints
+        .iter()
+        .enumerate()
+        .map(|(txn_idx, hint)| {
+            let dependency = hint
+                .reads
+                .iter()
+                .chain(hint.writes.iter())
+                .filter_map(|key| last_writer.get(key).copied())
+                .max();
+            for key in hint.writes.iter() {
+                last_writer.insert(key, txn_idx);
+            }
+            dependency
+        })
+        .collect()
+}
@@ -99,6 +99,14 @@ mechanism. Transaction tx_k is first recorded separately as a dependency of
 tx_j, and only added back to E when the next incarnation of tx_j completes
 (i.e. when the dependency is resolved).
 
+Optionally, the block comes with read/write hints in 'hints.rs', predicting the
+keys that each transaction reads and writes. Before its first incarnation, a
+transaction tx_k is then deferred, the same way as when it encounters a dependency,
+until the highest tx_j (with j < k) predicted to write a key that tx_k accesses
+completes an incarnation. Conflicts that the hints predict are thereby serialized
+upfront instead of being discovered through aborts and dependencies, while the
+optimistic mechanisms above keep the execution correct when the hints are wrong.
+
 In 'scheduler.rs', the ordered sets, V and E, are each implemented via a
 single atomic counter coupled with a mechanism to track the status of
 transactions, i.e. whether a given transaction is ready for validation or
@@ -135,8 +143,10 @@ and threads that perform these tasks can already detect validation failures
 due to the ESTIMATE markers on memory locations, instead of waiting for a
 subsequent incarnation to finish.
 **/
+pub mod counters;
 pub mod errors;
 pub mod executor;
+pub mod hints;
 pub mod output_delta_resolver;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
@@ -4,6 +4,7 @@
 use crate::{
     errors::Error,
     executor::ParallelTransactionExecutor,
+    hints::ReadWriteHint,
     proptest_types::types::{
         ExpectedOutput, KeyType, Task, Transaction, TransactionGen, TransactionGenParams, ValueType,
     },
@@ -256,6 +257,67 @@ fn dynamic_read_writes_contended() {
     );
 }
 
+#[test]
+fn dynamic_read_writes_contended_with_hints() {
+    let mut runner = TestRunner::default();
+
+    let universe = vec(any::<[u8; 32]>(), 10)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+        1000,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize(&universe, (false, false)))
+        .collect();
+
+    // Hints predict the accesses of the first incarnation, which later incarnations of the
+    // dynamic transactions do not match.
+    let hints: Vec<_> = transactions
+        .iter()
+        .map(|txn| match txn {
+            Transaction::Write {
+                reads,
+                writes_and_deltas,
+                ..
+            } => ReadWriteHint {
+                reads: reads.first().cloned().unwrap_or_default(),
+                writes: writes_and_deltas
+                    .first()
+                    .map(|(writes, deltas)| {
+                        writes
+                            .iter()
+                            .map(|(k, _)| k.clone())
+                            .chain(deltas.iter().map(|(k, _)| k.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            Transaction::SkipRest | Transaction::Abort => ReadWriteHint::default(),
+        })
+        .collect();
+
+    for _ in 0..100 {
+        let output = ParallelTransactionExecutor::<
+            Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+            Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        >::new(num_cpus::get())
+        .execute_transactions_parallel_with_hints((), transactions.clone(), hints.clone())
+        .map(|(res, _)| res);
+
+        let baseline = ExpectedOutput::generate_baseline(&transactions, None);
+        baseline.assert_output(&output, None);
+    }
+}
+
 #[test]
 fn module_publishing_fallback() {
     let mut runner = TestRunner::default();
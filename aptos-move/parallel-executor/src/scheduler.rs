@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::counters::PARALLEL_EXECUTION_DEFERRALS;
 use aptos_infallible::Mutex;
 use crossbeam::utils::CachePadded;
 use std::{
@@ -133,6 +134,12 @@ pub struct Scheduler {
     /// An index i maps to indices of other transactions that depend on transaction i, i.e. they
     /// should be re-executed once transaction i's next incarnation finishes.
     txn_dependency: Vec<CachePadded<Mutex<Vec<TxnIndex>>>>,
+    /// An index i maps to the transaction that transaction i is predicted to depend on, taken
+    /// when transaction i is first considered for execution.
+    predicted_dependency: Vec<CachePadded<Mutex<Option<TxnIndex>>>>,
+    /// An index i maps to indices of other transactions whose first incarnation is deferred
+    /// until transaction i's next incarnation finishes.
+    txn_deferred: Vec<CachePadded<Mutex<Vec<TxnIndex>>>>,
     /// An index i maps to the most up-to-date status of transaction i.
     txn_status: Vec<CachePadded<Mutex<TransactionStatus>>>,
 }
@@ -140,6 +147,18 @@ pub struct Scheduler {
 /// Public Interfaces for the Scheduler
 impl Scheduler {
     pub fn new(num_txns: usize) -> Self {
+        Self::new_with_predicted_dependencies(vec![None; num_txns])
+    }
+
+    /// Creates a scheduler deferring the first incarnation of each transaction until its
+    /// predicted dependency, if any, finishes an execution.
+    pub fn new_with_predicted_dependencies(predicted_dependencies: Vec<Option<TxnIndex>>) -> Self {
+        let num_txns = predicted_dependencies.len();
+        assert!(predicted_dependencies
+            .iter()
+            .enumerate()
+            .all(|(txn_idx, dep)| dep.map_or(true, |dep_txn_idx| dep_txn_idx < txn_idx)));
+
         Self {
             num_txns,
             execution_idx: AtomicUsize::new(0),
@@ -150,6 +169,13 @@ impl Scheduler {
             txn_dependency: (0..num_txns)
                 .map(|_| CachePadded::new(Mutex::new(Vec::new())))
                 .collect(),
+            predicted_dependency: predicted_dependencies
+                .into_iter()
+                .map(|dep| CachePadded::new(Mutex::new(dep)))
+                .collect(),
+            txn_deferred: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(Vec::new())))
+                .collect(),
             txn_status: (0..num_txns)
                 .map(|_| CachePadded::new(Mutex::new(TransactionStatus::ReadyToExecute(0, None))))
                 .collect(),
@@ -227,8 +253,8 @@ impl Scheduler {
                 // not add a (stale) dependency.
 
                 // Note: acquires (a different, status) mutex, while holding (dependency) mutex.
-                // Only place in scheduler where a thread may hold >1 mutexes, hence, such
-                // acquisitions always happens in the same order (this function), may not deadlock.
+                // Only places in scheduler where a thread may hold >1 mutexes (with try_defer),
+                // hence, such acquisitions always happens in the same order, may not deadlock.
 
                 return None;
             }
@@ -261,8 +287,14 @@ impl Scheduler {
             // Holding the lock, take dependency vector.
             std::mem::take(&mut stored_deps)
         };
+        let deferred_txns: Vec<TxnIndex> = {
+            let mut stored_deferred = self.txn_deferred[txn_idx].lock();
+            // Holding the lock, take the vector of deferred transactions.
+            std::mem::take(&mut stored_deferred)
+        };
 
-        // Mark dependencies as resolved and find the minimum index among them.
+        // Mark dependencies as resolved and find the minimum index among them. The deferred
+        // transactions are still 'ReadyToExecute' with their first incarnation.
         let min_dep = txn_deps
             .into_iter()
             .map(|dep| {
@@ -272,6 +304,7 @@ impl Scheduler {
 
                 dep
             })
+            .chain(deferred_txns)
             .min();
         if let Some(execution_target_idx) = min_dep {
             // Decrease the execution index as necessary to ensure resolved dependencies
@@ -437,6 +470,12 @@ impl Scheduler {
 
         let idx_to_execute = self.execution_idx.fetch_add(1, Ordering::SeqCst);
 
+        if self.try_defer(idx_to_execute) {
+            // The transaction will be considered again once its predicted dependency finishes
+            // an execution, which decreases execution_idx.
+            return None;
+        }
+
         // If successfully incarnated (changed status from ready to executing),
         // return version and guard for execution task, otherwise None.
         self.try_incarnate(idx_to_execute)
@@ -445,6 +484,34 @@ impl Scheduler {
             })
     }
 
+    /// If transaction txn_idx has a predicted dependency that is not executed, records txn_idx as
+    /// deferred by the dependency and returns true. Otherwise returns false and the transaction
+    /// can be incarnated. The predicted dependency is taken by the first call, so that the first
+    /// incarnation of a transaction is deferred at most once.
+    fn try_defer(&self, txn_idx: TxnIndex) -> bool {
+        if txn_idx >= self.num_txns {
+            return false;
+        }
+
+        let predicted_dependency = self.predicted_dependency[txn_idx].lock().take();
+        let dep_txn_idx = match predicted_dependency {
+            Some(dep_txn_idx) => dep_txn_idx,
+            None => return false,
+        };
+
+        let mut stored_deferred = self.txn_deferred[dep_txn_idx].lock();
+        if self.is_executed(dep_txn_idx).is_some() {
+            // Same as in wait_for_dependency, the status is checked while holding the lock that
+            // finish_execution acquires after setting the executed status, so that a deferred
+            // transaction is never missed.
+            return false;
+        }
+        stored_deferred.push(txn_idx);
+        PARALLEL_EXECUTION_DEFERRALS.inc();
+
+        true
+    }
+
     /// Put a transaction in a suspended state, with a condition variable that can be
     /// used to wake it up after the dependency is resolved.
     fn suspend(&self, txn_idx: TxnIndex, dep_condvar: DependencyCondvar) {
@@ -598,3 +598,53 @@ fn scheduler_drain_idx() {
 
     assert!(matches!(s.next_task(), SchedulerTask::Done));
 }
+
+#[test]
+fn scheduler_predicted_dependencies() {
+    let s = Scheduler::new_with_predicted_dependencies(vec![None, Some(0), None, Some(2)]);
+    let fake_counter = AtomicUsize::new(0);
+
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((0, 0), None, _)
+    ));
+    // Transaction 1 is deferred until transaction 0 finishes execution.
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((2, 0), None, _)
+    ));
+
+    assert!(matches!(
+        s.finish_execution(0, 0, false, TaskGuard::new(&fake_counter)),
+        SchedulerTask::ValidationTask((0, 0), _)
+    ));
+    // The execution index is decreased to the deferred transaction.
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((1, 0), None, _)
+    ));
+
+    let s = Scheduler::new_with_predicted_dependencies(vec![None, Some(0), Some(0)]);
+
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((0, 0), None, _)
+    ));
+    assert!(matches!(
+        s.finish_execution(0, 0, false, TaskGuard::new(&fake_counter)),
+        SchedulerTask::NoTask
+    ));
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ValidationTask((0, 0), _)
+    ));
+    // The predicted dependency is already executed, transactions are not deferred.
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((1, 0), None, _)
+    ));
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((2, 0), None, _)
+    ));
+}
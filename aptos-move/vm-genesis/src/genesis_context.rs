@@ -28,6 +28,13 @@ impl GenesisStateView {
             blob.to_vec(),
         );
     }
+
+    pub(crate) fn set(&mut self, state_key: StateKey, value: Option<Vec<u8>>) {
+        match value {
+            Some(value) => self.state_data.insert(state_key, value),
+            None => self.state_data.remove(&state_key),
+        };
+    }
 }
 
 impl StateView for GenesisStateView {
@@ -6,6 +6,7 @@
 mod genesis_context;
 
 use crate::genesis_context::GenesisStateView;
+use anyhow::anyhow;
 use aptos_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
@@ -25,6 +26,7 @@ use aptos_types::{
         ConsensusConfigV1, GasScheduleV2, OnChainConsensusConfig, APTOS_MAX_KNOWN_VERSION,
     },
     transaction::{authenticator::AuthenticationKey, ChangeSet, Transaction, WriteSetPayload},
+    write_set::{WriteOp, WriteSetMut},
 };
 use aptos_vm::{
     data_cache::{IntoMoveResolver, StateViewCache},
@@ -42,6 +44,7 @@ use move_vm_types::gas::UnmeteredGasMeter;
 use once_cell::sync::Lazy;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // The seed is arbitrarily picked to produce a consistent key. XXX make this more formal?
 const GENESIS_SEED: [u8; 32] = [42; 32];
@@ -721,6 +724,107 @@ pub fn generate_test_genesis(
     (genesis, test_validators)
 }
 
+/// Generate a test genesis `ChangeSet`, and apply the given release scripts on top of it in
+/// order. The scripts are executed by the core resources account, so they must be generated for
+/// test networks, see `framework::ReleaseSpec::generate_proposals`.
+pub fn generate_test_genesis_with_release_scripts(
+    framework: &ReleaseBundle,
+    count: Option<usize>,
+    scripts: &[Vec<u8>],
+) -> anyhow::Result<(ChangeSet, Vec<TestValidator>)> {
+    let (genesis, test_validators) = generate_test_genesis(framework, count);
+    Ok((apply_release_scripts(genesis, scripts)?, test_validators))
+}
+
+fn apply_release_scripts(genesis: ChangeSet, scripts: &[Vec<u8>]) -> anyhow::Result<ChangeSet> {
+    let (write_set, mut events) = genesis.into_inner();
+    let mut state_view = GenesisStateView::new();
+    let mut writes = BTreeMap::new();
+    for (state_key, write_op) in write_set.iter() {
+        state_view.set(state_key.clone(), write_op_value(write_op));
+        writes.insert(state_key.clone(), write_op.clone());
+    }
+
+    let move_vm = MoveVmExt::new(
+        NativeGasParameters::zeros(),
+        AbstractValueSizeGasParameters::zeros(),
+        Features::default().is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
+    )
+    .map_err(|e| anyhow!("Failed to create the VM: {:?}", e))?;
+    for (index, script) in scripts.iter().enumerate() {
+        let (write_set, script_events) = {
+            let data_cache = StateViewCache::new(&state_view).into_move_resolver();
+            let mut session = move_vm.new_session(
+                &data_cache,
+                SessionId::genesis(HashValue::sha3_256_of(script)),
+            );
+            session
+                .execute_script(
+                    script.as_slice(),
+                    vec![],
+                    serialize_values(&vec![MoveValue::Signer(aptos_test_root_address())]),
+                    &mut UnmeteredGasMeter,
+                )
+                .map_err(|e| anyhow!("Release script {} failed: {:?}", index, e))?;
+            // Package upgrades are only requested by the script, so publish them like the VM does
+            if let Some(request) = session.extract_publish_request() {
+                session
+                    .publish_module_bundle(
+                        request.bundle.into_inner(),
+                        request.destination,
+                        &mut UnmeteredGasMeter,
+                    )
+                    .map_err(|e| anyhow!("Release script {} failed to publish: {:?}", index, e))?;
+            }
+            let (deltas, change_set) = session
+                .finish()
+                .map_err(|e| anyhow!("Release script {} failed: {:?}", index, e))?
+                .into_change_set(&mut ())
+                .map_err(|e| anyhow!("Release script {} failed: {:?}", index, e))?
+                .into_inner();
+            let (write_set, script_events) = change_set.into_inner();
+            let delta_write_set = deltas
+                .try_into_write_set_mut(&state_view)
+                .map_err(|e| anyhow!("Release script {} failed: {:?}", index, e))?
+                .freeze()?;
+            let mut script_writes: Vec<_> = write_set
+                .iter()
+                .map(|(state_key, write_op)| (state_key.clone(), write_op.clone()))
+                .collect();
+            script_writes.extend(
+                delta_write_set
+                    .iter()
+                    .map(|(state_key, write_op)| (state_key.clone(), write_op.clone())),
+            );
+            (script_writes, script_events)
+        };
+
+        // Everything in the state is created by the genesis transaction, so the scripts'
+        // changes are folded into creations
+        for (state_key, write_op) in write_set {
+            let value = write_op_value(&write_op);
+            state_view.set(state_key.clone(), value.clone());
+            match value {
+                Some(value) => writes.insert(state_key, WriteOp::Creation(value)),
+                None => writes.remove(&state_key),
+            };
+        }
+        events.extend(script_events);
+    }
+
+    Ok(ChangeSet::new(
+        WriteSetMut::new(writes.into_iter().collect()).freeze()?,
+        events,
+    ))
+}
+
+fn write_op_value(write_op: &WriteOp) -> Option<Vec<u8>> {
+    match write_op {
+        WriteOp::Creation(value) | WriteOp::Modification(value) => Some(value.clone()),
+        WriteOp::Deletion => None,
+    }
+}
+
 pub fn generate_mainnet_genesis(
     framework: &ReleaseBundle,
     count: Option<usize>,
@@ -804,6 +908,51 @@ pub fn test_genesis_module_publishing() {
     publish_framework(&mut session, cached_packages::head_release_bundle());
 }
 
+#[test]
+pub fn test_release_scripts_on_test_genesis() {
+    use aptos_types::{
+        on_chain_config::{OnChainConfig, Version},
+        state_store::state_key::StateKey,
+        write_set::WriteSet,
+    };
+    use framework::{BuildOptions, ConfigUpdate, ReleaseSpec};
+
+    let new_version = APTOS_MAX_KNOWN_VERSION.major + 1;
+    let spec = ReleaseSpec {
+        name: "test".to_string(),
+        packages: vec![],
+        config_updates: vec![
+            ConfigUpdate::Version(Version { major: new_version }),
+            ConfigUpdate::FeatureFlags {
+                enabled: vec![FeatureFlag::CODE_DEPENDENCY_CHECK],
+                disabled: vec![],
+            },
+        ],
+    };
+    let output_dir = tempfile::tempdir().unwrap();
+    let scripts = spec
+        .generate_proposals(&BuildOptions::default(), output_dir.path(), true)
+        .unwrap()
+        .into_iter()
+        .map(|proposal| proposal.script)
+        .collect::<Vec<_>>();
+
+    let (change_set, _) = generate_test_genesis_with_release_scripts(
+        cached_packages::head_release_bundle(),
+        Some(1),
+        &scripts,
+    )
+    .unwrap();
+    let WriteSet::V0(write_set) = change_set.write_set();
+    let bytes = write_set
+        .get(&StateKey::AccessPath(Version::access_path()))
+        .unwrap()
+        .extract_raw_bytes()
+        .unwrap();
+    let version: Version = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(version.major, new_version);
+}
+
 #[test]
 pub fn test_mainnet_end_to_end() {
     use aptos_types::{
@@ -30,7 +30,13 @@ use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use backup_service::start_backup_service;
 use clap::Parser;
-use consensus::consensus_provider::start_consensus;
+use consensus::{
+    consensus_observer::network::{
+        ConsensusObserverMultiNetworkSender, ConsensusObserverNetworkEvents,
+        ConsensusObserverNetworkSender,
+    },
+    consensus_provider::{start_consensus, start_consensus_observer},
+};
 use consensus_notifications::ConsensusNotificationListener;
 use data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
@@ -621,6 +627,7 @@ pub fn setup_environment(
     let mut network_runtimes = vec![];
     let mut mempool_network_handles = vec![];
     let mut consensus_network_handles = None;
+    let mut consensus_observer_network_handles = vec![];
     let mut storage_service_server_network_handles = vec![];
     let mut storage_service_client_network_handles = HashMap::new();
 
@@ -740,6 +747,20 @@ pub fn setup_environment(
                 network_builder
                     .add_p2p_service(&consensus::network_interface::network_endpoint_config()),
             );
+        } else if node_config.consensus_observer.publisher_enabled
+            || (node_config.consensus_observer.observer_enabled
+                && !node_config.base.role.is_validator())
+        {
+            // Create the endpoints to connect the Network to the consensus observer.
+            let (observer_sender, observer_events): (
+                ConsensusObserverNetworkSender,
+                ConsensusObserverNetworkEvents,
+            ) = network_builder.add_p2p_service(
+                &consensus::consensus_observer::network::network_endpoint_config(
+                    node_config.consensus_observer.max_network_channel_size,
+                ),
+            );
+            consensus_observer_network_handles.push((network_id, observer_sender, observer_events));
         }
 
         let network_context = network_builder.network_context();
@@ -752,6 +773,22 @@ pub fn setup_environment(
     // TODO set up on-chain discovery network based on UpstreamConfig.fallback_network
     // and pass network handles to mempool/state sync
 
+    // Combine the consensus observer handles of all the full node networks
+    let consensus_observer_network = if consensus_observer_network_handles.is_empty() {
+        None
+    } else {
+        let mut observer_senders = HashMap::new();
+        let mut observer_events = vec![];
+        for (network_id, sender, events) in consensus_observer_network_handles {
+            observer_senders.insert(network_id, sender);
+            observer_events.push((network_id, events));
+        }
+        Some((
+            ConsensusObserverMultiNetworkSender::new(observer_senders),
+            observer_events,
+        ))
+    };
+
     // For state sync to send notifications to mempool and receive notifications from consensus.
     let (mempool_notifier, mempool_listener) =
         mempool_notifications::new_mempool_notifier_listener_pair();
@@ -813,10 +850,6 @@ pub fn setup_environment(
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
-    assert!(
-        !node_config.consensus.use_quorum_store,
-        "QuorumStore is not yet implemented"
-    );
     assert_ne!(
         node_config.consensus.use_quorum_store,
         node_config.mempool.shared_mempool_validator_broadcast,
@@ -847,8 +880,32 @@ pub fn setup_environment(
             consensus_reconfig_subscription
                 .expect("Consensus requires a reconfiguration subscription!"),
             peer_metadata_storage,
+            consensus_observer_network,
         ));
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+    } else if node_config.consensus_observer.observer_enabled {
+        if let Some((observer_network_sender, observer_network_events)) = consensus_observer_network
+        {
+            // Like consensus, the observer starts once state sync caught up to the waypoint.
+            debug!("Wait until state sync is initialized");
+            state_sync_runtimes.block_until_initialized();
+            debug!("State sync initialization complete.");
+
+            // The observer runs in place of consensus on full nodes.
+            instant = Instant::now();
+            consensus_runtime = Some(start_consensus_observer(
+                &node_config,
+                observer_network_sender,
+                observer_network_events,
+                peer_metadata_storage,
+                Arc::new(consensus_notifier),
+                db_rw,
+            ));
+            debug!(
+                "Consensus observer started in {} ms",
+                instant.elapsed().as_millis()
+            );
+        }
     }
 
     let build_info = build_information!();
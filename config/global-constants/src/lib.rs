@@ -18,6 +18,12 @@ pub const OWNER_ACCOUNT: &str = "owner_account";
 pub const OWNER_KEY: &str = "owner";
 pub const VALIDATOR_NETWORK_KEY: &str = "validator_network";
 
+/// Name of a consensus key stored next to `CONSENSUS_KEY`, for the hex encoded public key. This is
+/// how a rotated key is stored until the epoch in which it becomes active.
+pub fn consensus_key_for_public_key(public_key_hex: &str) -> String {
+    format!("{}_{}", CONSENSUS_KEY, public_key_hex)
+}
+
 /// Definitions of global data items (e.g., as held in secure storage)
 pub const SAFETY_DATA: &str = "safety_data";
 pub const WAYPOINT: &str = "waypoint";
@@ -30,6 +30,9 @@ pub struct ApiConfig {
     pub transaction_submission_enabled: bool,
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    // read-only endpoints exposing the state of mempool, for debugging stuck transactions
+    #[serde(default = "default_disabled")]
+    pub mempool_inspection_enabled: bool,
 
     pub max_submit_transaction_batch_size: usize,
 
@@ -68,6 +71,7 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            mempool_inspection_enabled: default_disabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
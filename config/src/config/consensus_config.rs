@@ -25,8 +25,21 @@ pub struct ConsensusConfig {
     // validators coordinate on the latest version to apply a manual transaction.
     pub sync_only: bool,
     pub channel_size: usize,
-    // When false, use the Direct Mempool Quorum Store
+    // When false, use the Direct Mempool Quorum Store. All the validators must agree on it, as
+    // quorum store proposals only carry the proofs of store of the batches.
     pub use_quorum_store: bool,
+    // Max number of txns / bytes of a batch disseminated by the quorum store
+    pub quorum_store_max_batch_txns: u64,
+    pub quorum_store_max_batch_bytes: u64,
+    // How often the quorum store pulls a batch from mempool (in milliseconds)
+    pub quorum_store_batch_interval_ms: u64,
+    // Number of rounds after the last commit a batch stays available
+    pub quorum_store_batch_expiry_rounds: u64,
+    // Max bytes of the unexpired batches of an author that are persisted and signed, the
+    // author's further batches are dropped until some of them expire
+    pub quorum_store_max_bytes_per_author: u64,
+    // Timeout for fetching a missing batch from one of its signers (in milliseconds)
+    pub quorum_store_batch_request_timeout_ms: u64,
     pub quorum_store_pull_timeout_ms: u64,
     // Decides how long the leader waits before proposing empty block if there's no txns in mempool
     // the period = (poll_count - 1) * 30ms
@@ -55,6 +68,12 @@ impl Default for ConsensusConfig {
             sync_only: false,
             channel_size: 30, // hard-coded
             use_quorum_store: false,
+            quorum_store_max_batch_txns: 250,
+            quorum_store_max_batch_bytes: 256 * 1024, // 256 KB
+            quorum_store_batch_interval_ms: 100,
+            quorum_store_batch_expiry_rounds: 20,
+            quorum_store_max_bytes_per_author: 64 * 1024 * 1024, // 64 MB
+            quorum_store_batch_request_timeout_ms: 1000,
 
             quorum_store_pull_timeout_ms: 1000,
             quorum_store_poll_count: 10,
@@ -69,6 +88,38 @@ impl ConsensusConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    // Push the ordered blocks and the commit decisions to the subscribed full nodes. Validators
+    // publish what consensus orders, VFNs relay what they observe.
+    pub publisher_enabled: bool,
+    // Subscribe to an upstream publisher and execute the ordered blocks before they are
+    // committed, instead of waiting for state sync
+    pub observer_enabled: bool,
+    pub max_network_channel_size: usize,
+    // Max number of ordered blocks waiting for their commit decision
+    pub max_pending_blocks: usize,
+    // How often the subscription is checked (in milliseconds)
+    pub progress_check_interval_ms: u64,
+    // The observer moves to another publisher when it hears nothing for this long, and lets
+    // state sync take over again if it can't commit anything for this long (in milliseconds)
+    pub observer_timeout_ms: u64,
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> ConsensusObserverConfig {
+        ConsensusObserverConfig {
+            publisher_enabled: false,
+            observer_enabled: false,
+            max_network_channel_size: 1000,
+            max_pending_blocks: 100,
+            progress_check_interval_ms: 5_000,
+            observer_timeout_ms: 15_000,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -79,5 +130,10 @@ mod test {
         let s = serde_yaml::to_string(&config).unwrap();
 
         serde_yaml::from_str::<ConsensusConfig>(&s).unwrap();
+
+        let config = ConsensusObserverConfig::default();
+        let s = serde_yaml::to_string(&config).unwrap();
+
+        serde_yaml::from_str::<ConsensusObserverConfig>(&s).unwrap();
     }
 }
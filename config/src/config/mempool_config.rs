@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::MAX_APPLICATION_MESSAGE_SIZE;
+use aptos_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -10,6 +11,12 @@ pub struct MempoolConfig {
     pub capacity: usize,
     pub capacity_bytes: usize,
     pub capacity_per_user: usize,
+    // when mempool is full, accounts with at least this many transactions in mempool can't evict
+    // transactions of other accounts, so that a single account can't take over a full mempool
+    pub capacity_per_user_for_eviction: usize,
+    // minimum gas unit price increase, in percent, for a transaction to replace the transaction
+    // in mempool with the same sender and sequence number
+    pub replace_by_fee_min_bump_percentage: u64,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
     pub max_broadcasts_per_peer: usize,
@@ -19,10 +26,19 @@ pub struct MempoolConfig {
     pub shared_mempool_batch_size: usize,
     pub shared_mempool_max_batch_bytes: u64,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
+    // when enabled, peers broadcasting to this node are sent a bloom filter of the transactions
+    // in its mempool, at most once per `shared_mempool_seen_filter_interval_ms`, so that they
+    // skip broadcasting transactions this node already holds
+    pub shared_mempool_seen_filter_enabled: bool,
+    pub shared_mempool_seen_filter_interval_ms: u64,
     pub shared_mempool_tick_interval_ms: u64,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub shared_mempool_validator_broadcast: bool,
+    pub persistence: MempoolPersistenceConfig,
+    // lanes for critical operational transactions, e.g. governance votes, with their own capacity
+    // and reserved block space. A transaction goes in the first lane it matches
+    pub lanes: Vec<MempoolLaneConfig>,
 }
 
 impl Default for MempoolConfig {
@@ -34,15 +50,46 @@ impl Default for MempoolConfig {
             shared_mempool_max_batch_bytes: MAX_APPLICATION_MESSAGE_SIZE as u64,
             shared_mempool_ack_timeout_ms: 2_000,
             shared_mempool_max_concurrent_inbound_syncs: 4,
+            shared_mempool_seen_filter_enabled: false,
+            shared_mempool_seen_filter_interval_ms: 1_000,
             max_broadcasts_per_peer: 1,
             mempool_snapshot_interval_secs: 180,
             capacity: 2_000_000,
             capacity_bytes: 2 * 1024 * 1024 * 1024,
             capacity_per_user: 100,
+            capacity_per_user_for_eviction: 10,
+            replace_by_fee_min_bump_percentage: 10,
             default_failovers: 3,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             shared_mempool_validator_broadcast: true,
+            persistence: MempoolPersistenceConfig::default(),
+            lanes: vec![],
         }
     }
 }
+
+/// Write-ahead store of the transactions accepted into mempool, so that they survive a restart
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolPersistenceConfig {
+    // when enabled, accepted transactions are written to a db under the storage dir, and
+    // reloaded and re-validated on startup
+    pub enabled: bool,
+}
+
+/// Mempool lane, so that critical operational transactions still land in blocks when mempool is
+/// congested. A transaction is in the lane if it calls one of `entry_functions`, or if it is sent
+/// by one of `senders`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolLaneConfig {
+    pub name: String,
+    // entry functions as `address::module::function`, e.g. `0x1::aptos_governance::vote`
+    pub entry_functions: Vec<String>,
+    pub senders: Vec<AccountAddress>,
+    // max number of transactions in the lane, they don't count towards the mempool `capacity`
+    pub capacity: usize,
+    // percentage of the transactions of each block pulled by consensus reserved for the lane
+    pub reserved_block_percentage: u64,
+}
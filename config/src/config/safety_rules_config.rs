@@ -5,11 +5,12 @@ use crate::{
     config::{IdentityBlob, LoggerConfig, SecureBackend, WaypointConfig},
     keys::ConfigKey,
 };
-use aptos_crypto::{bls12381, Uniform};
+use aptos_crypto::{bls12381, x25519, Uniform};
 use aptos_types::{network_address::NetworkAddress, waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     net::{SocketAddr, ToSocketAddrs},
     path::PathBuf,
 };
@@ -107,6 +108,9 @@ pub enum SafetyRulesService {
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    /// Authenticates and encrypts the link with the safety rules server, plain TCP if missing
+    #[serde(default)]
+    pub noise: Option<RemoteServiceNoise>,
 }
 
 impl RemoteService {
@@ -119,6 +123,19 @@ impl RemoteService {
     }
 }
 
+/// Pinned x25519 keys of the Noise IK handshake between consensus and the safety rules server.
+/// Both ends share the public keys and hold their own private key.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceNoise {
+    /// Static key of this end of the link
+    pub private_key: ConfigKey<x25519::PrivateKey>,
+    /// Static key of the safety rules server
+    pub server_public_key: x25519::PublicKey,
+    /// Static keys of the consensus nodes allowed to connect to the safety rules server
+    pub client_public_keys: HashSet<x25519::PublicKey>,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
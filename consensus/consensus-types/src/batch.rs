@@ -0,0 +1,163 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::Author,
+    proof_of_store::{LogicalTime, SignedDigestInfo},
+};
+use anyhow::ensure;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_types::transaction::SignedTransaction;
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::fmt::{Display, Formatter};
+
+/// The transactions of a batch, the batch digest is their hash.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct BatchPayload {
+    txns: Vec<SignedTransaction>,
+}
+
+impl BatchPayload {
+    pub fn new(txns: Vec<SignedTransaction>) -> Self {
+        Self { txns }
+    }
+
+    pub fn txns(&self) -> &[SignedTransaction] {
+        &self.txns
+    }
+
+    pub fn into_txns(self) -> Vec<SignedTransaction> {
+        self.txns
+    }
+
+    pub fn num_bytes(&self) -> usize {
+        self.txns.iter().map(|txn| txn.raw_txn_bytes_len()).sum()
+    }
+}
+
+/// Batch of transactions broadcast by the quorum store of its author. The validators that persist
+/// it sign its digest, so that proposals only need to carry the digest and the proof of store.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Batch {
+    author: Author,
+    batch_id: u64,
+    expiration: LogicalTime,
+    digest: HashValue,
+    payload: BatchPayload,
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Batch: [author: {}, batch_id: {}, digest: {}, expiration: {}, txns: {}]",
+            self.author.short_str(),
+            self.batch_id,
+            self.digest,
+            self.expiration,
+            self.payload.txns.len()
+        )
+    }
+}
+
+impl Batch {
+    pub fn new(
+        author: Author,
+        batch_id: u64,
+        expiration: LogicalTime,
+        payload: BatchPayload,
+    ) -> Self {
+        let digest = payload.hash();
+        Self {
+            author,
+            batch_id,
+            expiration,
+            digest,
+            payload,
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        self.author
+    }
+
+    pub fn batch_id(&self) -> u64 {
+        self.batch_id
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.expiration.epoch()
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.expiration
+    }
+
+    pub fn digest(&self) -> &HashValue {
+        &self.digest
+    }
+
+    pub fn num_txns(&self) -> usize {
+        self.payload.txns.len()
+    }
+
+    pub fn payload(&self) -> &BatchPayload {
+        &self.payload
+    }
+
+    pub fn into_payload(self) -> BatchPayload {
+        self.payload
+    }
+
+    /// The information signed by the validators persisting the batch
+    pub fn info(&self) -> SignedDigestInfo {
+        SignedDigestInfo::new(
+            self.digest,
+            self.expiration,
+            self.payload.txns.len() as u64,
+            self.payload.num_bytes() as u64,
+        )
+    }
+
+    /// Verifies that the digest matches the transactions
+    pub fn verify(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.payload.hash() == self.digest,
+            "Batch digest doesn't match its payload"
+        );
+        Ok(())
+    }
+}
+
+/// RPC to get a batch from one of the validators that signed its proof of store.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BatchRequest {
+    epoch: u64,
+    digest: HashValue,
+}
+
+impl Display for BatchRequest {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "[BatchRequest epoch: {}, digest: {}]",
+            self.epoch, self.digest
+        )
+    }
+}
+
+impl BatchRequest {
+    pub fn new(epoch: u64, digest: HashValue) -> Self {
+        Self { epoch, digest }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn digest(&self) -> &HashValue {
+        &self.digest
+    }
+}
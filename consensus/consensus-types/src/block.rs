@@ -15,13 +15,14 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
-    transaction::{Transaction, Version},
+    transaction::{SignedTransaction, Transaction, Version},
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use mirai_annotations::debug_checked_verify_eq;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    collections::HashSet,
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     iter::once,
@@ -245,6 +246,9 @@ impl Block {
                     .as_ref()
                     .ok_or_else(|| format_err!("Missing signature in Proposal"))?;
                 validator.verify(*author, &self.block_data, signature)?;
+                if let Some(payload) = self.payload() {
+                    payload.verify(validator)?;
+                }
                 self.quorum_cert().verify(validator)
             }
         }
@@ -272,6 +276,23 @@ impl Block {
                 "Reconfiguration suffix should not carry payload"
             );
         }
+        if let Some(Payload::InQuorumStore(proofs)) = self.payload() {
+            let mut digests = HashSet::new();
+            for proof in proofs {
+                ensure!(
+                    proof.epoch() == self.epoch(),
+                    "Proof of store should be in the same epoch as the block"
+                );
+                ensure!(
+                    proof.expiration().round() > self.round(),
+                    "Proof of store should not expire before the block"
+                );
+                ensure!(
+                    digests.insert(*proof.digest()),
+                    "Block should not carry the same batch twice"
+                );
+            }
+        }
         if let Some(failed_authors) = self.block_data().failed_authors() {
             // when validating for being well formed,
             // allow for missing failed authors,
@@ -331,17 +352,17 @@ impl Block {
         Ok(())
     }
 
-    pub fn transactions_to_execute(&self, validators: &[AccountAddress]) -> Vec<Transaction> {
+    /// The user transactions are the ones of the payload, resolved by the payload manager (the
+    /// quorum store payloads only carry proofs of the batches).
+    pub fn transactions_to_execute(
+        &self,
+        validators: &[AccountAddress],
+        user_txns: Vec<SignedTransaction>,
+    ) -> Vec<Transaction> {
         once(Transaction::BlockMetadata(
             self.new_block_metadata(validators),
         ))
-        .chain(
-            self.payload()
-                .unwrap_or(&Payload::empty())
-                .clone()
-                .into_iter()
-                .map(Transaction::UserTransaction),
-        )
+        .chain(user_txns.into_iter().map(Transaction::UserTransaction))
         .chain(once(Transaction::StateCheckpoint(self.id)))
         .collect()
     }
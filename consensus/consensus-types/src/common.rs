@@ -1,10 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use crate::proof_of_store::ProofOfStore;
+use aptos_crypto::HashValue;
+use aptos_types::{
+    account_address::AccountAddress, transaction::SignedTransaction,
+    validator_verifier::ValidatorVerifier,
+};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::{fmt, fmt::Write};
+use std::{collections::HashSet, fmt, fmt::Write};
 
 /// The round of a block is a consensus-internal counter, which starts with 0 and increases
 /// monotonically. It is used for the protocol safety and liveness (please see the detailed
@@ -29,6 +34,9 @@ impl fmt::Display for TransactionSummary {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum Payload {
     DirectMempool(Vec<SignedTransaction>),
+    /// Proofs of store of the batches disseminated by the quorum store, the transactions are
+    /// fetched from the batch store before execution.
+    InQuorumStore(Vec<ProofOfStore>),
 }
 
 impl Payload {
@@ -39,12 +47,17 @@ impl Payload {
     pub fn len(&self) -> usize {
         match self {
             Payload::DirectMempool(txns) => txns.len(),
+            Payload::InQuorumStore(proofs) => proofs
+                .iter()
+                .map(|proof| proof.info().num_txns as usize)
+                .sum(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Payload::DirectMempool(txns) => txns.is_empty(),
+            Payload::InQuorumStore(proofs) => proofs.is_empty(),
         }
     }
 
@@ -56,18 +69,20 @@ impl Payload {
                 .with_min_len(100)
                 .map(|txn| txn.raw_txn_bytes_len())
                 .sum(),
+            Payload::InQuorumStore(proofs) => proofs
+                .iter()
+                .map(|proof| proof.info().num_bytes as usize)
+                .sum(),
         }
     }
-}
-
-// TODO: What I really want is an iterator that isn't necessarily a vector (e.g., read lazily from RocksDB). This doesn't seem like the way.
-impl IntoIterator for Payload {
-    type Item = SignedTransaction;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
 
-    fn into_iter(self) -> Self::IntoIter {
+    /// Verifies the proofs of store carried by the payload
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
         match self {
-            Payload::DirectMempool(txns) => txns.into_iter(),
+            Payload::DirectMempool(_) => Ok(()),
+            Payload::InQuorumStore(proofs) => {
+                proofs.iter().try_for_each(|proof| proof.verify(validator))
+            }
         }
     }
 }
@@ -78,6 +93,9 @@ impl fmt::Display for Payload {
             Payload::DirectMempool(txns) => {
                 write!(f, "InMemory txns: {}", txns.len())
             }
+            Payload::InQuorumStore(proofs) => {
+                write!(f, "InQuorumStore proofs: {}", proofs.len())
+            }
         }
     }
 }
@@ -86,28 +104,40 @@ impl fmt::Display for Payload {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum PayloadFilter {
     DirectMempool(Vec<TransactionSummary>),
+    InQuorumStore(HashSet<HashValue>),
 }
 
 impl From<&Vec<&Payload>> for PayloadFilter {
     fn from(exclude_payloads: &Vec<&Payload>) -> Self {
-        if exclude_payloads.is_empty() {
-            return PayloadFilter::DirectMempool(vec![]);
-        }
-        match exclude_payloads.first().unwrap() {
-            Payload::DirectMempool(_) => {
-                let mut exclude_txns = vec![];
-                for payload in exclude_payloads {
-                    let Payload::DirectMempool(txns) = payload;
-                    for txn in txns {
-                        exclude_txns.push(TransactionSummary {
-                            sender: txn.sender(),
-                            sequence_number: txn.sequence_number(),
-                        });
+        // Pending blocks can mix payload types when one of them is empty (e.g. reconfiguration
+        // suffixes are always proposed with an empty `DirectMempool` payload).
+        if exclude_payloads
+            .iter()
+            .any(|payload| matches!(payload, Payload::InQuorumStore(_)))
+        {
+            let mut exclude_digests = HashSet::new();
+            for payload in exclude_payloads {
+                if let Payload::InQuorumStore(proofs) = payload {
+                    for proof in proofs {
+                        exclude_digests.insert(*proof.digest());
                     }
                 }
-                PayloadFilter::DirectMempool(exclude_txns)
             }
+            return PayloadFilter::InQuorumStore(exclude_digests);
         }
+
+        let mut exclude_txns = vec![];
+        for payload in exclude_payloads {
+            if let Payload::DirectMempool(txns) = payload {
+                for txn in txns {
+                    exclude_txns.push(TransactionSummary {
+                        sender: txn.sender(),
+                        sequence_number: txn.sequence_number(),
+                    });
+                }
+            }
+        }
+        PayloadFilter::DirectMempool(exclude_txns)
     }
 }
 
@@ -121,6 +151,13 @@ impl fmt::Display for PayloadFilter {
                 }
                 write!(f, "{}", txns_str)
             }
+            PayloadFilter::InQuorumStore(excluded_digests) => {
+                let mut digests_str = "".to_string();
+                for digest in excluded_digests.iter() {
+                    write!(digests_str, "{} ", digest)?;
+                }
+                write!(f, "{}", digests_str)
+            }
         }
     }
 }
@@ -12,7 +12,7 @@ use aptos_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
     contract_event::ContractEvent,
-    transaction::{Transaction, TransactionStatus},
+    transaction::{SignedTransaction, Transaction, TransactionStatus},
 };
 use executor_types::StateComputeResult;
 use std::fmt::{Debug, Display, Formatter};
@@ -103,13 +103,17 @@ impl ExecutedBlock {
         )
     }
 
-    pub fn transactions_to_commit(&self, validators: &[AccountAddress]) -> Vec<Transaction> {
+    pub fn transactions_to_commit(
+        &self,
+        validators: &[AccountAddress],
+        user_txns: Vec<SignedTransaction>,
+    ) -> Vec<Transaction> {
         // reconfiguration suffix don't execute
         if self.is_reconfiguration_suffix() {
             return vec![];
         }
         itertools::zip_eq(
-            self.block.transactions_to_execute(validators),
+            self.block.transactions_to_execute(validators, user_txns),
             self.state_compute_result.compute_status(),
         )
         .filter_map(|(txn, status)| match status {
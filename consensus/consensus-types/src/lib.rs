@@ -3,6 +3,7 @@
 
 #![forbid(unsafe_code)]
 
+pub mod batch;
 pub mod block;
 pub mod block_data;
 pub mod block_retrieval;
@@ -10,6 +11,7 @@ pub mod common;
 pub mod epoch_retrieval;
 pub mod executed_block;
 pub mod experimental;
+pub mod proof_of_store;
 pub mod proposal_msg;
 pub mod quorum_cert;
 pub mod request_response;
@@ -0,0 +1,175 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{Author, Round};
+use anyhow::Context;
+use aptos_crypto::{bls12381, HashValue};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_types::{aggregate_signature::AggregateSignature, validator_verifier::ValidatorVerifier};
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::fmt::{Display, Formatter};
+
+/// Logical time of the quorum store: batches expire once consensus commits a block past their
+/// expiration.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct LogicalTime {
+    epoch: u64,
+    round: Round,
+}
+
+impl LogicalTime {
+    pub fn new(epoch: u64, round: Round) -> Self {
+        Self { epoch, round }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+}
+
+impl Display for LogicalTime {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "[epoch: {}, round: {}]", self.epoch, self.round)
+    }
+}
+
+/// The batch information a validator signs once it has persisted the batch.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct SignedDigestInfo {
+    pub digest: HashValue,
+    pub expiration: LogicalTime,
+    pub num_txns: u64,
+    pub num_bytes: u64,
+}
+
+impl SignedDigestInfo {
+    pub fn new(digest: HashValue, expiration: LogicalTime, num_txns: u64, num_bytes: u64) -> Self {
+        Self {
+            digest,
+            expiration,
+            num_txns,
+            num_bytes,
+        }
+    }
+}
+
+/// A validator's promise to store a batch until it expires and to serve it to other validators.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedDigest {
+    author: Author,
+    info: SignedDigestInfo,
+    signature: bls12381::Signature,
+}
+
+impl Display for SignedDigest {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "SignedDigest: [author: {}, digest: {}, expiration: {}]",
+            self.author.short_str(),
+            self.info.digest,
+            self.info.expiration
+        )
+    }
+}
+
+impl SignedDigest {
+    pub fn new(author: Author, info: SignedDigestInfo, signature: bls12381::Signature) -> Self {
+        Self {
+            author,
+            info,
+            signature,
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        self.author
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> &HashValue {
+        &self.info.digest
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.info.expiration.epoch()
+    }
+
+    pub fn signature(&self) -> &bls12381::Signature {
+        &self.signature
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify(self.author, &self.info, &self.signature)
+            .context("Failed to verify SignedDigest")
+    }
+}
+
+/// Certifies that a quorum of validators persisted the batch, so that it can be fetched from them
+/// until it expires. Proposals carry proofs of store instead of the transactions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProofOfStore {
+    info: SignedDigestInfo,
+    multi_signature: AggregateSignature,
+}
+
+impl Display for ProofOfStore {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "ProofOfStore: [digest: {}, expiration: {}, signers: {}]",
+            self.info.digest,
+            self.info.expiration,
+            self.multi_signature.get_num_voters()
+        )
+    }
+}
+
+impl ProofOfStore {
+    pub fn new(info: SignedDigestInfo, multi_signature: AggregateSignature) -> Self {
+        Self {
+            info,
+            multi_signature,
+        }
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> &HashValue {
+        &self.info.digest
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.info.expiration
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.info.expiration.epoch()
+    }
+
+    pub fn multi_signature(&self) -> &AggregateSignature {
+        &self.multi_signature
+    }
+
+    /// Returns the validators that signed the proof, i.e. the ones holding the batch
+    pub fn signers(&self, ordered_validators: &[Author]) -> Vec<Author> {
+        self.multi_signature.get_voter_addresses(ordered_validators)
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify_multi_signatures(&self.info, &self.multi_signature)
+            .context("Failed to verify ProofOfStore")
+    }
+}
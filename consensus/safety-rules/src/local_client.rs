@@ -10,6 +10,7 @@ use aptos_types::{
 };
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::SignedDigestInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::VoteProposal,
@@ -71,4 +72,8 @@ impl TSafetyRules for LocalClient {
             .write()
             .sign_commit_vote(ledger_info, new_ledger_info)
     }
+
+    fn sign_digest(&mut self, info: &SignedDigestInfo) -> Result<bls12381::Signature, Error> {
+        self.internal.write().sign_digest(info)
+    }
 }
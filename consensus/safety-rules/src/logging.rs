@@ -53,6 +53,7 @@ pub enum LogEntry {
     State,
     Waypoint,
     SignCommitVote,
+    SignDigest,
 }
 
 impl LogEntry {
@@ -71,6 +72,7 @@ impl LogEntry {
             LogEntry::State => "state",
             LogEntry::Waypoint => "waypoint",
             LogEntry::SignCommitVote => "sign_commit_vote",
+            LogEntry::SignDigest => "sign_digest",
         }
     }
 }
@@ -7,7 +7,9 @@ use crate::{
     Error,
 };
 use aptos_crypto::{bls12381, PrivateKey};
-use aptos_global_constants::{CONSENSUS_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
+use aptos_global_constants::{
+    consensus_key_for_public_key, CONSENSUS_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT,
+};
 use aptos_logger::prelude::*;
 use aptos_secure_storage::{KVStorage, Storage};
 use aptos_types::waypoint::Waypoint;
@@ -95,12 +97,22 @@ impl PersistentSafetyStorage {
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
     }
 
+    /// Returns the consensus key for the given public key. A key stored under the name for its
+    /// public key takes precedence over the default `CONSENSUS_KEY`, so that a rotated key can be
+    /// stored before the epoch it becomes active in.
     pub fn consensus_key_for_version(
         &self,
         version: bls12381::PublicKey,
     ) -> Result<bls12381::PrivateKey, Error> {
         let _timer = counters::start_timer("get", CONSENSUS_KEY);
-        let key: bls12381::PrivateKey = self.internal_store.get(CONSENSUS_KEY).map(|v| v.value)?;
+        let name = consensus_key_for_public_key(&hex::encode(version.to_bytes()));
+        let key: bls12381::PrivateKey = match self.internal_store.get(&name) {
+            Ok(response) => response.value,
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => {
+                self.internal_store.get(CONSENSUS_KEY).map(|v| v.value)?
+            }
+            Err(error) => return Err(error.into()),
+        };
         if key.public_key() != version {
             return Err(Error::SecureStorageMissingDataError(format!(
                 "PrivateKey for {:?} not found",
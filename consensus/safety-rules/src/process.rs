@@ -6,7 +6,7 @@ use crate::{
     remote_service::{self, RemoteService},
     safety_rules_manager,
 };
-use aptos_config::config::{SafetyRulesConfig, SafetyRulesService};
+use aptos_config::config::{RemoteServiceNoise, SafetyRulesConfig, SafetyRulesService};
 
 use std::net::SocketAddr;
 
@@ -29,13 +29,19 @@ impl Process {
                 server_addr,
                 storage,
                 network_timeout: config.network_timeout_ms,
+                noise: service.noise.clone(),
             }),
         }
     }
 
     pub fn start(&mut self) {
         let data = self.data.take().expect("Unable to retrieve ProcessData");
-        remote_service::execute(data.storage, data.server_addr, data.network_timeout);
+        remote_service::execute(
+            data.storage,
+            data.server_addr,
+            data.network_timeout,
+            data.noise,
+        );
     }
 }
 
@@ -44,18 +50,25 @@ struct ProcessData {
     storage: PersistentSafetyStorage,
     // Timeout in Seconds for network operations
     network_timeout: u64,
+    noise: Option<RemoteServiceNoise>,
 }
 
 pub struct ProcessService {
     server_addr: SocketAddr,
     network_timeout_ms: u64,
+    noise: Option<RemoteServiceNoise>,
 }
 
 impl ProcessService {
-    pub fn new(server_addr: SocketAddr, network_timeout: u64) -> Self {
+    pub fn new(
+        server_addr: SocketAddr,
+        network_timeout: u64,
+        noise: Option<RemoteServiceNoise>,
+    ) -> Self {
         Self {
             server_addr,
             network_timeout_ms: network_timeout,
+            noise,
         }
     }
 }
@@ -68,4 +81,8 @@ impl RemoteService for ProcessService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout_ms
     }
+
+    fn noise(&self) -> Option<&RemoteServiceNoise> {
+        self.noise.as_ref()
+    }
 }
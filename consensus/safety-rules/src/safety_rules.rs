@@ -21,6 +21,7 @@ use aptos_types::{
 use consensus_types::{
     block_data::BlockData,
     common::{Author, Round},
+    proof_of_store::SignedDigestInfo,
     quorum_cert::QuorumCert,
     safety_data::SafetyData,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
@@ -359,6 +360,21 @@ impl SafetyRules {
 
         Ok(signature)
     }
+
+    fn guarded_sign_digest(
+        &mut self,
+        info: &SignedDigestInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        self.signer()?;
+
+        // Only batches of the current epoch are signed, they are not persisted past it
+        let epoch = self.epoch_state()?.epoch;
+        if info.expiration.epoch() != epoch {
+            return Err(Error::IncorrectEpoch(info.expiration.epoch(), epoch));
+        }
+
+        self.sign(info)
+    }
 }
 
 impl TSafetyRules for SafetyRules {
@@ -413,6 +429,11 @@ impl TSafetyRules for SafetyRules {
         let cb = || self.guarded_sign_commit_vote(ledger_info, new_ledger_info);
         run_and_log(cb, |log| log, LogEntry::SignCommitVote)
     }
+
+    fn sign_digest(&mut self, info: &SignedDigestInfo) -> Result<bls12381::Signature, Error> {
+        let cb = || self.guarded_sign_digest(info);
+        run_and_log(cb, |log| log, LogEntry::SignDigest)
+    }
 }
 
 fn run_and_log<F, L, R>(callback: F, log_cb: L, log_entry: LogEntry) -> Result<R, Error>
@@ -10,6 +10,7 @@ use aptos_types::{
 };
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::SignedDigestInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::VoteProposal,
@@ -28,6 +29,7 @@ pub enum SafetyRulesInput {
     ),
     ConstructAndSignVoteTwoChain(Box<VoteProposal>, Box<Option<TwoChainTimeoutCertificate>>),
     SignCommitVote(Box<LedgerInfoWithSignatures>, Box<LedgerInfo>),
+    SignDigest(Box<SignedDigestInfo>),
 }
 
 pub struct SerializerService {
@@ -68,6 +70,9 @@ impl SerializerService {
                     .internal
                     .sign_commit_vote(*ledger_info, *new_ledger_info),
             ),
+            SafetyRulesInput::SignDigest(info) => {
+                serde_json::to_vec(&self.internal.sign_digest(&info))
+            }
         };
 
         Ok(output?)
@@ -152,6 +157,12 @@ impl TSafetyRules for SerializerClient {
         ))?;
         serde_json::from_slice(&response)?
     }
+
+    fn sign_digest(&mut self, info: &SignedDigestInfo) -> Result<bls12381::Signature, Error> {
+        let _timer = counters::start_timer("external", LogEntry::SignDigest.as_str());
+        let response = self.request(SafetyRulesInput::SignDigest(Box::new(info.clone())))?;
+        serde_json::from_slice(&response)?
+    }
 }
 
 pub trait TSerializerClient: Send + Sync {
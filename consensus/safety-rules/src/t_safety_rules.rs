@@ -9,6 +9,7 @@ use aptos_types::{
 };
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::SignedDigestInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::VoteProposal,
@@ -51,4 +52,8 @@ pub trait TSafetyRules {
         ledger_info: LedgerInfoWithSignatures,
         new_ledger_info: LedgerInfo,
     ) -> Result<bls12381::Signature, Error>;
+
+    /// As the holder of the private key, SafetyRules also signs the digests of the quorum store
+    /// batches persisted by this validator.
+    fn sign_digest(&mut self, info: &SignedDigestInfo) -> Result<bls12381::Signature, Error>;
 }
@@ -1,8 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, SafetyRulesManager};
+use crate::{remote_service, test_utils, SafetyRulesManager};
+use aptos_config::{config::RemoteServiceNoise, keys::ConfigKey, utils};
+use aptos_crypto::{x25519, Uniform};
 use aptos_types::validator_signer::ValidatorSigner;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+};
 
 #[test]
 fn test_reconnect() {
@@ -17,3 +24,38 @@ fn test_reconnect() {
     let state1 = safety_rules_manager.client().consensus_state().unwrap();
     assert_eq!(state0, state1);
 }
+
+#[test]
+fn test_noise_reconnect() {
+    let signer = ValidatorSigner::from_int(0);
+    let storage = test_utils::test_storage(&signer);
+    // test value for network timeout, in milliseconds.
+    let network_timeout = 5_000;
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let server_key = x25519::PrivateKey::generate(&mut rng);
+    let client_key = x25519::PrivateKey::generate(&mut rng);
+    let server_public_key = server_key.public_key();
+    let client_public_keys = vec![client_key.public_key()].into_iter().collect();
+    let client_noise = RemoteServiceNoise {
+        private_key: ConfigKey::new(client_key),
+        server_public_key,
+        client_public_keys,
+    };
+    let server_noise = RemoteServiceNoise {
+        private_key: ConfigKey::new(server_key),
+        ..client_noise.clone()
+    };
+
+    thread::spawn(move || {
+        remote_service::execute(storage, server_addr, network_timeout, Some(server_noise))
+    });
+    let safety_rules_manager =
+        SafetyRulesManager::new_process(server_addr, network_timeout, Some(client_noise));
+
+    // Every new client runs its own handshake with the server
+    let state0 = safety_rules_manager.client().consensus_state().unwrap();
+    let state1 = safety_rules_manager.client().consensus_state().unwrap();
+    assert_eq!(state0, state1);
+}
@@ -1,8 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, tests::suite, SafetyRules};
-use aptos_types::validator_signer::ValidatorSigner;
+use crate::{test_utils, tests::suite, SafetyRules, TSafetyRules};
+use aptos_global_constants::consensus_key_for_public_key;
+use aptos_secure_storage::KVStorage;
+use aptos_types::{
+    epoch_state::EpochState, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+use consensus_types::common::Payload;
 
 #[test]
 fn test() {
@@ -17,3 +23,54 @@ fn safety_rules() -> suite::Callback {
         (safety_rules, signer)
     })
 }
+
+#[test]
+fn test_rotated_consensus_key() {
+    let signer = ValidatorSigner::from_int(0);
+    let rotated_signer = ValidatorSigner::new(
+        signer.author(),
+        ValidatorSigner::from_int(1).private_key().clone(),
+    );
+
+    // The new key is stored next to the current one before the epoch change, as done by
+    // `aptos node rotate-keys`
+    let mut storage = test_utils::test_storage(&signer);
+    storage
+        .internal_store()
+        .set(
+            &consensus_key_for_public_key(&hex::encode(rotated_signer.public_key().to_bytes())),
+            rotated_signer.private_key().clone(),
+        )
+        .unwrap();
+    let mut safety_rules = SafetyRules::new(storage);
+
+    // The validator keeps signing with the current key for the rest of the epoch
+    let (mut proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    safety_rules.initialize(&proof).unwrap();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer);
+    assert_eq!(
+        safety_rules.sign_proposal(a1.block().block_data()).unwrap(),
+        signer.sign(a1.block().block_data()).unwrap()
+    );
+
+    // The rotated key is picked up once it's in the validator set
+    let mut next_epoch_state = EpochState::empty();
+    next_epoch_state.epoch = 1;
+    next_epoch_state.verifier =
+        ValidatorVerifier::new_single(signer.author(), rotated_signer.public_key());
+    let a2 = test_utils::make_proposal_with_parent_and_overrides(
+        Payload::empty(),
+        round + 2,
+        &a1,
+        Some(&a1),
+        &signer,
+        Some(1),
+        Some(next_epoch_state),
+    );
+    proof
+        .ledger_info_with_sigs
+        .push(a2.block().quorum_cert().ledger_info().clone());
+    safety_rules.initialize(&proof).unwrap();
+    assert!(safety_rules.consensus_state().unwrap().in_validator_set());
+}
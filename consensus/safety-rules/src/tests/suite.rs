@@ -14,6 +14,7 @@ use aptos_types::{
 use consensus_types::{
     block::block_test_utils::random_payload,
     common::{Payload, Round},
+    proof_of_store::{LogicalTime, SignedDigestInfo},
     quorum_cert::QuorumCert,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote_proposal::VoteProposal,
@@ -55,6 +56,7 @@ pub fn run_test_suite(safety_rules: &Callback) {
     test_2chain_rules(safety_rules);
     test_2chain_timeout(safety_rules);
     test_sign_commit_vote(safety_rules);
+    test_sign_digest(safety_rules);
     test_bad_execution_output(safety_rules);
 }
 
@@ -668,3 +670,28 @@ fn test_sign_commit_vote(constructor: &Callback) {
         Error::InconsistentExecutionResult(_, _)
     ));
 }
+
+/// Test that digests are only signed for the current epoch
+fn test_sign_digest(constructor: &Callback) {
+    let (mut safety_rules, signer) = constructor();
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let epoch = genesis_qc.certified_block().epoch();
+    let info = SignedDigestInfo::new(HashValue::random(), LogicalTime::new(epoch, 10), 1, 100);
+
+    assert!(matches!(
+        safety_rules.sign_digest(&info).unwrap_err(),
+        Error::NotInitialized(_)
+    ));
+
+    safety_rules.initialize(&proof).unwrap();
+    let signature = safety_rules.sign_digest(&info).unwrap();
+    let verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    assert!(verifier.verify(signer.author(), &info, &signature).is_ok());
+
+    let bad_info =
+        SignedDigestInfo::new(HashValue::random(), LogicalTime::new(epoch + 1, 10), 1, 100);
+    assert_eq!(
+        safety_rules.sign_digest(&bad_info),
+        Err(Error::IncorrectEpoch(epoch + 1, epoch))
+    );
+}
@@ -0,0 +1,89 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Context, Result};
+use aptos_config::config::{
+    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_types::on_chain_config::{
+    LeaderReputationType, OnChainConsensusConfig, ProposerElectionType,
+};
+use aptosdb::AptosDB;
+use consensus::LeaderReputationSimulator;
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "leader-reputation-simulator",
+    about = "Replay the committed blocks of a local DB through a proposer election config, to estimate how often proposers would have failed their rounds with it."
+)]
+struct Opt {
+    /// Directory of the AptosDB to read the committed blocks from e.g. /opt/aptos/data/db
+    #[structopt(parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// First epoch to simulate
+    #[structopt(long, default_value = "1")]
+    start_epoch: u64,
+
+    /// Last epoch to simulate
+    #[structopt(long)]
+    end_epoch: u64,
+
+    /// YAML file of the on-chain consensus config to simulate, the default config otherwise
+    #[structopt(long, parse(from_os_str))]
+    consensus_config: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let config: OnChainConsensusConfig = match &opt.consensus_config {
+        Some(file) => serde_yaml::from_str(
+            &fs::read_to_string(file)
+                .with_context(|| format_err!("Failed to read {}", file.display()))?,
+        )?,
+        None => OnChainConsensusConfig::default(),
+    };
+    // The elections of the first epochs look at the blocks of the previous ones
+    let history_epochs = match config.proposer_election_type() {
+        ProposerElectionType::LeaderReputation(LeaderReputationType::ProposerAndVoter(config)) => {
+            config.use_history_from_previous_epoch_max_count
+        }
+        ProposerElectionType::LeaderReputation(LeaderReputationType::RecencyWeighted(config)) => {
+            config.use_history_from_previous_epoch_max_count
+        }
+        _ => 0,
+    };
+
+    // Read only, so that it can run alongside a node operating on the same DB.
+    let db = AptosDB::open(
+        &opt.db_dir,
+        true,
+        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+        RocksdbConfigs::default(),
+        false, /* indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+    .with_context(|| format_err!("Failed to open DB."))?;
+    let simulator = LeaderReputationSimulator::from_db(
+        &db,
+        std::cmp::max(1, opt.start_epoch.saturating_sub(history_epochs as u64)),
+        opt.end_epoch,
+    )?;
+
+    for epoch in opt.start_epoch..=opt.end_epoch {
+        let result = simulator.simulate_config(epoch, &config)?;
+        println!(
+            "Epoch {}: {} rounds, historical failure rate {:.3}, simulated failure rate {:.3}",
+            result.epoch,
+            result.rounds,
+            result.historical_failure_rate(),
+            result.simulated_failure_rate(),
+        );
+    }
+    Ok(())
+}
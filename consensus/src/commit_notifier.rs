@@ -5,7 +5,10 @@ use crate::error::QuorumStoreError;
 use crate::monitor;
 use anyhow::{format_err, Result};
 use aptos_infallible::Mutex;
-use consensus_types::{common::Round, request_response::ConsensusRequest};
+use consensus_types::{
+    common::{Payload, Round},
+    request_response::ConsensusRequest,
+};
 use futures::channel::{mpsc, mpsc::Sender, oneshot};
 use std::time::Duration;
 use tokio::time::timeout;
@@ -13,8 +16,13 @@ use tokio::time::timeout;
 /// Notification of execution committed logical time for QuorumStore to clean.
 #[async_trait::async_trait]
 pub trait CommitNotifier: Send + Sync {
-    /// Notification of committed logical time
-    async fn notify_commit(&self, epoch: u64, round: Round) -> Result<(), QuorumStoreError>;
+    /// Notification of committed logical time and of the payloads of the committed blocks
+    async fn notify_commit(
+        &self,
+        epoch: u64,
+        round: Round,
+        payloads: Vec<Payload>,
+    ) -> Result<(), QuorumStoreError>;
 
     fn new_epoch(&self, quorum_store_commit_sender: mpsc::Sender<ConsensusRequest>);
 }
@@ -39,9 +47,14 @@ impl QuorumStoreCommitNotifier {
 
 #[async_trait::async_trait]
 impl CommitNotifier for QuorumStoreCommitNotifier {
-    async fn notify_commit(&self, epoch: u64, round: Round) -> Result<(), QuorumStoreError> {
+    async fn notify_commit(
+        &self,
+        epoch: u64,
+        round: Round,
+        payloads: Vec<Payload>,
+    ) -> Result<(), QuorumStoreError> {
         let (callback, callback_rcv) = oneshot::channel();
-        let req = ConsensusRequest::CleanRequest(epoch, round, callback);
+        let req = ConsensusRequest::CleanRequest(epoch, round, payloads, callback);
 
         self.quorum_store_commit_sender
             .lock()
@@ -0,0 +1,14 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+/// Messages exchanged with the consensus observers and their network interface.
+pub mod network;
+/// Follows consensus from a full node, executing and committing the ordered blocks.
+pub mod observer;
+/// Pushes the ordered blocks and the commit decisions to the observers.
+pub mod publisher;
+
+mod pending_blocks;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,239 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interface between the consensus observer and the network layer.
+
+use crate::counters;
+use anyhow::{ensure, format_err};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+    validator_verifier::ValidatorVerifier, PeerId,
+};
+use async_trait::async_trait;
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{batch::BatchPayload, block::Block, common::Payload};
+use network::{
+    application::interface::MultiNetworkSender,
+    error::NetworkError,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::{
+        AppConfig, ApplicationNetworkSender, NetworkEvents, NetworkSender, NewNetworkSender,
+        RpcError,
+    },
+    ProtocolId,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Messages exchanged between a consensus publisher and its observers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// Sent by an observer to start receiving the ordered blocks and the commit decisions.
+    Subscribe,
+    /// Sent by an observer to stop receiving them.
+    Unsubscribe,
+    /// Blocks ordered by consensus, with the transactions needed to execute them.
+    OrderedBlock(Box<OrderedBlock>),
+    /// Commit proof of an ordered block and of its ancestors.
+    CommitDecision(Box<LedgerInfoWithSignatures>),
+}
+
+impl ConsensusObserverMessage {
+    /// ConsensusObserverMessage type in string
+    pub fn name(&self) -> &str {
+        match self {
+            ConsensusObserverMessage::Subscribe => "Subscribe",
+            ConsensusObserverMessage::Unsubscribe => "Unsubscribe",
+            ConsensusObserverMessage::OrderedBlock(_) => "OrderedBlock",
+            ConsensusObserverMessage::CommitDecision(_) => "CommitDecision",
+        }
+    }
+}
+
+/// A block ordered by consensus. The observers can't fetch the batches of the quorum store, so
+/// their transactions travel with the block.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ObservedBlock {
+    block: Block,
+    // transactions of the batches referenced by the proofs of store, in the order of the proofs,
+    // empty for the other payloads
+    batch_txns: Vec<SignedTransaction>,
+}
+
+impl ObservedBlock {
+    /// Creates an observed block from the transactions of its batches
+    pub fn new(block: Block, batch_txns: Vec<SignedTransaction>) -> Self {
+        Self { block, batch_txns }
+    }
+
+    /// The ordered block
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    /// Returns the user transactions of the block, after checking that the transactions of each
+    /// batch hash to the digest of its proof of store.
+    pub fn user_txns(&self) -> anyhow::Result<Vec<SignedTransaction>> {
+        match self.block.payload() {
+            None => Ok(vec![]),
+            Some(Payload::DirectMempool(txns)) => {
+                ensure!(
+                    self.batch_txns.is_empty(),
+                    "Unexpected batch transactions for a direct mempool payload"
+                );
+                Ok(txns.clone())
+            }
+            Some(Payload::InQuorumStore(proofs)) => {
+                let mut remaining = self.batch_txns.as_slice();
+                for proof in proofs {
+                    let num_txns = proof.info().num_txns as usize;
+                    ensure!(
+                        remaining.len() >= num_txns,
+                        "Missing transactions of batch {}",
+                        proof.digest()
+                    );
+                    let (batch_txns, rest) = remaining.split_at(num_txns);
+                    ensure!(
+                        BatchPayload::new(batch_txns.to_vec()).hash() == *proof.digest(),
+                        "Transactions don't match the digest of batch {}",
+                        proof.digest()
+                    );
+                    remaining = rest;
+                }
+                ensure!(remaining.is_empty(), "Unexpected extra batch transactions");
+                Ok(self.batch_txns.clone())
+            }
+        }
+    }
+}
+
+/// Chain of blocks ordered by consensus, the last one is certified by the ordered proof.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OrderedBlock {
+    blocks: Vec<ObservedBlock>,
+    ordered_proof: LedgerInfoWithSignatures,
+}
+
+impl OrderedBlock {
+    /// Creates the ordered blocks certified by the ordered proof
+    pub fn new(blocks: Vec<ObservedBlock>, ordered_proof: LedgerInfoWithSignatures) -> Self {
+        Self {
+            blocks,
+            ordered_proof,
+        }
+    }
+
+    /// The ordered blocks, parents first
+    pub fn blocks(&self) -> &[ObservedBlock] {
+        &self.blocks
+    }
+
+    /// The ledger info certifying the order of the last block
+    pub fn ordered_proof(&self) -> &LedgerInfoWithSignatures {
+        &self.ordered_proof
+    }
+
+    /// The first ordered block
+    pub fn first_block(&self) -> &Block {
+        self.blocks
+            .first()
+            .expect("Ordered blocks can't be empty")
+            .block()
+    }
+
+    /// The last ordered block, certified by the ordered proof
+    pub fn last_block(&self) -> &Block {
+        self.blocks
+            .last()
+            .expect("Ordered blocks can't be empty")
+            .block()
+    }
+
+    /// Verifies that the blocks form a chain ending with the block certified by the ordered
+    /// proof. The block ids are the hashes of the block data, so this certifies every block.
+    pub fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()> {
+        ensure!(!self.blocks.is_empty(), "Empty ordered blocks");
+        for (parent, child) in self.blocks.iter().zip(self.blocks.iter().skip(1)) {
+            ensure!(
+                child.block().parent_id() == parent.block().id(),
+                "Ordered block {} doesn't extend {}",
+                child.block(),
+                parent.block()
+            );
+        }
+        ensure!(
+            self.last_block().id() == self.ordered_proof.commit_info().id(),
+            "Ordered proof {} doesn't certify the last block {}",
+            self.ordered_proof.commit_info(),
+            self.last_block()
+        );
+        self.ordered_proof
+            .verify_signatures(verifier)
+            .map_err(|e| format_err!("Failed to verify the ordered proof: {:?}", e))
+    }
+}
+
+/// The interface from Network to the consensus observer.
+pub type ConsensusObserverNetworkEvents = NetworkEvents<ConsensusObserverMessage>;
+
+/// The interface from the consensus observer to the Networking layer, for a single network.
+#[derive(Clone, Debug)]
+pub struct ConsensusObserverNetworkSender {
+    inner: NetworkSender<ConsensusObserverMessage>,
+}
+
+/// The consensus observer talks to its publishers and observers on all the full node networks.
+pub type ConsensusObserverMultiNetworkSender =
+    MultiNetworkSender<ConsensusObserverMessage, ConsensusObserverNetworkSender>;
+
+/// Configuration for the network endpoints to support the consensus observer.
+pub fn network_endpoint_config(max_network_channel_size: usize) -> AppConfig {
+    AppConfig::p2p(
+        [ProtocolId::ConsensusObserver],
+        aptos_channel::Config::new(max_network_channel_size)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    )
+}
+
+impl NewNetworkSender for ConsensusObserverNetworkSender {
+    fn new(
+        peer_mgr_reqs_tx: PeerManagerRequestSender,
+        connection_reqs_tx: ConnectionRequestSender,
+    ) -> Self {
+        Self {
+            inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+        }
+    }
+}
+
+#[async_trait]
+impl ApplicationNetworkSender<ConsensusObserverMessage> for ConsensusObserverNetworkSender {
+    fn send_to(
+        &self,
+        recipient: PeerId,
+        message: ConsensusObserverMessage,
+    ) -> Result<(), NetworkError> {
+        self.inner
+            .send_to(recipient, ProtocolId::ConsensusObserver, message)
+    }
+
+    fn send_to_many(
+        &self,
+        recipients: impl Iterator<Item = PeerId>,
+        message: ConsensusObserverMessage,
+    ) -> Result<(), NetworkError> {
+        self.inner
+            .send_to_many(recipients, ProtocolId::ConsensusObserver, message)
+    }
+
+    async fn send_rpc(
+        &self,
+        _recipient: PeerId,
+        _req_msg: ConsensusObserverMessage,
+        _timeout: Duration,
+    ) -> Result<ConsensusObserverMessage, RpcError> {
+        unimplemented!("The consensus observer only supports direct send messages!");
+    }
+}
@@ -0,0 +1,565 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        network::{
+            ConsensusObserverMessage, ConsensusObserverMultiNetworkSender,
+            ConsensusObserverNetworkEvents, OrderedBlock,
+        },
+        pending_blocks::{root_block_id, PendingBlock, PendingBlocks},
+        publisher::ConsensusPublisher,
+    },
+    counters, monitor,
+};
+use anyhow::{ensure, format_err};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_address::AccountAddress, epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
+use consensus_notifications::ConsensusNotificationSender;
+use consensus_types::{block::Block, executed_block::ExecutedBlock};
+use executor_types::{BlockExecutorTrait, StateComputeResult};
+use futures::{stream::select_all, FutureExt, StreamExt};
+use netcore::transport::ConnectionOrigin;
+use network::{application::storage::PeerMetadataStorage, protocols::network::Event, ProtocolId};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_interface::DbReader;
+use tokio::time::interval;
+
+/// Follows consensus from a full node. The blocks ordered by the validators are executed as soon
+/// as a publisher pushes them, and committed once their commit decision arrives, instead of
+/// waiting for state sync to fetch them after the commit.
+///
+/// State sync stays in charge when the observer falls behind: the observer syncs to the commit
+/// decisions it can't apply. It notifies state sync before it starts committing, and hands back
+/// to state sync once it has been idle for too long.
+pub struct ConsensusObserver {
+    config: ConsensusObserverConfig,
+    network_sender: ConsensusObserverMultiNetworkSender,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    // the networks to look for a publisher in, most preferred first
+    network_ids: Vec<NetworkId>,
+    subscription: Option<PeerNetworkId>,
+    last_message_time: Instant,
+    last_commit_time: Instant,
+    // whether state sync handed the execution over to the observer
+    executing: bool,
+    db: Arc<dyn DbReader>,
+    executor: Arc<dyn BlockExecutorTrait>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    // the latest commit decision applied to the storage
+    root: LedgerInfoWithSignatures,
+    epoch_state: EpochState,
+    pending_blocks: PendingBlocks,
+    // relays what is observed to the downstream observers
+    publisher: Option<Arc<ConsensusPublisher>>,
+}
+
+impl ConsensusObserver {
+    /// Creates an observer starting from the latest ledger info in the storage
+    pub fn new(
+        config: ConsensusObserverConfig,
+        network_sender: ConsensusObserverMultiNetworkSender,
+        mut network_ids: Vec<NetworkId>,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+        db: Arc<dyn DbReader>,
+        executor: Arc<dyn BlockExecutorTrait>,
+        state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+        publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        // VFNs follow their validator, public full nodes follow the VFNs
+        network_ids.sort_by_key(|network_id| match network_id {
+            NetworkId::Vfn => 0,
+            NetworkId::Public => 1,
+            _ => 2,
+        });
+        let root = db
+            .get_latest_ledger_info()
+            .expect("Failed to read the latest ledger info");
+        let epoch_state = db
+            .get_latest_epoch_state()
+            .expect("Failed to read the latest epoch state");
+        let max_pending_blocks = config.max_pending_blocks;
+        Self {
+            config,
+            network_sender,
+            peer_metadata_storage,
+            network_ids,
+            subscription: None,
+            last_message_time: Instant::now(),
+            last_commit_time: Instant::now(),
+            executing: false,
+            db,
+            executor,
+            state_sync_notifier,
+            root,
+            epoch_state,
+            pending_blocks: PendingBlocks::new(max_pending_blocks),
+            publisher,
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.config.observer_timeout_ms)
+    }
+
+    fn validators(&self) -> Vec<AccountAddress> {
+        self.epoch_state
+            .verifier
+            .get_ordered_account_addresses_iter()
+            .collect()
+    }
+
+    /// Picks the publisher to subscribe to among the peers we connected to, on the most
+    /// preferred network.
+    fn choose_publisher(&self, exclude: Option<PeerNetworkId>) -> Option<PeerNetworkId> {
+        for network_id in &self.network_ids {
+            let mut candidates: Vec<_> = self
+                .peer_metadata_storage
+                .read_filtered(*network_id, |(_, peer_info)| {
+                    peer_info.is_connected()
+                        && peer_info.supports_protocol(ProtocolId::ConsensusObserver)
+                        && peer_info.active_connection.origin == ConnectionOrigin::Outbound
+                })
+                .into_keys()
+                .filter(|peer| Some(*peer) != exclude)
+                .collect();
+            candidates.sort();
+            if let Some(peer) = candidates.into_iter().next() {
+                return Some(peer);
+            }
+        }
+        None
+    }
+
+    fn subscribe(&mut self, exclude: Option<PeerNetworkId>) {
+        if let Some(peer) = self.choose_publisher(exclude) {
+            match self
+                .network_sender
+                .send_to(peer, ConsensusObserverMessage::Subscribe)
+            {
+                Ok(()) => {
+                    info!(remote_peer = peer, "[ConsensusObserver] subscribed");
+                    self.subscription = Some(peer);
+                    self.last_message_time = Instant::now();
+                }
+                Err(e) => {
+                    warn!(remote_peer = peer, error = ?e, "[ConsensusObserver] failed to subscribe");
+                }
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self) {
+        if let Some(peer) = self.subscription.take() {
+            info!(remote_peer = peer, "[ConsensusObserver] unsubscribed");
+            // the publisher also drops the subscription once the connection is lost
+            let _ = self
+                .network_sender
+                .send_to(peer, ConsensusObserverMessage::Unsubscribe);
+        }
+    }
+
+    /// Moves to another publisher when the current one is silent, and hands the execution back
+    /// to state sync when nothing was committed for too long.
+    pub async fn check_progress(&mut self) {
+        if self.executing && self.last_commit_time.elapsed() >= self.timeout() {
+            self.stop_executing().await;
+        }
+        let previous = self.subscription;
+        if let Some(peer) = previous {
+            let connected = self
+                .peer_metadata_storage
+                .read(peer)
+                .map_or(false, |peer_info| peer_info.is_connected());
+            if connected && self.last_message_time.elapsed() < self.timeout() {
+                return;
+            }
+            self.unsubscribe();
+        }
+        self.subscribe(previous);
+        if self.subscription.is_none() && previous.is_some() {
+            // the silent publisher is the only one available
+            self.subscribe(None);
+        }
+    }
+
+    /// Takes the execution over from state sync, which stops syncing and commits the data it
+    /// has in flight first. Then catches up with the storage, as state sync may have moved it.
+    async fn start_executing(&mut self) -> anyhow::Result<()> {
+        self.state_sync_notifier
+            .notify_observer_executing(true)
+            .await
+            .map_err(|e| format_err!("Failed to take over from state sync: {:?}", e))?;
+        info!("[ConsensusObserver] took over the execution from state sync");
+        self.executing = true;
+        self.last_commit_time = Instant::now();
+        self.refresh_root()
+    }
+
+    /// Hands the execution back to state sync. Commits are awaited before processing the next
+    /// event, so none is in flight.
+    async fn stop_executing(&mut self) {
+        match self
+            .state_sync_notifier
+            .notify_observer_executing(false)
+            .await
+        {
+            Ok(()) => {
+                info!("[ConsensusObserver] handed the execution back to state sync");
+                self.executing = false;
+            }
+            Err(e) => {
+                warn!(error = ?e, "[ConsensusObserver] failed to hand back to state sync");
+            }
+        }
+    }
+
+    /// Reloads the root from the storage, as state sync may have moved it.
+    fn refresh_root(&mut self) -> anyhow::Result<()> {
+        let latest = self.db.get_latest_ledger_info()?;
+        if latest.commit_info().version() <= self.root.commit_info().version() {
+            return Ok(());
+        }
+        info!(
+            "[ConsensusObserver] state sync moved the root from {} to {}",
+            self.root.commit_info(),
+            latest.commit_info()
+        );
+        self.executor.reset()?;
+        self.epoch_state = self.db.get_latest_epoch_state()?;
+        self.root = latest;
+        self.pending_blocks.clear();
+        Ok(())
+    }
+
+    fn update_root(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        self.last_commit_time = Instant::now();
+        if let Some(epoch_state) = commit_proof.ledger_info().next_epoch_state() {
+            info!(
+                epoch = epoch_state.epoch,
+                "[ConsensusObserver] new epoch {}", epoch_state
+            );
+            self.epoch_state = epoch_state.clone();
+            self.pending_blocks.clear();
+        }
+        self.root = commit_proof;
+        counters::CONSENSUS_OBSERVER_PENDING_BLOCKS.set(self.pending_blocks.len() as i64);
+    }
+
+    /// Processes a message or a connection event of one of the networks
+    pub async fn process_network_event(
+        &mut self,
+        network_id: NetworkId,
+        event: Event<ConsensusObserverMessage>,
+    ) {
+        match event {
+            Event::Message(peer_id, message) => {
+                let peer = PeerNetworkId::new(network_id, peer_id);
+                if let Err(e) = self.process_message(peer, message).await {
+                    warn!(remote_peer = peer, error = ?e, "[ConsensusObserver] failed to process message");
+                }
+            }
+            Event::NewPeer(_) => {
+                if self.subscription.is_none() {
+                    self.subscribe(None);
+                }
+            }
+            Event::LostPeer(metadata) => {
+                let peer = PeerNetworkId::new(network_id, metadata.remote_peer_id);
+                if let Some(publisher) = &self.publisher {
+                    publisher.remove_subscriber(&peer);
+                }
+                if self.subscription == Some(peer) {
+                    self.subscription = None;
+                    self.subscribe(Some(peer));
+                }
+            }
+            Event::RpcRequest(..) => {}
+        }
+    }
+
+    async fn process_message(
+        &mut self,
+        peer: PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) -> anyhow::Result<()> {
+        counters::CONSENSUS_OBSERVER_RECEIVED_MESSAGES
+            .with_label_values(&[message.name()])
+            .inc();
+        if let Some(publisher) = &self.publisher {
+            if publisher.process_subscription_message(peer, &message) {
+                return Ok(());
+            }
+        }
+        ensure!(
+            self.subscription == Some(peer),
+            "{} message from a peer we're not subscribed to",
+            message.name()
+        );
+        self.last_message_time = Instant::now();
+        match message {
+            ConsensusObserverMessage::OrderedBlock(ordered_block) => {
+                monitor!(
+                    "observer_process_ordered_block",
+                    self.process_ordered_block(*ordered_block).await
+                )
+            }
+            ConsensusObserverMessage::CommitDecision(commit_proof) => {
+                monitor!(
+                    "observer_process_commit_decision",
+                    self.process_commit_decision(*commit_proof).await
+                )
+            }
+            ConsensusObserverMessage::Subscribe | ConsensusObserverMessage::Unsubscribe => Err(
+                format_err!("Subscription message while the publisher is disabled"),
+            ),
+        }
+    }
+
+    /// Verifies the ordered blocks and executes them on top of the pending blocks
+    pub async fn process_ordered_block(
+        &mut self,
+        ordered_block: OrderedBlock,
+    ) -> anyhow::Result<()> {
+        let ordered_info = ordered_block.ordered_proof().commit_info();
+        if ordered_info.epoch() != self.epoch_state.epoch {
+            // blocks of the next epoch wait for the commit of the reconfiguration
+            debug!(
+                "[ConsensusObserver] ignoring ordered block {} of another epoch",
+                ordered_info
+            );
+            return Ok(());
+        }
+        if (ordered_info.epoch(), ordered_info.round())
+            <= self.pending_blocks.last_round(&self.root)
+        {
+            return Ok(());
+        }
+        ordered_block.verify(&self.epoch_state.verifier)?;
+        let blocks = ordered_block
+            .blocks()
+            .iter()
+            .map(|observed| {
+                Ok(PendingBlock::new(
+                    observed.block().clone(),
+                    observed.user_txns()?,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        // the missing blocks are caught up with the commit decision
+        self.pending_blocks.extend(&self.root, blocks)?;
+        counters::CONSENSUS_OBSERVER_PENDING_BLOCKS.set(self.pending_blocks.len() as i64);
+
+        if let Some(publisher) = &self.publisher {
+            publisher.publish_message(ConsensusObserverMessage::OrderedBlock(Box::new(
+                ordered_block,
+            )));
+        }
+        self.execute_pending_blocks().await;
+        Ok(())
+    }
+
+    /// Executes the pending blocks that aren't yet, in order.
+    async fn execute_pending_blocks(&mut self) {
+        let validators = self.validators();
+        let executor = self.executor.clone();
+        let mut parent_id = root_block_id(&self.root);
+        for pending in self.pending_blocks.iter_mut() {
+            if pending.compute_result.is_none() {
+                match execute_block(
+                    executor.clone(),
+                    &pending.block,
+                    parent_id,
+                    &validators,
+                    pending.user_txns.clone(),
+                )
+                .await
+                {
+                    Ok(compute_result) => pending.compute_result = Some(compute_result),
+                    Err(e) => {
+                        warn!(error = ?e, "[ConsensusObserver] failed to execute {}", pending.block);
+                        return;
+                    }
+                }
+            }
+            parent_id = pending.block.id();
+        }
+    }
+
+    /// Verifies the commit decision and commits the executed blocks it certifies, or syncs to it
+    pub async fn process_commit_decision(
+        &mut self,
+        commit_proof: LedgerInfoWithSignatures,
+    ) -> anyhow::Result<()> {
+        let commit_info = commit_proof.commit_info().clone();
+        let root_info = self.root.commit_info();
+        if (commit_info.epoch(), commit_info.round()) <= (root_info.epoch(), root_info.round()) {
+            return Ok(());
+        }
+        // state sync catches up with the later epochs once the observer stops committing
+        ensure!(
+            commit_info.epoch() == self.epoch_state.epoch,
+            "Commit decision {} of another epoch, current epoch {}",
+            commit_info,
+            self.epoch_state.epoch
+        );
+        commit_proof
+            .verify_signatures(&self.epoch_state.verifier)
+            .map_err(|e| format_err!("Failed to verify the commit decision: {:?}", e))?;
+        if let Some(publisher) = &self.publisher {
+            publisher.publish_commit_decision(commit_proof.clone());
+        }
+        if !self.executing {
+            self.start_executing().await?;
+        }
+
+        let executed = self
+            .pending_blocks
+            .get_mut(commit_info.id())
+            .and_then(|pending| pending.compute_result.as_ref())
+            .map_or(false, |compute_result| {
+                compute_result.root_hash() == commit_info.executed_state_id()
+                    && compute_result.version() == commit_info.version()
+            });
+        if executed {
+            self.commit(commit_proof).await
+        } else {
+            self.sync_to(commit_proof).await
+        }
+    }
+
+    /// Commits the executed blocks up to the one certified by the commit proof.
+    async fn commit(&mut self, commit_proof: LedgerInfoWithSignatures) -> anyhow::Result<()> {
+        let commit_info = commit_proof.commit_info();
+        let blocks = self
+            .pending_blocks
+            .take_up_to(commit_info.epoch(), commit_info.round());
+        let validators = self.validators();
+        let mut block_ids = vec![];
+        let mut txns = vec![];
+        let mut reconfig_events = vec![];
+        for pending in blocks {
+            block_ids.push(pending.block.id());
+            let executed_block = ExecutedBlock::new(
+                pending.block,
+                pending
+                    .compute_result
+                    .ok_or_else(|| format_err!("Committing a block that isn't executed"))?,
+            );
+            txns.extend(executed_block.transactions_to_commit(&validators, pending.user_txns));
+            reconfig_events.extend(executed_block.reconfig_event());
+        }
+
+        let num_blocks = block_ids.len();
+        let executor = self.executor.clone();
+        let proof = commit_proof.clone();
+        monitor!(
+            "observer_commit_blocks",
+            tokio::task::spawn_blocking(move || executor.commit_blocks(block_ids, proof)).await
+        )
+        .expect("spawn_blocking failed")?;
+        counters::CONSENSUS_OBSERVER_COMMITTED_BLOCKS.inc_by(num_blocks as u64);
+
+        if let Err(e) = self
+            .state_sync_notifier
+            .notify_new_commit(txns, reconfig_events)
+            .await
+        {
+            error!(error = ?e, "[ConsensusObserver] failed to notify state sync");
+        }
+        self.update_root(commit_proof);
+        Ok(())
+    }
+
+    /// Lets state sync apply the commit decision, then executes the remaining pending blocks on
+    /// top of it.
+    async fn sync_to(&mut self, commit_proof: LedgerInfoWithSignatures) -> anyhow::Result<()> {
+        counters::CONSENSUS_OBSERVER_SYNC_COUNT.inc();
+        info!(
+            "[ConsensusObserver] syncing to {}",
+            commit_proof.commit_info()
+        );
+        // free the in-memory SMT held by the executor, the storage is about to change
+        self.executor.finish();
+        let result = monitor!(
+            "observer_sync_to",
+            self.state_sync_notifier
+                .sync_to_target(commit_proof.clone())
+                .await
+        );
+        self.executor.reset()?;
+        if let Err(e) = result {
+            // state sync may already be past the target
+            self.refresh_root()?;
+            return Err(format_err!(
+                "Failed to sync to the commit decision: {:?}",
+                e
+            ));
+        }
+
+        let commit_info = commit_proof.commit_info();
+        self.pending_blocks
+            .take_up_to(commit_info.epoch(), commit_info.round());
+        for pending in self.pending_blocks.iter_mut() {
+            pending.compute_result = None;
+        }
+        self.update_root(commit_proof);
+        self.execute_pending_blocks().await;
+        Ok(())
+    }
+
+    /// Subscribes to a publisher and follows consensus until the node stops
+    pub async fn start(mut self, network_events: Vec<(NetworkId, ConsensusObserverNetworkEvents)>) {
+        info!(
+            root = %self.root.commit_info(),
+            "[ConsensusObserver] started"
+        );
+        let mut events = select_all(
+            network_events
+                .into_iter()
+                .map(|(network_id, events)| events.map(move |event| (network_id, event))),
+        );
+        let mut progress_check_interval = interval(Duration::from_millis(
+            self.config.progress_check_interval_ms,
+        ));
+        loop {
+            ::futures::select! {
+                (network_id, event) = events.select_next_some() => {
+                    self.process_network_event(network_id, event).await;
+                },
+                _ = progress_check_interval.tick().fuse() => {
+                    self.check_progress().await;
+                },
+            }
+        }
+    }
+}
+
+async fn execute_block(
+    executor: Arc<dyn BlockExecutorTrait>,
+    block: &Block,
+    parent_id: HashValue,
+    validators: &[AccountAddress],
+    user_txns: Vec<SignedTransaction>,
+) -> anyhow::Result<StateComputeResult> {
+    let block_id = block.id();
+    let txns = block.transactions_to_execute(validators, user_txns);
+    let compute_result = monitor!(
+        "observer_execute_block",
+        tokio::task::spawn_blocking(move || executor.execute_block((block_id, txns), parent_id))
+            .await
+    )
+    .expect("spawn_blocking failed")?;
+    Ok(compute_result)
+}
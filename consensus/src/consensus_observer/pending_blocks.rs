@@ -0,0 +1,128 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::ensure;
+use aptos_crypto::HashValue;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction};
+use consensus_types::{block::Block, common::Round};
+use executor_types::StateComputeResult;
+use std::collections::BTreeMap;
+
+/// Id of the block the blocks following the commit extend: the first blocks of an epoch extend
+/// its genesis block, a virtual block derived from the ledger info ending the previous epoch.
+pub fn root_block_id(root: &LedgerInfoWithSignatures) -> HashValue {
+    if root.ledger_info().ends_epoch() {
+        Block::make_genesis_block_from_ledger_info(root.ledger_info()).id()
+    } else {
+        root.commit_info().id()
+    }
+}
+
+/// An ordered block waiting for its commit decision.
+pub struct PendingBlock {
+    pub block: Block,
+    pub user_txns: Vec<SignedTransaction>,
+    // set once the block is executed
+    pub compute_result: Option<StateComputeResult>,
+}
+
+impl PendingBlock {
+    pub fn new(block: Block, user_txns: Vec<SignedTransaction>) -> Self {
+        Self {
+            block,
+            user_txns,
+            compute_result: None,
+        }
+    }
+}
+
+/// The chain of ordered blocks on top of the last commit, ordered by round.
+pub struct PendingBlocks {
+    blocks: BTreeMap<(u64, Round), PendingBlock>,
+    max_pending_blocks: usize,
+}
+
+impl PendingBlocks {
+    pub fn new(max_pending_blocks: usize) -> Self {
+        Self {
+            blocks: BTreeMap::new(),
+            max_pending_blocks,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&PendingBlock> {
+        self.blocks.values().next_back()
+    }
+
+    /// Id of the block the next ordered blocks must extend
+    pub fn last_block_id(&self, root: &LedgerInfoWithSignatures) -> HashValue {
+        self.last()
+            .map_or_else(|| root_block_id(root), |pending| pending.block.id())
+    }
+
+    /// (epoch, round) of the last ordered block, or of the root if there's none
+    pub fn last_round(&self, root: &LedgerInfoWithSignatures) -> (u64, Round) {
+        self.last().map_or(
+            (root.commit_info().epoch(), root.commit_info().round()),
+            |pending| (pending.block.epoch(), pending.block.round()),
+        )
+    }
+
+    /// Appends ordered blocks to the chain, the first one must extend the last pending block (or
+    /// the root if there's none).
+    pub fn extend(
+        &mut self,
+        root: &LedgerInfoWithSignatures,
+        blocks: Vec<PendingBlock>,
+    ) -> anyhow::Result<()> {
+        let first_block = match blocks.first() {
+            Some(pending) => &pending.block,
+            None => return Ok(()),
+        };
+        ensure!(
+            first_block.parent_id() == self.last_block_id(root),
+            "Ordered block {} doesn't extend the pending blocks",
+            first_block
+        );
+        ensure!(
+            self.blocks.len() + blocks.len() <= self.max_pending_blocks,
+            "Too many pending blocks: {}",
+            self.blocks.len()
+        );
+        for pending in blocks {
+            self.blocks
+                .insert((pending.block.epoch(), pending.block.round()), pending);
+        }
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self, block_id: HashValue) -> Option<&mut PendingBlock> {
+        self.blocks
+            .values_mut()
+            .find(|pending| pending.block.id() == block_id)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut PendingBlock> {
+        self.blocks.values_mut()
+    }
+
+    /// Removes and returns the blocks up to the given one, in order
+    pub fn take_up_to(&mut self, epoch: u64, round: Round) -> Vec<PendingBlock> {
+        let remaining = self.blocks.split_off(&(epoch, round + 1));
+        std::mem::replace(&mut self.blocks, remaining)
+            .into_values()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::network::{
+        ConsensusObserverMessage, ConsensusObserverMultiNetworkSender,
+        ConsensusObserverNetworkEvents, ObservedBlock, OrderedBlock,
+    },
+    counters,
+    state_replication::PayloadManager,
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use consensus_types::{block::Block, common::Payload};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    stream::select_all,
+    StreamExt,
+};
+use network::protocols::network::Event;
+use std::{collections::BTreeSet, sync::Arc};
+
+enum PublishRequest {
+    OrderedBlocks(
+        Vec<Block>,
+        LedgerInfoWithSignatures,
+        Arc<dyn PayloadManager>,
+    ),
+    Message(ConsensusObserverMessage),
+}
+
+/// Pushes the blocks ordered by consensus and their commit decisions to the subscribed
+/// observers. The messages are sent in order by a single task, so that the observers receive
+/// the ordered blocks before their commit decision.
+pub struct ConsensusPublisher {
+    subscribers: Arc<Mutex<BTreeSet<PeerNetworkId>>>,
+    publish_tx: UnboundedSender<PublishRequest>,
+    // resolves the transactions of the quorum store payloads, set at the start of each epoch
+    payload_manager: Mutex<Option<Arc<dyn PayloadManager>>>,
+}
+
+impl ConsensusPublisher {
+    /// Creates the publisher and spawns its publish task on the given runtime
+    pub fn new(
+        network_sender: ConsensusObserverMultiNetworkSender,
+        handle: &tokio::runtime::Handle,
+    ) -> Self {
+        let subscribers = Arc::new(Mutex::new(BTreeSet::new()));
+        let (publish_tx, publish_rx) = unbounded();
+        handle.spawn(Self::publish_task(
+            network_sender,
+            subscribers.clone(),
+            publish_rx,
+        ));
+        Self {
+            subscribers,
+            publish_tx,
+            payload_manager: Mutex::new(None),
+        }
+    }
+
+    /// Sets the payload manager of the new epoch
+    pub fn new_epoch(&self, payload_manager: Arc<dyn PayloadManager>) {
+        *self.payload_manager.lock() = Some(payload_manager);
+    }
+
+    /// The observers currently subscribed
+    pub fn subscribers(&self) -> Vec<PeerNetworkId> {
+        self.subscribers.lock().iter().copied().collect()
+    }
+
+    /// Publishes the blocks ordered by consensus, with the transactions of their batches.
+    pub fn publish_ordered_blocks(
+        &self,
+        blocks: Vec<Block>,
+        ordered_proof: LedgerInfoWithSignatures,
+    ) {
+        if self.subscribers.lock().is_empty() {
+            return;
+        }
+        let payload_manager = match self.payload_manager.lock().clone() {
+            Some(payload_manager) => payload_manager,
+            None => {
+                warn!("[ConsensusPublisher] not started for the epoch, dropping ordered blocks");
+                return;
+            }
+        };
+        self.send_request(PublishRequest::OrderedBlocks(
+            blocks,
+            ordered_proof,
+            payload_manager,
+        ));
+    }
+
+    /// Publishes the commit proof of the ordered blocks.
+    pub fn publish_commit_decision(&self, commit_proof: LedgerInfoWithSignatures) {
+        self.publish_message(ConsensusObserverMessage::CommitDecision(Box::new(
+            commit_proof,
+        )));
+    }
+
+    /// Publishes a message as is, used by the observers relaying what they observe.
+    pub fn publish_message(&self, message: ConsensusObserverMessage) {
+        if self.subscribers.lock().is_empty() {
+            return;
+        }
+        self.send_request(PublishRequest::Message(message));
+    }
+
+    fn send_request(&self, request: PublishRequest) {
+        if self.publish_tx.unbounded_send(request).is_err() {
+            error!("[ConsensusPublisher] publish task stopped");
+        }
+    }
+
+    /// Handles the subscription requests of the observers, returns false for the other messages.
+    pub fn process_subscription_message(
+        &self,
+        peer: PeerNetworkId,
+        message: &ConsensusObserverMessage,
+    ) -> bool {
+        let mut subscribers = self.subscribers.lock();
+        match message {
+            ConsensusObserverMessage::Subscribe => {
+                if subscribers.insert(peer) {
+                    info!(remote_peer = peer, "[ConsensusPublisher] new subscriber");
+                }
+            }
+            ConsensusObserverMessage::Unsubscribe => {
+                if subscribers.remove(&peer) {
+                    info!(remote_peer = peer, "[ConsensusPublisher] subscriber left");
+                }
+            }
+            _ => return false,
+        }
+        counters::CONSENSUS_PUBLISHER_SUBSCRIBERS.set(subscribers.len() as i64);
+        true
+    }
+
+    /// Drops the subscription of a disconnected observer
+    pub fn remove_subscriber(&self, peer: &PeerNetworkId) {
+        let mut subscribers = self.subscribers.lock();
+        if subscribers.remove(peer) {
+            info!(
+                remote_peer = *peer,
+                "[ConsensusPublisher] subscriber disconnected"
+            );
+        }
+        counters::CONSENSUS_PUBLISHER_SUBSCRIBERS.set(subscribers.len() as i64);
+    }
+
+    /// Serves the subscriptions when the node doesn't run an observer itself (e.g. validators).
+    pub async fn start(
+        self: Arc<Self>,
+        network_events: Vec<(NetworkId, ConsensusObserverNetworkEvents)>,
+    ) {
+        let mut events = select_all(
+            network_events
+                .into_iter()
+                .map(|(network_id, events)| events.map(move |event| (network_id, event))),
+        );
+        while let Some((network_id, event)) = events.next().await {
+            match event {
+                Event::Message(peer_id, message) => {
+                    let peer = PeerNetworkId::new(network_id, peer_id);
+                    if !self.process_subscription_message(peer, &message) {
+                        warn!(
+                            remote_peer = peer,
+                            "[ConsensusPublisher] unexpected {} message",
+                            message.name()
+                        );
+                    }
+                }
+                Event::LostPeer(metadata) => {
+                    self.remove_subscriber(&PeerNetworkId::new(
+                        network_id,
+                        metadata.remote_peer_id,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        info!("[ConsensusPublisher] network events stopped");
+    }
+
+    async fn publish_task(
+        network_sender: ConsensusObserverMultiNetworkSender,
+        subscribers: Arc<Mutex<BTreeSet<PeerNetworkId>>>,
+        mut publish_rx: UnboundedReceiver<PublishRequest>,
+    ) {
+        while let Some(request) = publish_rx.next().await {
+            let message = match request {
+                PublishRequest::OrderedBlocks(blocks, ordered_proof, payload_manager) => {
+                    match Self::observed_blocks(blocks, payload_manager.as_ref()).await {
+                        Ok(blocks) => ConsensusObserverMessage::OrderedBlock(Box::new(
+                            OrderedBlock::new(blocks, ordered_proof),
+                        )),
+                        Err(e) => {
+                            // the observers catch up with the commit decision
+                            warn!(error = ?e, "[ConsensusPublisher] failed to get the transactions of the ordered blocks");
+                            continue;
+                        }
+                    }
+                }
+                PublishRequest::Message(message) => message,
+            };
+            let recipients: Vec<_> = subscribers.lock().iter().copied().collect();
+            if recipients.is_empty() {
+                continue;
+            }
+            counters::CONSENSUS_PUBLISHER_MESSAGES
+                .with_label_values(&[message.name()])
+                .inc();
+            if let Err(e) = network_sender.send_to_many(recipients.into_iter(), message) {
+                warn!(error = ?e, "[ConsensusPublisher] failed to publish message");
+            }
+        }
+    }
+
+    async fn observed_blocks(
+        blocks: Vec<Block>,
+        payload_manager: &dyn PayloadManager,
+    ) -> anyhow::Result<Vec<ObservedBlock>> {
+        let mut observed_blocks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let batch_txns = match block.payload() {
+                Some(Payload::InQuorumStore(_)) => payload_manager.get_transactions(&block).await?,
+                _ => vec![],
+            };
+            observed_blocks.push(ObservedBlock::new(block, batch_txns));
+        }
+        Ok(observed_blocks)
+    }
+}
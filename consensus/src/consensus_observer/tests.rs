@@ -0,0 +1,421 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    network::{ConsensusObserverMultiNetworkSender, ObservedBlock, OrderedBlock},
+    observer::ConsensusObserver,
+    pending_blocks::{root_block_id, PendingBlock, PendingBlocks},
+};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    transaction::{SignedTransaction, Transaction},
+    validator_signer::ValidatorSigner,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
+};
+use async_trait::async_trait;
+use consensus_notifications::{ConsensusNotificationSender, Error};
+use consensus_types::{
+    batch::BatchPayload,
+    block::{
+        block_test_utils::{
+            certificate_for_genesis, placeholder_certificate_for_block, random_payload,
+        },
+        Block,
+    },
+    common::Payload,
+    proof_of_store::{LogicalTime, ProofOfStore, SignedDigestInfo},
+};
+use executor_types::{BlockExecutorTrait, StateComputeResult};
+use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::DbReader;
+
+fn random_txns(count: usize) -> Vec<SignedTransaction> {
+    match random_payload(count) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    }
+}
+
+/// Chain of blocks on top of the genesis block, with the given payloads
+fn create_chain(signer: &ValidatorSigner, payloads: Vec<Payload>) -> Vec<Block> {
+    let genesis = Block::make_genesis_block();
+    let mut blocks: Vec<Block> = vec![];
+    for (i, payload) in payloads.into_iter().enumerate() {
+        let quorum_cert = match blocks.last() {
+            None => certificate_for_genesis(),
+            Some(parent) => placeholder_certificate_for_block(
+                &[signer.clone()],
+                parent.id(),
+                parent.round(),
+                parent.parent_id(),
+                parent.round() - 1,
+            ),
+        };
+        let round = i as u64 + 1;
+        blocks.push(
+            Block::new_proposal(payload, round, round * 1000, quorum_cert, signer, vec![]).unwrap(),
+        );
+    }
+    assert_eq!(blocks[0].parent_id(), genesis.id());
+    blocks
+}
+
+fn ordered_proof(signers: &[ValidatorSigner], block: &Block) -> LedgerInfoWithSignatures {
+    generate_ledger_info_with_sig(
+        signers,
+        LedgerInfo::new(
+            block.gen_block_info(HashValue::zero(), 0, None),
+            HashValue::zero(),
+        ),
+    )
+}
+
+fn observed_blocks(blocks: &[Block]) -> Vec<ObservedBlock> {
+    blocks
+        .iter()
+        .map(|block| ObservedBlock::new(block.clone(), vec![]))
+        .collect()
+}
+
+fn genesis_root() -> LedgerInfoWithSignatures {
+    LedgerInfoWithSignatures::new(LedgerInfo::mock_genesis(None), AggregateSignature::empty())
+}
+
+fn pending_blocks(blocks: &[Block]) -> Vec<PendingBlock> {
+    blocks
+        .iter()
+        .map(|block| PendingBlock::new(block.clone(), vec![]))
+        .collect()
+}
+
+#[test]
+fn test_ordered_block_verify() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let blocks = create_chain(
+        &signers[0],
+        (0..3).map(|_| random_payload(2)).collect::<Vec<_>>(),
+    );
+
+    let ordered_block = OrderedBlock::new(
+        observed_blocks(&blocks),
+        ordered_proof(&signers, &blocks[2]),
+    );
+    ordered_block.verify(&verifier).unwrap();
+    assert_eq!(ordered_block.first_block(), &blocks[0]);
+    assert_eq!(ordered_block.last_block(), &blocks[2]);
+
+    // the proof must certify the last block
+    let ordered_block = OrderedBlock::new(
+        observed_blocks(&blocks),
+        ordered_proof(&signers, &blocks[1]),
+    );
+    assert!(ordered_block.verify(&verifier).is_err());
+
+    // the blocks must form a chain
+    let gap = vec![blocks[0].clone(), blocks[2].clone()];
+    let ordered_block =
+        OrderedBlock::new(observed_blocks(&gap), ordered_proof(&signers, &blocks[2]));
+    assert!(ordered_block.verify(&verifier).is_err());
+
+    // the proof must be signed by a quorum
+    let ordered_block = OrderedBlock::new(
+        observed_blocks(&blocks),
+        ordered_proof(&signers[..1], &blocks[2]),
+    );
+    assert!(ordered_block.verify(&verifier).is_err());
+
+    let ordered_block = OrderedBlock::new(vec![], ordered_proof(&signers, &blocks[2]));
+    assert!(ordered_block.verify(&verifier).is_err());
+}
+
+#[test]
+fn test_observed_block_user_txns() {
+    let (signers, _) = random_validator_verifier(1, None, false);
+    let payload = random_payload(3);
+    let block = create_chain(&signers[0], vec![payload.clone()]).remove(0);
+    let txns = match payload {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        ObservedBlock::new(block.clone(), vec![])
+            .user_txns()
+            .unwrap(),
+        txns
+    );
+    assert!(ObservedBlock::new(block, txns).user_txns().is_err());
+
+    let batches = vec![random_txns(2), random_txns(3)];
+    let proofs = batches
+        .iter()
+        .map(|txns| {
+            ProofOfStore::new(
+                SignedDigestInfo::new(
+                    BatchPayload::new(txns.clone()).hash(),
+                    LogicalTime::new(1, 100),
+                    txns.len() as u64,
+                    0,
+                ),
+                AggregateSignature::empty(),
+            )
+        })
+        .collect();
+    let block = create_chain(&signers[0], vec![Payload::InQuorumStore(proofs)]).remove(0);
+    let batch_txns: Vec<_> = batches.iter().flatten().cloned().collect();
+    assert_eq!(
+        ObservedBlock::new(block.clone(), batch_txns.clone())
+            .user_txns()
+            .unwrap(),
+        batch_txns
+    );
+
+    // missing, extra or swapped transactions don't match the digests
+    assert!(ObservedBlock::new(block.clone(), batch_txns[..4].to_vec())
+        .user_txns()
+        .is_err());
+    let mut extra = batch_txns.clone();
+    extra.extend(random_txns(1));
+    assert!(ObservedBlock::new(block.clone(), extra)
+        .user_txns()
+        .is_err());
+    let mut swapped = batch_txns;
+    swapped.swap(1, 2);
+    assert!(ObservedBlock::new(block, swapped).user_txns().is_err());
+}
+
+#[test]
+fn test_root_block_id() {
+    // the first blocks of an epoch extend its genesis block
+    let root = genesis_root();
+    assert!(root.ledger_info().ends_epoch());
+    assert_eq!(root_block_id(&root), Block::make_genesis_block().id());
+
+    let (signers, _) = random_validator_verifier(1, None, false);
+    let block = create_chain(&signers[0], vec![random_payload(1)]).remove(0);
+    let root = ordered_proof(&signers, &block);
+    assert_eq!(root_block_id(&root), block.id());
+}
+
+#[test]
+fn test_pending_blocks() {
+    let (signers, _) = random_validator_verifier(1, None, false);
+    let blocks = create_chain(
+        &signers[0],
+        (0..5).map(|_| random_payload(1)).collect::<Vec<_>>(),
+    );
+    let root = genesis_root();
+    let mut pending = PendingBlocks::new(4);
+    assert_eq!(
+        pending.last_block_id(&root),
+        Block::make_genesis_block().id()
+    );
+    assert_eq!(pending.last_round(&root), (root.commit_info().epoch(), 0));
+
+    // the blocks must extend the root, then the last pending block
+    assert!(pending
+        .extend(&root, pending_blocks(&blocks[1..2]))
+        .is_err());
+    pending.extend(&root, pending_blocks(&blocks[..2])).unwrap();
+    assert!(pending.extend(&root, pending_blocks(&blocks[..1])).is_err());
+    pending
+        .extend(&root, pending_blocks(&blocks[2..3]))
+        .unwrap();
+    assert_eq!(pending.len(), 3);
+    assert_eq!(pending.last_block_id(&root), blocks[2].id());
+    assert_eq!(pending.last_round(&root), (blocks[2].epoch(), 3));
+
+    // no more than max_pending_blocks
+    assert!(pending.extend(&root, pending_blocks(&blocks[3..])).is_err());
+    assert_eq!(pending.len(), 3);
+
+    assert!(pending.get_mut(blocks[1].id()).is_some());
+    assert!(pending.get_mut(blocks[4].id()).is_none());
+
+    let committed: Vec<_> = pending
+        .take_up_to(blocks[1].epoch(), blocks[1].round())
+        .into_iter()
+        .map(|pending| pending.block)
+        .collect();
+    assert_eq!(committed, blocks[..2].to_vec());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.last().unwrap().block, blocks[2]);
+
+    pending.clear();
+    assert!(pending.is_empty());
+}
+
+/// What the observer asked state sync to do
+#[derive(Debug, PartialEq)]
+enum StateSyncCall {
+    ObserverExecuting(bool),
+    SyncToTarget(u64),
+}
+
+#[derive(Default)]
+struct MockStateSyncNotifier {
+    calls: Mutex<Vec<StateSyncCall>>,
+}
+
+impl MockStateSyncNotifier {
+    fn take_calls(&self) -> Vec<StateSyncCall> {
+        std::mem::take(&mut *self.calls.lock())
+    }
+}
+
+#[async_trait]
+impl ConsensusNotificationSender for MockStateSyncNotifier {
+    async fn notify_new_commit(
+        &self,
+        _transactions: Vec<Transaction>,
+        _reconfiguration_events: Vec<ContractEvent>,
+    ) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    async fn sync_to_target(&self, target: LedgerInfoWithSignatures) -> Result<(), Error> {
+        self.calls
+            .lock()
+            .push(StateSyncCall::SyncToTarget(target.commit_info().round()));
+        Ok(())
+    }
+
+    async fn notify_observer_executing(&self, executing: bool) -> Result<(), Error> {
+        self.calls
+            .lock()
+            .push(StateSyncCall::ObserverExecuting(executing));
+        Ok(())
+    }
+}
+
+/// Storage at genesis, with the validators of the first epoch
+struct MockDbReader {
+    epoch_state: EpochState,
+}
+
+impl DbReader for MockDbReader {
+    fn get_latest_ledger_info_option(&self) -> anyhow::Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(genesis_root()))
+    }
+
+    fn get_latest_epoch_state(&self) -> anyhow::Result<EpochState> {
+        Ok(self.epoch_state.clone())
+    }
+}
+
+/// Executor of an observer that never gets the blocks, as there's no publisher
+struct MockBlockExecutor;
+
+impl BlockExecutorTrait for MockBlockExecutor {
+    fn committed_block_id(&self) -> HashValue {
+        Block::make_genesis_block().id()
+    }
+
+    fn reset(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn execute_block(
+        &self,
+        _block: (HashValue, Vec<Transaction>),
+        _parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, executor_types::Error> {
+        unreachable!()
+    }
+
+    fn commit_blocks_ext(
+        &self,
+        _block_ids: Vec<HashValue>,
+        _ledger_info_with_sigs: LedgerInfoWithSignatures,
+        _save_state_snapshots: bool,
+    ) -> Result<(), executor_types::Error> {
+        unreachable!()
+    }
+
+    fn finish(&self) {}
+}
+
+fn create_observer(
+    config: ConsensusObserverConfig,
+    epoch: u64,
+    verifier: ValidatorVerifier,
+    state_sync_notifier: Arc<MockStateSyncNotifier>,
+) -> ConsensusObserver {
+    let network_sender: ConsensusObserverMultiNetworkSender =
+        MultiNetworkSender::new(HashMap::new());
+    ConsensusObserver::new(
+        config,
+        network_sender,
+        vec![],
+        PeerMetadataStorage::new(&[]),
+        Arc::new(MockDbReader {
+            epoch_state: EpochState { epoch, verifier },
+        }),
+        Arc::new(MockBlockExecutor),
+        state_sync_notifier,
+        None,
+    )
+}
+
+#[tokio::test]
+async fn test_execution_hand_off() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let blocks = create_chain(
+        &signers[0],
+        (0..3).map(|_| random_payload(1)).collect::<Vec<_>>(),
+    );
+    let state_sync_notifier = Arc::new(MockStateSyncNotifier::default());
+    let mut observer = create_observer(
+        ConsensusObserverConfig {
+            observer_timeout_ms: 0,
+            ..ConsensusObserverConfig::default()
+        },
+        blocks[0].epoch(),
+        verifier,
+        state_sync_notifier.clone(),
+    );
+
+    // state sync stays in control until a commit decision arrives
+    observer.check_progress().await;
+    assert!(state_sync_notifier.take_calls().is_empty());
+
+    // the observer takes over before applying the commit decision
+    observer
+        .process_commit_decision(ordered_proof(&signers, &blocks[0]))
+        .await
+        .unwrap();
+    assert_eq!(
+        state_sync_notifier.take_calls(),
+        vec![
+            StateSyncCall::ObserverExecuting(true),
+            StateSyncCall::SyncToTarget(blocks[0].round()),
+        ]
+    );
+
+    // the observer hands back once idle, only once
+    observer.check_progress().await;
+    observer.check_progress().await;
+    assert_eq!(
+        state_sync_notifier.take_calls(),
+        vec![StateSyncCall::ObserverExecuting(false)]
+    );
+
+    // and takes over again with the next commit decision
+    observer
+        .process_commit_decision(ordered_proof(&signers, &blocks[2]))
+        .await
+        .unwrap();
+    assert_eq!(
+        state_sync_notifier.take_calls(),
+        vec![
+            StateSyncCall::ObserverExecuting(true),
+            StateSyncCall::SyncToTarget(blocks[2].round()),
+        ]
+    );
+}
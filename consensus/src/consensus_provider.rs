@@ -3,6 +3,11 @@
 
 use crate::{
     commit_notifier::QuorumStoreCommitNotifier,
+    consensus_observer::{
+        network::{ConsensusObserverMultiNetworkSender, ConsensusObserverNetworkEvents},
+        observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+    },
     counters,
     epoch_manager::EpochManager,
     network::NetworkTask,
@@ -12,7 +17,7 @@ use crate::{
     txn_notifier::MempoolNotifier,
     util::time_service::ClockTimeService,
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::{config::NodeConfig, network_id::NetworkId};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_vm::AptosVM;
@@ -38,6 +43,10 @@ pub fn start_consensus(
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    consensus_observer_network: Option<(
+        ConsensusObserverMultiNetworkSender,
+        Vec<(NetworkId, ConsensusObserverNetworkEvents)>,
+    )>,
 ) -> Runtime {
     let runtime = runtime::Builder::new_multi_thread()
         .thread_name_fn(|| {
@@ -58,12 +67,23 @@ pub fn start_consensus(
         node_config.consensus.quorum_store_pull_timeout_ms,
     ));
 
+    let consensus_publisher =
+        consensus_observer_network.map(|(observer_network_sender, observer_network_events)| {
+            let publisher = Arc::new(ConsensusPublisher::new(
+                observer_network_sender,
+                runtime.handle(),
+            ));
+            runtime.spawn(publisher.clone().start(observer_network_events));
+            publisher
+        });
+
     let state_computer = Arc::new(ExecutionProxy::new(
         Arc::new(BlockExecutor::<AptosVM>::new(aptos_db)),
         txn_notifier,
         state_sync_notifier,
         commit_notifier.clone(),
         runtime.handle(),
+        consensus_publisher.clone(),
     ));
 
     let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
@@ -83,6 +103,7 @@ pub fn start_consensus(
         storage,
         reconfig_events,
         commit_notifier,
+        consensus_publisher,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
@@ -93,3 +114,52 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer of a full node and return the runtime.
+/// The observer also publishes what it observes when the publisher is enabled.
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_sender: ConsensusObserverMultiNetworkSender,
+    network_events: Vec<(NetworkId, ConsensusObserverNetworkEvents)>,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    aptos_db: DbReaderWriter,
+) -> Runtime {
+    let runtime = runtime::Builder::new_multi_thread()
+        .thread_name_fn(|| {
+            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
+            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
+            format!("observer-{}", id)
+        })
+        .disable_lifo_slot()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime!");
+    let config = node_config.consensus_observer.clone();
+    let publisher = if config.publisher_enabled {
+        Some(Arc::new(ConsensusPublisher::new(
+            network_sender.clone(),
+            runtime.handle(),
+        )))
+    } else {
+        None
+    };
+    let network_ids = network_events
+        .iter()
+        .map(|(network_id, _)| *network_id)
+        .collect();
+    let observer = ConsensusObserver::new(
+        config,
+        network_sender,
+        network_ids,
+        peer_metadata_storage,
+        aptos_db.reader.clone(),
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db)),
+        state_sync_notifier,
+        publisher,
+    );
+    runtime.spawn(observer.start(network_events));
+
+    debug!("Consensus observer started.");
+    runtime
+}
@@ -470,6 +470,46 @@ pub static BLOCK_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counters(queued,dequeued,dropped) related to quorum store channel
+pub static QUORUM_STORE_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_channel_msgs_count",
+        "Counters(queued,dequeued,dropped) related to quorum store channel",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to batch retrieval channel
+pub static BATCH_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_batch_retrieval_channel_msgs_count",
+        "Counters(queued,dequeued,dropped) related to batch retrieval channel",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to quorum store task
+pub static QUORUM_STORE_TASK_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_task_msgs_count",
+        "Counters(queued,dequeued,dropped) related to quorum store task",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to batch retrieval task
+pub static BATCH_RETRIEVAL_TASK_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_batch_retrieval_task_msgs_count",
+        "Counters(queued,dequeued,dropped) related to batch retrieval task",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Counters(queued,dequeued,dropped) related to block retrieval task
 pub static BLOCK_RETRIEVAL_TASK_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -488,3 +528,69 @@ pub static BUFFER_MANAGER_RETRY_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Counters(queued,dequeued,dropped) related to pending network events to the consensus observer
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to pending network notifications to the consensus observer",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Number of observers subscribed to the consensus publisher
+pub static CONSENSUS_PUBLISHER_SUBSCRIBERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_publisher_subscribers",
+        "Number of observers subscribed to the consensus publisher"
+    )
+    .unwrap()
+});
+
+/// Counters for the messages published to the consensus observers broken down by type
+pub static CONSENSUS_PUBLISHER_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_publisher_msgs_count",
+        "Counters for the messages published to the consensus observers broken down by type",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Counters for the messages received by the consensus observer broken down by type
+pub static CONSENSUS_OBSERVER_RECEIVED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_received_msgs_count",
+        "Counters for the messages received by the consensus observer broken down by type",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Number of ordered blocks waiting for their commit decision in the consensus observer
+pub static CONSENSUS_OBSERVER_PENDING_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_pending_blocks",
+        "Number of ordered blocks waiting for their commit decision in the consensus observer"
+    )
+    .unwrap()
+});
+
+/// Count of the blocks committed by the consensus observer
+pub static CONSENSUS_OBSERVER_COMMITTED_BLOCKS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_committed_blocks_count",
+        "Count of the blocks committed by the consensus observer"
+    )
+    .unwrap()
+});
+
+/// Count of the commit decisions the consensus observer handed over to state sync
+pub static CONSENSUS_OBSERVER_SYNC_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_sync_count",
+        "Count of the commit decisions the consensus observer handed over to state sync"
+    )
+    .unwrap()
+});
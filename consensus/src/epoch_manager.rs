@@ -7,6 +7,7 @@ use crate::{
         BlockStore,
     },
     commit_notifier::CommitNotifier,
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::{error_kind, DbError},
     experimental::{
@@ -17,8 +18,7 @@ use crate::{
     liveness::{
         cached_proposer_election::CachedProposerElection,
         leader_reputation::{
-            extract_epoch_to_proposers, AptosDBBackend, LeaderReputation,
-            ProposerAndVoterHeuristic, ReputationHeuristic,
+            extract_epoch_to_proposers, AptosDBBackend, LeaderReputation, LeaderReputationParams,
         },
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
@@ -29,11 +29,19 @@ use crate::{
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
-    network::{IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender},
+    network::{
+        IncomingBatchRetrievalRequest, IncomingBlockRetrievalRequest, NetworkReceivers,
+        NetworkSender,
+    },
     network_interface::{ConsensusMsg, ConsensusNetworkSender},
     payload_manager::QuorumStoreClient,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
-    quorum_store::direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    quorum_store::{
+        batch_dissemination::{QuorumStore, QuorumStoreConfig},
+        batch_store::BatchStore,
+        direct_mempool_quorum_store::DirectMempoolQuorumStore,
+        quorum_store_db::QuorumStoreDB,
+    },
     recovery_manager::RecoveryManager,
     round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
     state_replication::StateComputer,
@@ -49,8 +57,7 @@ use aptos_types::{
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     on_chain_config::{
-        LeaderReputationType, OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType,
-        ValidatorSet,
+        OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType, ValidatorSet,
     },
     validator_verifier::ValidatorVerifier,
 };
@@ -58,6 +65,7 @@ use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
+    proof_of_store::LogicalTime,
     request_response::ConsensusRequest,
 };
 use event_notifications::ReconfigNotificationListener;
@@ -120,6 +128,16 @@ pub struct EpochManager {
     epoch_state: Option<EpochState>,
     block_retrieval_tx:
         Option<aptos_channel::Sender<AccountAddress, IncomingBlockRetrievalRequest>>,
+    // only opened when the quorum store is enabled
+    quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    // channels to quorum store
+    quorum_store_msg_tx:
+        Option<aptos_channel::Sender<AccountAddress, (AccountAddress, VerifiedEvent)>>,
+    batch_retrieval_tx:
+        Option<aptos_channel::Sender<AccountAddress, IncomingBatchRetrievalRequest>>,
+    quorum_store_close_tx: Option<oneshot::Sender<oneshot::Sender<()>>>,
+    // publishes the ordered blocks to the consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl EpochManager {
@@ -134,11 +152,17 @@ impl EpochManager {
         storage: Arc<dyn PersistentLivenessStorage>,
         reconfig_events: ReconfigNotificationListener,
         commit_notifier: Arc<dyn CommitNotifier>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
         let sr_config = &node_config.consensus.safety_rules;
         let safety_rules_manager = SafetyRulesManager::new(sr_config);
+        let quorum_store_db = if config.use_quorum_store {
+            Some(Arc::new(QuorumStoreDB::new(node_config.storage.dir())))
+        } else {
+            None
+        };
         Self {
             author,
             config,
@@ -158,6 +182,11 @@ impl EpochManager {
             round_manager_close_tx: None,
             epoch_state: None,
             block_retrieval_tx: None,
+            quorum_store_db,
+            quorum_store_msg_tx: None,
+            batch_retrieval_tx: None,
+            quorum_store_close_tx: None,
+            consensus_publisher,
         }
     }
 
@@ -204,35 +233,16 @@ impl EpochManager {
                 Box::new(RotatingProposer::new(vec![proposer], *contiguous_rounds))
             }
             ProposerElectionType::LeaderReputation(leader_reputation_type) => {
-                let (
+                let LeaderReputationParams {
                     heuristic,
                     window_size,
                     weight_by_voting_power,
                     use_history_from_previous_epoch_max_count,
-                ) = match &leader_reputation_type {
-                    LeaderReputationType::ProposerAndVoter(proposer_and_voter_config) => {
-                        let proposer_window_size = proposers.len()
-                            * proposer_and_voter_config.proposer_window_num_validators_multiplier;
-                        let voter_window_size = proposers.len()
-                            * proposer_and_voter_config.voter_window_num_validators_multiplier;
-                        let heuristic: Box<dyn ReputationHeuristic> =
-                            Box::new(ProposerAndVoterHeuristic::new(
-                                self.author,
-                                proposer_and_voter_config.active_weight,
-                                proposer_and_voter_config.inactive_weight,
-                                proposer_and_voter_config.failed_weight,
-                                proposer_and_voter_config.failure_threshold_percent,
-                                voter_window_size,
-                                proposer_window_size,
-                            ));
-                        (
-                            heuristic,
-                            std::cmp::max(proposer_window_size, voter_window_size),
-                            proposer_and_voter_config.weight_by_voting_power,
-                            proposer_and_voter_config.use_history_from_previous_epoch_max_count,
-                        )
-                    }
-                };
+                } = LeaderReputationParams::new(
+                    self.author,
+                    proposers.len(),
+                    leader_reputation_type,
+                );
 
                 let seek_len = onchain_config.leader_reputation_exclude_round() as usize
                     + onchain_config.max_failed_authors_to_store()
@@ -421,16 +431,77 @@ impl EpochManager {
         Ok(())
     }
 
+    /// Spawns the quorum store of the epoch, returns the batch store when batches are
+    /// disseminated (i.e. `use_quorum_store` is set) instead of pulled directly from mempool.
     fn spawn_quorum_store(
         &mut self,
         consensus_to_quorum_store_receiver: Receiver<ConsensusRequest>,
-    ) {
-        let quorum_store = DirectMempoolQuorumStore::new(
+        last_committed: LogicalTime,
+        network_sender: NetworkSender,
+        safety_rules_container: Arc<Mutex<MetricsSafetyRules>>,
+        verifier: ValidatorVerifier,
+    ) -> Option<Arc<BatchStore>> {
+        let quorum_store_db = match &self.quorum_store_db {
+            Some(quorum_store_db) => quorum_store_db.clone(),
+            None => {
+                let quorum_store = DirectMempoolQuorumStore::new(
+                    consensus_to_quorum_store_receiver,
+                    self.quorum_store_to_mempool_sender.clone(),
+                    self.config.mempool_txn_pull_timeout_ms,
+                );
+                spawn_named!("Quorum Store", quorum_store.start());
+                return None;
+            }
+        };
+
+        let batch_store = Arc::new(BatchStore::new(
+            last_committed.epoch(),
+            self.author,
+            quorum_store_db,
+            network_sender.clone(),
+            verifier.get_ordered_account_addresses_iter().collect(),
+            self.config.quorum_store_batch_request_timeout_ms,
+        ));
+        let config = QuorumStoreConfig {
+            max_batch_txns: self.config.quorum_store_max_batch_txns,
+            max_batch_bytes: self.config.quorum_store_max_batch_bytes,
+            batch_interval_ms: self.config.quorum_store_batch_interval_ms,
+            batch_expiry_rounds: self.config.quorum_store_batch_expiry_rounds,
+            max_bytes_per_author: self.config.quorum_store_max_bytes_per_author,
+            mempool_txn_pull_timeout_ms: self.config.mempool_txn_pull_timeout_ms,
+        };
+        let quorum_store = QuorumStore::new(
+            last_committed,
+            self.author,
+            config,
             consensus_to_quorum_store_receiver,
             self.quorum_store_to_mempool_sender.clone(),
-            self.config.mempool_txn_pull_timeout_ms,
+            network_sender,
+            safety_rules_container,
+            verifier,
+            batch_store.clone(),
+        );
+
+        let (quorum_store_msg_tx, quorum_store_msg_rx) =
+            aptos_channel::new::<AccountAddress, (AccountAddress, VerifiedEvent)>(
+                QueueStyle::FIFO,
+                self.config.channel_size,
+                Some(&counters::QUORUM_STORE_TASK_MSGS),
+            );
+        let (batch_retrieval_tx, batch_retrieval_rx) = aptos_channel::new(
+            QueueStyle::LIFO,
+            1,
+            Some(&counters::BATCH_RETRIEVAL_TASK_MSGS),
+        );
+        let (close_tx, close_rx) = oneshot::channel();
+        self.quorum_store_msg_tx = Some(quorum_store_msg_tx);
+        self.batch_retrieval_tx = Some(batch_retrieval_tx);
+        self.quorum_store_close_tx = Some(close_tx);
+        spawn_named!(
+            "Quorum Store",
+            quorum_store.start(quorum_store_msg_rx, batch_retrieval_rx, close_rx)
         );
-        spawn_named!("Quorum Store", quorum_store.start());
+        Some(batch_store)
     }
 
     fn spawn_block_retrieval_task(&mut self, epoch: u64, block_store: Arc<BlockStore>) {
@@ -472,11 +543,12 @@ impl EpochManager {
         let (block_tx, block_rx) = unbounded::<OrderedBlocks>();
         let (reset_tx, reset_rx) = unbounded::<ResetRequest>();
 
-        let (commit_msg_tx, commit_msg_rx) = aptos_channel::new::<AccountAddress, VerifiedEvent>(
-            QueueStyle::FIFO,
-            self.config.channel_size,
-            Some(&counters::BUFFER_MANAGER_MSGS),
-        );
+        let (commit_msg_tx, commit_msg_rx) =
+            aptos_channel::new::<AccountAddress, (AccountAddress, VerifiedEvent)>(
+                QueueStyle::FIFO,
+                self.config.channel_size,
+                Some(&counters::BUFFER_MANAGER_MSGS),
+            );
 
         self.buffer_manager_msg_tx = Some(commit_msg_tx);
         self.buffer_manager_reset_tx = Some(reset_tx.clone());
@@ -499,7 +571,12 @@ impl EpochManager {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        OrderingStateComputer::new(block_tx, self.commit_state_computer.clone(), reset_tx)
+        OrderingStateComputer::new(
+            block_tx,
+            self.commit_state_computer.clone(),
+            reset_tx,
+            self.consensus_publisher.clone(),
+        )
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -532,6 +609,19 @@ impl EpochManager {
 
         // Shutdown the block retrieval task by dropping the sender
         self.block_retrieval_tx = None;
+
+        // Shutdown the quorum store, to release the SafetyRule client
+        if let Some(close_tx) = self.quorum_store_close_tx.take() {
+            let (ack_tx, ack_rx) = oneshot::channel();
+            close_tx
+                .send(ack_tx)
+                .expect("[EpochManager] Fail to drop quorum store");
+            ack_rx
+                .await
+                .expect("[EpochManager] Fail to drop quorum store");
+        }
+        self.quorum_store_msg_tx = None;
+        self.batch_retrieval_tx = None;
     }
 
     async fn start_recovery_manager(
@@ -609,16 +699,24 @@ impl EpochManager {
 
         let (consensus_to_quorum_store_sender, consensus_to_quorum_store_receiver) =
             mpsc::channel(self.config.intra_consensus_channel_buffer_size);
-        self.spawn_quorum_store(consensus_to_quorum_store_receiver);
-        let payload_manager = QuorumStoreClient::new(
+        let batch_store = self.spawn_quorum_store(
+            consensus_to_quorum_store_receiver,
+            LogicalTime::new(epoch, recovery_data.root_block().round()),
+            network_sender.clone(),
+            safety_rules_container.clone(),
+            epoch_state.verifier.clone(),
+        );
+        let payload_manager = Arc::new(QuorumStoreClient::new(
             consensus_to_quorum_store_sender.clone(),
             self.config.quorum_store_poll_count,
             self.config.quorum_store_pull_timeout_ms,
-        );
+            batch_store,
+        ));
         self.commit_notifier
             .new_epoch(consensus_to_quorum_store_sender);
 
-        self.commit_state_computer.new_epoch(&epoch_state);
+        self.commit_state_computer
+            .new_epoch(&epoch_state, payload_manager.clone());
         let state_computer = if onchain_config.decoupled_execution() {
             Arc::new(self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
@@ -644,7 +742,7 @@ impl EpochManager {
         let proposal_generator = ProposalGenerator::new(
             self.author,
             block_store.clone(),
-            Arc::new(payload_manager),
+            payload_manager,
             self.time_service.clone(),
             self.config.max_sending_block_txns,
             self.config.max_sending_block_bytes,
@@ -768,7 +866,10 @@ impl EpochManager {
             | ConsensusMsg::SyncInfo(_)
             | ConsensusMsg::VoteMsg(_)
             | ConsensusMsg::CommitVoteMsg(_)
-            | ConsensusMsg::CommitDecisionMsg(_) => {
+            | ConsensusMsg::CommitDecisionMsg(_)
+            | ConsensusMsg::BatchMsg(_)
+            | ConsensusMsg::SignedDigestMsg(_)
+            | ConsensusMsg::ProofOfStoreMsg(_) => {
                 let event: UnverifiedEvent = msg.into();
                 if event.epoch() == self.epoch() {
                     return Ok(Some(event));
@@ -834,6 +935,15 @@ impl EpochManager {
                     bail!("Commit Phase not started but received Commit Message (CommitVote/CommitDecision)");
                 }
             }
+            quorum_store_event @ (VerifiedEvent::BatchMsg(_)
+            | VerifiedEvent::SignedDigestMsg(_)
+            | VerifiedEvent::ProofOfStoreMsg(_)) => {
+                if let Some(sender) = &mut self.quorum_store_msg_tx {
+                    sender.push(peer_id, (peer_id, quorum_store_event))?;
+                } else {
+                    bail!("QuorumStore not started but received QuorumStore Message (Batch/SignedDigest/ProofOfStore)");
+                }
+            }
             round_manager_event => {
                 self.forward_to_round_manager(peer_id, round_manager_event);
             }
@@ -866,6 +976,21 @@ impl EpochManager {
         }
     }
 
+    fn process_batch_retrieval(
+        &self,
+        peer_id: Author,
+        request: IncomingBatchRetrievalRequest,
+    ) -> anyhow::Result<()> {
+        fail_point!("consensus::process::any", |_| {
+            Err(anyhow::anyhow!("Injected error in process_batch_retrieval"))
+        });
+        if let Some(tx) = &self.batch_retrieval_tx {
+            tx.push(peer_id, request)
+        } else {
+            Err(anyhow::anyhow!("QuorumStore not started"))
+        }
+    }
+
     fn process_local_timeout(&mut self, round: u64) {
         self.forward_to_round_manager(self.author, VerifiedEvent::LocalTimeout(round));
     }
@@ -899,6 +1024,16 @@ impl EpochManager {
                         error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
                     }
                 },
+                (peer, msg) = network_receivers.quorum_store_messages.select_next_some() => {
+                    if let Err(e) = self.process_message(peer, msg).await {
+                        error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+                    }
+                },
+                (peer, request) = network_receivers.batch_retrieval.select_next_some() => {
+                    if let Err(e) = self.process_batch_retrieval(peer, request) {
+                        error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+                    }
+                },
                 round = round_timeout_sender_rx.select_next_some() => {
                     self.process_local_timeout(round);
                 },
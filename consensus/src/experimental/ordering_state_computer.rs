@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::publisher::ConsensusPublisher,
     error::StateSyncError,
     experimental::{
         buffer_manager::{OrderedBlocks, ResetAck, ResetRequest},
         errors::Error,
     },
-    state_replication::{StateComputer, StateComputerCommitCallBackType},
+    state_replication::{PayloadManager, StateComputer, StateComputerCommitCallBackType},
 };
 use anyhow::Result;
 use aptos_crypto::HashValue;
@@ -31,6 +32,8 @@ pub struct OrderingStateComputer {
     executor_channel: UnboundedSender<OrderedBlocks>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: UnboundedSender<ResetRequest>,
+    // publishes the ordered blocks to the consensus observers, ahead of their execution
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl OrderingStateComputer {
@@ -38,11 +41,13 @@ impl OrderingStateComputer {
         executor_channel: UnboundedSender<OrderedBlocks>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: UnboundedSender<ResetRequest>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            consensus_publisher,
         }
     }
 }
@@ -73,6 +78,13 @@ impl StateComputer for OrderingStateComputer {
     ) -> Result<(), ExecutionError> {
         assert!(!blocks.is_empty());
 
+        if let Some(publisher) = &self.consensus_publisher {
+            publisher.publish_ordered_blocks(
+                blocks.iter().map(|b| b.block().clone()).collect(),
+                finality_proof.clone(),
+            );
+        }
+
         if self
             .executor_channel
             .clone()
@@ -117,5 +129,5 @@ impl StateComputer for OrderingStateComputer {
         Ok(())
     }
 
-    fn new_epoch(&self, _: &EpochState) {}
+    fn new_epoch(&self, _: &EpochState, _: Arc<dyn PayloadManager>) {}
 }
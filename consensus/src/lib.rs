@@ -41,6 +41,8 @@ mod twins;
 mod txn_notifier;
 mod util;
 
+/// Consensus observer: follows consensus from the full nodes
+pub mod consensus_observer;
 /// AptosBFT implementation
 pub mod consensus_provider;
 /// Required by the telemetry service
@@ -50,6 +52,8 @@ pub mod network_interface;
 
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
+/// Required by the leader reputation simulation of the CLI
+pub use liveness::leader_reputation_simulator::{EpochSimulationResult, LeaderReputationSimulator};
 
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
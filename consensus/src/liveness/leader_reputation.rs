@@ -18,6 +18,7 @@ use aptos_types::{
     account_config::{new_block_event_key, NewBlockEvent},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
+    on_chain_config::LeaderReputationType,
 };
 use consensus_types::common::{Author, Round};
 use std::{
@@ -456,6 +457,239 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
     }
 }
 
+/// Heuristic with the same logic as ProposerAndVoterHeuristic, except that each block contributes
+/// to the statistics with a weight that decays exponentially with its age, i.e. the number of
+/// successful blocks committed after it. A block that is half_life blocks old counts half.
+///
+/// Compared to counting within a window, a node that failed recently is excluded faster, and a node
+/// that recovered is included again sooner, without reacting to every single failure.
+///
+/// The decay is computed in fixed point, so that all validators compute the same weights.
+pub struct RecencyWeightedHeuristic {
+    author: Author,
+    active_weight: u64,
+    inactive_weight: u64,
+    failed_weight: u64,
+    failure_threshold_percent: u32,
+    voter_window_size: usize,
+    proposer_window_size: usize,
+    half_life: usize,
+}
+
+/// Number of steps the decay is quantized to within a half life.
+const DECAY_STEPS_PER_HALF_LIFE: usize = 16;
+
+/// 2^(-step / DECAY_STEPS_PER_HALF_LIFE), as a fixed point number with 32 fractional bits.
+const DECAY_TABLE: [u64; DECAY_STEPS_PER_HALF_LIFE] = [
+    4294967296, 4112874773, 3938502376, 3771522796, 3611622603, 3458501653, 3311872529, 3171459999,
+    3037000500, 2908241642, 2784941738, 2666869345, 2553802834, 2445529972, 2341847524, 2242560872,
+];
+
+impl RecencyWeightedHeuristic {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        author: Author,
+        active_weight: u64,
+        inactive_weight: u64,
+        failed_weight: u64,
+        failure_threshold_percent: u32,
+        voter_window_size: usize,
+        proposer_window_size: usize,
+        half_life: usize,
+    ) -> Self {
+        Self {
+            author,
+            active_weight,
+            inactive_weight,
+            failed_weight,
+            failure_threshold_percent,
+            voter_window_size,
+            proposer_window_size,
+            half_life: std::cmp::max(half_life, 1),
+        }
+    }
+
+    /// Weight of a block of the given age, as a fixed point number with 32 fractional bits.
+    /// Halves every half_life blocks, in between it is rounded down to a step of the table.
+    fn decay(&self, age: usize) -> u64 {
+        let steps = age.saturating_mul(DECAY_STEPS_PER_HALF_LIFE) / self.half_life;
+        let halvings = u32::try_from(steps / DECAY_STEPS_PER_HALF_LIFE).unwrap_or(u32::MAX);
+        DECAY_TABLE[steps % DECAY_STEPS_PER_HALF_LIFE]
+            .checked_shr(halvings)
+            .unwrap_or(0)
+    }
+}
+
+impl ReputationHeuristic for RecencyWeightedHeuristic {
+    fn get_weights(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<u64> {
+        assert!(epoch_to_candidates.contains_key(&epoch));
+
+        // the age of a block is its position, latest first
+        let mut blocks: Vec<_> = history
+            .iter()
+            .filter(|meta| epoch_to_candidates.contains_key(&meta.epoch()))
+            .collect();
+        blocks.sort_by_key(|meta| std::cmp::Reverse((meta.epoch(), meta.round())));
+
+        let mut votes: HashMap<Author, u64> = HashMap::new();
+        let mut proposals: HashMap<Author, u64> = HashMap::new();
+        let mut failed_proposals: HashMap<Author, u64> = HashMap::new();
+        // undecayed counts for the metrics
+        let (mut own_votes, mut own_proposals, mut own_failed_proposals) = (0, 0, 0);
+        let mut num_rounds = 0;
+        for (age, meta) in blocks.into_iter().enumerate() {
+            let decay = self.decay(age);
+            let candidates = &epoch_to_candidates[&meta.epoch()];
+            if age < self.voter_window_size {
+                match NewBlockEventAggregation::bitvec_to_voters(
+                    candidates,
+                    &meta.previous_block_votes_bitvec().clone().into(),
+                ) {
+                    Ok(voters) => {
+                        for &voter in voters {
+                            *votes.entry(voter).or_insert(0) += decay;
+                            if voter == self.author {
+                                own_votes += 1;
+                            }
+                        }
+                    }
+                    Err(msg) => {
+                        error!(
+                            "Voter conversion from bitmap failed at epoch {}, round {}: {}",
+                            meta.epoch(),
+                            meta.round(),
+                            msg
+                        )
+                    }
+                }
+            }
+            if age < self.proposer_window_size {
+                *proposals.entry(meta.proposer()).or_insert(0) += decay;
+                num_rounds += 1;
+                if meta.proposer() == self.author {
+                    own_proposals += 1;
+                }
+                match NewBlockEventAggregation::indices_to_validators(
+                    candidates,
+                    meta.failed_proposer_indices(),
+                ) {
+                    Ok(failed_proposers) => {
+                        for &failed_proposer in failed_proposers {
+                            *failed_proposals.entry(failed_proposer).or_insert(0) += decay;
+                            num_rounds += 1;
+                            if failed_proposer == self.author {
+                                own_failed_proposals += 1;
+                            }
+                        }
+                    }
+                    Err(msg) => {
+                        error!(
+                            "Failed proposer conversion from indices failed at epoch {}, round {}: {}",
+                            meta.epoch(),
+                            meta.round(),
+                            msg
+                        )
+                    }
+                }
+            }
+        }
+
+        COMMITTED_PROPOSALS_IN_WINDOW.set(own_proposals);
+        FAILED_PROPOSALS_IN_WINDOW.set(own_failed_proposals);
+        COMMITTED_VOTES_IN_WINDOW.set(own_votes);
+        LEADER_REPUTATION_ROUND_HISTORY_SIZE.set(num_rounds);
+
+        epoch_to_candidates[&epoch]
+            .iter()
+            .map(|author| {
+                let cur_votes = *votes.get(author).unwrap_or(&0);
+                let cur_proposals = *proposals.get(author).unwrap_or(&0);
+                let cur_failed_proposals = *failed_proposals.get(author).unwrap_or(&0);
+
+                // u128, as the decayed counts are scaled by 2^32
+                if cur_failed_proposals as u128 * 100
+                    > (cur_proposals + cur_failed_proposals) as u128
+                        * self.failure_threshold_percent as u128
+                {
+                    self.failed_weight
+                } else if cur_proposals > 0 || cur_votes > 0 {
+                    self.active_weight
+                } else {
+                    self.inactive_weight
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parameters of the leader reputation election derived from its on-chain config.
+pub struct LeaderReputationParams {
+    pub heuristic: Box<dyn ReputationHeuristic>,
+    /// Number of blocks the heuristic looks at
+    pub window_size: usize,
+    pub weight_by_voting_power: bool,
+    pub use_history_from_previous_epoch_max_count: u32,
+}
+
+impl LeaderReputationParams {
+    pub fn new(
+        author: Author,
+        num_validators: usize,
+        leader_reputation_type: &LeaderReputationType,
+    ) -> Self {
+        match leader_reputation_type {
+            LeaderReputationType::ProposerAndVoter(config) => {
+                let proposer_window_size =
+                    num_validators * config.proposer_window_num_validators_multiplier;
+                let voter_window_size =
+                    num_validators * config.voter_window_num_validators_multiplier;
+                Self {
+                    heuristic: Box::new(ProposerAndVoterHeuristic::new(
+                        author,
+                        config.active_weight,
+                        config.inactive_weight,
+                        config.failed_weight,
+                        config.failure_threshold_percent,
+                        voter_window_size,
+                        proposer_window_size,
+                    )),
+                    window_size: std::cmp::max(proposer_window_size, voter_window_size),
+                    weight_by_voting_power: config.weight_by_voting_power,
+                    use_history_from_previous_epoch_max_count: config
+                        .use_history_from_previous_epoch_max_count,
+                }
+            }
+            LeaderReputationType::RecencyWeighted(config) => {
+                let proposer_window_size =
+                    num_validators * config.proposer_window_num_validators_multiplier;
+                let voter_window_size =
+                    num_validators * config.voter_window_num_validators_multiplier;
+                Self {
+                    heuristic: Box::new(RecencyWeightedHeuristic::new(
+                        author,
+                        config.active_weight,
+                        config.inactive_weight,
+                        config.failed_weight,
+                        config.failure_threshold_percent,
+                        voter_window_size,
+                        proposer_window_size,
+                        num_validators * config.half_life_num_validators_multiplier,
+                    )),
+                    window_size: std::cmp::max(proposer_window_size, voter_window_size),
+                    weight_by_voting_power: config.weight_by_voting_power,
+                    use_history_from_previous_epoch_max_count: config
+                        .use_history_from_previous_epoch_max_count,
+                }
+            }
+        }
+    }
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
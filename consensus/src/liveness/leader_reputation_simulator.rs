@@ -0,0 +1,370 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::{
+    leader_reputation::{
+        LeaderReputation, LeaderReputationParams, MetadataBackend, NewBlockEventAggregation,
+        ReputationHeuristic,
+    },
+    proposer_election::ProposerElection,
+    rotating_proposer_election::RotatingProposer,
+};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_config::{new_block_event_key, NewBlockEvent},
+    epoch_state::EpochState,
+    on_chain_config::{OnChainConsensusConfig, ProposerElectionType},
+};
+use consensus_types::common::{Author, Round};
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::{DbReader, Order};
+
+/// Serves the history of committed blocks as it was at any past round, so that proposer
+/// elections can be replayed. Like AptosDBBackend, the latest block comes first.
+pub struct ReplayBackend {
+    window_size: usize,
+    // ordered by (epoch, round)
+    history: Arc<Vec<NewBlockEvent>>,
+}
+
+impl ReplayBackend {
+    pub fn new(window_size: usize, history: Arc<Vec<NewBlockEvent>>) -> Self {
+        Self {
+            window_size,
+            history,
+        }
+    }
+}
+
+impl MetadataBackend for ReplayBackend {
+    fn get_block_metadata(&self, target_epoch: u64, target_round: Round) -> Vec<NewBlockEvent> {
+        let end = self.history.partition_point(|event| {
+            (event.epoch(), event.round()) <= (target_epoch, target_round)
+        });
+        self.history[end.saturating_sub(self.window_size)..end]
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Proposer failures of an epoch, as they happened and as estimated for the simulated election.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpochSimulationResult {
+    pub epoch: u64,
+    pub rounds: u64,
+    /// Rounds that didn't commit a block proposed by a validator
+    pub historical_failed_rounds: u64,
+    /// Expected number of rounds the simulated proposers fail, given their failure rate before
+    /// each round
+    pub simulated_failed_rounds: f64,
+}
+
+impl EpochSimulationResult {
+    pub fn historical_failure_rate(&self) -> f64 {
+        self.historical_failed_rounds as f64 / std::cmp::max(self.rounds, 1) as f64
+    }
+
+    pub fn simulated_failure_rate(&self) -> f64 {
+        self.simulated_failed_rounds / std::cmp::max(self.rounds, 1) as f64
+    }
+}
+
+/// Replays the committed blocks through a proposer election, to evaluate a change of the
+/// leader reputation config (or of its heuristic) before changing the on-chain config.
+///
+/// Which validators would have failed their rounds is unknown, so a simulated round fails with
+/// the rate at which its proposer failed the rounds it was elected for before that round.
+/// Validators that were never elected yet get the overall failure rate up to that round.
+pub struct LeaderReputationSimulator {
+    // validators of each epoch, in the validator set order, and their voting powers
+    epoch_to_validators: HashMap<u64, (Vec<Author>, Vec<u64>)>,
+    // ordered by (epoch, round)
+    history: Arc<Vec<NewBlockEvent>>,
+    // (epoch, round, proposer, succeeded) of every proposal of the history, ordered by
+    // (epoch, round)
+    proposals: Vec<(u64, Round, Author, bool)>,
+}
+
+impl LeaderReputationSimulator {
+    pub fn new(
+        epoch_to_validators: HashMap<u64, (Vec<Author>, Vec<u64>)>,
+        mut history: Vec<NewBlockEvent>,
+    ) -> Self {
+        history.sort_by_key(|event| (event.epoch(), event.round()));
+        let history = history
+            .into_iter()
+            .filter(|event| epoch_to_validators.contains_key(&event.epoch()))
+            .collect::<Vec<_>>();
+
+        let mut proposals = vec![];
+        for event in &history {
+            let (validators, _) = &epoch_to_validators[&event.epoch()];
+            // the failed rounds come before the round of the committed block
+            match NewBlockEventAggregation::indices_to_validators(
+                validators,
+                event.failed_proposer_indices(),
+            ) {
+                Ok(failed_proposers) => proposals.extend(
+                    failed_proposers
+                        .into_iter()
+                        .map(|author| (event.epoch(), event.round(), *author, false)),
+                ),
+                Err(msg) => error!(
+                    "Failed proposer conversion from indices failed at epoch {}, round {}: {}",
+                    event.epoch(),
+                    event.round(),
+                    msg
+                ),
+            }
+            // NIL blocks are committed without a proposer
+            if validators.contains(&event.proposer()) {
+                proposals.push((event.epoch(), event.round(), event.proposer(), true));
+            }
+        }
+
+        Self {
+            epoch_to_validators,
+            history: Arc::new(history),
+            proposals,
+        }
+    }
+
+    /// Reads the validator sets and the committed blocks of the given epochs from the DB.
+    pub fn from_db(db: &dyn DbReader, start_epoch: u64, end_epoch: u64) -> Result<Self> {
+        // First block (after genesis) is epoch=1
+        ensure!(
+            start_epoch >= 1 && start_epoch <= end_epoch,
+            "Invalid epoch range [{}, {}]",
+            start_epoch,
+            end_epoch
+        );
+
+        // the ledger info ending an epoch carries the validator set of the next one
+        let mut epoch_to_validators = HashMap::new();
+        let mut next_epoch = start_epoch - 1;
+        while next_epoch < end_epoch {
+            let proof = db.get_epoch_ending_ledger_infos(next_epoch, end_epoch)?;
+            ensure!(
+                !proof.ledger_info_with_sigs.is_empty(),
+                "No epoch ending ledger info for epoch {}",
+                next_epoch
+            );
+            for ledger_info in proof.ledger_info_with_sigs {
+                let epoch_state = ledger_info
+                    .ledger_info()
+                    .next_epoch_state()
+                    .ok_or_else(|| format_err!("Epoch ending ledger info without next epoch"))?;
+                epoch_to_validators.insert(epoch_state.epoch, validators_of(epoch_state));
+                next_epoch = epoch_state.epoch;
+            }
+        }
+
+        let history = fetch_history(db, start_epoch, end_epoch)?;
+        Ok(Self::new(epoch_to_validators, history))
+    }
+
+    /// Elects the proposer of every round of the epoch with the given election.
+    pub fn simulate_election(
+        &self,
+        epoch: u64,
+        proposer_election: &dyn ProposerElection,
+    ) -> Result<EpochSimulationResult> {
+        let (validators, _) = self
+            .epoch_to_validators
+            .get(&epoch)
+            .ok_or_else(|| format_err!("No validators for epoch {}", epoch))?;
+        let blocks: Vec<_> = self
+            .history
+            .iter()
+            .filter(|event| event.epoch() == epoch)
+            .collect();
+        let rounds = blocks
+            .last()
+            .map(|event| event.round())
+            .ok_or_else(|| format_err!("No committed blocks in epoch {}", epoch))?;
+        // NIL blocks are committed without a proposer
+        let successful_rounds = blocks
+            .iter()
+            .filter(|event| validators.contains(&event.proposer()))
+            .count() as u64;
+
+        // Counts of the successful and failed proposals of each validator, and of all of them,
+        // over the proposals before the simulated round only.
+        let mut counts: HashMap<Author, (u64, u64)> = HashMap::new();
+        let mut total_counts = (0, 0);
+        let mut next_proposal = 0;
+        let mut simulated_failed_rounds = 0.0;
+        for round in 1..=rounds {
+            while let Some(&(proposal_epoch, proposal_round, author, succeeded)) =
+                self.proposals.get(next_proposal)
+            {
+                if (proposal_epoch, proposal_round) >= (epoch, round) {
+                    break;
+                }
+                let count = counts.entry(author).or_insert((0, 0));
+                if succeeded {
+                    count.0 += 1;
+                    total_counts.0 += 1;
+                } else {
+                    count.1 += 1;
+                    total_counts.1 += 1;
+                }
+                next_proposal += 1;
+            }
+
+            let proposer = proposer_election.get_valid_proposer(round);
+            let (successes, failures) = counts.get(&proposer).unwrap_or(&total_counts);
+            simulated_failed_rounds +=
+                *failures as f64 / std::cmp::max(successes + failures, 1) as f64;
+        }
+
+        Ok(EpochSimulationResult {
+            epoch,
+            rounds,
+            historical_failed_rounds: rounds.saturating_sub(successful_rounds),
+            simulated_failed_rounds,
+        })
+    }
+
+    /// Replays the epoch through a leader reputation election with the given backend and
+    /// heuristic.
+    pub fn simulate_leader_reputation(
+        &self,
+        epoch: u64,
+        backend: Box<dyn MetadataBackend>,
+        heuristic: Box<dyn ReputationHeuristic>,
+        exclude_round: u64,
+        weight_by_voting_power: bool,
+        use_history_from_previous_epoch_max_count: u32,
+    ) -> Result<EpochSimulationResult> {
+        let (proposers, voting_powers) = self
+            .epoch_to_validators
+            .get(&epoch)
+            .ok_or_else(|| format_err!("No validators for epoch {}", epoch))?;
+        let first_epoch_to_consider = std::cmp::max(
+            1,
+            epoch.saturating_sub(use_history_from_previous_epoch_max_count as u64),
+        );
+        let epoch_to_proposers = (first_epoch_to_consider..=epoch)
+            .filter_map(|epoch| {
+                self.epoch_to_validators
+                    .get(&epoch)
+                    .map(|(validators, _)| (epoch, validators.clone()))
+            })
+            .collect();
+        let voting_powers = if weight_by_voting_power {
+            voting_powers.clone()
+        } else {
+            vec![1; proposers.len()]
+        };
+
+        let leader_reputation = LeaderReputation::new(
+            epoch,
+            epoch_to_proposers,
+            voting_powers,
+            backend,
+            heuristic,
+            // The block of a round is only committed after its election, so the election must
+            // not see it even when no round is excluded.
+            std::cmp::max(exclude_round, 1),
+        );
+        self.simulate_election(epoch, &leader_reputation)
+    }
+
+    /// Replays the epoch through the proposer election of the on-chain consensus config.
+    pub fn simulate_config(
+        &self,
+        epoch: u64,
+        config: &OnChainConsensusConfig,
+    ) -> Result<EpochSimulationResult> {
+        let (proposers, _) = self
+            .epoch_to_validators
+            .get(&epoch)
+            .ok_or_else(|| format_err!("No validators for epoch {}", epoch))?;
+        match config.proposer_election_type() {
+            ProposerElectionType::LeaderReputation(leader_reputation_type) => {
+                let LeaderReputationParams {
+                    heuristic,
+                    window_size,
+                    weight_by_voting_power,
+                    use_history_from_previous_epoch_max_count,
+                } = LeaderReputationParams::new(
+                    Author::ZERO,
+                    proposers.len(),
+                    leader_reputation_type,
+                );
+                self.simulate_leader_reputation(
+                    epoch,
+                    Box::new(ReplayBackend::new(window_size, self.history.clone())),
+                    heuristic,
+                    config.leader_reputation_exclude_round(),
+                    weight_by_voting_power,
+                    use_history_from_previous_epoch_max_count,
+                )
+            }
+            ProposerElectionType::RotatingProposer(contiguous_rounds) => self.simulate_election(
+                epoch,
+                &RotatingProposer::new(proposers.clone(), *contiguous_rounds),
+            ),
+            proposer_election_type => bail!(
+                "Simulation of {:?} is not supported",
+                proposer_election_type
+            ),
+        }
+    }
+}
+
+fn validators_of(epoch_state: &EpochState) -> (Vec<Author>, Vec<u64>) {
+    let validators: Vec<_> = epoch_state
+        .verifier
+        .get_ordered_account_addresses_iter()
+        .collect();
+    let voting_powers = validators
+        .iter()
+        .map(|validator| epoch_state.verifier.get_voting_power(validator).unwrap())
+        .collect();
+    (validators, voting_powers)
+}
+
+/// Reads the NewBlockEvents of the given epochs, latest first.
+fn fetch_history(
+    db: &dyn DbReader,
+    start_epoch: u64,
+    end_epoch: u64,
+) -> Result<Vec<NewBlockEvent>> {
+    const BATCH_SIZE: u64 = 1000;
+
+    let ledger_version = db.get_latest_version()?;
+    let mut cursor = u64::max_value();
+    let mut history = vec![];
+    loop {
+        let events = db.get_events(
+            &new_block_event_key(),
+            cursor,
+            Order::Descending,
+            BATCH_SIZE,
+            ledger_version,
+        )?;
+        let hit_end = (events.len() as u64) < BATCH_SIZE;
+        for event in events {
+            let sequence_number = event.event.sequence_number();
+            let new_block_event = bcs::from_bytes::<NewBlockEvent>(event.event.event_data())?;
+            if new_block_event.epoch() < start_epoch {
+                return Ok(history);
+            }
+            if new_block_event.epoch() <= end_epoch {
+                history.push(new_block_event);
+            }
+            if sequence_number == 0 {
+                return Ok(history);
+            }
+            cursor = sequence_number - 1;
+        }
+        if hit_end {
+            return Ok(history);
+        }
+    }
+}
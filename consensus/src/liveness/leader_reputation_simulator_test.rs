@@ -0,0 +1,141 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::{
+    leader_reputation::MetadataBackend,
+    leader_reputation_simulator::{LeaderReputationSimulator, ReplayBackend},
+    rotating_proposer_election::RotatingProposer,
+};
+use aptos_bitvec::BitVec;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    on_chain_config::{
+        ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerElectionType,
+        RecencyWeightedConfig,
+    },
+};
+use consensus_types::common::{Author, Round};
+use itertools::Itertools;
+use std::{collections::HashMap, sync::Arc};
+
+fn create_block(
+    epoch: u64,
+    round: Round,
+    proposer: Author,
+    failed_proposers: Vec<u64>,
+) -> NewBlockEvent {
+    NewBlockEvent::new(
+        AccountAddress::random(),
+        epoch,
+        round,
+        round,
+        BitVec::from(vec![true, true]).into(),
+        proposer,
+        failed_proposers,
+        round * 3600,
+    )
+}
+
+/// validators[0] proposes every other round, validators[1] fails all of its rounds
+fn create_history(validators: &[Author], epoch: u64, num_blocks: u64) -> Vec<NewBlockEvent> {
+    (1..=num_blocks)
+        .map(|i| create_block(epoch, 2 * i, validators[0], vec![1]))
+        .collect()
+}
+
+#[test]
+fn test_replay_backend() {
+    let validators: Vec<Author> = (0..2).map(|_| Author::random()).sorted().collect();
+    let history = Arc::new(create_history(&validators, 1, 5));
+    let backend = ReplayBackend::new(2, history.clone());
+
+    // latest first, up to the target round
+    assert_eq!(
+        backend.get_block_metadata(1, 7),
+        vec![history[2].clone(), history[1].clone()]
+    );
+    assert_eq!(backend.get_block_metadata(1, 2), vec![history[0].clone()]);
+    assert!(backend.get_block_metadata(1, 1).is_empty());
+    assert_eq!(
+        backend.get_block_metadata(2, 0),
+        vec![history[4].clone(), history[3].clone()]
+    );
+}
+
+#[test]
+fn test_simulate_election() {
+    let validators: Vec<Author> = (0..2).map(|_| Author::random()).sorted().collect();
+    let simulator = LeaderReputationSimulator::new(
+        HashMap::from([(1, (validators.clone(), vec![1, 1]))]),
+        create_history(&validators, 1, 10),
+    );
+
+    let result = simulator
+        .simulate_election(1, &RotatingProposer::new(validators.clone(), 1))
+        .unwrap();
+    assert_eq!(result.rounds, 20);
+    assert_eq!(result.historical_failed_rounds, 10);
+    // validators[1] is elected every other round, and always fails once its first failure, in
+    // round 1, is committed with the block of round 2
+    assert_eq!(result.simulated_failed_rounds, 9.0);
+    assert_eq!(result.historical_failure_rate(), 0.5);
+    assert_eq!(result.simulated_failure_rate(), 0.45);
+
+    assert!(simulator
+        .simulate_election(2, &RotatingProposer::new(validators, 1))
+        .is_err());
+}
+
+#[test]
+fn test_simulate_config() {
+    let validators: Vec<Author> = (0..2).map(|_| Author::random()).sorted().collect();
+    let simulator = LeaderReputationSimulator::new(
+        HashMap::from([(1, (validators.clone(), vec![1, 1]))]),
+        create_history(&validators, 1, 10),
+    );
+
+    let rotating_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+        proposer_election_type: ProposerElectionType::RotatingProposer(1),
+        ..ConsensusConfigV1::default()
+    });
+    assert_eq!(
+        simulator
+            .simulate_config(1, &rotating_config)
+            .unwrap()
+            .simulated_failed_rounds,
+        9.0
+    );
+
+    // once validators[1] failed, it is (almost) never elected again
+    let recency_weighted_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+        exclude_round: 0,
+        proposer_election_type: ProposerElectionType::LeaderReputation(
+            LeaderReputationType::RecencyWeighted(RecencyWeightedConfig {
+                active_weight: 1000,
+                inactive_weight: 10,
+                failed_weight: 1,
+                failure_threshold_percent: 10,
+                proposer_window_num_validators_multiplier: 10,
+                voter_window_num_validators_multiplier: 1,
+                half_life_num_validators_multiplier: 5,
+                weight_by_voting_power: true,
+                use_history_from_previous_epoch_max_count: 0,
+            }),
+        ),
+        ..ConsensusConfigV1::default()
+    });
+    let result = simulator
+        .simulate_config(1, &recency_weighted_config)
+        .unwrap();
+    assert_eq!(result.historical_failed_rounds, 10);
+    assert!(result.simulated_failed_rounds < 3.0);
+
+    let round_proposer_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+        proposer_election_type: ProposerElectionType::RoundProposer(HashMap::new()),
+        ..ConsensusConfigV1::default()
+    });
+    assert!(simulator
+        .simulate_config(1, &round_proposer_config)
+        .is_err());
+}
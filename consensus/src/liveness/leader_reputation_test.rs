@@ -3,6 +3,7 @@
 
 use super::leader_reputation::{
     extract_epoch_to_proposers_impl, AptosDBBackend, ProposerAndVoterHeuristic,
+    RecencyWeightedHeuristic,
 };
 use crate::liveness::{
     leader_reputation::{
@@ -348,6 +349,60 @@ fn test_proposer_and_voter_heuristic() {
     );
 }
 
+#[test]
+fn test_recency_weighted_heuristic() {
+    let validators: Vec<Author> = (0..2).map(|_| Author::random()).sorted().collect();
+    let epoch_to_validators = HashMap::from([(1u64, validators.clone())]);
+    let proposer_and_voter = ProposerAndVoterHeuristic::new(validators[0], 100, 10, 1, 10, 20, 20);
+    let recency_weighted = RecencyWeightedHeuristic::new(validators[0], 100, 10, 1, 10, 20, 20, 2);
+
+    let mut block_builder = TestBlockBuilder::new();
+    block_builder.new_epoch();
+    let mut history = vec![];
+    // validators[1] failed a few times, long ago
+    for _ in 0..3 {
+        history.push(block_builder.create_block(validators[0], vec![true, true], vec![1]));
+    }
+    for _ in 0..10 {
+        history.push(block_builder.create_block(validators[1], vec![true, true], vec![]));
+    }
+    assert_eq!(
+        proposer_and_voter.get_weights(1, &epoch_to_validators, &history),
+        vec![100, 1]
+    );
+    assert_eq!(
+        recency_weighted.get_weights(1, &epoch_to_validators, &history),
+        vec![100, 100]
+    );
+
+    // a recent failure counts more than the old ones
+    history.push(block_builder.create_block(validators[0], vec![true, true], vec![1]));
+    assert_eq!(
+        recency_weighted.get_weights(1, &epoch_to_validators, &history),
+        vec![100, 1]
+    );
+
+    // blocks outside of the windows are ignored
+    let recency_weighted_small_window =
+        RecencyWeightedHeuristic::new(validators[0], 100, 10, 1, 10, 1, 1, 2);
+    history.push(block_builder.create_block(validators[0], vec![false, false], vec![]));
+    assert_eq!(
+        recency_weighted_small_window.get_weights(1, &epoch_to_validators, &history),
+        vec![100, 10]
+    );
+
+    // blocks more than 64 half lives old don't count anymore
+    let recency_weighted_short_half_life =
+        RecencyWeightedHeuristic::new(validators[0], 100, 10, 1, 10, 200, 200, 1);
+    for _ in 0..70 {
+        history.push(block_builder.create_block(validators[1], vec![true, true], vec![]));
+    }
+    assert_eq!(
+        recency_weighted_short_half_life.get_weights(1, &epoch_to_validators, &history),
+        vec![100, 100]
+    );
+}
+
 /// #### LeaderReputation test ####
 
 #[test]
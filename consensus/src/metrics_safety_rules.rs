@@ -10,6 +10,7 @@ use aptos_types::{
 };
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::SignedDigestInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::VoteProposal,
@@ -130,6 +131,10 @@ impl TSafetyRules for MetricsSafetyRules {
             )
         })
     }
+
+    fn sign_digest(&mut self, info: &SignedDigestInfo) -> Result<bls12381::Signature, Error> {
+        self.retry(|inner| monitor!("safety_rules", inner.sign_digest(info)))
+    }
 }
 
 #[cfg(test)]
@@ -143,6 +148,7 @@ mod tests {
     use claims::{assert_matches, assert_ok};
     use consensus_types::{
         block_data::BlockData,
+        proof_of_store::SignedDigestInfo,
         timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
         vote::Vote,
         vote_proposal::VoteProposal,
@@ -219,6 +225,10 @@ mod tests {
         ) -> Result<bls12381::Signature, Error> {
             unimplemented!()
         }
+
+        fn sign_digest(&mut self, _: &SignedDigestInfo) -> Result<bls12381::Signature, Error> {
+            unimplemented!()
+        }
     }
 
     #[test]
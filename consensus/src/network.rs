@@ -17,9 +17,11 @@ use aptos_types::{
 use bytes::Bytes;
 use channel::{self, aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
+    batch::{Batch, BatchRequest},
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, MAX_BLOCKS_PER_REQUEST},
     common::Author,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -47,6 +49,19 @@ pub struct IncomingBlockRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
+/// Per peer capacity of the quorum store queue: unlike the other consensus messages, the quorum
+/// store messages of a peer can't replace each other (e.g. signatures of different batches).
+const QUORUM_STORE_CHANNEL_SIZE_PER_PEER: usize = 100;
+
+/// The batch retrieval request is used internally for implementing RPC: the callback is executed
+/// for carrying the response
+#[derive(Debug)]
+pub struct IncomingBatchRetrievalRequest {
+    pub req: BatchRequest,
+    pub protocol: ProtocolId,
+    pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
 /// Just a convenience struct to keep all the network proxy receiving queues in one place.
 /// Will be returned by the NetworkTask upon startup.
 pub struct NetworkReceivers {
@@ -57,6 +72,11 @@ pub struct NetworkReceivers {
     >,
     pub block_retrieval:
         aptos_channel::Receiver<AccountAddress, (AccountAddress, IncomingBlockRetrievalRequest)>,
+    /// Provide a FIFO buffer for each Author
+    pub quorum_store_messages:
+        aptos_channel::Receiver<AccountAddress, (AccountAddress, ConsensusMsg)>,
+    pub batch_retrieval:
+        aptos_channel::Receiver<AccountAddress, (AccountAddress, IncomingBatchRetrievalRequest)>,
 }
 
 /// Implements the actual networking support for all consensus messaging.
@@ -128,6 +148,45 @@ impl NetworkSender {
         Ok(response)
     }
 
+    /// Tries to retrieve the batch with the given digest from the given peer: the function
+    /// returns a future that is fulfilled with the batch.
+    pub async fn request_batch(
+        &self,
+        request: BatchRequest,
+        from: Author,
+        timeout: Duration,
+    ) -> anyhow::Result<Batch> {
+        fail_point!("consensus::send::any", |_| {
+            Err(anyhow::anyhow!("Injected error in request_batch"))
+        });
+        fail_point!("consensus::send::batch_request", |_| {
+            Err(anyhow::anyhow!("Injected error in request_batch"))
+        });
+
+        ensure!(from != self.author, "Retrieve batch from self");
+        let msg = ConsensusMsg::BatchRequestMsg(Box::new(request.clone()));
+        counters::CONSENSUS_SENT_MSGS
+            .with_label_values(&[msg.name()])
+            .inc();
+        let response_msg = monitor!(
+            "batch_request",
+            self.network_sender.send_rpc(from, msg, timeout).await
+        )?;
+        let batch = match response_msg {
+            ConsensusMsg::BatchMsg(batch) => *batch,
+            _ => return Err(anyhow!("Invalid response to request")),
+        };
+        ensure!(
+            batch.digest() == request.digest() && batch.epoch() == request.epoch(),
+            "Retrieved batch {} doesn't match {}",
+            batch,
+            request
+        );
+        batch.verify()?;
+
+        Ok(batch)
+    }
+
     /// Tries to send the given msg to all the participants.
     ///
     /// The future is fulfilled as soon as the message put into the mpsc channel to network
@@ -264,6 +323,24 @@ impl NetworkSender {
         self.author
     }
 
+    pub async fn broadcast_batch(&mut self, batch: Batch) {
+        fail_point!("consensus::send::broadcast_batch", |_| ());
+        let msg = ConsensusMsg::BatchMsg(Box::new(batch));
+        self.broadcast(msg).await
+    }
+
+    pub async fn send_signed_digest(&self, signed_digest: SignedDigest, recipient: Author) {
+        fail_point!("consensus::send::signed_digest", |_| ());
+        let msg = ConsensusMsg::SignedDigestMsg(Box::new(signed_digest));
+        self.send(msg, vec![recipient]).await
+    }
+
+    pub async fn broadcast_proof_of_store(&mut self, proof: ProofOfStore) {
+        fail_point!("consensus::send::broadcast_proof_of_store", |_| ());
+        let msg = ConsensusMsg::ProofOfStoreMsg(Box::new(proof));
+        self.broadcast(msg).await
+    }
+
     pub async fn broadcast_commit_proof(&mut self, ledger_info: LedgerInfoWithSignatures) {
         fail_point!("consensus::send::broadcast_commit_proof", |_| ());
         let msg = ConsensusMsg::CommitDecisionMsg(Box::new(CommitDecision::new(ledger_info)));
@@ -278,6 +355,9 @@ pub struct NetworkTask {
     >,
     block_retrieval_tx:
         aptos_channel::Sender<AccountAddress, (AccountAddress, IncomingBlockRetrievalRequest)>,
+    quorum_store_messages_tx: aptos_channel::Sender<AccountAddress, (AccountAddress, ConsensusMsg)>,
+    batch_retrieval_tx:
+        aptos_channel::Sender<AccountAddress, (AccountAddress, IncomingBatchRetrievalRequest)>,
     all_events: Box<dyn Stream<Item = Event<ConsensusMsg>> + Send + Unpin>,
 }
 
@@ -294,16 +374,30 @@ impl NetworkTask {
             1,
             Some(&counters::BLOCK_RETRIEVAL_CHANNEL_MSGS),
         );
+        let (quorum_store_messages_tx, quorum_store_messages) = aptos_channel::new(
+            QueueStyle::FIFO,
+            QUORUM_STORE_CHANNEL_SIZE_PER_PEER,
+            Some(&counters::QUORUM_STORE_CHANNEL_MSGS),
+        );
+        let (batch_retrieval_tx, batch_retrieval) = aptos_channel::new(
+            QueueStyle::LIFO,
+            1,
+            Some(&counters::BATCH_RETRIEVAL_CHANNEL_MSGS),
+        );
         let all_events = Box::new(select(network_events, self_receiver));
         (
             NetworkTask {
                 consensus_messages_tx,
                 block_retrieval_tx,
+                quorum_store_messages_tx,
+                batch_retrieval_tx,
                 all_events,
             },
             NetworkReceivers {
                 consensus_messages,
                 block_retrieval,
+                quorum_store_messages,
+                batch_retrieval,
             },
         )
     }
@@ -321,10 +415,17 @@ impl NetworkTask {
                             BlockStage::NETWORK_RECEIVED,
                         );
                     }
-                    if let Err(e) = self
-                        .consensus_messages_tx
-                        .push((peer_id, discriminant(&msg)), (peer_id, msg))
-                    {
+                    let result = match msg {
+                        quorum_store_msg @ (ConsensusMsg::BatchMsg(_)
+                        | ConsensusMsg::SignedDigestMsg(_)
+                        | ConsensusMsg::ProofOfStoreMsg(_)) => self
+                            .quorum_store_messages_tx
+                            .push(peer_id, (peer_id, quorum_store_msg)),
+                        msg => self
+                            .consensus_messages_tx
+                            .push((peer_id, discriminant(&msg)), (peer_id, msg)),
+                    };
+                    if let Err(e) = result {
                         warn!(
                             remote_peer = peer_id,
                             error = ?e, "Error pushing consensus msg",
@@ -362,6 +463,23 @@ impl NetworkTask {
                             warn!(error = ?e, "aptos channel closed");
                         }
                     }
+                    ConsensusMsg::BatchRequestMsg(request) => {
+                        counters::CONSENSUS_RECEIVED_MSGS
+                            .with_label_values(&["BatchRequestMsg"])
+                            .inc();
+                        debug!(remote_peer = peer_id, "{}", request);
+                        let req_with_callback = IncomingBatchRetrievalRequest {
+                            req: *request,
+                            protocol,
+                            response_sender: callback,
+                        };
+                        if let Err(e) = self
+                            .batch_retrieval_tx
+                            .push(peer_id, (peer_id, req_with_callback))
+                        {
+                            warn!(error = ?e, "aptos channel closed");
+                        }
+                    }
                     _ => {
                         warn!(remote_peer = peer_id, "Unexpected msg: {:?}", msg);
                         continue;
@@ -11,9 +11,11 @@ use aptos_types::{epoch_change::EpochChangeProof, PeerId};
 use async_trait::async_trait;
 use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
+    batch::{Batch, BatchRequest},
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse},
     epoch_retrieval::EpochRetrievalRequest,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -62,6 +64,16 @@ pub enum ConsensusMsg {
     /// than 2f + 1 signatures on the commit proposal. This part is not on the critical path, but
     /// it can save slow machines to quickly confirm the execution result.
     CommitDecisionMsg(Box<CommitDecision>),
+    /// Batch of transactions broadcast by the quorum store of its author, it is also the response
+    /// to a BatchRequest.
+    BatchMsg(Box<Batch>),
+    /// RPC to get a batch from one of the validators that signed its proof of store.
+    BatchRequestMsg(Box<BatchRequest>),
+    /// SignedDigest is sent back to the batch author by the validators that persisted the batch.
+    SignedDigestMsg(Box<SignedDigest>),
+    /// ProofOfStore is broadcast by the batch author once a quorum of validators persisted the
+    /// batch, so that any proposer can include it.
+    ProofOfStoreMsg(Box<ProofOfStore>),
 }
 
 /// Network type for consensus
@@ -79,6 +91,10 @@ impl ConsensusMsg {
             ConsensusMsg::VoteMsg(_) => "VoteMsg",
             ConsensusMsg::CommitVoteMsg(_) => "CommitVoteMsg",
             ConsensusMsg::CommitDecisionMsg(_) => "CommitDecisionMsg",
+            ConsensusMsg::BatchMsg(_) => "BatchMsg",
+            ConsensusMsg::BatchRequestMsg(_) => "BatchRequestMsg",
+            ConsensusMsg::SignedDigestMsg(_) => "SignedDigestMsg",
+            ConsensusMsg::ProofOfStoreMsg(_) => "ProofOfStoreMsg",
         }
     }
 }
@@ -298,7 +298,7 @@ impl NetworkPlayground {
     }
 
     /// Return the round of a given message
-    fn get_message_round(msg: ConsensusMsg) -> Option<u64> {
+    pub fn get_message_round(msg: ConsensusMsg) -> Option<u64> {
         match msg {
             ConsensusMsg::ProposalMsg(proposal_msg) => Some(proposal_msg.proposal().round()),
             ConsensusMsg::VoteMsg(vote_msg) => Some(vote_msg.vote().vote_data().proposed().round()),
@@ -486,8 +486,10 @@ mod tests {
     use aptos_types::validator_verifier::random_validator_verifier;
     use bytes::Bytes;
     use consensus_types::{
+        batch::{Batch, BatchPayload, BatchRequest},
         block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, BlockRetrievalStatus},
         common::Payload,
+        proof_of_store::LogicalTime,
     };
     use futures::{channel::oneshot, future};
     use network::{
@@ -759,6 +761,117 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_quorum_store_messages() {
+        let mut runtime = consensus_runtime();
+        let mut receivers: Vec<NetworkReceivers> = Vec::new();
+        let mut playground = NetworkPlayground::new(runtime.handle().clone());
+        let mut nodes = Vec::new();
+        let (signers, validator_verifier) = random_validator_verifier(2, None, false);
+        let peers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+        let peer_metadata_storage = PeerMetadataStorage::new(&[NetworkId::Validator]);
+
+        for (peer_id, peer) in peers.iter().enumerate() {
+            let (network_reqs_tx, network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+            let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+            let (consensus_tx, consensus_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+            let (_conn_mgr_reqs_tx, conn_mgr_reqs_rx) = channel::new_test(8);
+            let (_, conn_status_rx) = conn_notifs_channel::new();
+            let mut network_sender = ConsensusNetworkSender::new(
+                PeerManagerRequestSender::new(network_reqs_tx),
+                ConnectionRequestSender::new(connection_reqs_tx),
+            );
+            add_peer_to_storage(
+                &peer_metadata_storage,
+                peer,
+                &[
+                    ProtocolId::ConsensusDirectSendJson,
+                    ProtocolId::ConsensusDirectSendBcs,
+                    ProtocolId::ConsensusRpcJson,
+                ],
+            );
+            network_sender.initialize(peer_metadata_storage.clone());
+            let network_events = ConsensusNetworkEvents::new(consensus_rx, conn_status_rx);
+
+            let twin_id = TwinId {
+                id: peer_id,
+                author: *peer,
+            };
+
+            playground.add_node(twin_id, consensus_tx, network_reqs_rx, conn_mgr_reqs_rx);
+
+            let (self_sender, self_receiver) = channel::new_test(8);
+            let node = NetworkSender::new(
+                *peer,
+                network_sender,
+                self_sender,
+                validator_verifier.clone(),
+            );
+            let (task, receiver) = NetworkTask::new(network_events, self_receiver);
+            receivers.push(receiver);
+            runtime.handle().spawn(task.start());
+            nodes.push(node);
+        }
+        let mut receiver_1 = receivers.remove(1);
+        let mut receiver_0 = receivers.remove(0);
+        let batch = Batch::new(
+            peers[0],
+            0,
+            LogicalTime::new(1, 10),
+            BatchPayload::new(vec![]),
+        );
+
+        // node 0 serves its batch to the batch requests
+        let mut batch_retrieval = receiver_0.batch_retrieval;
+        let served_batch = batch.clone();
+        let on_request_batch = async move {
+            while let Some((_, request)) = batch_retrieval.next().await {
+                let response = ConsensusMsg::BatchMsg(Box::new(served_batch.clone()));
+                let bytes = Bytes::from(serde_json::to_vec(&response).unwrap());
+                request.response_sender.send(Ok(bytes)).unwrap();
+            }
+        };
+        runtime.handle().spawn(on_request_batch);
+
+        timed_block_on(&mut runtime, async {
+            // the batch is broadcast to the author as well, so that it persists and signs it
+            nodes[0].broadcast_batch(batch.clone()).await;
+            playground
+                .wait_for_messages(1, NetworkPlayground::take_all)
+                .await;
+            for receiver in [
+                &mut receiver_0.quorum_store_messages,
+                &mut receiver_1.quorum_store_messages,
+            ] {
+                let (author, msg) = receiver.next().await.unwrap();
+                assert_eq!(author, peers[0]);
+                match msg {
+                    ConsensusMsg::BatchMsg(b) => assert_eq!(*b, batch),
+                    _ => panic!("unexpected messages"),
+                }
+            }
+
+            let fetched_batch = nodes[1]
+                .request_batch(
+                    BatchRequest::new(1, *batch.digest()),
+                    peers[0],
+                    Duration::from_secs(5),
+                )
+                .await
+                .unwrap();
+            assert_eq!(fetched_batch, batch);
+            // a batch that doesn't match the requested digest is rejected
+            assert!(nodes[1]
+                .request_batch(
+                    BatchRequest::new(1, HashValue::random()),
+                    peers[0],
+                    Duration::from_secs(5),
+                )
+                .await
+                .is_err());
+        });
+    }
+
     #[test]
     fn test_bad_message() {
         let (peer_mgr_notifs_tx, peer_mgr_notifs_rx) =
@@ -1,19 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::QuorumStoreError, monitor, state_replication::PayloadManager};
+use crate::{
+    error::QuorumStoreError, monitor, quorum_store::batch_store::BatchStore,
+    state_replication::PayloadManager,
+};
 use anyhow::Result;
 use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
 use consensus_types::{
+    block::Block,
     common::{Payload, PayloadFilter},
     request_response::{ConsensusRequest, ConsensusResponse},
 };
+use executor_types::Error as ExecutionError;
 use fail::fail_point;
 use futures::{
     channel::{mpsc, oneshot},
     future::BoxFuture,
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::time::{sleep, timeout};
 
 const NO_TXN_DELAY: u64 = 30;
@@ -25,6 +31,8 @@ pub struct QuorumStoreClient {
     poll_count: u64,
     /// Timeout for consensus to pull transactions from quorum store and get a response (in milliseconds)
     pull_timeout_ms: u64,
+    /// Holds the batches referenced by the proofs of store, None without a quorum store
+    batch_store: Option<Arc<BatchStore>>,
 }
 
 impl QuorumStoreClient {
@@ -32,6 +40,7 @@ impl QuorumStoreClient {
         consensus_to_quorum_store_sender: mpsc::Sender<ConsensusRequest>,
         poll_count: u64,
         pull_timeout_ms: u64,
+        batch_store: Option<Arc<BatchStore>>,
     ) -> Self {
         assert!(
             poll_count > 0,
@@ -41,6 +50,7 @@ impl QuorumStoreClient {
             consensus_to_quorum_store_sender,
             poll_count,
             pull_timeout_ms,
+            batch_store,
         }
     }
 
@@ -116,4 +126,27 @@ impl PayloadManager for QuorumStoreClient {
         );
         Ok(payload)
     }
+
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, ExecutionError> {
+        match block.payload() {
+            None => Ok(vec![]),
+            Some(Payload::DirectMempool(txns)) => Ok(txns.clone()),
+            Some(Payload::InQuorumStore(proofs)) => {
+                let batch_store = self.batch_store.as_ref().ok_or_else(|| {
+                    anyhow::anyhow!("[consensus] received proofs of store without quorum store")
+                })?;
+                let mut txns = vec![];
+                for proof in proofs {
+                    txns.extend(monitor!(
+                        "get_batch",
+                        batch_store.get_or_fetch(proof).await
+                    )?);
+                }
+                Ok(txns)
+            }
+        }
+    }
 }
@@ -0,0 +1,485 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics_safety_rules::MetricsSafetyRules,
+    monitor,
+    network::{IncomingBatchRetrievalRequest, NetworkSender},
+    quorum_store::{batch_store::BatchStore, counters},
+    round_manager::VerifiedEvent,
+};
+use anyhow::{bail, ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_mempool::{QuorumStoreRequest, QuorumStoreResponse};
+use aptos_types::{
+    account_address::AccountAddress, aggregate_signature::PartialSignatures,
+    transaction::SignedTransaction, validator_verifier::ValidatorVerifier,
+};
+use channel::aptos_channel;
+use consensus_types::{
+    batch::{Batch, BatchPayload},
+    common::{Author, Payload, PayloadFilter, Round, TransactionSummary},
+    proof_of_store::{LogicalTime, ProofOfStore, SignedDigest, SignedDigestInfo},
+    request_response::{ConsensusRequest, ConsensusResponse},
+};
+use futures::{
+    channel::{
+        mpsc::{Receiver, Sender},
+        oneshot,
+    },
+    FutureExt, StreamExt,
+};
+use safety_rules::TSafetyRules;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::{interval, timeout};
+
+/// Batch parameters of the quorum store, taken from the consensus config.
+#[derive(Clone, Debug)]
+pub struct QuorumStoreConfig {
+    pub max_batch_txns: u64,
+    pub max_batch_bytes: u64,
+    pub batch_interval_ms: u64,
+    pub batch_expiry_rounds: Round,
+    pub max_bytes_per_author: u64,
+    pub mempool_txn_pull_timeout_ms: u64,
+}
+
+/// Disseminates the batches pulled from the local mempool and collects the proofs of store of
+/// the batches of all the validators, which consensus proposes instead of the transactions.
+///
+/// A batch is broadcast to all the validators (including its author), each of them persists it
+/// and replies with a signature of its digest. Once the author has a quorum of signatures it
+/// aggregates them into a proof of store and broadcasts the proof.
+pub struct QuorumStore {
+    epoch: u64,
+    author: Author,
+    config: QuorumStoreConfig,
+    consensus_receiver: Receiver<ConsensusRequest>,
+    mempool_sender: Sender<QuorumStoreRequest>,
+    network_sender: NetworkSender,
+    safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+    verifier: ValidatorVerifier,
+    batch_store: Arc<BatchStore>,
+    next_batch_id: u64,
+    // txns of the own batches that didn't expire yet, they are excluded from the next batches
+    batched_txns: HashMap<HashValue, (LogicalTime, Vec<TransactionSummary>)>,
+    // signatures collected for the own batches that don't have a proof of store yet
+    partial_proofs: HashMap<HashValue, (SignedDigestInfo, PartialSignatures)>,
+    // proofs of store that are not committed nor expired yet
+    proofs: HashMap<HashValue, ProofOfStore>,
+    // expiration and size of the persisted batches of all the authors that didn't expire yet
+    persisted_batches: HashMap<(Author, HashValue), (LogicalTime, u64)>,
+    // total size of the persisted batches of each author, bounded by `max_bytes_per_author`
+    author_bytes: HashMap<Author, u64>,
+    last_committed: LogicalTime,
+}
+
+impl QuorumStore {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        // the logical time of the committed root the epoch starts from
+        last_committed: LogicalTime,
+        author: Author,
+        config: QuorumStoreConfig,
+        consensus_receiver: Receiver<ConsensusRequest>,
+        mempool_sender: Sender<QuorumStoreRequest>,
+        network_sender: NetworkSender,
+        safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+        verifier: ValidatorVerifier,
+        batch_store: Arc<BatchStore>,
+    ) -> Self {
+        Self {
+            epoch: last_committed.epoch(),
+            author,
+            config,
+            consensus_receiver,
+            mempool_sender,
+            network_sender,
+            safety_rules,
+            verifier,
+            batch_store,
+            next_batch_id: 0,
+            batched_txns: HashMap::new(),
+            partial_proofs: HashMap::new(),
+            proofs: HashMap::new(),
+            persisted_batches: HashMap::new(),
+            author_bytes: HashMap::new(),
+            last_committed,
+        }
+    }
+
+    async fn pull_from_mempool(
+        &self,
+        max_items: u64,
+        max_bytes: u64,
+        exclude_txns: Vec<TransactionSummary>,
+    ) -> Result<Vec<SignedTransaction>> {
+        let (callback, callback_rcv) = oneshot::channel();
+        let msg = QuorumStoreRequest::GetBatchRequest(max_items, max_bytes, exclude_txns, callback);
+        self.mempool_sender
+            .clone()
+            .try_send(msg)
+            .map_err(anyhow::Error::from)?;
+        // wait for response
+        match monitor!(
+            "pull_txn",
+            timeout(
+                Duration::from_millis(self.config.mempool_txn_pull_timeout_ms),
+                callback_rcv
+            )
+            .await
+        ) {
+            Err(_) => bail!("[quorum_store] did not receive GetBatchResponse on time"),
+            Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
+                QuorumStoreResponse::GetBatchResponse(txns) => Ok(txns),
+                _ => bail!("[quorum_store] did not receive expected GetBatchResponse"),
+            },
+        }
+    }
+
+    /// Pulls a batch of the txns that are not in the own pending batches and broadcasts it.
+    async fn create_batch(&mut self) {
+        let exclude_txns = self
+            .batched_txns
+            .values()
+            .flat_map(|(_, txns)| txns.iter().cloned())
+            .collect();
+        let txns = match self
+            .pull_from_mempool(
+                self.config.max_batch_txns,
+                self.config.max_batch_bytes,
+                exclude_txns,
+            )
+            .await
+        {
+            Ok(txns) => txns,
+            Err(e) => {
+                warn!(error = ?e, "Failed to pull batch from mempool");
+                return;
+            }
+        };
+        if txns.is_empty() {
+            return;
+        }
+
+        let expiration = LogicalTime::new(
+            self.epoch,
+            self.last_committed.round() + self.config.batch_expiry_rounds,
+        );
+        let summaries = txns
+            .iter()
+            .map(|txn| TransactionSummary {
+                sender: txn.sender(),
+                sequence_number: txn.sequence_number(),
+            })
+            .collect();
+        let batch = Batch::new(
+            self.author,
+            self.next_batch_id,
+            expiration,
+            BatchPayload::new(txns),
+        );
+        self.next_batch_id += 1;
+        self.batched_txns
+            .insert(*batch.digest(), (expiration, summaries));
+        self.partial_proofs
+            .insert(*batch.digest(), (batch.info(), PartialSignatures::empty()));
+        counters::CREATED_BATCH_COUNT.inc();
+        debug!("[quorum_store] broadcast {}", batch);
+        self.network_sender.broadcast_batch(batch).await;
+    }
+
+    /// Persists the batch and replies to its author with the signed digest.
+    async fn process_batch(&mut self, peer: AccountAddress, batch: Batch) -> Result<()> {
+        ensure!(
+            batch.author() == peer,
+            "Batch author {} is not the sender {}",
+            batch.author(),
+            peer
+        );
+        let expiration_round = batch.expiration().round();
+        ensure!(
+            batch.epoch() == self.epoch
+                && expiration_round > self.last_committed.round()
+                && expiration_round
+                    <= self.last_committed.round() + 2 * self.config.batch_expiry_rounds,
+            "Batch expiration {} is out of the accepted range, last committed {}",
+            batch.expiration(),
+            self.last_committed
+        );
+        ensure!(
+            batch.num_txns() as u64 <= self.config.max_batch_txns,
+            "Batch has {} txns, max {}",
+            batch.num_txns(),
+            self.config.max_batch_txns
+        );
+        let info = batch.info();
+        ensure!(
+            info.num_bytes <= self.config.max_batch_bytes,
+            "Batch has {} bytes, max {}",
+            info.num_bytes,
+            self.config.max_batch_bytes
+        );
+
+        // a batch that is sent again was already accounted for
+        let key = (peer, info.digest);
+        if !self.persisted_batches.contains_key(&key) {
+            let author_bytes = self.author_bytes.get(&peer).copied().unwrap_or(0);
+            ensure!(
+                author_bytes + info.num_bytes <= self.config.max_bytes_per_author,
+                "Batch of {} bytes exceeds the quota of {}, which already has {} bytes of unexpired batches, max {}",
+                info.num_bytes,
+                peer,
+                author_bytes,
+                self.config.max_bytes_per_author
+            );
+            self.persisted_batches
+                .insert(key, (info.expiration, info.num_bytes));
+            self.author_bytes
+                .insert(peer, author_bytes + info.num_bytes);
+        }
+
+        self.batch_store.persist(batch)?;
+        let signature = self.safety_rules.lock().sign_digest(&info)?;
+        let signed_digest = SignedDigest::new(self.author, info, signature);
+        self.network_sender
+            .send_signed_digest(signed_digest, peer)
+            .await;
+        Ok(())
+    }
+
+    /// Aggregates the signature and broadcasts the proof of store once there's a quorum.
+    async fn process_signed_digest(&mut self, signed_digest: SignedDigest) -> Result<()> {
+        let digest = *signed_digest.digest();
+        let proof = match self.partial_proofs.get_mut(&digest) {
+            Some((info, signatures)) => {
+                ensure!(
+                    info == signed_digest.info(),
+                    "SignedDigest info doesn't match the batch {}",
+                    digest
+                );
+                signatures.add_signature(signed_digest.author(), signed_digest.signature().clone());
+                if self
+                    .verifier
+                    .check_voting_power(signatures.signatures().keys())
+                    .is_err()
+                {
+                    return Ok(());
+                }
+                let multi_signature = self.verifier.aggregate_signatures(signatures)?;
+                ProofOfStore::new(info.clone(), multi_signature)
+            }
+            // the proof is already formed or the batch expired
+            None => return Ok(()),
+        };
+        self.partial_proofs.remove(&digest);
+        counters::AGGREGATED_PROOF_COUNT.inc();
+        debug!("[quorum_store] broadcast {}", proof);
+        self.network_sender.broadcast_proof_of_store(proof).await;
+        Ok(())
+    }
+
+    fn process_proof_of_store(&mut self, proof: ProofOfStore) {
+        if proof.epoch() != self.epoch || proof.expiration() <= self.last_committed {
+            return;
+        }
+        // the same transactions batched by different validators have the same digest, keep the
+        // proof that expires last
+        match self.proofs.get(proof.digest()) {
+            Some(existing) if existing.expiration() >= proof.expiration() => {}
+            _ => {
+                self.proofs.insert(*proof.digest(), proof);
+            }
+        }
+        counters::PROOF_QUEUE_SIZE.set(self.proofs.len() as i64);
+    }
+
+    async fn process_message(&mut self, peer: AccountAddress, event: VerifiedEvent) -> Result<()> {
+        match event {
+            VerifiedEvent::BatchMsg(batch) => {
+                monitor!("process_batch", self.process_batch(peer, *batch).await)
+            }
+            VerifiedEvent::SignedDigestMsg(signed_digest) => monitor!(
+                "process_signed_digest",
+                self.process_signed_digest(*signed_digest).await
+            ),
+            VerifiedEvent::ProofOfStoreMsg(proof) => {
+                self.process_proof_of_store(*proof);
+                Ok(())
+            }
+            unexpected_event => bail!("Unexpected event: {:?}", unexpected_event),
+        }
+    }
+
+    /// Returns the proofs of store that are not excluded, earliest expiration first. Proofs are
+    /// only proposed while they are valid for half of the expiry period past the last commit, so
+    /// that they are still valid in the round of the proposal.
+    fn pull_proofs(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        filter: PayloadFilter,
+    ) -> Vec<ProofOfStore> {
+        let excluded = match filter {
+            PayloadFilter::InQuorumStore(digests) => digests,
+            // empty blocks are proposed with direct mempool payloads, nothing to exclude
+            PayloadFilter::DirectMempool(_) => HashSet::new(),
+        };
+        let min_expiration_round =
+            self.last_committed.round() + self.config.batch_expiry_rounds / 2;
+        let mut candidates: Vec<_> = self
+            .proofs
+            .values()
+            .filter(|proof| {
+                !excluded.contains(proof.digest())
+                    && proof.expiration().round() > min_expiration_round
+            })
+            .collect();
+        candidates.sort_by_key(|proof| (proof.expiration(), *proof.digest()));
+
+        let mut proofs = vec![];
+        let (mut num_txns, mut num_bytes) = (0, 0);
+        for proof in candidates {
+            if num_txns + proof.info().num_txns > max_txns
+                || num_bytes + proof.info().num_bytes > max_bytes
+            {
+                break;
+            }
+            num_txns += proof.info().num_txns;
+            num_bytes += proof.info().num_bytes;
+            proofs.push(proof.clone());
+        }
+        proofs
+    }
+
+    fn handle_block_request(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        payload_filter: PayloadFilter,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        let get_block_response_start_time = Instant::now();
+        let payload = Payload::InQuorumStore(self.pull_proofs(max_txns, max_bytes, payload_filter));
+        let result = match callback.send(Ok(ConsensusResponse::GetBlockResponse(payload))) {
+            Err(_) => {
+                error!("Callback failed");
+                counters::CALLBACK_FAIL_LABEL
+            }
+            Ok(_) => counters::CALLBACK_SUCCESS_LABEL,
+        };
+        counters::quorum_store_service_latency(
+            counters::GET_BLOCK_RESPONSE_LABEL,
+            result,
+            get_block_response_start_time.elapsed(),
+        );
+    }
+
+    /// Drops the committed proofs and everything that expired at the committed logical time.
+    fn handle_clean_request(
+        &mut self,
+        epoch: u64,
+        round: Round,
+        payloads: Vec<Payload>,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        let committed = LogicalTime::new(epoch, round);
+        if committed > self.last_committed {
+            self.last_committed = committed;
+        }
+        for payload in payloads {
+            if let Payload::InQuorumStore(proofs) = payload {
+                for proof in proofs {
+                    self.proofs.remove(proof.digest());
+                }
+            }
+        }
+        let last_committed = self.last_committed;
+        self.proofs
+            .retain(|_, proof| proof.expiration() > last_committed);
+        self.batched_txns
+            .retain(|_, (expiration, _)| *expiration > last_committed);
+        self.partial_proofs
+            .retain(|_, (info, _)| info.expiration > last_committed);
+        let author_bytes = &mut self.author_bytes;
+        self.persisted_batches
+            .retain(|(author, _), (expiration, num_bytes)| {
+                if *expiration > last_committed {
+                    return true;
+                }
+                if let Some(bytes) = author_bytes.get_mut(author) {
+                    *bytes -= *num_bytes;
+                }
+                false
+            });
+        author_bytes.retain(|_, bytes| *bytes > 0);
+        self.batch_store.clean(last_committed);
+        counters::PROOF_QUEUE_SIZE.set(self.proofs.len() as i64);
+
+        if callback
+            .send(Ok(ConsensusResponse::CleanResponse()))
+            .is_err()
+        {
+            error!("Callback failed");
+        }
+    }
+
+    fn handle_consensus_request(&mut self, req: ConsensusRequest) {
+        match req {
+            ConsensusRequest::GetBlockRequest(max_txns, max_bytes, payload_filter, callback) => {
+                self.handle_block_request(max_txns, max_bytes, payload_filter, callback);
+            }
+            ConsensusRequest::CleanRequest(epoch, round, payloads, callback) => {
+                self.handle_clean_request(epoch, round, payloads, callback);
+            }
+        }
+    }
+
+    pub async fn start(
+        mut self,
+        mut message_rx: aptos_channel::Receiver<AccountAddress, (AccountAddress, VerifiedEvent)>,
+        mut batch_request_rx: aptos_channel::Receiver<
+            AccountAddress,
+            IncomingBatchRetrievalRequest,
+        >,
+        close_rx: oneshot::Receiver<oneshot::Sender<()>>,
+    ) {
+        info!(epoch = self.epoch, "QuorumStore started");
+        let mut close_rx = close_rx.into_stream();
+        let mut batch_interval = interval(Duration::from_millis(self.config.batch_interval_ms));
+        loop {
+            let _timer = counters::MAIN_LOOP.start_timer();
+            ::futures::select! {
+                msg = self.consensus_receiver.select_next_some() => {
+                    self.handle_consensus_request(msg);
+                },
+                (peer, event) = message_rx.select_next_some() => {
+                    if let Err(e) = self.process_message(peer, event).await {
+                        warn!(epoch = self.epoch, remote_peer = peer, error = ?e, "[quorum_store] failed to process message");
+                    }
+                },
+                request = batch_request_rx.select_next_some() => {
+                    if let Err(e) = self.batch_store.process_batch_request(request) {
+                        warn!(epoch = self.epoch, error = ?e, "[quorum_store] failed to serve batch request");
+                    }
+                },
+                _ = batch_interval.tick().fuse() => {
+                    monitor!("create_batch", self.create_batch().await);
+                },
+                close_req = close_rx.select_next_some() => {
+                    if let Ok(ack_sender) = close_req {
+                        ack_sender.send(()).expect("[QuorumStore] Fail to ack shutdown");
+                    }
+                    break;
+                },
+            }
+        }
+        info!(epoch = self.epoch, "QuorumStore stopped");
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network::{IncomingBatchRetrievalRequest, NetworkSender},
+    network_interface::ConsensusMsg,
+    quorum_store::{counters, quorum_store_db::QuorumStoreDB},
+};
+use anyhow::{bail, ensure};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
+use consensus_types::{
+    batch::{Batch, BatchRequest},
+    common::Author,
+    proof_of_store::{LogicalTime, ProofOfStore},
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// Batches persisted by the quorum store, keyed by digest. The in-memory map mirrors the db, so
+/// that batches are served to execution and to other validators without reading the db.
+pub struct BatchStore {
+    epoch: u64,
+    author: Author,
+    db: Arc<QuorumStoreDB>,
+    batches: Mutex<HashMap<HashValue, Batch>>,
+    network_sender: NetworkSender,
+    // validators in the order of the multi-signature bitvec of the proofs
+    ordered_validators: Vec<Author>,
+    request_timeout: Duration,
+}
+
+impl BatchStore {
+    /// Loads the batches persisted in the given epoch and deletes the ones from other epochs.
+    pub fn new(
+        epoch: u64,
+        author: Author,
+        db: Arc<QuorumStoreDB>,
+        network_sender: NetworkSender,
+        ordered_validators: Vec<Author>,
+        request_timeout_ms: u64,
+    ) -> Self {
+        let mut batches = HashMap::new();
+        let mut stale_digests = vec![];
+        for (digest, batch) in db
+            .get_all_batches()
+            .expect("Failed to read the quorum store db")
+        {
+            if batch.epoch() == epoch {
+                batches.insert(digest, batch);
+            } else {
+                stale_digests.push(digest);
+            }
+        }
+        if !stale_digests.is_empty() {
+            if let Err(e) = db.delete_batches(&stale_digests) {
+                error!(error = ?e, "Failed to delete the batches of previous epochs");
+            }
+        }
+        counters::BATCH_STORE_SIZE.set(batches.len() as i64);
+        Self {
+            epoch,
+            author,
+            db,
+            batches: Mutex::new(batches),
+            network_sender,
+            ordered_validators,
+            request_timeout: Duration::from_millis(request_timeout_ms),
+        }
+    }
+
+    pub fn persist(&self, batch: Batch) -> anyhow::Result<()> {
+        ensure!(
+            batch.epoch() == self.epoch,
+            "Batch of epoch {} can't be persisted in epoch {}",
+            batch.epoch(),
+            self.epoch
+        );
+        self.db.save_batch(&batch)?;
+        let mut batches = self.batches.lock();
+        batches.insert(*batch.digest(), batch);
+        counters::BATCH_STORE_SIZE.set(batches.len() as i64);
+        Ok(())
+    }
+
+    pub fn get(&self, digest: &HashValue) -> Option<Batch> {
+        self.batches.lock().get(digest).cloned()
+    }
+
+    /// Returns the transactions of the batch certified by the proof, fetching the batch from the
+    /// validators that signed the proof if it's not stored locally.
+    pub async fn get_or_fetch(
+        &self,
+        proof: &ProofOfStore,
+    ) -> anyhow::Result<Vec<SignedTransaction>> {
+        if let Some(batch) = self.get(proof.digest()) {
+            return Ok(batch.into_payload().into_txns());
+        }
+        let request = BatchRequest::new(proof.epoch(), *proof.digest());
+        for signer in proof.signers(&self.ordered_validators) {
+            if signer == self.author {
+                continue;
+            }
+            match self
+                .network_sender
+                .request_batch(request.clone(), signer, self.request_timeout)
+                .await
+            {
+                Ok(batch) => {
+                    counters::BATCH_FETCH_COUNT
+                        .with_label_values(&["success"])
+                        .inc();
+                    if let Err(e) = self.persist(batch.clone()) {
+                        warn!(error = ?e, "Failed to persist fetched batch");
+                    }
+                    return Ok(batch.into_payload().into_txns());
+                }
+                Err(e) => {
+                    counters::BATCH_FETCH_COUNT
+                        .with_label_values(&["fail"])
+                        .inc();
+                    warn!(
+                        remote_peer = signer,
+                        error = ?e, "Failed to fetch batch {}", proof.digest()
+                    );
+                }
+            }
+        }
+        bail!("Failed to fetch batch {} from its signers", proof.digest())
+    }
+
+    /// Deletes the batches that expired before the committed logical time.
+    pub fn clean(&self, committed: LogicalTime) {
+        let expired: Vec<_> = {
+            let mut batches = self.batches.lock();
+            let expired: Vec<_> = batches
+                .values()
+                .filter(|batch| batch.expiration() < committed)
+                .map(|batch| *batch.digest())
+                .collect();
+            for digest in &expired {
+                batches.remove(digest);
+            }
+            counters::BATCH_STORE_SIZE.set(batches.len() as i64);
+            expired
+        };
+        if !expired.is_empty() {
+            if let Err(e) = self.db.delete_batches(&expired) {
+                error!(error = ?e, "Failed to delete expired batches");
+            }
+        }
+    }
+
+    pub fn process_batch_request(
+        &self,
+        request: IncomingBatchRetrievalRequest,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            request.req.epoch() == self.epoch,
+            "Batch request for epoch {}, local epoch {}",
+            request.req.epoch(),
+            self.epoch
+        );
+        let batch = match self.get(request.req.digest()) {
+            Some(batch) => batch,
+            None => bail!("Batch {} not found", request.req.digest()),
+        };
+        let response_bytes = request
+            .protocol
+            .to_bytes(&ConsensusMsg::BatchMsg(Box::new(batch)))?;
+        request
+            .response_sender
+            .send(Ok(response_bytes.into()))
+            .map_err(|_| anyhow::anyhow!("Failed to send batch response"))
+    }
+}
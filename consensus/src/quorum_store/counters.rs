@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use aptos_metrics_core::{
-    op_counters::DurationHistogram, register_histogram, register_histogram_vec, HistogramVec,
+    op_counters::DurationHistogram, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 use std::time::Duration;
@@ -42,3 +44,49 @@ pub static MAIN_LOOP: Lazy<DurationHistogram> = Lazy::new(|| {
         .unwrap(),
     )
 });
+
+/// Count of the batches created and broadcast by this validator.
+pub static CREATED_BATCH_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_created_batch_count",
+        "Count of the batches created by this validator"
+    )
+    .unwrap()
+});
+
+/// Count of the proofs of store aggregated by this validator for its own batches.
+pub static AGGREGATED_PROOF_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_aggregated_proof_count",
+        "Count of the proofs of store aggregated by this validator"
+    )
+    .unwrap()
+});
+
+/// Number of proofs of store waiting to be proposed.
+pub static PROOF_QUEUE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_proof_queue_size",
+        "Number of proofs of store waiting to be proposed"
+    )
+    .unwrap()
+});
+
+/// Number of batches held by the batch store.
+pub static BATCH_STORE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_batch_store_size",
+        "Number of batches held by the batch store"
+    )
+    .unwrap()
+});
+
+/// Count of the batches fetched from other validators, labeled by result.
+pub static BATCH_FETCH_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_batch_fetch_count",
+        "Count of the batches fetched from other validators",
+        &["result"]
+    )
+    .unwrap()
+});
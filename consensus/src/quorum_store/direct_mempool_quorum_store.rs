@@ -90,6 +90,10 @@ impl DirectMempoolQuorumStore {
                     Ok(txns) => (txns, counters::REQUEST_SUCCESS_LABEL),
                 }
             }
+            PayloadFilter::InQuorumStore(_) => {
+                error!("QuorumStore payload filter without QuorumStore");
+                (vec![], counters::REQUEST_FAIL_LABEL)
+            }
         };
         counters::quorum_store_service_latency(
             counters::GET_BATCH_LABEL,
@@ -129,7 +133,7 @@ impl DirectMempoolQuorumStore {
                 self.handle_block_request(max_txns, max_bytes, payload_filter, callback)
                     .await;
             }
-            ConsensusRequest::CleanRequest(_, _, callback) => {
+            ConsensusRequest::CleanRequest(_, _, _, callback) => {
                 self.handle_clean_request(callback).await;
             }
         }
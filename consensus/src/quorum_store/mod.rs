@@ -1,9 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+/// Disseminates batches of transactions and aggregates their proofs of store.
+pub mod batch_dissemination;
+/// Batches persisted by this validator, served to execution and to the other validators.
+pub mod batch_store;
 /// Equivalent to directly fetching blocks from mempool without a quorum store.
 pub mod direct_mempool_quorum_store;
+pub mod quorum_store_db;
 
 mod counters;
+mod schema;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::DbError,
+    quorum_store::schema::{BatchSchema, BATCH_CF_NAME},
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use consensus_types::batch::Batch;
+use schemadb::{Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
+use std::{collections::HashMap, path::Path, time::Instant};
+
+/// The name of the quorum store db file
+pub const QUORUM_STORE_DB_NAME: &str = "quorumstoreDB";
+
+/// Persists the batches this validator promised to store, so that they can still be served
+/// after a restart until they expire.
+pub struct QuorumStoreDB {
+    db: DB,
+}
+
+impl QuorumStoreDB {
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        let column_families = vec![
+            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+            BATCH_CF_NAME,
+        ];
+
+        let path = db_root_path.as_ref().join(QUORUM_STORE_DB_NAME);
+        let instant = Instant::now();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open(path.clone(), "quorum_store", column_families, &opts)
+            .expect("QuorumStoreDB open failed; unable to continue");
+
+        info!(
+            "Opened QuorumStoreDB at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    pub fn save_batch(&self, batch: &Batch) -> Result<(), DbError> {
+        let schema_batch = SchemaBatch::new();
+        schema_batch.put::<BatchSchema>(batch.digest(), batch)?;
+        self.db.write_schemas(schema_batch)?;
+        Ok(())
+    }
+
+    pub fn delete_batches(&self, digests: &[HashValue]) -> Result<(), DbError> {
+        let schema_batch = SchemaBatch::new();
+        digests
+            .iter()
+            .try_for_each(|digest| schema_batch.delete::<BatchSchema>(digest))?;
+        self.db.write_schemas(schema_batch)?;
+        Ok(())
+    }
+
+    /// Get all the persisted batches.
+    pub fn get_all_batches(&self) -> Result<HashMap<HashValue, Batch>, DbError> {
+        let mut iter = self.db.iter::<BatchSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.collect::<Result<HashMap<HashValue, Batch>>>()?)
+    }
+}
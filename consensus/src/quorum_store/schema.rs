@@ -0,0 +1,49 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the batches of the quorum store.
+//!
+//! Serialized batch bytes identified by the batch digest.
+//! ```text
+//! |<---key---->|<---value--->|
+//! |   digest   |    batch    |
+//! ```
+
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use consensus_types::batch::Batch;
+use schemadb::{
+    schema::{KeyCodec, Schema, ValueCodec},
+    ColumnFamilyName,
+};
+
+pub(crate) const BATCH_CF_NAME: ColumnFamilyName = "batch";
+
+#[derive(Debug)]
+pub(crate) struct BatchSchema;
+
+impl Schema for BatchSchema {
+    const COLUMN_FAMILY_NAME: ColumnFamilyName = BATCH_CF_NAME;
+    type Key = HashValue;
+    type Value = Batch;
+}
+
+impl KeyCodec<BatchSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<BatchSchema> for Batch {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
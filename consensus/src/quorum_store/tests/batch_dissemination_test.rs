@@ -0,0 +1,385 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics_safety_rules::MetricsSafetyRules,
+    network_interface::ConsensusMsg,
+    quorum_store::{
+        batch_dissemination::{QuorumStore, QuorumStoreConfig},
+        batch_store::BatchStore,
+        quorum_store_db::QuorumStoreDB,
+        tests::create_network_sender,
+    },
+    round_manager::VerifiedEvent,
+    test_utils::MockStorage,
+};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_mempool::{QuorumStoreRequest, QuorumStoreResponse};
+use aptos_secure_storage::{InMemoryStorage, Storage};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    ledger_info::LedgerInfo,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
+    waypoint::Waypoint,
+};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{
+    batch::{Batch, BatchPayload},
+    block::block_test_utils::random_payload,
+    common::{Author, Payload, PayloadFilter, Round},
+    proof_of_store::LogicalTime,
+    request_response::{ConsensusRequest, ConsensusResponse},
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
+use network::protocols::network::Event;
+use safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::{task::JoinHandle, time::timeout};
+
+async fn next_self_message(
+    self_receiver: &mut channel::Receiver<Event<ConsensusMsg>>,
+) -> ConsensusMsg {
+    match timeout(
+        Duration::from_millis(1_000),
+        self_receiver.select_next_some(),
+    )
+    .await
+    .unwrap()
+    {
+        Event::Message(_, msg) => msg,
+        _ => panic!("Unexpected event"),
+    }
+}
+
+async fn get_block(
+    consensus_sender: &mut mpsc::Sender<ConsensusRequest>,
+    payload_filter: PayloadFilter,
+) -> Payload {
+    let (callback, callback_rcv) = oneshot::channel();
+    consensus_sender
+        .send(ConsensusRequest::GetBlockRequest(
+            100,
+            1_000_000,
+            payload_filter,
+            callback,
+        ))
+        .await
+        .unwrap();
+    match callback_rcv.await.unwrap().unwrap() {
+        ConsensusResponse::GetBlockResponse(payload) => payload,
+        _ => panic!("Unexpected response"),
+    }
+}
+
+fn test_config() -> QuorumStoreConfig {
+    QuorumStoreConfig {
+        max_batch_txns: 100,
+        max_batch_bytes: 1_000_000,
+        // only the first tick, which completes immediately, creates a batch
+        batch_interval_ms: 3_600_000,
+        batch_expiry_rounds: 20,
+        max_bytes_per_author: 10_000_000,
+        mempool_txn_pull_timeout_ms: 1_000,
+    }
+}
+
+/// A running quorum store of the only validator, and the channels to talk to it
+struct TestQuorumStore {
+    author: Author,
+    verifier: ValidatorVerifier,
+    batch_store: Arc<BatchStore>,
+    self_receiver: channel::Receiver<Event<ConsensusMsg>>,
+    consensus_sender: mpsc::Sender<ConsensusRequest>,
+    mempool_receiver: mpsc::Receiver<QuorumStoreRequest>,
+    message_tx: aptos_channel::Sender<AccountAddress, (AccountAddress, VerifiedEvent)>,
+    close_tx: oneshot::Sender<oneshot::Sender<()>>,
+    join_handle: JoinHandle<()>,
+}
+
+impl TestQuorumStore {
+    fn start(config: QuorumStoreConfig) -> Self {
+        let (signers, verifier) = random_validator_verifier(1, None, false);
+        let author = signers[0].author();
+        let (_, storage) = MockStorage::start_for_testing((&verifier).into());
+        let waypoint =
+            Waypoint::new_epoch_boundary(&LedgerInfo::mock_genesis(Some((&verifier).into())))
+                .unwrap();
+        let safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            author,
+            signers[0].private_key().clone(),
+            waypoint,
+            true,
+        );
+        let safety_rules_manager = SafetyRulesManager::new_local(safety_storage);
+        let mut safety_rules = MetricsSafetyRules::new(safety_rules_manager.client(), storage);
+        safety_rules.perform_initialize().unwrap();
+
+        let tmp_dir = TempPath::new();
+        let (network_sender, self_receiver) = create_network_sender(author, verifier.clone());
+        let batch_store = Arc::new(BatchStore::new(
+            1,
+            author,
+            Arc::new(QuorumStoreDB::new(&tmp_dir)),
+            network_sender.clone(),
+            vec![author],
+            1_000,
+        ));
+        let (consensus_sender, consensus_receiver) = mpsc::channel(8);
+        let (mempool_sender, mempool_receiver) = mpsc::channel(8);
+        let (message_tx, message_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let (_batch_request_tx, batch_request_rx) = aptos_channel::new(QueueStyle::LIFO, 1, None);
+        let (close_tx, close_rx) = oneshot::channel();
+        let quorum_store = QuorumStore::new(
+            LogicalTime::new(1, 0),
+            author,
+            config,
+            consensus_receiver,
+            mempool_sender,
+            network_sender,
+            Arc::new(Mutex::new(safety_rules)),
+            verifier.clone(),
+            batch_store.clone(),
+        );
+        let join_handle = tokio::spawn(quorum_store.start(message_rx, batch_request_rx, close_rx));
+
+        Self {
+            author,
+            verifier,
+            batch_store,
+            self_receiver,
+            consensus_sender,
+            mempool_receiver,
+            message_tx,
+            close_tx,
+            join_handle,
+        }
+    }
+
+    /// Sends the batch to the quorum store, as if it was broadcast by its author
+    fn send_batch(&self, batch: &Batch) {
+        self.message_tx
+            .push(
+                batch.author(),
+                (
+                    batch.author(),
+                    VerifiedEvent::BatchMsg(Box::new(batch.clone())),
+                ),
+            )
+            .unwrap();
+    }
+
+    /// Returns the digest of the next batch the quorum store signed
+    async fn next_signed_digest(&mut self) -> HashValue {
+        match next_self_message(&mut self.self_receiver).await {
+            ConsensusMsg::SignedDigestMsg(signed_digest) => {
+                signed_digest.verify(&self.verifier).unwrap();
+                *signed_digest.digest()
+            }
+            _ => panic!("Unexpected message"),
+        }
+    }
+
+    async fn commit(&mut self, round: Round, payloads: Vec<Payload>) {
+        let (callback, callback_rcv) = oneshot::channel();
+        self.consensus_sender
+            .send(ConsensusRequest::CleanRequest(1, round, payloads, callback))
+            .await
+            .unwrap();
+        callback_rcv.await.unwrap().unwrap();
+    }
+
+    async fn stop(self) {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.close_tx.send(ack_tx).unwrap();
+        ack_rx.await.unwrap();
+        self.join_handle.await.unwrap();
+    }
+}
+
+fn random_batch_payload(num_txns: usize) -> BatchPayload {
+    match random_payload(num_txns) {
+        Payload::DirectMempool(txns) => BatchPayload::new(txns),
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_batch_to_proposed_proof() {
+    let mut quorum_store = TestQuorumStore::start(test_config());
+    let author = quorum_store.author;
+    let verifier = quorum_store.verifier.clone();
+
+    let txns = match random_payload(5) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    match timeout(
+        Duration::from_millis(1_000),
+        quorum_store.mempool_receiver.select_next_some(),
+    )
+    .await
+    .unwrap()
+    {
+        QuorumStoreRequest::GetBatchRequest(_, _, _, callback) => callback
+            .send(Ok(QuorumStoreResponse::GetBatchResponse(txns.clone())))
+            .unwrap(),
+        _ => panic!("Unexpected request"),
+    }
+
+    // the batch is broadcast to self, persisted and signed
+    let batch = match next_self_message(&mut quorum_store.self_receiver).await {
+        ConsensusMsg::BatchMsg(batch) => batch,
+        _ => panic!("Unexpected message"),
+    };
+    batch.verify().unwrap();
+    assert_eq!(batch.payload().txns(), txns.as_slice());
+    quorum_store.send_batch(&batch);
+    let signed_digest = match next_self_message(&mut quorum_store.self_receiver).await {
+        ConsensusMsg::SignedDigestMsg(signed_digest) => signed_digest,
+        _ => panic!("Unexpected message"),
+    };
+    signed_digest.verify(&verifier).unwrap();
+    quorum_store
+        .message_tx
+        .push(
+            author,
+            (author, VerifiedEvent::SignedDigestMsg(signed_digest)),
+        )
+        .unwrap();
+
+    // a single signature is a quorum
+    let proof = match next_self_message(&mut quorum_store.self_receiver).await {
+        ConsensusMsg::ProofOfStoreMsg(proof) => proof,
+        _ => panic!("Unexpected message"),
+    };
+    proof.verify(&verifier).unwrap();
+    assert_eq!(proof.digest(), batch.digest());
+    quorum_store
+        .message_tx
+        .push(
+            author,
+            (author, VerifiedEvent::ProofOfStoreMsg(proof.clone())),
+        )
+        .unwrap();
+
+    // wait for the proof to be processed
+    let mut payload = Payload::empty();
+    for _ in 0..10 {
+        payload = get_block(
+            &mut quorum_store.consensus_sender,
+            PayloadFilter::InQuorumStore(HashSet::new()),
+        )
+        .await;
+        if !payload.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(payload, Payload::InQuorumStore(vec![*proof.clone()]));
+    assert!(get_block(
+        &mut quorum_store.consensus_sender,
+        PayloadFilter::InQuorumStore(HashSet::from([*proof.digest()]))
+    )
+    .await
+    .is_empty());
+    assert_eq!(
+        quorum_store.batch_store.get_or_fetch(&proof).await.unwrap(),
+        txns
+    );
+
+    // committed proofs are not proposed again
+    quorum_store.commit(1, vec![payload]).await;
+    assert!(get_block(
+        &mut quorum_store.consensus_sender,
+        PayloadFilter::InQuorumStore(HashSet::new())
+    )
+    .await
+    .is_empty());
+
+    quorum_store.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_batch_over_max_bytes_is_dropped() {
+    let small_payload = random_batch_payload(1);
+    let large_payload = random_batch_payload(10);
+    assert!(large_payload.num_bytes() > small_payload.num_bytes());
+    let mut quorum_store = TestQuorumStore::start(QuorumStoreConfig {
+        max_batch_bytes: small_payload.num_bytes() as u64,
+        ..test_config()
+    });
+    let author = quorum_store.author;
+    let expiration = LogicalTime::new(1, 10);
+    let large_batch = Batch::new(author, 0, expiration, large_payload);
+    let small_batch = Batch::new(author, 1, expiration, small_payload);
+
+    // the large batch is neither persisted nor signed, messages are processed in order
+    quorum_store.send_batch(&large_batch);
+    quorum_store.send_batch(&small_batch);
+    assert_eq!(
+        quorum_store.next_signed_digest().await,
+        *small_batch.digest()
+    );
+    assert!(quorum_store.batch_store.get(large_batch.digest()).is_none());
+    assert!(quorum_store.batch_store.get(small_batch.digest()).is_some());
+
+    quorum_store.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_author_bytes_quota() {
+    let payloads: Vec<_> = (0..3).map(|_| random_batch_payload(2)).collect();
+    let batch_bytes = payloads[0].num_bytes() as u64;
+    assert!(payloads
+        .iter()
+        .all(|payload| payload.num_bytes() as u64 == batch_bytes));
+    let mut quorum_store = TestQuorumStore::start(QuorumStoreConfig {
+        // room for two batches, the third one is dropped
+        max_bytes_per_author: 2 * batch_bytes,
+        ..test_config()
+    });
+    let author = quorum_store.author;
+    let mut payloads = payloads.into_iter();
+    let early_batch = Batch::new(author, 0, LogicalTime::new(1, 5), payloads.next().unwrap());
+    let late_batch = Batch::new(author, 1, LogicalTime::new(1, 30), payloads.next().unwrap());
+    let over_quota_batch = Batch::new(author, 2, LogicalTime::new(1, 30), payloads.next().unwrap());
+
+    quorum_store.send_batch(&early_batch);
+    assert_eq!(
+        quorum_store.next_signed_digest().await,
+        *early_batch.digest()
+    );
+    quorum_store.send_batch(&late_batch);
+    assert_eq!(
+        quorum_store.next_signed_digest().await,
+        *late_batch.digest()
+    );
+
+    // a batch over the quota isn't signed, a batch sent again doesn't count twice
+    quorum_store.send_batch(&over_quota_batch);
+    quorum_store.send_batch(&early_batch);
+    assert_eq!(
+        quorum_store.next_signed_digest().await,
+        *early_batch.digest()
+    );
+    assert!(quorum_store
+        .batch_store
+        .get(over_quota_batch.digest())
+        .is_none());
+
+    // the quota is released once the early batch expires
+    quorum_store.commit(5, vec![]).await;
+    quorum_store.send_batch(&over_quota_batch);
+    assert_eq!(
+        quorum_store.next_signed_digest().await,
+        *over_quota_batch.digest()
+    );
+
+    quorum_store.stop().await;
+}
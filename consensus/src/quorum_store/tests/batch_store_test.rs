@@ -0,0 +1,89 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    batch_store::BatchStore, quorum_store_db::QuorumStoreDB, tests::create_network_sender,
+};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    aggregate_signature::AggregateSignature, validator_verifier::random_validator_verifier,
+};
+use consensus_types::{
+    batch::{Batch, BatchPayload},
+    block::block_test_utils::random_payload,
+    common::{Author, Payload},
+    proof_of_store::{LogicalTime, ProofOfStore},
+};
+use std::sync::Arc;
+
+fn create_batch(author: Author, batch_id: u64, expiration: LogicalTime) -> Batch {
+    let txns = match random_payload(3) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    Batch::new(author, batch_id, expiration, BatchPayload::new(txns))
+}
+
+fn create_batch_store(epoch: u64, author: Author, db: Arc<QuorumStoreDB>) -> BatchStore {
+    let (_, verifier) = random_validator_verifier(1, None, false);
+    let (network_sender, _) = create_network_sender(author, verifier);
+    BatchStore::new(epoch, author, db, network_sender, vec![author], 1_000)
+}
+
+#[test]
+fn test_persist_and_clean() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(QuorumStoreDB::new(&tmp_dir));
+    let author = Author::random();
+    let batch_store = create_batch_store(1, author, db.clone());
+
+    let first_batch = create_batch(author, 0, LogicalTime::new(1, 10));
+    let second_batch = create_batch(author, 1, LogicalTime::new(1, 20));
+    batch_store.persist(first_batch.clone()).unwrap();
+    batch_store.persist(second_batch.clone()).unwrap();
+    // batches of other epochs are rejected
+    assert!(batch_store
+        .persist(create_batch(author, 2, LogicalTime::new(2, 10)))
+        .is_err());
+    assert_eq!(
+        batch_store.get(first_batch.digest()),
+        Some(first_batch.clone())
+    );
+    assert_eq!(db.get_all_batches().unwrap().len(), 2);
+
+    batch_store.clean(LogicalTime::new(1, 15));
+    assert_eq!(batch_store.get(first_batch.digest()), None);
+    assert_eq!(
+        batch_store.get(second_batch.digest()),
+        Some(second_batch.clone())
+    );
+    assert_eq!(db.get_all_batches().unwrap().len(), 1);
+
+    // the batches are recovered in the same epoch and dropped in the next one
+    let recovered_store = create_batch_store(1, author, db.clone());
+    assert_eq!(
+        recovered_store.get(second_batch.digest()),
+        Some(second_batch.clone())
+    );
+    let next_epoch_store = create_batch_store(2, author, db.clone());
+    assert_eq!(next_epoch_store.get(second_batch.digest()), None);
+    assert!(db.get_all_batches().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_or_fetch_local_batch() {
+    let tmp_dir = TempPath::new();
+    let author = Author::random();
+    let batch_store = create_batch_store(1, author, Arc::new(QuorumStoreDB::new(&tmp_dir)));
+
+    let batch = create_batch(author, 0, LogicalTime::new(1, 10));
+    let proof = ProofOfStore::new(batch.info(), AggregateSignature::empty());
+    // there are no other signers to fetch the batch from
+    assert!(batch_store.get_or_fetch(&proof).await.is_err());
+
+    batch_store.persist(batch.clone()).unwrap();
+    assert_eq!(
+        batch_store.get_or_fetch(&proof).await.unwrap(),
+        batch.payload().txns().to_vec()
+    );
+}
@@ -66,6 +66,7 @@ async fn test_block_request_no_txns() {
             assert!(payload.is_empty());
             match payload {
                 Payload::DirectMempool(txns) => assert!(txns.is_empty()),
+                _ => panic!("Unexpected payload"),
             }
         }
         _ => {
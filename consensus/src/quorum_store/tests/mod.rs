@@ -1,5 +1,38 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    network::NetworkSender,
+    network_interface::{ConsensusMsg, ConsensusNetworkSender},
+};
+use aptos_types::{account_address::AccountAddress, validator_verifier::ValidatorVerifier};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use network::{
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::{Event, NewNetworkSender},
+};
+
+#[cfg(test)]
+mod batch_dissemination_test;
+#[cfg(test)]
+mod batch_store_test;
 #[cfg(test)]
 mod direct_mempool_quorum_store_test;
+
+/// Network sender that only delivers the messages sent to self, to the returned receiver.
+fn create_network_sender(
+    author: AccountAddress,
+    verifier: ValidatorVerifier,
+) -> (NetworkSender, channel::Receiver<Event<ConsensusMsg>>) {
+    let (network_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let network_sender = ConsensusNetworkSender::new(
+        PeerManagerRequestSender::new(network_reqs_tx),
+        ConnectionRequestSender::new(connection_reqs_tx),
+    );
+    let (self_sender, self_receiver) = channel::new_test(8);
+    (
+        NetworkSender::new(author, network_sender, self_sender, verifier),
+        self_receiver,
+    )
+}
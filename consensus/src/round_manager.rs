@@ -32,9 +32,11 @@ use aptos_types::{
 };
 use channel::aptos_channel;
 use consensus_types::{
+    batch::Batch,
     block::Block,
     common::{Author, Round},
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
     sync_info::SyncInfo,
@@ -62,6 +64,9 @@ pub enum UnverifiedEvent {
     SyncInfo(Box<SyncInfo>),
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
+    BatchMsg(Box<Batch>),
+    SignedDigestMsg(Box<SignedDigest>),
+    ProofOfStoreMsg(Box<ProofOfStore>),
 }
 
 pub const BACK_PRESSURE_POLLING_INTERVAL_MS: u64 = 10;
@@ -87,6 +92,18 @@ impl UnverifiedEvent {
                 cd.verify(validator)?;
                 VerifiedEvent::CommitDecision(cd)
             }
+            UnverifiedEvent::BatchMsg(b) => {
+                b.verify()?;
+                VerifiedEvent::BatchMsg(b)
+            }
+            UnverifiedEvent::SignedDigestMsg(sd) => {
+                sd.verify(validator)?;
+                VerifiedEvent::SignedDigestMsg(sd)
+            }
+            UnverifiedEvent::ProofOfStoreMsg(p) => {
+                p.verify(validator)?;
+                VerifiedEvent::ProofOfStoreMsg(p)
+            }
         })
     }
 
@@ -97,6 +114,9 @@ impl UnverifiedEvent {
             UnverifiedEvent::SyncInfo(s) => s.epoch(),
             UnverifiedEvent::CommitVote(cv) => cv.epoch(),
             UnverifiedEvent::CommitDecision(cd) => cd.epoch(),
+            UnverifiedEvent::BatchMsg(b) => b.epoch(),
+            UnverifiedEvent::SignedDigestMsg(sd) => sd.epoch(),
+            UnverifiedEvent::ProofOfStoreMsg(p) => p.epoch(),
         }
     }
 }
@@ -109,6 +129,9 @@ impl From<ConsensusMsg> for UnverifiedEvent {
             ConsensusMsg::SyncInfo(m) => UnverifiedEvent::SyncInfo(m),
             ConsensusMsg::CommitVoteMsg(m) => UnverifiedEvent::CommitVote(m),
             ConsensusMsg::CommitDecisionMsg(m) => UnverifiedEvent::CommitDecision(m),
+            ConsensusMsg::BatchMsg(m) => UnverifiedEvent::BatchMsg(m),
+            ConsensusMsg::SignedDigestMsg(m) => UnverifiedEvent::SignedDigestMsg(m),
+            ConsensusMsg::ProofOfStoreMsg(m) => UnverifiedEvent::ProofOfStoreMsg(m),
             _ => unreachable!("Unexpected conversion"),
         }
     }
@@ -123,6 +146,9 @@ pub enum VerifiedEvent {
     UnverifiedSyncInfo(Box<SyncInfo>),
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
+    BatchMsg(Box<Batch>),
+    SignedDigestMsg(Box<SignedDigest>),
+    ProofOfStoreMsg(Box<ProofOfStore>),
     // local messages
     LocalTimeout(Round),
 }
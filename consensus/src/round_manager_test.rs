@@ -15,6 +15,7 @@ use crate::{
     network_tests::{NetworkPlayground, TwinId},
     persistent_liveness_storage::RecoveryData,
     round_manager::RoundManager,
+    state_replication::StateComputer,
     test_utils::{
         consensus_runtime, timed_block_on, MockPayloadManager, MockStateComputer, MockStorage,
         TreeInserter,
@@ -186,6 +187,7 @@ impl NodeSetup {
             commit_cb_sender,
             Arc::clone(&storage),
         ));
+        state_computer.new_epoch(&epoch_state, Arc::new(MockPayloadManager::new(None)));
         let time_service = Arc::new(ClockTimeService::new(executor));
 
         let block_store = Arc::new(BlockStore::new(
@@ -5,9 +5,10 @@ use crate::monitor;
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
     commit_notifier::CommitNotifier,
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::StateSyncError,
-    state_replication::{StateComputer, StateComputerCommitCallBackType},
+    state_replication::{PayloadManager, StateComputer, StateComputerCommitCallBackType},
     txn_notifier::TxnNotifier,
 };
 use anyhow::Result;
@@ -19,7 +20,11 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, transaction::Transaction,
 };
 use consensus_notifications::ConsensusNotificationSender;
-use consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    executed_block::ExecutedBlock,
+};
 use executor_types::{BlockExecutorTrait, Error as ExecutionError, StateComputeResult};
 use fail::fail_point;
 use futures::{SinkExt, StreamExt};
@@ -32,7 +37,7 @@ type NotificationType = (
     Vec<ContractEvent>,
 );
 
-type CommitType = (u64, Round);
+type CommitType = (u64, Round, Vec<Payload>);
 
 /// Basic communication with the Execution module;
 /// implements StateComputer traits.
@@ -43,7 +48,11 @@ pub struct ExecutionProxy {
     async_state_sync_notifier: channel::Sender<NotificationType>,
     async_commit_notifier: channel::Sender<CommitType>,
     validators: Mutex<Vec<AccountAddress>>,
+    // resolves the transactions of the block payloads, set at the start of each epoch
+    payload_manager: Mutex<Option<Arc<dyn PayloadManager>>>,
     write_mutex: AsyncMutex<()>,
+    // publishes the commit decisions to the consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl ExecutionProxy {
@@ -53,6 +62,7 @@ impl ExecutionProxy {
         state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
         commit_notifier: Arc<dyn CommitNotifier>,
         handle: &tokio::runtime::Handle,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let (tx, mut rx) =
             channel::new::<NotificationType>(10, &counters::PENDING_STATE_SYNC_NOTIFICATION);
@@ -73,10 +83,11 @@ impl ExecutionProxy {
             channel::new::<CommitType>(10, &counters::PENDING_QUORUM_STORE_COMMIT_NOTIFICATION);
         let notifier = commit_notifier.clone();
         handle.spawn(async move {
-            while let Some((epoch, round)) = commit_rx.next().await {
-                if let Err(e) =
-                    monitor!("notify_commit", notifier.notify_commit(epoch, round).await)
-                {
+            while let Some((epoch, round, payloads)) = commit_rx.next().await {
+                if let Err(e) = monitor!(
+                    "notify_commit",
+                    notifier.notify_commit(epoch, round, payloads).await
+                ) {
                     error!(error = ?e, "Failed to notify commit notifier");
                 }
             }
@@ -88,9 +99,19 @@ impl ExecutionProxy {
             async_state_sync_notifier: tx,
             async_commit_notifier: commit_tx,
             validators: Mutex::new(vec![]),
+            payload_manager: Mutex::new(None),
             write_mutex: AsyncMutex::new(()),
+            consensus_publisher,
         }
     }
+
+    fn payload_manager(&self) -> Arc<dyn PayloadManager> {
+        self.payload_manager
+            .lock()
+            .as_ref()
+            .expect("ExecutionProxy not started for the epoch")
+            .clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -116,7 +137,12 @@ impl StateComputer for ExecutionProxy {
 
         // TODO: figure out error handling for the prologue txn
         let executor = self.executor.clone();
-        let transactions_to_execute = block.transactions_to_execute(&self.validators.lock());
+        let user_txns = monitor!(
+            "get_transactions",
+            self.payload_manager().get_transactions(block).await
+        )?;
+        let transactions_to_execute =
+            block.transactions_to_execute(&self.validators.lock(), user_txns.clone());
         let compute_result = monitor!(
             "execute_block",
             tokio::task::spawn_blocking(move || {
@@ -130,7 +156,7 @@ impl StateComputer for ExecutionProxy {
         // notify mempool about failed transaction
         if let Err(e) = self
             .txn_notifier
-            .notify_failed_txn(block, &compute_result)
+            .notify_failed_txn(user_txns, &compute_result)
             .await
         {
             error!(
@@ -152,14 +178,20 @@ impl StateComputer for ExecutionProxy {
         let mut block_ids = Vec::new();
         let mut txns = Vec::new();
         let mut reconfig_events = Vec::new();
+        let mut payloads = Vec::new();
+        let payload_manager = self.payload_manager();
         let skip_clean = blocks.is_empty();
         let mut latest_epoch: u64 = 0;
         let mut latest_round: u64 = 0;
 
         for block in blocks {
             block_ids.push(block.id());
-            txns.extend(block.transactions_to_commit(&self.validators.lock()));
+            let user_txns = payload_manager.get_transactions(block.block()).await?;
+            txns.extend(block.transactions_to_commit(&self.validators.lock(), user_txns));
             reconfig_events.extend(block.reconfig_event());
+            if let Some(payload) = block.payload() {
+                payloads.push(payload.clone());
+            }
 
             if block.epoch() > latest_epoch {
                 latest_epoch = block.epoch();
@@ -182,6 +214,12 @@ impl StateComputer for ExecutionProxy {
         )
         .expect("spawn_blocking failed");
 
+        if let Some(publisher) = &self.consensus_publisher {
+            if !skip_clean {
+                publisher.publish_commit_decision(finality_proof.clone());
+            }
+        }
+
         let blocks = blocks.to_vec();
         let wrapped_callback = move || {
             callback(&blocks, finality_proof);
@@ -199,7 +237,7 @@ impl StateComputer for ExecutionProxy {
         }
         self.async_commit_notifier
             .clone()
-            .send((latest_epoch, latest_round))
+            .send((latest_epoch, latest_round, payloads))
             .await
             .expect("Failed to send async commit notification");
         Ok(())
@@ -236,10 +274,14 @@ impl StateComputer for ExecutionProxy {
         })
     }
 
-    fn new_epoch(&self, epoch_state: &EpochState) {
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<dyn PayloadManager>) {
         *self.validators.lock() = epoch_state
             .verifier
             .get_ordered_account_addresses_iter()
             .collect();
+        if let Some(publisher) = &self.consensus_publisher {
+            publisher.new_epoch(payload_manager.clone());
+        }
+        *self.payload_manager.lock() = Some(payload_manager);
     }
 }
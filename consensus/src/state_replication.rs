@@ -4,7 +4,9 @@
 use crate::error::{QuorumStoreError, StateSyncError};
 use anyhow::Result;
 use aptos_crypto::HashValue;
-use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
 use consensus_types::{
     block::Block,
     common::{Payload, PayloadFilter},
@@ -28,6 +30,13 @@ pub trait PayloadManager: Send + Sync {
         pending_ordering: bool,
     ) -> Result<Payload, QuorumStoreError>;
 
+    /// Returns the user transactions of the block payload, fetching the batches referenced by
+    /// the proofs of store if they are not available locally.
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, ExecutionError>;
+
     fn trace_payloads(&self) {}
 }
 
@@ -62,5 +71,5 @@ pub trait StateComputer: Send + Sync {
     async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError>;
 
     // Reconfigure to execute transactions for a new epoch.
-    fn new_epoch(&self, epoch_state: &EpochState);
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<dyn PayloadManager>);
 }
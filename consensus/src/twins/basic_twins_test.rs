@@ -10,7 +10,10 @@ use crate::{
 use aptos_types::on_chain_config::ProposerElectionType::{
     FixedProposer, RotatingProposer, RoundProposer,
 };
-use consensus_types::{block::Block, common::Round};
+use consensus_types::{
+    block::{block_test_utils::random_payload, Block},
+    common::{Payload, Round},
+};
 use futures::StreamExt;
 use std::collections::HashMap;
 
@@ -35,6 +38,7 @@ fn basic_start_test() {
         &mut playground,
         RotatingProposer(2),
         None,
+        false,
     );
     let genesis = Block::make_genesis_block_from_ledger_info(&nodes[0].storage.get_ledger_info());
     timed_block_on(&mut runtime, async {
@@ -57,6 +61,48 @@ fn basic_start_test() {
     });
 }
 
+#[test]
+/// This test checks that the transactions batched by the quorum store of a node are
+/// committed, the nodes fetch the batches of the proposed proofs of store before execution.
+///
+/// Setup:
+///
+/// 4 honest nodes with the quorum store enabled, and 0 twins.
+/// The transactions are only in the mempool of n0.
+///
+/// Run the test:
+/// cargo xtest -p consensus quorum_store_commit_test -- --nocapture
+fn quorum_store_commit_test() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let num_nodes = 4;
+    let num_twins = 0;
+    let mut nodes = SMRNode::start_num_nodes_with_twins(
+        num_nodes,
+        num_twins,
+        &mut playground,
+        RotatingProposer(2),
+        None,
+        true,
+    );
+    let txns = match random_payload(10) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    nodes[0].shared_mempool.add_txns(txns.clone()).unwrap();
+    runtime.spawn(playground.start());
+
+    timed_block_on(&mut runtime, async {
+        for node in &mut nodes {
+            let mut committed_txns = vec![];
+            while committed_txns.len() < txns.len() {
+                committed_txns.extend(node.state_sync.next().await.unwrap());
+            }
+            assert_eq!(committed_txns, txns);
+        }
+    });
+}
+
 #[test]
 /// This test checks that the split_network function works
 /// as expected, that is: nodes in a partition with less nodes
@@ -87,6 +133,7 @@ fn drop_config_test() {
         &mut playground,
         FixedProposer(2),
         None,
+        false,
     );
 
     // 4 honest nodes
@@ -143,6 +190,7 @@ fn twins_vote_dedup_test() {
         &mut playground,
         RotatingProposer(2),
         None,
+        false,
     );
 
     // 4 honest nodes
@@ -210,6 +258,7 @@ fn twins_proposer_test() {
         &mut playground,
         RoundProposer(HashMap::new()),
         Some(round_proposers),
+        false,
     );
 
     // 4 honest nodes
@@ -293,6 +342,7 @@ fn twins_commit_test() {
         &mut playground,
         RoundProposer(HashMap::new()),
         Some(round_proposers),
+        false,
     );
     runtime.spawn(playground.start());
 
@@ -2,4 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod basic_twins_test;
+mod randomized_twins_test;
+mod scenario_generator;
+mod twins_executor;
 mod twins_node;
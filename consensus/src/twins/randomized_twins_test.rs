@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    test_utils::consensus_runtime,
+    twins::{
+        scenario_generator::{
+            minimize, set_partitions, RoundSchedule, Scenario, ScenarioGenerator,
+        },
+        twins_executor::run_scenario,
+    },
+};
+use consensus_types::common::Round;
+
+/// Runs the scenarios of the seeds, and panics with the first failing seed and its minimized
+/// scenario.
+fn check_seeds(generator: &ScenarioGenerator, seeds: impl IntoIterator<Item = u64>) {
+    let mut runtime = consensus_runtime();
+    for seed in seeds {
+        let scenario = generator.generate(seed);
+        if let Err(e) = run_scenario(&mut runtime, &scenario) {
+            let minimized = minimize(&scenario, |candidate| {
+                run_scenario(&mut runtime, candidate).is_err()
+            });
+            panic!(
+                "Scenario of seed {} failed: {:?}\nMinimized scenario: {:?}",
+                seed, e, minimized
+            );
+        }
+    }
+}
+
+#[test]
+fn set_partitions_test() {
+    // Bell numbers
+    assert_eq!(set_partitions(4, 4).len(), 15);
+    assert_eq!(set_partitions(5, 5).len(), 52);
+    // Stirling numbers of the second kind S(4, 1) + S(4, 2)
+    assert_eq!(set_partitions(4, 2).len(), 8);
+    for partitions in set_partitions(5, 3) {
+        assert!(partitions.len() <= 3);
+        let mut instances: Vec<_> = partitions.into_iter().flatten().collect();
+        instances.sort_unstable();
+        assert_eq!(instances, vec![0, 1, 2, 3, 4]);
+    }
+}
+
+#[test]
+fn generated_scenarios_test() {
+    let generator = ScenarioGenerator::new(4, 1, 10, 3);
+    for seed in 0..20 {
+        let scenario = generator.generate(seed);
+        assert_eq!(scenario, generator.generate(seed));
+        assert!(scenario.is_valid());
+        assert_eq!(scenario.twins.len(), 1);
+        assert_eq!(scenario.rounds.len(), 10);
+        assert_eq!(scenario.honest_nodes().len(), 3);
+    }
+    assert_ne!(generator.generate(0), generator.generate(1));
+}
+
+#[test]
+fn minimize_test() {
+    let scenario = Scenario {
+        num_nodes: 4,
+        twins: vec![0],
+        rounds: vec![
+            RoundSchedule {
+                leader: 0,
+                partitions: vec![vec![0, 1, 2, 3, 4]],
+            },
+            RoundSchedule {
+                leader: 1,
+                partitions: vec![vec![0, 1, 3], vec![2], vec![4]],
+            },
+            RoundSchedule {
+                leader: 2,
+                partitions: vec![vec![0, 1, 2, 3], vec![4]],
+            },
+        ],
+    };
+    // fails when a round separates the instances 1 and 2, with a twin
+    let fails = |scenario: &Scenario| {
+        !scenario.twins.is_empty()
+            && (1..=scenario.rounds.len() as Round).any(|round| !scenario.is_connected(round, 1, 2))
+    };
+    assert!(fails(&scenario));
+
+    let minimized = minimize(&scenario, fails);
+    assert_eq!(minimized.twins, vec![0]);
+    assert_eq!(
+        minimized.rounds,
+        vec![RoundSchedule {
+            leader: 1,
+            partitions: vec![vec![0, 1, 3, 4], vec![2]],
+        }]
+    );
+}
+
+#[test]
+/// This test checks that the nodes commit the same chain, and keep committing once the network
+/// is synchronous, for random partitions and leaders and without twins.
+///
+/// Setup:
+///
+/// 4 honest nodes, 8 scheduled rounds with at most 3 partitions.
+///
+/// Run the test:
+/// cargo xtest -p consensus randomized_no_twins_test -- --nocapture
+fn randomized_no_twins_test() {
+    check_seeds(&ScenarioGenerator::new(4, 0, 8, 3), 0..5);
+}
+
+#[test]
+/// This test checks that the honest nodes commit the same chain, and keep committing once the
+/// network is synchronous, for random partitions, leaders and placements of a twin.
+///
+/// Setup:
+///
+/// 4 nodes, one of them has a twin, 8 scheduled rounds with at most 3 partitions.
+///
+/// Run the test:
+/// cargo xtest -p consensus randomized_one_twin_test -- --nocapture
+fn randomized_one_twin_test() {
+    check_seeds(&ScenarioGenerator::new(4, 1, 8, 3), 0..10);
+}
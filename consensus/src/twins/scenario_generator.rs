@@ -0,0 +1,256 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use consensus_types::common::Round;
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+use std::collections::HashSet;
+
+/// Leader and network partitions of a round of a twins scenario.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundSchedule {
+    /// Index of the node proposing in the round, both instances of a twinned node propose.
+    pub leader: usize,
+    /// Groups of instances that can talk to each other in the round.
+    pub partitions: Vec<Vec<usize>>,
+}
+
+/// A twins scenario: the nodes running a twin, and the leader and the network partitions of
+/// each of the first rounds.
+///
+/// The instances 0..num_nodes run the nodes, the instance num_nodes + i runs the twin of the
+/// node twins[i], with the same key but its own storage. After the scheduled rounds, the network
+/// is fully connected and a node without twin proposes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scenario {
+    pub num_nodes: usize,
+    pub twins: Vec<usize>,
+    pub rounds: Vec<RoundSchedule>,
+}
+
+impl Scenario {
+    pub fn num_instances(&self) -> usize {
+        self.num_nodes + self.twins.len()
+    }
+
+    /// Index of the node run by the instance
+    pub fn node_of(&self, instance: usize) -> usize {
+        if instance < self.num_nodes {
+            instance
+        } else {
+            self.twins[instance - self.num_nodes]
+        }
+    }
+
+    /// The nodes without twin, which must stay safe and live
+    pub fn honest_nodes(&self) -> Vec<usize> {
+        (0..self.num_nodes)
+            .filter(|node| !self.twins.contains(node))
+            .collect()
+    }
+
+    /// Schedule of the given round, None after the scheduled rounds
+    pub fn round_schedule(&self, round: Round) -> Option<&RoundSchedule> {
+        if round == 0 {
+            return None;
+        }
+        self.rounds.get(round as usize - 1)
+    }
+
+    /// Whether the messages of the given round go from the instance src to the instance dst
+    pub fn is_connected(&self, round: Round, src: usize, dst: usize) -> bool {
+        self.round_schedule(round).map_or(true, |schedule| {
+            schedule
+                .partitions
+                .iter()
+                .any(|partition| partition.contains(&src) && partition.contains(&dst))
+        })
+    }
+
+    /// Every scheduled round needs a partition with a quorum of nodes, otherwise no certificate
+    /// can end the round.
+    pub fn is_valid(&self) -> bool {
+        self.twins.len() < self.num_nodes
+            && self.rounds.iter().all(|schedule| {
+                schedule.leader < self.num_nodes
+                    && schedule
+                        .partitions
+                        .iter()
+                        .any(|partition| self.has_quorum(partition))
+            })
+    }
+
+    fn has_quorum(&self, partition: &[usize]) -> bool {
+        let nodes: HashSet<_> = partition
+            .iter()
+            .map(|instance| self.node_of(*instance))
+            .collect();
+        nodes.len() >= quorum_size(self.num_nodes)
+    }
+
+    // Scenarios one step smaller: without one of the rounds, without one of the twins, or with
+    // two partitions of a round merged.
+    fn shrink_candidates(&self) -> Vec<Scenario> {
+        let mut candidates = vec![];
+        for i in 0..self.rounds.len() {
+            let mut candidate = self.clone();
+            candidate.rounds.remove(i);
+            candidates.push(candidate);
+        }
+        for i in 0..self.twins.len() {
+            candidates.push(self.without_twin(i));
+        }
+        for (r, schedule) in self.rounds.iter().enumerate() {
+            for i in 0..schedule.partitions.len() {
+                for j in i + 1..schedule.partitions.len() {
+                    let mut candidate = self.clone();
+                    let partitions = &mut candidate.rounds[r].partitions;
+                    let merged = partitions.remove(j);
+                    partitions[i].extend(merged);
+                    partitions[i].sort_unstable();
+                    candidates.push(candidate);
+                }
+            }
+        }
+        candidates
+    }
+
+    fn without_twin(&self, index: usize) -> Scenario {
+        let removed = self.num_nodes + index;
+        let mut scenario = self.clone();
+        scenario.twins.remove(index);
+        for schedule in scenario.rounds.iter_mut() {
+            for partition in schedule.partitions.iter_mut() {
+                partition.retain(|instance| *instance != removed);
+                for instance in partition.iter_mut() {
+                    if *instance > removed {
+                        *instance -= 1;
+                    }
+                }
+            }
+            schedule
+                .partitions
+                .retain(|partition| !partition.is_empty());
+        }
+        scenario
+    }
+}
+
+/// Number of nodes forming a quorum, the nodes have the same voting power
+pub fn quorum_size(num_nodes: usize) -> usize {
+    num_nodes * 2 / 3 + 1
+}
+
+/// Enumerates the partitions of the instances 0..num_instances in at most max_partitions groups.
+pub fn set_partitions(num_instances: usize, max_partitions: usize) -> Vec<Vec<Vec<usize>>> {
+    fn extend(
+        instance: usize,
+        num_instances: usize,
+        max_partitions: usize,
+        partitions: &mut Vec<Vec<usize>>,
+        result: &mut Vec<Vec<Vec<usize>>>,
+    ) {
+        if instance == num_instances {
+            result.push(partitions.clone());
+            return;
+        }
+        for i in 0..partitions.len() {
+            partitions[i].push(instance);
+            extend(
+                instance + 1,
+                num_instances,
+                max_partitions,
+                partitions,
+                result,
+            );
+            partitions[i].pop();
+        }
+        if partitions.len() < max_partitions {
+            partitions.push(vec![instance]);
+            extend(
+                instance + 1,
+                num_instances,
+                max_partitions,
+                partitions,
+                result,
+            );
+            partitions.pop();
+        }
+    }
+
+    let mut result = vec![];
+    extend(0, num_instances, max_partitions, &mut vec![], &mut result);
+    result
+}
+
+/// Generates the scenarios of a given shape from seeds: the twinned nodes, and for each round a
+/// random leader and one of the partitions of the instances having a quorum.
+pub struct ScenarioGenerator {
+    num_nodes: usize,
+    num_twins: usize,
+    num_rounds: usize,
+    // the partitions of the instances in at most max_partitions groups
+    partitions: Vec<Vec<Vec<usize>>>,
+}
+
+impl ScenarioGenerator {
+    pub fn new(
+        num_nodes: usize,
+        num_twins: usize,
+        num_rounds: usize,
+        max_partitions: usize,
+    ) -> Self {
+        assert!(
+            num_twins < num_nodes,
+            "At least one node must run without twin"
+        );
+        Self {
+            num_nodes,
+            num_twins,
+            num_rounds,
+            partitions: set_partitions(num_nodes + num_twins, max_partitions),
+        }
+    }
+
+    /// The scenario of the seed, the same seed always generates the same scenario.
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut twins = index::sample(&mut rng, self.num_nodes, self.num_twins).into_vec();
+        twins.sort_unstable();
+        let mut scenario = Scenario {
+            num_nodes: self.num_nodes,
+            twins,
+            rounds: vec![],
+        };
+        let partitions: Vec<_> = self
+            .partitions
+            .iter()
+            .filter(|partitions| {
+                partitions
+                    .iter()
+                    .any(|partition| scenario.has_quorum(partition))
+            })
+            .collect();
+        scenario.rounds = (0..self.num_rounds)
+            .map(|_| RoundSchedule {
+                leader: rng.gen_range(0, self.num_nodes),
+                partitions: partitions[rng.gen_range(0, partitions.len())].clone(),
+            })
+            .collect();
+        scenario
+    }
+}
+
+/// Shrinks a failing scenario by dropping rounds and twins and merging partitions, as long as
+/// it keeps failing. Returns a scenario none of these steps can shrink.
+pub fn minimize(scenario: &Scenario, mut fails: impl FnMut(&Scenario) -> bool) -> Scenario {
+    let mut current = scenario.clone();
+    'shrink: loop {
+        for candidate in current.shrink_candidates() {
+            if candidate.is_valid() && fails(&candidate) {
+                current = candidate;
+                continue 'shrink;
+            }
+        }
+        return current;
+    }
+}
@@ -0,0 +1,512 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockStore,
+    liveness::{
+        proposal_generator::ProposalGenerator,
+        round_proposer_election::RoundProposer,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    metrics_safety_rules::MetricsSafetyRules,
+    network::{IncomingBlockRetrievalRequest, NetworkSender},
+    network_interface::{ConsensusMsg, ConsensusNetworkSender},
+    network_tests::{NetworkPlayground, TwinId},
+    round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
+    state_replication::StateComputer,
+    test_utils::{timed_block_on, MockPayloadManager, MockStateComputer, MockStorage},
+    twins::scenario_generator::Scenario,
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use anyhow::{bail, ensure};
+use aptos_config::{config::ConsensusConfig, network_id::NetworkId};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_secure_storage::Storage;
+use aptos_types::{
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::OnChainConsensusConfig,
+    transaction::SignedTransaction,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
+    waypoint::Waypoint,
+    PeerId,
+};
+use channel::{self, aptos_channel, message_queues::QueueStyle};
+use consensus_types::common::{Author, Round};
+use futures::{channel::mpsc, executor::block_on, future, pin_mut, FutureExt, StreamExt};
+use network::{
+    application::storage::PeerMetadataStorage,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::{
+        network::{Event, NewNetworkSender, SerializedRequest},
+        wire::handshake::v1::ProtocolIdSet,
+    },
+    transport::ConnectionMetadata,
+    ProtocolId,
+};
+use safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::Runtime;
+
+/// Rounds after the scheduled ones for the honest nodes to commit a new block
+const LIVENESS_ROUNDS: Round = 10;
+/// Bounds the runs not making progress
+const MAX_TIMEOUTS: usize = 200;
+const MAX_DELIVERIES: usize = 200_000;
+/// Simulated time elapsed for each delivered message, the timestamps of a chain must increase
+const DELIVERY_TIME: Duration = Duration::from_millis(1);
+const ROUND_TIMEOUT: Duration = Duration::from_secs(60);
+const CHANNEL_SIZE: usize = 1_024;
+
+enum Input {
+    Message(Author, ConsensusMsg),
+    LocalTimeout,
+}
+
+/// An instance of a node, its inputs and outputs go through the executor.
+struct TwinsNode {
+    twin_id: TwinId,
+    block_store: Arc<BlockStore>,
+    round_manager: RoundManager,
+    network_reqs_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    self_receiver: channel::Receiver<Event<ConsensusMsg>>,
+    commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    _state_sync_receiver: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+    _safety_rules_manager: SafetyRulesManager,
+    // the blocks committed by the instance, in order
+    committed: Vec<BlockInfo>,
+}
+
+impl TwinsNode {
+    fn new(
+        twin_id: TwinId,
+        signer: &ValidatorSigner,
+        validators: &ValidatorVerifier,
+        waypoint: Waypoint,
+        proposer_election: RoundProposer,
+        time_service: Arc<dyn TimeService>,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+    ) -> Self {
+        let (initial_data, storage) = MockStorage::start_for_testing(validators.into());
+        let safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(aptos_secure_storage::InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            waypoint,
+            true,
+        );
+        let safety_rules_manager = SafetyRulesManager::new_local(safety_storage);
+        let epoch_state = EpochState {
+            epoch: 1,
+            verifier: storage.get_validator_set().into(),
+        };
+
+        let (network_reqs_tx, network_reqs_rx) =
+            aptos_channel::new(QueueStyle::FIFO, CHANNEL_SIZE, None);
+        let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let mut network_sender = ConsensusNetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        network_sender.initialize(peer_metadata_storage);
+        let (self_sender, self_receiver) = channel::new_test(CHANNEL_SIZE);
+        let network = NetworkSender::new(
+            twin_id.author,
+            network_sender,
+            self_sender,
+            validators.clone(),
+        );
+
+        let last_vote_sent = initial_data.last_vote();
+        let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded();
+        let (state_sync_client, _state_sync_receiver) = mpsc::unbounded();
+        let state_computer = Arc::new(MockStateComputer::new(
+            state_sync_client,
+            commit_cb_sender,
+            Arc::clone(&storage),
+        ));
+        state_computer.new_epoch(&epoch_state, Arc::new(MockPayloadManager::new(None)));
+        let block_store = Arc::new(BlockStore::new(
+            storage.clone(),
+            initial_data,
+            state_computer,
+            10, // max pruned blocks in mem
+            time_service.clone(),
+            10,
+        ));
+        let proposal_generator = ProposalGenerator::new(
+            twin_id.author,
+            block_store.clone(),
+            Arc::new(MockPayloadManager::new(None)),
+            time_service.clone(),
+            10,
+            1000,
+            10,
+        );
+        // the executor fires the local timeouts, the scheduled ones never run on the simulated time
+        let (round_timeout_sender, _) = channel::new_test(CHANNEL_SIZE);
+        let round_state = RoundState::new(
+            Box::new(ExponentialTimeInterval::fixed(ROUND_TIMEOUT)),
+            time_service,
+            round_timeout_sender,
+        );
+        let mut safety_rules =
+            MetricsSafetyRules::new(safety_rules_manager.client(), storage.clone());
+        safety_rules.perform_initialize().unwrap();
+        let (round_manager_tx, _) = aptos_channel::new(QueueStyle::LIFO, 1, None);
+
+        let mut round_manager = RoundManager::new(
+            epoch_state,
+            Arc::clone(&block_store),
+            round_state,
+            Box::new(proposer_election),
+            proposal_generator,
+            Arc::new(Mutex::new(safety_rules)),
+            network,
+            storage,
+            OnChainConsensusConfig::default(),
+            round_manager_tx,
+            ConsensusConfig::default(),
+        );
+        block_on(round_manager.init(last_vote_sent));
+        Self {
+            twin_id,
+            block_store,
+            round_manager,
+            network_reqs_rx,
+            self_receiver,
+            commit_cb_receiver,
+            _state_sync_receiver,
+            _safety_rules_manager: safety_rules_manager,
+            committed: vec![],
+        }
+    }
+}
+
+/// Runs a twins scenario against `RoundManager`s deterministically, on a single task and the
+/// simulated time. The messages are delivered one at a time in the order they are sent, the
+/// messages of a scheduled round are dropped between its partitions, and the local timeouts fire
+/// whenever no message is in flight. Like in the `NetworkPlayground`, the block retrievals
+/// ignore the partitions.
+pub struct TwinsExecutor {
+    scenario: Scenario,
+    nodes: Vec<TwinsNode>,
+    // the instances of the nodes serve the block retrievals, rather than their twins
+    block_stores: HashMap<Author, Arc<BlockStore>>,
+    verifier: ValidatorVerifier,
+    time_service: SimulatedTimeService,
+    // (sender, recipient instance, message)
+    in_flight: VecDeque<(Author, usize, ConsensusMsg)>,
+    // round and parent id of the proposed and voted blocks
+    blocks: HashMap<HashValue, (Round, HashValue)>,
+}
+
+impl TwinsExecutor {
+    pub fn new(scenario: Scenario) -> Self {
+        assert!(scenario.is_valid(), "Invalid scenario {:?}", scenario);
+        let (signers, verifier) = random_validator_verifier(scenario.num_nodes, None, false);
+        let waypoint =
+            Waypoint::new_epoch_boundary(&LedgerInfo::mock_genesis(Some((&verifier).into())))
+                .unwrap();
+        let peer_metadata_storage = PeerMetadataStorage::new(&[NetworkId::Validator]);
+        for signer in signers.iter() {
+            let mut conn_meta = ConnectionMetadata::mock(signer.author());
+            conn_meta.application_protocols = ProtocolIdSet::from_iter([
+                ProtocolId::ConsensusDirectSendJson,
+                ProtocolId::ConsensusDirectSendBcs,
+                ProtocolId::ConsensusRpcBcs,
+            ]);
+            peer_metadata_storage.insert_connection(NetworkId::Validator, conn_meta);
+        }
+
+        let proposers: HashMap<Round, Author> = scenario
+            .rounds
+            .iter()
+            .enumerate()
+            .map(|(i, schedule)| (i as Round + 1, signers[schedule.leader].author()))
+            .collect();
+        let default_proposer = signers[scenario.honest_nodes()[0]].author();
+        // the first proposals must be more recent than the genesis
+        let time_service = SimulatedTimeService::new();
+        block_on(time_service.sleep(Duration::from_secs(1)));
+
+        let nodes: Vec<_> = (0..scenario.num_instances())
+            .map(|instance| {
+                let signer = &signers[scenario.node_of(instance)];
+                TwinsNode::new(
+                    TwinId {
+                        id: instance,
+                        author: signer.author(),
+                    },
+                    signer,
+                    &verifier,
+                    waypoint,
+                    RoundProposer::new(proposers.clone(), default_proposer),
+                    Arc::new(time_service.clone()),
+                    peer_metadata_storage.clone(),
+                )
+            })
+            .collect();
+        let block_stores = nodes
+            .iter()
+            .take(scenario.num_nodes)
+            .map(|node| (node.twin_id.author, node.block_store.clone()))
+            .collect();
+
+        Self {
+            scenario,
+            nodes,
+            block_stores,
+            verifier,
+            time_service,
+            in_flight: VecDeque::new(),
+            blocks: HashMap::new(),
+        }
+    }
+
+    /// Runs the scenario until all the honest nodes commit a block of a round after the scheduled
+    /// ones. Returns an error if two honest instances commit conflicting blocks, or if the honest
+    /// nodes don't commit within LIVENESS_ROUNDS rounds once the network is synchronous.
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let last_scheduled_round = self.scenario.rounds.len() as Round;
+        // the outputs of the first round
+        for instance in 0..self.nodes.len() {
+            self.collect_outputs(instance, vec![]).await;
+        }
+
+        let (mut deliveries, mut timeouts) = (0, 0);
+        loop {
+            self.check_safety()?;
+            if self
+                .honest_nodes()
+                .all(|node| Self::committed_round(node) > last_scheduled_round)
+            {
+                return Ok(());
+            }
+            let highest_round = self
+                .honest_nodes()
+                .map(|node| node.round_manager.round_state().current_round())
+                .max()
+                .unwrap_or_default();
+            if highest_round > last_scheduled_round + LIVENESS_ROUNDS
+                || deliveries > MAX_DELIVERIES
+                || timeouts > MAX_TIMEOUTS
+            {
+                bail!(
+                    "Liveness violation: (round, committed round) of the honest nodes {:?} after {} messages and {} timeouts",
+                    self.honest_nodes()
+                        .map(|node| (
+                            node.round_manager.round_state().current_round(),
+                            Self::committed_round(node)
+                        ))
+                        .collect::<Vec<_>>(),
+                    deliveries,
+                    timeouts,
+                );
+            }
+
+            match self.in_flight.pop_front() {
+                Some((author, instance, msg)) => {
+                    deliveries += 1;
+                    self.time_service.sleep(DELIVERY_TIME).await;
+                    self.process(instance, Input::Message(author, msg)).await;
+                }
+                None => {
+                    timeouts += 1;
+                    for instance in 0..self.nodes.len() {
+                        self.process(instance, Input::LocalTimeout).await;
+                    }
+                }
+            }
+        }
+    }
+
+    fn honest_nodes(&self) -> impl Iterator<Item = &TwinsNode> {
+        let twins = &self.scenario.twins;
+        self.nodes
+            .iter()
+            .take(self.scenario.num_nodes)
+            .filter(move |node| !twins.contains(&node.twin_id.id))
+    }
+
+    fn committed_round(node: &TwinsNode) -> Round {
+        node.committed.last().map_or(0, |info| info.round())
+    }
+
+    /// The blocks committed by the honest instances must form a single chain.
+    fn check_safety(&self) -> anyhow::Result<()> {
+        let mut commits: Vec<_> = self
+            .honest_nodes()
+            .flat_map(|node| node.committed.iter())
+            .collect();
+        commits.sort_by_key(|info| info.round());
+        commits.dedup_by_key(|info| info.id());
+        for (lower, higher) in commits.iter().zip(commits.iter().skip(1)) {
+            ensure!(
+                self.extends(higher.id(), lower),
+                "Safety violation: conflicting commits {} and {}",
+                lower,
+                higher
+            );
+        }
+        Ok(())
+    }
+
+    fn extends(&self, mut block_id: HashValue, ancestor: &BlockInfo) -> bool {
+        while block_id != ancestor.id() {
+            match self.blocks.get(&block_id) {
+                Some((round, parent_id)) if *round > ancestor.round() => block_id = *parent_id,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Processes an input of the instance, answering its block retrievals meanwhile.
+    async fn process(&mut self, instance: usize, input: Input) {
+        let mut outbox = vec![];
+        {
+            let node = &mut self.nodes[instance];
+            let process = Self::process_input(&mut node.round_manager, &self.verifier, input);
+            let serve =
+                Self::serve_requests(&mut node.network_reqs_rx, &self.block_stores, &mut outbox);
+            pin_mut!(process, serve);
+            // the errors are part of the scenario, e.g. stale messages and timeouts
+            future::select(process, serve).await;
+        }
+        self.collect_outputs(instance, outbox).await;
+    }
+
+    async fn process_input(
+        round_manager: &mut RoundManager,
+        verifier: &ValidatorVerifier,
+        input: Input,
+    ) -> anyhow::Result<()> {
+        let (author, msg) = match input {
+            Input::Message(author, msg) => (author, msg),
+            Input::LocalTimeout => {
+                let round = round_manager.round_state().current_round();
+                return round_manager.process_local_timeout(round).await;
+            }
+        };
+        let event = match msg {
+            ConsensusMsg::ProposalMsg(_) | ConsensusMsg::VoteMsg(_) | ConsensusMsg::SyncInfo(_) => {
+                UnverifiedEvent::from(msg).verify(verifier)?
+            }
+            _ => bail!("Unexpected message {:?}", msg),
+        };
+        match event {
+            VerifiedEvent::ProposalMsg(proposal_msg) => {
+                round_manager.process_proposal_msg(*proposal_msg).await
+            }
+            VerifiedEvent::VoteMsg(vote_msg) => round_manager.process_vote_msg(*vote_msg).await,
+            VerifiedEvent::UnverifiedSyncInfo(sync_info) => {
+                round_manager
+                    .process_sync_info_msg(*sync_info, author)
+                    .await
+            }
+            event => bail!("Unexpected event {:?}", event),
+        }
+    }
+
+    async fn serve_requests(
+        network_reqs_rx: &mut aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+        block_stores: &HashMap<Author, Arc<BlockStore>>,
+        outbox: &mut Vec<(Author, ConsensusMsg)>,
+    ) {
+        while let Some(request) = network_reqs_rx.next().await {
+            Self::serve_request(request, block_stores, outbox).await;
+        }
+    }
+
+    /// Queues the direct sends, and answers the block retrievals right away.
+    async fn serve_request(
+        request: PeerManagerRequest,
+        block_stores: &HashMap<Author, Arc<BlockStore>>,
+        outbox: &mut Vec<(Author, ConsensusMsg)>,
+    ) {
+        match request {
+            PeerManagerRequest::SendDirectSend(recipient, msg) => {
+                outbox.push((recipient, msg.to_message::<ConsensusMsg>().unwrap()));
+            }
+            PeerManagerRequest::SendRpc(recipient, request) => {
+                // the dropped response sender fails the other requests
+                if let Ok(ConsensusMsg::BlockRetrievalRequest(req)) =
+                    request.to_message::<ConsensusMsg>()
+                {
+                    let _ = block_stores[&recipient]
+                        .process_block_retrieval(IncomingBlockRetrievalRequest {
+                            req: *req,
+                            protocol: request.protocol_id,
+                            response_sender: request.res_tx,
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Collects the commits and the messages sent by the instance, and queues the messages the
+    /// partitions of their round let through.
+    async fn collect_outputs(&mut self, instance: usize, mut outbox: Vec<(Author, ConsensusMsg)>) {
+        let node = &mut self.nodes[instance];
+        let author = node.twin_id.author;
+        while let Some(Some(request)) = node.network_reqs_rx.next().now_or_never() {
+            Self::serve_request(request, &self.block_stores, &mut outbox).await;
+        }
+        while let Some(Some(commit)) = node.commit_cb_receiver.next().now_or_never() {
+            node.committed.push(commit.commit_info().clone());
+        }
+        while let Some(Some(event)) = node.self_receiver.next().now_or_never() {
+            if let Event::Message(_, msg) = event {
+                Self::record_blocks(&mut self.blocks, &msg);
+                self.in_flight.push_back((author, instance, msg));
+            }
+        }
+
+        for (recipient, msg) in outbox {
+            Self::record_blocks(&mut self.blocks, &msg);
+            let round = NetworkPlayground::get_message_round(msg.clone());
+            for node in self.nodes.iter() {
+                let dst = node.twin_id.id;
+                if node.twin_id.author == recipient
+                    && round.map_or(true, |round| {
+                        self.scenario.is_connected(round, instance, dst)
+                    })
+                {
+                    self.in_flight.push_back((author, dst, msg.clone()));
+                }
+            }
+        }
+    }
+
+    fn record_blocks(blocks: &mut HashMap<HashValue, (Round, HashValue)>, msg: &ConsensusMsg) {
+        match msg {
+            ConsensusMsg::ProposalMsg(proposal_msg) => {
+                let proposal = proposal_msg.proposal();
+                blocks.insert(proposal.id(), (proposal.round(), proposal.parent_id()));
+            }
+            ConsensusMsg::VoteMsg(vote_msg) => {
+                let vote_data = vote_msg.vote().vote_data();
+                blocks.insert(
+                    vote_data.proposed().id(),
+                    (vote_data.proposed().round(), vote_data.parent().id()),
+                );
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Runs the scenario on the runtime, see `TwinsExecutor::run`.
+pub fn run_scenario(runtime: &mut Runtime, scenario: &Scenario) -> anyhow::Result<()> {
+    let executor = TwinsExecutor::new(scenario.clone());
+    timed_block_on(runtime, executor.run())
+}
@@ -17,6 +17,7 @@ use aptos_config::{
     network_id::NetworkId,
 };
 use aptos_mempool::mocks::MockSharedMempool;
+use aptos_temppath::TempPath;
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{
@@ -49,9 +50,10 @@ pub struct SMRNode {
     pub id: TwinId,
     pub storage: Arc<MockStorage>,
     pub commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    pub shared_mempool: MockSharedMempool,
+    pub state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
     _runtime: Runtime,
-    _shared_mempool: MockSharedMempool,
-    _state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+    _data_dir: TempPath,
 }
 
 fn author_from_config(config: &NodeConfig) -> Author {
@@ -65,6 +67,7 @@ impl SMRNode {
         consensus_config: OnChainConsensusConfig,
         storage: Arc<MockStorage>,
         twin_id: TwinId,
+        data_dir: TempPath,
     ) -> Self {
         let (network_reqs_tx, network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
         let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
@@ -83,7 +86,12 @@ impl SMRNode {
         let (state_sync_client, state_sync) = mpsc::unbounded();
         let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
         let shared_mempool = MockSharedMempool::new();
-        let (quorum_store_to_mempool_sender, _) = mpsc::channel(1_024);
+        // the quorum store batches the txns of the mempool, direct mempool proposals are empty
+        let quorum_store_to_mempool_sender = if config.consensus.use_quorum_store {
+            shared_mempool.consensus_to_mempool_sender.clone()
+        } else {
+            mpsc::channel(1_024).0
+        };
         let state_computer = Arc::new(MockStateComputer::new(
             state_sync_client,
             commit_cb_sender,
@@ -144,6 +152,7 @@ impl SMRNode {
             storage.clone(),
             reconfig_listener,
             commit_notifier,
+            None,
         );
         let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
 
@@ -151,11 +160,12 @@ impl SMRNode {
         runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
         Self {
             id: twin_id,
-            _runtime: runtime,
             commit_cb_receiver,
             storage,
-            _shared_mempool: shared_mempool,
-            _state_sync: state_sync,
+            shared_mempool,
+            state_sync,
+            _runtime: runtime,
+            _data_dir: data_dir,
         }
     }
 
@@ -166,6 +176,7 @@ impl SMRNode {
         playground: &mut NetworkPlayground,
         proposer_type: ProposerElectionType,
         round_proposers_idx: Option<HashMap<Round, usize>>,
+        use_quorum_store: bool,
     ) -> Vec<Self> {
         assert!(num_nodes >= num_twins);
         let ValidatorSwarm {
@@ -239,6 +250,11 @@ impl SMRNode {
             config.base.waypoint = WaypointConfig::FromConfig(waypoint);
             // Disable timeout in twins test to avoid flakiness
             config.consensus.round_initial_timeout_ms = 2_000_000;
+            config.consensus.use_quorum_store = use_quorum_store;
+            // twins need their own quorum store db
+            let data_dir = TempPath::new();
+            data_dir.create_as_dir().unwrap();
+            config.storage.set_data_dir(data_dir.path().to_path_buf());
 
             let author = author_from_config(&config);
 
@@ -255,6 +271,7 @@ impl SMRNode {
                 consensus_config,
                 storage,
                 twin_id,
+                data_dir,
             ));
         }
         smr_nodes
@@ -0,0 +1,134 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Executes blocks on top of a genesis transaction, with a mock consensus that has each validator
+//! propose in turn and commits every block right away.  This catches a genesis that produces a
+//! chain which can't make progress, e.g. because the validator set is empty or the block prologue
+//! aborts, before any node is started with it.
+
+use anyhow::{anyhow, bail, ensure};
+use aptos_config::config::{
+    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    block_metadata::BlockMetadata,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::{ExecutionStatus, Transaction, TransactionStatus, Version},
+};
+use aptos_vm::AptosVM;
+use aptosdb::AptosDB;
+use executor::{
+    block_executor::BlockExecutor,
+    db_bootstrapper::{generate_waypoint, maybe_bootstrap},
+};
+use executor_types::BlockExecutorTrait;
+use storage_interface::DbReaderWriter;
+
+/// Time between two blocks of the dry run
+const BLOCK_INTERVAL_USECS: u64 = 1_000_000;
+
+/// Bootstraps a temporary DB with the genesis transaction and executes and commits `num_blocks`
+/// empty blocks on top of it.  Returns the version of the last committed block.
+pub fn execute_blocks_after_genesis(
+    genesis: &Transaction,
+    num_blocks: u64,
+) -> anyhow::Result<Version> {
+    let path = TempPath::new();
+    let aptosdb = AptosDB::open(
+        &path,
+        false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )?;
+    let db_rw = DbReaderWriter::new(aptosdb);
+    let waypoint = generate_waypoint::<AptosVM>(&db_rw, genesis)?;
+    ensure!(
+        maybe_bootstrap::<AptosVM>(&db_rw, genesis, waypoint)?,
+        "Genesis was not committed to the empty DB"
+    );
+
+    let mut epoch_state = db_rw
+        .reader
+        .get_latest_ledger_info()?
+        .ledger_info()
+        .next_epoch_state()
+        .cloned()
+        .ok_or_else(|| anyhow!("Genesis did not start an epoch"))?;
+
+    let executor = BlockExecutor::<AptosVM>::new(db_rw);
+    let mut parent_block_id = executor.committed_block_id();
+    let mut version = 0;
+    for round in 1..=num_blocks {
+        let proposers = proposers(&epoch_state)?;
+        let proposer = proposers[round as usize % proposers.len()];
+        let block_id = HashValue::sha3_256_of(&round.to_le_bytes());
+        let timestamp_usecs = round * BLOCK_INTERVAL_USECS;
+        let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+            block_id,
+            epoch_state.epoch,
+            round,
+            proposer,
+            vec![],
+            vec![],
+            timestamp_usecs,
+        ));
+
+        let output = executor.execute_block((block_id, vec![block_metadata]), parent_block_id)?;
+        match output.compute_status().first() {
+            Some(TransactionStatus::Keep(ExecutionStatus::Success)) => {}
+            status => bail!(
+                "Block {} proposed by {} failed to execute: {:?}",
+                round,
+                proposer,
+                status
+            ),
+        }
+
+        let ledger_info = LedgerInfo::new(
+            BlockInfo::new(
+                epoch_state.epoch,
+                round,
+                block_id,
+                output.root_hash(),
+                output.version(),
+                timestamp_usecs,
+                output.epoch_state().clone(),
+            ),
+            HashValue::zero(),
+        );
+        executor.commit_blocks(
+            vec![block_id],
+            LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty()),
+        )?;
+
+        if let Some(next_epoch_state) = output.epoch_state() {
+            epoch_state = next_epoch_state.clone();
+        }
+        parent_block_id = block_id;
+        version = output.version();
+    }
+    Ok(version)
+}
+
+fn proposers(epoch_state: &EpochState) -> anyhow::Result<Vec<AccountAddress>> {
+    let proposers: Vec<_> = epoch_state
+        .verifier
+        .get_ordered_account_addresses_iter()
+        .collect();
+    ensure!(
+        !proposers.is_empty(),
+        "The validator set of epoch {} is empty",
+        epoch_state.epoch
+    );
+    Ok(proposers)
+}
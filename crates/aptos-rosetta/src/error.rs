@@ -283,10 +283,19 @@ impl From<RestError> for ApiError {
                     ApiError::SequenceNumberTooOld(Some(err.error.message))
                 }
                 AptosErrorCode::VmError => ApiError::VmError(Some(err.error.message)),
+                AptosErrorCode::ReplacementUnderpriced => {
+                    ApiError::InvalidInput(Some(err.error.message))
+                }
                 AptosErrorCode::HealthCheckFailed => {
                     ApiError::InternalError(Some(err.error.message))
                 }
                 AptosErrorCode::MempoolIsFull => ApiError::MempoolIsFull(Some(err.error.message)),
+                AptosErrorCode::EvictionUnderpriced => {
+                    ApiError::MempoolIsFull(Some(err.error.message))
+                }
+                AptosErrorCode::AccountFairnessCapReached => {
+                    ApiError::MempoolIsFull(Some(err.error.message))
+                }
                 AptosErrorCode::WebFrameworkError => {
                     ApiError::InternalError(Some(err.error.message))
                 }
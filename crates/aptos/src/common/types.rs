@@ -1214,6 +1214,34 @@ impl TransactionOptions {
         )
     }
 
+    /// Retrieves the public key and the address of the sender, for transactions which are only
+    /// simulated, and so don't need the private key of the profile
+    pub fn get_public_key_and_address(&self) -> CliTypedResult<(Ed25519PublicKey, AccountAddress)> {
+        if let Some(key) = self
+            .private_key_options
+            .extract_private_key_cli(self.encoding_options.encoding)?
+        {
+            let public_key = key.public_key();
+            let address = self
+                .sender_account
+                .unwrap_or_else(|| account_address_from_public_key(&public_key));
+            return Ok((public_key, address));
+        }
+
+        let profile = self.profile_options.profile()?;
+        let public_key = profile.public_key.ok_or_else(|| {
+            CliError::CommandArgumentError(format!(
+                "Profile {} has no public key, one of ['--private-key', '--private-key-file'] must be used",
+                self.profile_options.profile
+            ))
+        })?;
+        let address = self
+            .sender_account
+            .or(profile.account)
+            .unwrap_or_else(|| account_address_from_public_key(&public_key));
+        Ok((public_key, address))
+    }
+
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
         Ok(self.get_key_and_address()?.1)
     }
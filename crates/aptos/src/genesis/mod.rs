@@ -23,6 +23,7 @@ use aptos_genesis::builder::GenesisConfiguration;
 use aptos_genesis::config::{
     AccountBalanceMap, EmployeePoolMap, StringOperatorConfiguration, StringOwnerConfiguration,
 };
+use aptos_genesis::dry_run::execute_blocks_after_genesis;
 use aptos_genesis::{
     config::{Layout, ValidatorConfiguration},
     mainnet::MainnetGenesisInfo,
@@ -30,8 +31,12 @@ use aptos_genesis::{
 };
 use aptos_logger::info;
 use aptos_types::account_address::AccountAddress;
+use aptos_types::transaction::authenticator::AuthenticationKey;
+use aptos_types::transaction::Version;
+use aptos_types::waypoint::Waypoint;
 use async_trait::async_trait;
 use clap::Parser;
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::{path::PathBuf, str::FromStr};
@@ -52,6 +57,7 @@ pub enum GenesisTool {
     GenerateAdminWriteSet(keys::GenerateAdminWriteSet),
     SetupGit(git::SetupGit),
     SetValidatorConfiguration(keys::SetValidatorConfiguration),
+    Validate(ValidateGenesis),
 }
 
 impl GenesisTool {
@@ -63,6 +69,7 @@ impl GenesisTool {
             GenesisTool::GenerateAdminWriteSet(tool) => tool.execute_serialized_success().await,
             GenesisTool::SetupGit(tool) => tool.execute_serialized_success().await,
             GenesisTool::SetValidatorConfiguration(tool) => tool.execute_serialized_success().await,
+            GenesisTool::Validate(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -120,6 +127,252 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
     }
 }
 
+/// Validate the genesis inputs in a git repository, and check that the chain can start from them
+///
+/// Checks every validator configuration, collecting all problems instead of stopping at the
+/// first: proofs of possession of the consensus keys, network addresses, stake within the min and
+/// max stake, unique keys, and that owner, operator and voter accounts are consistent.  Then
+/// builds genesis in memory and executes blocks on top of it with a mock consensus.
+#[derive(Parser)]
+pub struct ValidateGenesis {
+    /// Whether this is mainnet genesis.
+    ///
+    /// Default is false
+    #[clap(long)]
+    mainnet: bool,
+
+    /// Number of blocks to execute on top of genesis
+    #[clap(long, default_value = "10")]
+    num_blocks: u64,
+
+    #[clap(flatten)]
+    git_options: GitOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenesisValidation {
+    pub num_validators: usize,
+    pub num_validators_joining_during_genesis: usize,
+    pub waypoint: Waypoint,
+    pub num_blocks_executed: u64,
+    pub last_version: Version,
+}
+
+#[async_trait]
+impl CliCommand<GenesisValidation> for ValidateGenesis {
+    fn command_name(&self) -> &'static str {
+        "ValidateGenesis"
+    }
+
+    async fn execute(self) -> CliTypedResult<GenesisValidation> {
+        let client = self.git_options.clone().get_client()?;
+        let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+        let validators =
+            get_validator_configs(&client, &layout, self.mainnet).map_err(parse_error)?;
+
+        check_validator_configurations(&layout, &validators, self.mainnet)?;
+
+        // Building genesis runs the remaining checks, e.g. of balances and employee pools
+        let (genesis, waypoint) = if self.mainnet {
+            let mut mainnet_genesis = fetch_mainnet_genesis_info(self.git_options)?;
+            (
+                mainnet_genesis.get_genesis().clone(),
+                mainnet_genesis.generate_waypoint()?,
+            )
+        } else {
+            let mut test_genesis = fetch_genesis_info(self.git_options)?;
+            (
+                test_genesis.get_genesis().clone(),
+                test_genesis.generate_waypoint()?,
+            )
+        };
+        let last_version =
+            execute_blocks_after_genesis(&genesis, self.num_blocks).map_err(|err| {
+                CliError::UnexpectedError(format!("Chain failed to make progress: {:#}", err))
+            })?;
+
+        Ok(GenesisValidation {
+            num_validators: validators.len(),
+            num_validators_joining_during_genesis: validators
+                .iter()
+                .filter(|validator| validator.join_during_genesis)
+                .count(),
+            waypoint,
+            num_blocks_executed: self.num_blocks,
+            last_version,
+        })
+    }
+}
+
+/// Checks the validator configurations against the layout and each other, failing with every
+/// problem found
+fn check_validator_configurations(
+    layout: &Layout,
+    validators: &[ValidatorConfiguration],
+    is_mainnet: bool,
+) -> CliTypedResult<()> {
+    let mut errors = vec![];
+    let mut owners = BTreeMap::new();
+    let mut consensus_keys = BTreeMap::new();
+    let mut network_keys = BTreeMap::new();
+
+    for (i, validator) in validators.iter().enumerate() {
+        let name = format!("Validator #{} ({})", i, validator.owner_account_address);
+
+        if let Some(other) = owners.insert(validator.owner_account_address, i) {
+            errors.push(format!(
+                "{} has the same owner as validator #{}",
+                name, other
+            ));
+        }
+        if validator.stake_amount < layout.min_stake {
+            errors.push(format!(
+                "{} has stake {} under the min stake {}",
+                name, validator.stake_amount, layout.min_stake
+            ));
+        }
+        if validator.stake_amount > layout.max_stake {
+            errors.push(format!(
+                "{} has stake {} over the max stake {}",
+                name, validator.stake_amount, layout.max_stake
+            ));
+        }
+        if validator.commission_percentage > 100 {
+            errors.push(format!(
+                "{} has a commission percentage {} over 100",
+                name, validator.commission_percentage
+            ));
+        }
+
+        // The same account must have the same key in every role, and on test chains accounts are
+        // created with the authentication key derived from their address
+        let accounts = [
+            (
+                "owner",
+                validator.owner_account_address,
+                &validator.owner_account_public_key,
+            ),
+            (
+                "operator",
+                validator.operator_account_address,
+                &validator.operator_account_public_key,
+            ),
+            (
+                "voter",
+                validator.voter_account_address,
+                &validator.voter_account_public_key,
+            ),
+        ];
+        for (j, (role, address, public_key)) in accounts.iter().enumerate() {
+            for (other_role, other_address, other_public_key) in &accounts[j + 1..] {
+                if address == other_address && public_key != other_public_key {
+                    errors.push(format!(
+                        "{} has the same {} and {} account {}, but with different public keys",
+                        name, role, other_role, address
+                    ));
+                }
+            }
+            if !is_mainnet && AuthenticationKey::ed25519(public_key).derived_address() != *address {
+                errors.push(format!(
+                    "{} has {} account {} which is not derived from its public key {}",
+                    name, role, address, public_key
+                ));
+            }
+        }
+
+        if !validator.join_during_genesis {
+            continue;
+        }
+        match (
+            validator.consensus_public_key.as_ref(),
+            validator.proof_of_possession.as_ref(),
+        ) {
+            (Some(consensus_public_key), Some(proof_of_possession)) => {
+                if let Err(err) = proof_of_possession.verify(consensus_public_key) {
+                    errors.push(format!(
+                        "{} has a proof of possession that doesn't match its consensus key: {}",
+                        name, err
+                    ));
+                }
+                if let Some(other) = consensus_keys.insert(consensus_public_key.to_bytes(), i) {
+                    errors.push(format!(
+                        "{} has the same consensus key as validator #{}",
+                        name, other
+                    ));
+                }
+            }
+            _ => errors.push(format!(
+                "{} is missing its consensus key or proof of possession",
+                name
+            )),
+        }
+
+        let hosts = [
+            (
+                "validator",
+                validator.validator_host.as_ref(),
+                validator.validator_network_public_key,
+            ),
+            (
+                "full node",
+                validator.full_node_host.as_ref(),
+                validator.full_node_network_public_key,
+            ),
+        ];
+        for (role, host, public_key) in hosts {
+            let public_key = match (host, public_key) {
+                (Some(host), Some(public_key)) => {
+                    if let Err(err) = host.as_network_address(public_key) {
+                        errors.push(format!(
+                            "{} has an invalid {} network address {:?}: {}",
+                            name, role, host, err
+                        ));
+                    }
+                    public_key
+                }
+                (None, None) if role == "full node" => continue,
+                (None, None) => {
+                    errors.push(format!(
+                        "{} is missing its {} host and network public key",
+                        name, role
+                    ));
+                    continue;
+                }
+                _ => {
+                    errors.push(format!(
+                        "{} has a {} host or network public key but not both",
+                        name, role
+                    ));
+                    continue;
+                }
+            };
+            if let Some(other) = network_keys.insert(public_key, i) {
+                errors.push(format!(
+                    "{} has the same {} network key as validator #{}",
+                    name, role, other
+                ));
+            }
+        }
+    }
+
+    if !validators
+        .iter()
+        .any(|validator| validator.join_during_genesis)
+    {
+        errors.push("No validator is joining during genesis".to_string());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::UnexpectedError(format!(
+            "Found {} problems in the validator configurations:\n{}",
+            errors.len(),
+            errors.join("\n")
+        )))
+    }
+}
+
 /// Retrieves all information for mainnet genesis from the Git repository
 pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<MainnetGenesisInfo> {
     let client = git_options.get_client()?;
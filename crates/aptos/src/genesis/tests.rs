@@ -1,11 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::OptionalPoolAddressArgs;
+use crate::common::types::{CliError, OptionalPoolAddressArgs};
 use crate::common::utils::read_from_file;
 use crate::genesis::git::{from_yaml, BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE};
-use crate::genesis::git::{FRAMEWORK_NAME, OWNER_FILE};
+use crate::genesis::git::{FRAMEWORK_NAME, LAYOUT_FILE, OWNER_FILE};
 use crate::genesis::keys::{GenerateLayoutTemplate, PUBLIC_KEYS_FILE};
+use crate::genesis::{check_validator_configurations, get_validator_configs};
 use crate::{
     common::{
         types::{PromptOptions, RngArgs},
@@ -122,6 +123,112 @@ async fn test_mainnet_genesis_e2e_flow() {
     assert!(genesis_file.exists());
 }
 
+#[tokio::test]
+async fn test_check_validator_configurations() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let validators = get_validator_configs(&client, &layout, false).unwrap();
+
+    check_validator_configurations(&layout, &validators, false).unwrap();
+
+    // Every invalid validator is reported, not just the first one
+    let mut invalid = validators.clone();
+    invalid[0].stake_amount = layout.max_stake + 1;
+    invalid[1].commission_percentage = 101;
+    invalid[1].proof_of_possession = None;
+    let message = match check_validator_configurations(&layout, &invalid, false) {
+        Err(CliError::UnexpectedError(message)) => message,
+        result => panic!(
+            "Expected invalid validators to be rejected, got {:?}",
+            result
+        ),
+    };
+    assert!(message.starts_with("Found 3 problems"), "{}", message);
+    let first = format!("Validator #0 ({})", invalid[0].owner_account_address);
+    let second = format!("Validator #1 ({})", invalid[1].owner_account_address);
+    assert!(
+        message.contains(&format!("{} has stake", first)),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains(&format!("{} has a commission", second)),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains(&format!("{} is missing its consensus key", second)),
+        "{}",
+        message
+    );
+
+    let mut missing_host = validators.clone();
+    missing_host[0].validator_host = None;
+    missing_host[0].validator_network_public_key = None;
+    let message = match check_validator_configurations(&layout, &missing_host, false) {
+        Err(CliError::UnexpectedError(message)) => message,
+        result => panic!(
+            "Expected a validator without a host to be rejected, got {:?}",
+            result
+        ),
+    };
+    assert!(
+        message.contains(&format!(
+            "{} is missing its validator host and network public key",
+            first
+        )),
+        "{}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_check_validator_configurations_duplicates() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let mut validators = get_validator_configs(&client, &layout, false).unwrap();
+
+    validators[1].consensus_public_key = validators[0].consensus_public_key.clone();
+    validators[1].proof_of_possession = validators[0].proof_of_possession.clone();
+    validators[1].validator_network_public_key = validators[0].validator_network_public_key;
+    let message = match check_validator_configurations(&layout, &validators, false) {
+        Err(CliError::UnexpectedError(message)) => message,
+        result => panic!("Expected duplicate keys to be rejected, got {:?}", result),
+    };
+    assert!(
+        message.contains("has the same consensus key as validator #0"),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains("has the same validator network key as validator #0"),
+        "{}",
+        message
+    );
+
+    for validator in validators.iter_mut() {
+        validator.join_during_genesis = false;
+    }
+    let message = match check_validator_configurations(&layout, &validators, false) {
+        Err(CliError::UnexpectedError(message)) => message,
+        result => panic!(
+            "Expected no joining validators to be rejected, got {:?}",
+            result
+        ),
+    };
+    assert!(
+        message.contains("No validator is joining during genesis"),
+        "{}",
+        message
+    );
+}
+
 fn get_owner_address(git_options: GitOptions, index: u64) -> AccountAddress {
     let git_client = git_options.get_client().unwrap();
     let owner_config: OwnerConfiguration = git_client
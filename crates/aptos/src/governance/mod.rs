@@ -1,6 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod proposals;
+
 use crate::common::types::{
     CliError, CliTypedResult, MovePackageDir, PoolAddressArgs, PromptOptions, TransactionOptions,
     TransactionSummary,
@@ -8,7 +10,7 @@ use crate::common::types::{
 use crate::common::utils::prompt_yes_with_override;
 #[cfg(feature = "no-upload-proposal")]
 use crate::common::utils::read_from_file;
-use crate::move_tool::{FrameworkPackageArgs, IncludedArtifacts};
+use crate::move_tool::{lockfile::check_lockfile, FrameworkPackageArgs, IncludedArtifacts};
 use crate::{CliCommand, CliResult};
 use aptos_crypto::HashValue;
 use aptos_logger::warn;
@@ -23,6 +25,7 @@ use cached_packages::aptos_stdlib;
 use clap::Parser;
 use framework::{BuildOptions, BuiltPackage, ReleasePackage};
 use move_deps::move_core_types::transaction_argument::TransactionArgument;
+use proposals::{ListProposals, ShowProposal, SimulateProposal};
 use reqwest::Url;
 use serde::Deserialize;
 use serde::Serialize;
@@ -41,6 +44,9 @@ pub enum GovernanceTool {
     Vote(SubmitVote),
     ExecuteProposal(ExecuteProposal),
     GenerateUpgradeProposal(GenerateUpgradeProposal),
+    ListProposals(ListProposals),
+    ShowProposal(ShowProposal),
+    SimulateProposal(SimulateProposal),
 }
 
 impl GovernanceTool {
@@ -51,6 +57,9 @@ impl GovernanceTool {
             Vote(tool) => tool.execute_serialized().await,
             ExecuteProposal(tool) => tool.execute_serialized().await,
             GenerateUpgradeProposal(tool) => tool.execute_serialized_success().await,
+            ListProposals(tool) => tool.execute_serialized().await,
+            ShowProposal(tool) => tool.execute_serialized().await,
+            SimulateProposal(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -283,7 +292,7 @@ impl std::fmt::Display for ProposalMetadata {
     }
 }
 
-fn compile_in_temp_dir(
+pub(crate) fn compile_in_temp_dir(
     script_name: &str,
     script_path: &Path,
     framework_package_args: &FrameworkPackageArgs,
@@ -327,6 +336,7 @@ fn compile_in_temp_dir(
 }
 
 fn compile_script(package_dir: &Path) -> CliTypedResult<(Vec<u8>, HashValue)> {
+    check_lockfile(package_dir)?;
     let build_options = BuildOptions {
         with_srcs: false,
         with_abis: false,
@@ -488,6 +498,7 @@ impl CliCommand<()> for GenerateUpgradeProposal {
             output,
         } = self;
         let package_path = move_options.get_package_path()?;
+        check_lockfile(package_path.as_path())?;
         let options = included_artifacts.build_options(move_options.named_addresses());
         let package = BuiltPackage::build(package_path, options)?;
         let release = ReleasePackage::new(package)?;
@@ -0,0 +1,686 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands to inspect on-chain governance proposals, which are stored in the
+//! `0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>` resource.
+
+use crate::common::types::{
+    CliError, CliTypedResult, ProfileOptions, RestOptions, TransactionOptions,
+};
+use crate::common::utils::chain_id;
+use crate::governance::{get_metadata_from_url, CompileScriptFunction, ProposalMetadata};
+use crate::move_tool::{describe_change, ReplayStateView};
+use crate::CliCommand;
+use aptos_crypto::{ed25519::Ed25519Signature, HashValue};
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_rest_client::{error::RestError, Client};
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    event::EventHandle,
+    on_chain_config::{ApprovedExecutionHashes, OnChainConfig},
+    state_store::{state_key::StateKey, table::TableHandle},
+    transaction::{Script, SignedTransaction, TransactionPayload},
+};
+use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
+use aptos_vm::data_cache::AsMoveResolver;
+use aptos_vm::AptosVM;
+use async_trait::async_trait;
+use clap::Parser;
+use framework::natives::code::MoveOption;
+use move_deps::move_core_types::{
+    language_storage::ResourceKey, transaction_argument::TransactionArgument,
+};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use tokio::task;
+
+const VOTING_FORUM_TYPE: &str =
+    "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>";
+const PROPOSAL_TYPE: &str = "0x1::voting::Proposal<0x1::governance_proposal::GovernanceProposal>";
+const APPROVED_EXECUTION_HASHES_TYPE: &str = "0x1::aptos_governance::ApprovedExecutionHashes";
+
+/// Metadata keys, see `voting.move` and `aptos_governance.move`
+const RESOLVABLE_TIME_METADATA_KEY: &str = "RESOLVABLE_TIME_METADATA_KEY";
+const METADATA_LOCATION_KEY: &str = "metadata_location";
+const METADATA_HASH_KEY: &str = "metadata_hash";
+
+/// Rust representation of `0x1::voting::VotingForum`
+#[derive(Debug, Deserialize)]
+struct VotingForum {
+    proposals: Table,
+    _events: VotingEvents,
+    next_proposal_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Table {
+    handle: AccountAddress,
+}
+
+#[derive(Debug, Deserialize)]
+struct VotingEvents {
+    _create_proposal_events: EventHandle,
+    _register_forum_events: EventHandle,
+    _resolve_proposal_events: EventHandle,
+    _vote_events: EventHandle,
+}
+
+/// Rust representation of `0x1::governance_proposal::GovernanceProposal`, which as an empty
+/// struct is serialized with a dummy field
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GovernanceProposal {
+    dummy_field: bool,
+}
+
+/// Rust representation of `0x1::voting::Proposal<0x1::governance_proposal::GovernanceProposal>`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Proposal {
+    proposer: AccountAddress,
+    execution_content: MoveOption<GovernanceProposal>,
+    /// A `SimpleMap<String, vector<u8>>`
+    metadata: Vec<(String, Vec<u8>)>,
+    creation_time_secs: u64,
+    execution_hash: Vec<u8>,
+    min_vote_threshold: u128,
+    expiration_secs: u64,
+    early_resolution_vote_threshold: MoveOption<u128>,
+    yes_votes: u128,
+    no_votes: u128,
+    is_resolved: bool,
+    resolution_time_secs: u64,
+}
+
+impl Proposal {
+    fn metadata(&self, key: &str) -> Option<&[u8]> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    fn metadata_string(&self, key: &str) -> Option<String> {
+        self.metadata(key)
+            .map(|value| String::from_utf8_lossy(value).to_string())
+    }
+
+    fn early_resolution_vote_threshold(&self) -> Option<u128> {
+        self.early_resolution_vote_threshold.value.first().copied()
+    }
+
+    /// Computes the state of the proposal the same way as `voting::get_proposal_state`
+    fn state(&self, now_secs: u64) -> ProposalState {
+        if self.is_resolved {
+            return ProposalState::Resolved;
+        }
+        let can_be_resolved_early = self
+            .early_resolution_vote_threshold()
+            .map(|threshold| self.yes_votes >= threshold || self.no_votes >= threshold)
+            .unwrap_or(false);
+        if !can_be_resolved_early && now_secs <= self.expiration_secs {
+            ProposalState::Pending
+        } else if self.yes_votes > self.no_votes
+            && self.yes_votes + self.no_votes >= self.min_vote_threshold
+        {
+            ProposalState::Succeeded
+        } else {
+            ProposalState::Failed
+        }
+    }
+
+    /// Changes the proposal so that it can be resolved right away, as if it passed voting
+    fn make_resolvable(&mut self) {
+        self.yes_votes = self.no_votes + self.min_vote_threshold.max(1);
+        self.early_resolution_vote_threshold = MoveOption {
+            value: vec![self.yes_votes],
+        };
+        let resolvable_time = bcs::to_bytes(&0u64).expect("u64 must serialize");
+        match self
+            .metadata
+            .iter_mut()
+            .find(|(key, _)| key == RESOLVABLE_TIME_METADATA_KEY)
+        {
+            Some((_, value)) => *value = resolvable_time,
+            None => self
+                .metadata
+                .push((RESOLVABLE_TIME_METADATA_KEY.to_string(), resolvable_time)),
+        }
+    }
+
+    fn summary(&self, proposal_id: u64, now_secs: u64) -> ProposalSummary {
+        ProposalSummary {
+            proposal_id,
+            proposer: self.proposer,
+            state: self.state(now_secs),
+            yes_votes: self.yes_votes,
+            no_votes: self.no_votes,
+            min_vote_threshold: self.min_vote_threshold,
+            early_resolution_vote_threshold: self.early_resolution_vote_threshold(),
+            creation_time_secs: self.creation_time_secs,
+            expiration_secs: self.expiration_secs,
+            metadata_location: self.metadata_string(METADATA_LOCATION_KEY),
+            execution_hash: hex::encode(&self.execution_hash),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ProposalState {
+    Pending,
+    Succeeded,
+    Failed,
+    Resolved,
+}
+
+impl Display for ProposalState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            ProposalState::Pending => "Pending",
+            ProposalState::Succeeded => "Succeeded",
+            ProposalState::Failed => "Failed",
+            ProposalState::Resolved => "Resolved",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalSummary {
+    proposal_id: u64,
+    proposer: AccountAddress,
+    state: ProposalState,
+    yes_votes: u128,
+    no_votes: u128,
+    min_vote_threshold: u128,
+    early_resolution_vote_threshold: Option<u128>,
+    creation_time_secs: u64,
+    expiration_secs: u64,
+    metadata_location: Option<String>,
+    execution_hash: String,
+}
+
+/// Reads governance proposals from the voting forum of a fullnode
+struct ProposalReader {
+    client: Client,
+    proposals_handle: AccountAddress,
+    next_proposal_id: u64,
+    version: u64,
+    now_secs: u64,
+}
+
+impl ProposalReader {
+    async fn new(client: Client) -> CliTypedResult<Self> {
+        let ledger_info = client.get_ledger_information().await?.into_inner();
+        let forum: VotingForum = client
+            .get_account_resource_bcs(CORE_CODE_ADDRESS, VOTING_FORUM_TYPE)
+            .await?
+            .into_inner();
+        Ok(Self {
+            client,
+            proposals_handle: forum.proposals.handle,
+            next_proposal_id: forum.next_proposal_id,
+            version: ledger_info.version,
+            now_secs: ledger_info.timestamp_usecs / 1_000_000,
+        })
+    }
+
+    async fn get_proposal(&self, proposal_id: u64) -> CliTypedResult<Proposal> {
+        if proposal_id >= self.next_proposal_id {
+            return Err(CliError::CommandArgumentError(format!(
+                "Proposal {} does not exist, the latest proposal is {}",
+                proposal_id,
+                self.next_proposal_id.saturating_sub(1)
+            )));
+        }
+        Ok(self
+            .client
+            .get_table_item_bcs(
+                self.proposals_handle,
+                "u64",
+                PROPOSAL_TYPE,
+                proposal_id.to_string(),
+            )
+            .await?
+            .into_inner())
+    }
+}
+
+/// List all governance proposals
+///
+/// Shows the vote tallies, thresholds and state of every proposal in the voting forum.
+#[derive(Parser)]
+pub struct ListProposals {
+    /// Only show proposals which are still pending
+    #[clap(long)]
+    pub(crate) pending: bool,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<ProposalSummary>> for ListProposals {
+    fn command_name(&self) -> &'static str {
+        "ListProposals"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<ProposalSummary>> {
+        let client = self.rest_options.client(&self.profile_options.profile)?;
+        let reader = ProposalReader::new(client).await?;
+
+        let mut proposals = vec![];
+        for proposal_id in 0..reader.next_proposal_id {
+            let summary = reader
+                .get_proposal(proposal_id)
+                .await?
+                .summary(proposal_id, reader.now_secs);
+            if !self.pending || summary.state == ProposalState::Pending {
+                proposals.push(summary);
+            }
+        }
+        Ok(proposals)
+    }
+}
+
+/// Show a single governance proposal
+///
+/// Alongside the on-chain state of the proposal, this fetches the proposal's metadata from its
+/// metadata URL, and verifies it against the metadata hash that was submitted with the proposal.
+#[derive(Parser)]
+pub struct ShowProposal {
+    /// Id of the proposal to show
+    #[clap(long)]
+    pub(crate) proposal_id: u64,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalDetails {
+    #[serde(flatten)]
+    summary: ProposalSummary,
+    resolution_time_secs: Option<u64>,
+    metadata_hash: Option<String>,
+    /// Whether the metadata at the metadata URL matches the metadata hash
+    metadata_verified: bool,
+    metadata: Option<ProposalMetadata>,
+}
+
+#[async_trait]
+impl CliCommand<ProposalDetails> for ShowProposal {
+    fn command_name(&self) -> &'static str {
+        "ShowProposal"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProposalDetails> {
+        let client = self.rest_options.client(&self.profile_options.profile)?;
+        let reader = ProposalReader::new(client).await?;
+        let proposal = reader.get_proposal(self.proposal_id).await?;
+        let summary = proposal.summary(self.proposal_id, reader.now_secs);
+        let metadata_hash = proposal.metadata_string(METADATA_HASH_KEY);
+
+        // The metadata is hosted off-chain, so failing to fetch it isn't an error
+        let bytes = match summary
+            .metadata_location
+            .as_deref()
+            .and_then(|location| Url::parse(location).ok())
+        {
+            Some(url) => get_metadata_from_url(&url).await.ok(),
+            None => None,
+        };
+        let (metadata_verified, metadata) = match bytes {
+            Some(bytes) => verify_metadata(metadata_hash.as_deref(), &bytes),
+            None => (false, None),
+        };
+
+        Ok(ProposalDetails {
+            resolution_time_secs: if proposal.is_resolved {
+                Some(proposal.resolution_time_secs)
+            } else {
+                None
+            },
+            summary,
+            metadata_hash,
+            metadata_verified,
+            metadata,
+        })
+    }
+}
+
+/// Checks fetched metadata against the metadata hash of a proposal, and parses it
+fn verify_metadata(metadata_hash: Option<&str>, bytes: &[u8]) -> (bool, Option<ProposalMetadata>) {
+    (
+        metadata_hash == Some(HashValue::sha3_256_of(bytes).to_hex().as_str()),
+        serde_json::from_slice(bytes).ok(),
+    )
+}
+
+/// Simulate the execution of a governance proposal
+///
+/// Executes the proposal's script against the current state of the chain as if the proposal had
+/// already passed voting, and shows the resulting changes to on-chain configs and the framework.
+/// Nothing is submitted to the chain, so only the public key of the sender is needed.  The script
+/// must match the execution hash of the proposal.
+#[derive(Parser)]
+pub struct SimulateProposal {
+    /// Id of the proposal to simulate
+    #[clap(long)]
+    pub(crate) proposal_id: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) compile_proposal_args: CompileScriptFunction,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalSimulationSummary {
+    proposal_id: u64,
+    script_hash: String,
+    vm_status: String,
+    status: String,
+    gas_used: u64,
+    changes: Vec<String>,
+    events: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProposalSimulationSummary> for SimulateProposal {
+    fn command_name(&self) -> &'static str {
+        "SimulateProposal"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProposalSimulationSummary> {
+        let (bytecode, script_hash) = self
+            .compile_proposal_args
+            .compile("SimulateProposal", self.txn_options.prompt_options)?;
+
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options.profile)?;
+        let reader = ProposalReader::new(client.clone()).await?;
+        let mut proposal = reader.get_proposal(self.proposal_id).await?;
+        if proposal.is_resolved {
+            return Err(CliError::CommandArgumentError(format!(
+                "Proposal {} has already been resolved",
+                self.proposal_id
+            )));
+        }
+        if proposal.execution_hash != script_hash.to_vec() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Script hash {} does not match the execution hash {} of proposal {}",
+                script_hash,
+                hex::encode(&proposal.execution_hash),
+                self.proposal_id
+            )));
+        }
+
+        // Overlay the state so that the proposal is resolvable, and so that its script is
+        // approved in case it's larger than the maximum transaction size
+        proposal.make_resolvable();
+        let mut approved_hashes = get_approved_execution_hashes(&client, reader.version).await?;
+        approved_hashes
+            .entries
+            .retain(|(proposal_id, _)| *proposal_id != self.proposal_id);
+        approved_hashes
+            .entries
+            .push((self.proposal_id, script_hash.to_vec()));
+        let mut replacements = HashMap::new();
+        replacements.insert(
+            StateKey::table_item(
+                TableHandle(reader.proposals_handle),
+                bcs::to_bytes(&self.proposal_id)?,
+            ),
+            bcs::to_bytes(&proposal)?,
+        );
+        replacements.insert(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                CORE_CODE_ADDRESS,
+                ApprovedExecutionHashes::struct_tag(),
+            ))),
+            bcs::to_bytes(&approved_hashes)?,
+        );
+
+        // The transaction is never submitted, so only the sender's public key is needed, and it
+        // is signed with an invalid signature
+        let (sender_public_key, sender_address) = self.txn_options.get_public_key_and_address()?;
+        let sequence_number = self.txn_options.sequence_number(sender_address).await?;
+        let gas_unit_price = match self.txn_options.gas_options.gas_unit_price {
+            Some(gas_unit_price) => gas_unit_price,
+            None => client.estimate_gas_price().await?.into_inner().gas_estimate,
+        };
+        let mut transaction_factory =
+            TransactionFactory::new(chain_id(&client).await?).with_gas_unit_price(gas_unit_price);
+        if let Some(max_gas) = self.txn_options.gas_options.max_gas {
+            transaction_factory = transaction_factory.with_max_gas_amount(max_gas);
+        }
+        let raw_txn = transaction_factory
+            .payload(TransactionPayload::Script(Script::new(
+                bytecode,
+                vec![],
+                vec![TransactionArgument::U64(self.proposal_id)],
+            )))
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build();
+        let signed_txn = SignedTransaction::new(
+            raw_txn,
+            sender_public_key,
+            Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+        );
+
+        let version = reader.version;
+        let (vm_status, status, gas_used, changes, events) = task::spawn_blocking(move || {
+            let debugger = RestDebuggerInterface::new(client);
+            let state_view = ReplayStateView {
+                base: DebuggerStateView::new(&debugger, Some(version)),
+                replacements,
+            };
+            let (vm_status, output) =
+                AptosVM::simulate_signed_transaction(&signed_txn, &state_view);
+            let output = output.into_transaction_output(&state_view);
+
+            let resolver = state_view.as_move_resolver();
+            let annotator = AptosValueAnnotator::new(&resolver);
+            let changes = output
+                .write_set()
+                .iter()
+                .map(|(state_key, write_op)| describe_change(&annotator, state_key, write_op))
+                .collect();
+            let events = output
+                .events()
+                .iter()
+                .map(|event| match annotator.view_contract_event(event) {
+                    Ok(value) => format!("{}: {}", event.type_tag(), value),
+                    Err(_) => event.type_tag().to_string(),
+                })
+                .collect();
+            (
+                vm_status.to_string(),
+                format!("{:?}", output.status()),
+                output.gas_used(),
+                changes,
+                events,
+            )
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        Ok(ProposalSimulationSummary {
+            proposal_id: self.proposal_id,
+            script_hash: script_hash.to_hex(),
+            vm_status,
+            status,
+            gas_used,
+            changes,
+            events,
+        })
+    }
+}
+
+/// Retrieves the approved execution hashes, which don't exist until the first proposal passes
+async fn get_approved_execution_hashes(
+    client: &Client,
+    version: u64,
+) -> CliTypedResult<ApprovedExecutionHashes> {
+    match client
+        .get_account_resource_at_version_bytes(
+            CORE_CODE_ADDRESS,
+            APPROVED_EXECUTION_HASHES_TYPE,
+            version,
+        )
+        .await
+    {
+        Ok(response) => Ok(bcs::from_bytes(&response.into_inner())?),
+        Err(RestError::Api(err)) if err.status_code == StatusCode::NOT_FOUND => {
+            Ok(ApprovedExecutionHashes { entries: vec![] })
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::event::EventKey;
+
+    /// BCS of a `0x1::voting::Proposal<GovernanceProposal>`, laid out field by field as in Move
+    fn proposal_bytes(
+        yes_votes: u128,
+        no_votes: u128,
+        early_resolution_vote_threshold: Option<u128>,
+        is_resolved: bool,
+    ) -> Vec<u8> {
+        let metadata = vec![
+            (
+                METADATA_LOCATION_KEY.to_string(),
+                b"https://example.com/metadata.json".to_vec(),
+            ),
+            (METADATA_HASH_KEY.to_string(), b"abcd".to_vec()),
+        ];
+        bcs::to_bytes(&(
+            AccountAddress::ONE,
+            vec![false],
+            metadata,
+            100u64,
+            vec![0xabu8, 0xcd],
+            10u128,
+            1_000u64,
+            early_resolution_vote_threshold
+                .into_iter()
+                .collect::<Vec<_>>(),
+            yes_votes,
+            no_votes,
+            is_resolved,
+            0u64,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_voting_forum() {
+        let handle = || EventHandle::new(EventKey::new(0, AccountAddress::ONE), 0);
+        let proposals_handle = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let bytes = bcs::to_bytes(&(
+            proposals_handle,
+            (handle(), handle(), handle(), handle()),
+            7u64,
+        ))
+        .unwrap();
+        let forum: VotingForum = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(forum.proposals.handle, proposals_handle);
+        assert_eq!(forum.next_proposal_id, 7);
+    }
+
+    #[test]
+    fn parse_proposal() {
+        let proposal: Proposal = bcs::from_bytes(&proposal_bytes(10, 5, None, false)).unwrap();
+        assert_eq!(
+            proposal.metadata_string(METADATA_HASH_KEY).as_deref(),
+            Some("abcd")
+        );
+        assert_eq!(proposal.metadata(RESOLVABLE_TIME_METADATA_KEY), None);
+        let summary = proposal.summary(3, 500);
+        assert_eq!(summary.proposal_id, 3);
+        assert_eq!(summary.proposer, AccountAddress::ONE);
+        assert_eq!(summary.state, ProposalState::Pending);
+        assert_eq!(summary.early_resolution_vote_threshold, None);
+        assert_eq!(
+            summary.metadata_location.as_deref(),
+            Some("https://example.com/metadata.json")
+        );
+        assert_eq!(summary.execution_hash, "abcd");
+
+        // Proposals are serialized back in the same layout when they are overlaid on the state
+        assert_eq!(
+            bcs::to_bytes(&proposal).unwrap(),
+            proposal_bytes(10, 5, None, false)
+        );
+    }
+
+    #[test]
+    fn proposal_state() {
+        let state = |yes_votes, no_votes, early_threshold, is_resolved, now_secs| {
+            bcs::from_bytes::<Proposal>(&proposal_bytes(
+                yes_votes,
+                no_votes,
+                early_threshold,
+                is_resolved,
+            ))
+            .unwrap()
+            .state(now_secs)
+        };
+        assert_eq!(state(10, 5, None, false, 1_000), ProposalState::Pending);
+        assert_eq!(state(10, 5, None, false, 1_001), ProposalState::Succeeded);
+        assert_eq!(state(5, 10, None, false, 1_001), ProposalState::Failed);
+        // Not enough votes in total
+        assert_eq!(state(6, 3, None, false, 1_001), ProposalState::Failed);
+        assert_eq!(state(10, 5, Some(10), false, 0), ProposalState::Succeeded);
+        assert_eq!(state(10, 5, Some(20), false, 0), ProposalState::Pending);
+        assert_eq!(state(10, 5, None, true, 0), ProposalState::Resolved);
+
+        let mut proposal: Proposal = bcs::from_bytes(&proposal_bytes(0, 5, None, false)).unwrap();
+        proposal.make_resolvable();
+        assert_eq!(proposal.state(0), ProposalState::Succeeded);
+        assert_eq!(
+            proposal.metadata(RESOLVABLE_TIME_METADATA_KEY),
+            Some(bcs::to_bytes(&0u64).unwrap().as_slice())
+        );
+    }
+
+    #[test]
+    fn metadata_hash_check() {
+        let bytes = serde_json::to_vec(&serde_json::json!({
+            "title": "Title",
+            "description": "Description",
+            "source_code_url": "https://example.com/source",
+            "discussion_url": "https://example.com/discussion",
+        }))
+        .unwrap();
+        let hash = HashValue::sha3_256_of(&bytes).to_hex();
+
+        let (verified, metadata) = verify_metadata(Some(hash.as_str()), &bytes);
+        assert!(verified);
+        assert_eq!(metadata.unwrap().title, "Title");
+
+        // Mismatched metadata is still shown, but not verified
+        let (verified, metadata) = verify_metadata(Some("abcd"), &bytes);
+        assert!(!verified);
+        assert!(metadata.is_some());
+        assert!(!verify_metadata(None, &bytes).0);
+
+        let (verified, metadata) = verify_metadata(Some(hash.as_str()), b"not json");
+        assert!(!verified);
+        assert!(metadata.is_none());
+    }
+}
@@ -12,7 +12,7 @@ use std::process::exit;
 #[tokio::main]
 async fn main() {
     // Register hooks
-    move_tool::register_package_hooks();
+    move_tool::register_package_hooks(vec![]);
     // Run the corresponding tools
     let result = Tool::parse().execute().await;
 
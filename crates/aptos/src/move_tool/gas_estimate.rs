@@ -0,0 +1,358 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult, TransactionOptions};
+use crate::common::utils::{chain_id, read_from_file};
+use crate::genesis::git::from_yaml;
+use crate::move_tool::{ArgWithType, MemberId};
+use crate::CliCommand;
+use aptos_gas::{AptosGasParameters, FromOnChainGasSchedule, InternalGas, TraceEvent};
+use aptos_rest_client::aptos_api_types::{ExplainVMStatus, MoveType};
+use aptos_rest_client::Client;
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_sdk::types::LocalAccount;
+use aptos_types::account_config::{AccountResource, CoinStoreResource, CORE_CODE_ADDRESS};
+use aptos_types::on_chain_config::GasScheduleV2;
+use aptos_types::transaction::{
+    EntryFunction, ExecutionStatus, TransactionPayload, TransactionStatus,
+};
+use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
+use aptos_vm::AptosVM;
+use async_trait::async_trait;
+use clap::Parser;
+use move_deps::move_core_types::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::task;
+
+/// Estimate the cost of Move entry function calls
+///
+/// Executes each entry function invocation in the input file locally from the sender account,
+/// and reports the gas used split into execution, IO and storage, and the total cost in Octas at
+/// the current estimated gas unit price.  All invocations run against the state of the network at
+/// the same ledger version, without being submitted.
+///
+/// The input file is a YAML or JSON list of invocations, e.g.
+///
+/// - name: transfer
+///   function_id: 0x1::coin::transfer
+///   type_args: [0x1::aptos_coin::AptosCoin]
+///   args: ["address:0x1", "u64:100"]
+#[derive(Parser)]
+pub struct GasEstimate {
+    /// YAML or JSON file with the list of entry function invocations to estimate
+    #[clap(long, parse(from_os_str))]
+    pub(crate) invocations_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+/// An entry function invocation, with arguments in the same format as `aptos move run`
+#[derive(Debug, Deserialize)]
+pub struct EntryFunctionInvocation {
+    /// Label for the invocation in the output, defaults to the function id
+    #[serde(default)]
+    pub name: Option<String>,
+    pub function_id: String,
+    #[serde(default)]
+    pub type_args: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl EntryFunctionInvocation {
+    fn payload(&self) -> CliTypedResult<TransactionPayload> {
+        let function_id = MemberId::from_str(&self.function_id)?;
+        let mut type_args = vec![];
+        for type_arg in &self.type_args {
+            let move_type = MoveType::from_str(type_arg)
+                .map_err(|err| CliError::UnableToParse("type_args", err.to_string()))?;
+            type_args.push(
+                TypeTag::try_from(move_type)
+                    .map_err(|err| CliError::UnableToParse("type_args", err.to_string()))?,
+            );
+        }
+        let mut args = vec![];
+        for arg in &self.args {
+            args.push(ArgWithType::from_str(arg)?.arg);
+        }
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            function_id.module_id,
+            function_id.member_id,
+            type_args,
+            args,
+        )))
+    }
+}
+
+/// Cost of an entry function invocation
+///
+/// Gas amounts are in gas units, fees in Octas at `gas_unit_price`
+#[derive(Debug, Serialize)]
+pub struct EntryFunctionGasEstimate {
+    pub name: String,
+    pub function_id: String,
+    pub success: bool,
+    pub vm_status: String,
+    /// Gas for executing Move code, including the intrinsic gas of the transaction
+    pub execution_gas: u64,
+    /// Gas for reading state
+    pub io_gas: u64,
+    /// Gas for writing state, as set by `storage_gas.move`
+    pub storage_gas: u64,
+    pub gas_used: u64,
+    pub gas_unit_price: u64,
+    pub execution_fee: u64,
+    pub io_fee: u64,
+    pub storage_fee: u64,
+    pub total_fee: u64,
+}
+
+#[async_trait]
+impl CliCommand<Vec<EntryFunctionGasEstimate>> for GasEstimate {
+    fn command_name(&self) -> &'static str {
+        "GasEstimate"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<EntryFunctionGasEstimate>> {
+        let invocations: Vec<EntryFunctionInvocation> = from_yaml(
+            &String::from_utf8(read_from_file(&self.invocations_file)?).map_err(CliError::from)?,
+        )?;
+
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options.profile)?;
+        let (sender_key, sender_address) = self.txn_options.get_key_and_address()?;
+
+        // Everything is read at the same version, so the estimates don't depend on new blocks
+        let version = client.get_ledger_information().await?.into_inner().version;
+        let gas_params = gas_parameters(&client, version).await?;
+        let gas_unit_price = match self.txn_options.gas_options.gas_unit_price {
+            Some(gas_unit_price) => gas_unit_price,
+            None => client.estimate_gas_price().await?.into_inner().gas_estimate,
+        };
+        let sequence_number = client
+            .get_account_resource_at_version_bcs::<AccountResource>(
+                sender_address,
+                "0x1::account::Account",
+                version,
+            )
+            .await?
+            .into_inner()
+            .sequence_number();
+        let max_gas_amount = match self.txn_options.gas_options.max_gas {
+            Some(max_gas) => max_gas,
+            None => {
+                let balance = client
+                    .get_account_resource_at_version_bcs::<CoinStoreResource>(
+                        sender_address,
+                        "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                        version,
+                    )
+                    .await?
+                    .into_inner()
+                    .coin();
+                estimate_max_gas_amount(&gas_params, balance, gas_unit_price)
+            }
+        };
+        let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas_amount);
+        let sender_account = LocalAccount::new(sender_address, sender_key, sequence_number);
+
+        let mut estimates = vec![];
+        for invocation in invocations {
+            // Every invocation runs on its own against the pinned state, with the same sequence
+            // number
+            let signed_transaction = sender_account.sign_transaction(
+                transaction_factory
+                    .payload(invocation.payload()?)
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build(),
+            );
+            let debugger_client = client.clone();
+            let (output, trace, vm_status) = task::spawn_blocking(move || {
+                let debugger = RestDebuggerInterface::new(debugger_client.clone());
+                let state_view = DebuggerStateView::new(&debugger, Some(version));
+                let (_, output, trace) =
+                    AptosVM::execute_user_transaction_with_trace(signed_transaction, &state_view)?;
+                let vm_status = match output.status() {
+                    TransactionStatus::Keep(status) => debugger_client.explain_vm_status(status),
+                    TransactionStatus::Discard(status_code) => {
+                        format!("Transaction discarded: {:?}", status_code)
+                    }
+                    TransactionStatus::Retry => "Transaction retried".to_string(),
+                };
+                Ok::<_, anyhow::Error>((output, trace, vm_status))
+            })
+            .await
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))??;
+
+            let gas_used = output.gas_used();
+            let (execution_gas, io_gas, storage_gas) = split_gas_used(
+                &gas_params,
+                trace.as_ref().map(|trace| trace.events()).unwrap_or(&[]),
+                gas_used,
+            );
+            estimates.push(EntryFunctionGasEstimate {
+                name: invocation
+                    .name
+                    .unwrap_or_else(|| invocation.function_id.clone()),
+                function_id: invocation.function_id,
+                success: matches!(
+                    output.status(),
+                    TransactionStatus::Keep(ExecutionStatus::Success)
+                ),
+                vm_status,
+                execution_gas,
+                io_gas,
+                storage_gas,
+                gas_used,
+                gas_unit_price,
+                execution_fee: execution_gas * gas_unit_price,
+                io_fee: io_gas * gas_unit_price,
+                storage_fee: storage_gas * gas_unit_price,
+                total_fee: gas_used * gas_unit_price,
+            });
+        }
+        Ok(estimates)
+    }
+}
+
+/// Retrieves the gas parameters of the network, to convert the traced internal gas into gas units
+async fn gas_parameters(client: &Client, version: u64) -> CliTypedResult<AptosGasParameters> {
+    let gas_schedule: GasScheduleV2 = client
+        .get_account_resource_at_version_bcs(
+            CORE_CODE_ADDRESS,
+            "0x1::gas_schedule::GasScheduleV2",
+            version,
+        )
+        .await?
+        .into_inner();
+    AptosGasParameters::from_on_chain_gas_schedule(&gas_schedule.to_btree_map()).ok_or_else(|| {
+        CliError::UnexpectedError("Unable to parse the on-chain gas schedule".to_string())
+    })
+}
+
+/// The most gas the sender can pay for, the same way the simulation API estimates it
+fn estimate_max_gas_amount(
+    gas_params: &AptosGasParameters,
+    balance: u64,
+    gas_unit_price: u64,
+) -> u64 {
+    let max_account_gas_units = if gas_unit_price == 0 {
+        balance
+    } else {
+        balance / gas_unit_price
+    };
+    std::cmp::min(
+        max_account_gas_units,
+        u64::from(gas_params.txn.maximum_number_of_gas_units),
+    )
+}
+
+/// Splits the gas used into execution, IO and storage gas, by the charges in the trace
+fn split_gas_used(
+    gas_params: &AptosGasParameters,
+    events: &[TraceEvent],
+    gas_used: u64,
+) -> (u64, u64, u64) {
+    let mut io_cost = 0;
+    let mut storage_cost = 0;
+    for event in events {
+        match event {
+            TraceEvent::Instruction { name, cost, .. } if name == "LoadResource" => io_cost += cost,
+            TraceEvent::Transaction { name, cost } if name == "WriteSet" => storage_cost += cost,
+            _ => {}
+        }
+    }
+    let io_gas = to_gas_units(gas_params, io_cost).min(gas_used);
+    let storage_gas = to_gas_units(gas_params, storage_cost).min(gas_used - io_gas);
+    (gas_used - io_gas - storage_gas, io_gas, storage_gas)
+}
+
+fn to_gas_units(gas_params: &AptosGasParameters, internal_gas: u64) -> u64 {
+    InternalGas::new(internal_gas)
+        .to_unit_round_up_with_params(&gas_params.txn)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_gas::InitialGasSchedule;
+
+    #[test]
+    fn split_gas_used_by_trace() {
+        let gas_params = AptosGasParameters::initial();
+        let scaling_factor = u64::from(gas_params.txn.gas_unit_scaling_factor);
+        let events = vec![
+            TraceEvent::Transaction {
+                name: "Intrinsic".to_string(),
+                cost: 4 * scaling_factor,
+            },
+            TraceEvent::Instruction {
+                depth: 1,
+                name: "LoadResource".to_string(),
+                cost: 2 * scaling_factor,
+            },
+            TraceEvent::Instruction {
+                depth: 1,
+                name: "LoadResource".to_string(),
+                cost: scaling_factor / 2,
+            },
+            TraceEvent::Transaction {
+                name: "WriteSet".to_string(),
+                cost: 3 * scaling_factor,
+            },
+        ];
+
+        // IO gas is rounded up, and the rest of the gas used is execution gas
+        assert_eq!(split_gas_used(&gas_params, &events, 12), (6, 3, 3));
+        // The split never exceeds the gas used
+        assert_eq!(split_gas_used(&gas_params, &events, 4), (0, 3, 1));
+        assert_eq!(split_gas_used(&gas_params, &[], 12), (12, 0, 0));
+    }
+
+    #[test]
+    fn max_gas_amount_is_limited_by_balance() {
+        let gas_params = AptosGasParameters::initial();
+        let max_number_of_gas_units = u64::from(gas_params.txn.maximum_number_of_gas_units);
+
+        assert_eq!(estimate_max_gas_amount(&gas_params, 1_000, 100), 10);
+        assert_eq!(
+            estimate_max_gas_amount(&gas_params, u64::MAX, 100),
+            max_number_of_gas_units
+        );
+        assert_eq!(
+            estimate_max_gas_amount(&gas_params, 1_000, 0),
+            max_number_of_gas_units.min(1_000)
+        );
+    }
+
+    #[test]
+    fn parse_invocations() {
+        let invocations: Vec<EntryFunctionInvocation> = from_yaml(
+            "- name: transfer\n  function_id: 0x1::coin::transfer\n  type_args: [0x1::aptos_coin::AptosCoin]\n  args: [\"address:0x1\", \"u64:100\"]\n- function_id: 0x1::account::create_account\n  args: [\"address:0x2\"]\n",
+        )
+        .unwrap();
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].name.as_deref(), Some("transfer"));
+        assert!(invocations[1].name.is_none());
+        assert!(invocations[1].type_args.is_empty());
+
+        match invocations[0].payload().unwrap() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(entry_function.function().as_str(), "transfer");
+                assert_eq!(entry_function.ty_args().len(), 1);
+                assert_eq!(entry_function.args().len(), 2);
+            }
+            payload => panic!("Unexpected payload {:?}", payload),
+        }
+    }
+}
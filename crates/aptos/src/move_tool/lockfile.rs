@@ -0,0 +1,334 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for the `Move.lock` lockfile, which pins the on-chain (`aptos = ...`) dependencies of
+//! a package to the exact version that was resolved when the lockfile was last updated.
+
+use crate::common::types::{load_account_arg, CliError, CliTypedResult, MovePackageDir};
+use crate::common::utils::{read_from_file, write_to_file};
+use crate::move_tool::manifest::MovePackageManifest;
+use crate::move_tool::{register_package_hooks, CachedPackageRegistry};
+use crate::CliCommand;
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+pub const LOCKFILE_NAME: &str = "Move.lock";
+const LOCKFILE_HEADER: &str =
+    "# This file is generated by `aptos move update-deps`. It is not intended for manual editing.\n\n";
+
+/// A Rust representation of the `Move.lock` lockfile
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MoveLockfile {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, LockedDependency>,
+    /// The on-chain packages the dependencies depend on, which aren't declared in the manifest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitive_dependencies: Vec<LockedDependency>,
+}
+
+/// An on-chain dependency pinned to the version it was resolved to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LockedDependency {
+    pub node_url: String,
+    pub address: String,
+    pub package_name: String,
+    pub upgrade_number: u64,
+    pub source_digest: String,
+}
+
+impl MoveLockfile {
+    /// Loads the lockfile of the package at `package_dir`, if there is one
+    pub fn load(package_dir: &Path) -> CliTypedResult<Option<Self>> {
+        let path = package_dir.join(LOCKFILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = read_from_file(path.as_path())?;
+        let contents = String::from_utf8(bytes)?;
+        toml::from_str(&contents)
+            .map(Some)
+            .map_err(|err| CliError::UnableToParse(LOCKFILE_NAME, err.to_string()))
+    }
+
+    /// Writes the lockfile to the package at `package_dir`, replacing any previous one
+    pub fn save(&self, package_dir: &Path) -> CliTypedResult<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(
+            package_dir.join(LOCKFILE_NAME).as_path(),
+            LOCKFILE_NAME,
+            format!("{}{}", LOCKFILE_HEADER, contents).as_bytes(),
+        )
+    }
+
+    /// Resolves every on-chain dependency in the package's manifest to its current version
+    ///
+    /// This queries the nodes the dependencies are published on, so it's only done when the
+    /// lockfile is explicitly updated.
+    pub async fn resolve(package_dir: &Path) -> CliTypedResult<Self> {
+        let manifest = MovePackageManifest::load(package_dir)?;
+
+        let mut dependencies = BTreeMap::new();
+        // (node URL, address, package name) of the dependencies of the on-chain dependencies
+        let mut transitive = BTreeSet::new();
+        for (name, node_url, address) in on_chain_dependencies(&manifest)? {
+            let url = Url::parse(&node_url)
+                .map_err(|err| CliError::UnableToParse("node URL", err.to_string()))?;
+            let registry = CachedPackageRegistry::create(url, load_account_arg(&address)?).await?;
+            let package = registry.get_package(&name).await?;
+            // The metadata of a package lists all of its dependencies, not only the direct ones
+            for dep in package.deps() {
+                transitive.insert((node_url.clone(), dep.account, dep.package_name.clone()));
+            }
+            dependencies.insert(
+                name.clone(),
+                LockedDependency {
+                    node_url,
+                    address,
+                    package_name: name,
+                    upgrade_number: package.upgrade_number(),
+                    source_digest: package.source_digest().to_string(),
+                },
+            );
+        }
+
+        let mut transitive_dependencies = vec![];
+        for (node_url, account, package_name) in transitive {
+            if dependencies.values().any(|locked| {
+                locked.node_url == node_url
+                    && locked.package_name == package_name
+                    && load_account_arg(&locked.address).ok() == Some(account)
+            }) {
+                continue;
+            }
+            let url = Url::parse(&node_url)
+                .map_err(|err| CliError::UnableToParse("node URL", err.to_string()))?;
+            let registry = CachedPackageRegistry::create(url, account).await?;
+            let package = registry.get_package(&package_name).await?;
+            transitive_dependencies.push(LockedDependency {
+                node_url,
+                address: account.to_hex_literal(),
+                package_name,
+                upgrade_number: package.upgrade_number(),
+                source_digest: package.source_digest().to_string(),
+            });
+        }
+        Ok(Self {
+            dependencies,
+            transitive_dependencies,
+        })
+    }
+
+    /// Returns every locked on-chain package, direct dependencies first
+    pub fn locked_packages(&self) -> Vec<LockedDependency> {
+        self.dependencies
+            .values()
+            .chain(self.transitive_dependencies.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Checks that the lockfile pins exactly the on-chain dependencies declared in `manifest`
+    pub fn verify(&self, manifest: &MovePackageManifest) -> CliTypedResult<()> {
+        let declared = on_chain_dependencies(manifest)?;
+
+        let mut changes = vec![];
+        for (name, node_url, address) in &declared {
+            match self.dependencies.get(name) {
+                Some(locked) => {
+                    if &locked.node_url != node_url
+                        || load_account_arg(&locked.address)? != load_account_arg(address)?
+                    {
+                        changes.push(format!("`{}` moved to a different account", name))
+                    }
+                }
+                None => changes.push(format!("`{}` is not in {}", name, LOCKFILE_NAME)),
+            }
+        }
+        for name in self.dependencies.keys() {
+            if !declared
+                .iter()
+                .any(|(declared_name, _, _)| declared_name == name)
+            {
+                changes.push(format!("`{}` is no longer a dependency", name));
+            }
+        }
+
+        if changes.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::MoveCompilationError(format!(
+                "On-chain dependencies don't match {}: {}. Run `aptos move update-deps` to update it",
+                LOCKFILE_NAME,
+                changes.join(", ")
+            )))
+        }
+    }
+}
+
+/// Returns the name, node URL and address of every on-chain dependency in `manifest`
+fn on_chain_dependencies(
+    manifest: &MovePackageManifest,
+) -> CliTypedResult<Vec<(String, String, String)>> {
+    let mut dependencies = vec![];
+    for (name, dependency) in &manifest.dependencies {
+        let node_url = match &dependency.aptos {
+            Some(node_url) => node_url.clone(),
+            None => continue,
+        };
+        let address = dependency.address.clone().ok_or_else(|| {
+            CliError::CommandArgumentError(format!(
+                "On-chain dependency `{}` is missing an `address`",
+                name
+            ))
+        })?;
+        dependencies.push((name.clone(), node_url, address));
+    }
+    Ok(dependencies)
+}
+
+/// Checks that the package at `package_dir` still declares the on-chain dependencies in its
+/// lockfile, and registers the package hooks with them pinned, so that the build only uses the
+/// locked versions of the on-chain packages, direct dependencies or not.
+///
+/// Every command building a package runs this first. It doesn't touch the network or the
+/// lockfile: a locked package that was upgraded on-chain fails the build when it's downloaded.
+/// Packages without a lockfile build against whatever version is on-chain.
+pub fn check_lockfile(package_dir: &Path) -> CliTypedResult<()> {
+    let pinned = match MoveLockfile::load(package_dir)? {
+        Some(locked) => {
+            locked.verify(&MovePackageManifest::load(package_dir)?)?;
+            locked.locked_packages()
+        }
+        None => vec![],
+    };
+    register_package_hooks(pinned);
+    Ok(())
+}
+
+/// Updates the `Move.lock` lockfile of a package
+///
+/// Resolves every on-chain dependency of the package to its current version on-chain and records
+/// it in the lockfile.  Builds of the package fail if an on-chain dependency is upgraded after
+/// this, until this command is run again.
+#[derive(Parser)]
+pub struct UpdateDependencies {
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+#[async_trait]
+impl CliCommand<MoveLockfile> for UpdateDependencies {
+    fn command_name(&self) -> &'static str {
+        "UpdateDependencies"
+    }
+
+    async fn execute(self) -> CliTypedResult<MoveLockfile> {
+        let package_dir = self.move_options.get_package_path()?;
+        let lockfile = MoveLockfile::resolve(package_dir.as_path()).await?;
+        lockfile.save(package_dir.as_path())?;
+        Ok(lockfile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_deps::move_package::source_package::layout::SourcePackageLayout;
+
+    fn manifest(dependencies: &str) -> MovePackageManifest {
+        toml::from_str(&format!(
+            "[package]\nname = \"Hello\"\nversion = \"0.0.0\"\n\n[dependencies]\n{}",
+            dependencies
+        ))
+        .unwrap()
+    }
+
+    fn lockfile(dependencies: &[(&str, &str)]) -> MoveLockfile {
+        MoveLockfile {
+            dependencies: dependencies
+                .iter()
+                .map(|(name, address)| {
+                    (
+                        name.to_string(),
+                        LockedDependency {
+                            node_url: "http://localhost:8080".to_string(),
+                            address: address.to_string(),
+                            package_name: name.to_string(),
+                            upgrade_number: 0,
+                            source_digest: "ABCD".to_string(),
+                        },
+                    )
+                })
+                .collect(),
+            transitive_dependencies: vec![],
+        }
+    }
+
+    #[test]
+    fn lockfile_matching_manifest_is_accepted() {
+        let manifest = manifest(
+            r#"
+            Local = { local = "../local" }
+            Registry = { aptos = "http://localhost:8080", address = "0xcafe" }
+            "#,
+        );
+        // Addresses are compared by value, not by how they're written
+        lockfile(&[("Registry", "0x000cafe")])
+            .verify(&manifest)
+            .unwrap();
+    }
+
+    #[test]
+    fn lockfile_out_of_date_with_manifest_is_rejected() {
+        let manifest =
+            manifest(r#"Registry = { aptos = "http://localhost:8080", address = "0xcafe" }"#);
+        for stale in [
+            lockfile(&[]),
+            lockfile(&[("Registry", "0xbeef")]),
+            lockfile(&[("Registry", "0xcafe"), ("Removed", "0xcafe")]),
+        ] {
+            assert!(matches!(
+                stale.verify(&manifest),
+                Err(CliError::MoveCompilationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn lockfile_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(MoveLockfile::load(dir.path()).unwrap(), None);
+
+        let mut locked = lockfile(&[("Registry", "0xcafe")]);
+        locked.transitive_dependencies = lockfile(&[("Base", "0xbeef")])
+            .dependencies
+            .into_values()
+            .collect();
+        locked.save(dir.path()).unwrap();
+        assert_eq!(MoveLockfile::load(dir.path()).unwrap(), Some(locked));
+    }
+
+    #[test]
+    fn check_lockfile_does_not_create_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(SourcePackageLayout::Manifest.path()),
+            r#"
+            [package]
+            name = "Hello"
+            version = "0.0.0"
+
+            [dependencies]
+            Registry = { aptos = "http://localhost:8080", address = "0xcafe" }
+            "#,
+        )
+        .unwrap();
+        check_lockfile(dir.path()).unwrap();
+        assert!(!dir.path().join(LOCKFILE_NAME).exists());
+    }
+}
@@ -1,10 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::load_manifest_account_arg;
+use crate::common::types::{load_manifest_account_arg, CliError, CliTypedResult};
+use crate::common::utils::read_from_file;
 use aptos_types::account_address::AccountAddress;
+use move_deps::move_package::source_package::layout::SourcePackageLayout;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
+use std::path::Path;
 
 /// A Rust representation of the Move package manifest
 ///
@@ -13,12 +16,22 @@ use std::collections::BTreeMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MovePackageManifest {
     pub package: PackageInfo,
-    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub addresses: BTreeMap<String, ManifestNamedAddress>,
-    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub dependencies: BTreeMap<String, Dependency>,
 }
 
+impl MovePackageManifest {
+    /// Loads the manifest of the package at `package_dir`
+    pub fn load(package_dir: &Path) -> CliTypedResult<Self> {
+        let manifest_path = package_dir.join(SourcePackageLayout::Manifest.path());
+        let contents = String::from_utf8(read_from_file(manifest_path.as_path())?)?;
+        toml::from_str(&contents)
+            .map_err(|err| CliError::UnableToParse("Move.toml", err.to_string()))
+    }
+}
+
 /// Representation of an option address so we can print it as "_"
 #[derive(Debug, Clone)]
 pub struct ManifestNamedAddress {
@@ -88,3 +101,57 @@ pub struct PackageInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest_without_optional_sections() {
+        let manifest: MovePackageManifest = toml::from_str(
+            r#"
+            [package]
+            name = "Hello"
+            version = "0.0.0"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.package.name, "Hello");
+        assert!(manifest.addresses.is_empty());
+        assert!(manifest.dependencies.is_empty());
+
+        // Empty sections are left out again when the manifest is written
+        let contents = toml::to_string_pretty(&manifest).unwrap();
+        assert!(!contents.contains("[addresses]"));
+        assert!(!contents.contains("[dependencies]"));
+    }
+
+    #[test]
+    fn parse_manifest_with_dependencies() {
+        let manifest: MovePackageManifest = toml::from_str(
+            r#"
+            [package]
+            name = "Hello"
+            version = "0.0.0"
+
+            [addresses]
+            hello = "_"
+            std = "0x1"
+
+            [dependencies]
+            AptosFramework = { local = "../aptos-framework" }
+            Registry = { aptos = "http://localhost:8080", address = "0xcafe" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.addresses["hello"].address, None);
+        assert_eq!(manifest.addresses["std"].address, Some(AccountAddress::ONE));
+        assert_eq!(
+            manifest.dependencies["AptosFramework"].local.as_deref(),
+            Some("../aptos-framework")
+        );
+        let registry = &manifest.dependencies["Registry"];
+        assert_eq!(registry.aptos.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(registry.address.as_deref(), Some("0xcafe"));
+    }
+}
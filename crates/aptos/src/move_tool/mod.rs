@@ -2,14 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aptos_debug_natives;
+mod gas_estimate;
+pub mod lockfile;
 mod manifest;
 pub mod package_hooks;
 pub use package_hooks::*;
+mod repl;
+mod replay;
 pub mod stored_package;
 mod transactional_tests_runner;
 
 pub use stored_package::*;
 
+use crate::account::create_resource_account::CreateResourceAccountSummary;
 use crate::common::types::MoveManifestAccountWrapper;
 use crate::common::types::{ProfileOptions, RestOptions};
 use crate::common::utils::{
@@ -33,7 +38,7 @@ use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
 use aptos_module_verifier::module_init::verify_module_init_function;
 use aptos_rest_client::aptos_api_types::MoveType;
 use aptos_transactional_test_harness::run_aptos_test;
-use aptos_types::account_address::AccountAddress;
+use aptos_types::account_address::{create_resource_address, AccountAddress};
 use aptos_types::transaction::{
     EntryFunction, ModuleBundle, Script, TransactionArgument, TransactionPayload,
 };
@@ -41,7 +46,9 @@ use async_trait::async_trait;
 use clap::{ArgEnum, Parser, Subcommand};
 use framework::natives::code::UpgradePolicy;
 use framework::{BuildOptions, BuiltPackage};
+use gas_estimate::GasEstimate;
 use itertools::Itertools;
+use lockfile::{check_lockfile, UpdateDependencies};
 use move_deps::move_cli::base::test::UnitTestResult;
 use move_deps::move_command_line_common::env::MOVE_HOME;
 use move_deps::{
@@ -54,6 +61,10 @@ use move_deps::{
     move_prover, move_prover_boogie_backend,
     move_unit_test::UnitTestingConfig,
 };
+pub(crate) use repl::describe_change;
+use repl::Repl;
+pub(crate) use replay::ReplayStateView;
+use replay::ReplayTransaction;
 use std::fmt::{Display, Formatter};
 use std::{
     collections::BTreeMap,
@@ -72,32 +83,46 @@ use transactional_tests_runner::TransactionalTestOpts;
 #[derive(Subcommand)]
 pub enum MoveTool {
     Compile(CompilePackage),
+    Document(DocumentPackage),
     Init(InitPackage),
     Publish(PublishPackage),
+    CreateResourceAccountAndPublishPackage(CreateResourceAccountAndPublishPackage),
     Download(DownloadPackage),
     List(ListPackage),
     Clean(CleanPackage),
     Run(RunFunction),
     RunScript(RunScript),
+    GasEstimate(GasEstimate),
     Test(TestPackage),
     Prove(ProvePackage),
+    Repl(Repl),
+    Replay(ReplayTransaction),
     TransactionalTest(TransactionalTestOpts),
+    UpdateDeps(UpdateDependencies),
 }
 
 impl MoveTool {
     pub async fn execute(self) -> CliResult {
         match self {
             MoveTool::Compile(tool) => tool.execute_serialized().await,
+            MoveTool::Document(tool) => tool.execute_serialized().await,
             MoveTool::Init(tool) => tool.execute_serialized_success().await,
             MoveTool::Publish(tool) => tool.execute_serialized().await,
+            MoveTool::CreateResourceAccountAndPublishPackage(tool) => {
+                tool.execute_serialized().await
+            }
             MoveTool::Download(tool) => tool.execute_serialized().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Clean(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
+            MoveTool::GasEstimate(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
+            MoveTool::Repl(tool) => tool.execute_serialized().await,
+            MoveTool::Replay(tool) => tool.execute_serialized().await,
             MoveTool::TransactionalTest(tool) => tool.execute_serialized_success().await,
+            MoveTool::UpdateDeps(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -253,6 +278,7 @@ impl CliCommand<Vec<String>> for CompilePackage {
     }
 
     async fn execute(self) -> CliTypedResult<Vec<String>> {
+        check_lockfile(self.move_options.get_package_path()?.as_path())?;
         let build_options = BuildOptions {
             install_dir: self.move_options.output_dir.clone(),
             ..self
@@ -274,6 +300,50 @@ impl CliCommand<Vec<String>> for CompilePackage {
     }
 }
 
+/// Generates documentation for a package
+///
+/// Runs the Move documentation generator, and extends its markdown with the entry functions of
+/// each module together with the abort codes they reference, the abilities of each struct, and
+/// the events each module emits.  The same information is also written as
+/// `<doc-dir>/<package>.json`.
+#[derive(Parser)]
+pub struct DocumentPackage {
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    /// Directory to write the documentation to
+    ///
+    /// Defaults to `<package_dir>/doc`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) doc_dir: Option<PathBuf>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for DocumentPackage {
+    fn command_name(&self) -> &'static str {
+        "DocumentPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let package_path = self.move_options.get_package_path()?;
+        check_lockfile(package_path.as_path())?;
+        let doc_dir = self
+            .doc_dir
+            .clone()
+            .unwrap_or_else(|| package_path.join("doc"));
+        let build_options = BuildOptions {
+            install_dir: self.move_options.output_dir.clone(),
+            ..IncludedArtifacts::None.build_options(self.move_options.named_addresses())
+        };
+        let (_, written) =
+            framework::docgen::generate_docs(package_path.as_path(), &build_options, &doc_dir)
+                .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        Ok(written
+            .into_iter()
+            .map(|path| path.display().to_string())
+            .collect())
+    }
+}
+
 /// Runs Move unit tests for a package
 ///
 /// This will run Move unit tests against a package with debug mode
@@ -304,6 +374,7 @@ impl CliCommand<&'static str> for TestPackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
+        check_lockfile(self.move_options.get_package_path()?.as_path())?;
         let config = BuildConfig {
             additional_named_addresses: self.move_options.named_addresses(),
             test_mode: true,
@@ -376,6 +447,7 @@ impl CliCommand<&'static str> for ProvePackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
+        check_lockfile(self.move_options.get_package_path()?.as_path())?;
         let config = BuildConfig {
             additional_named_addresses: self.move_options.named_addresses(),
             test_mode: true,
@@ -508,6 +580,24 @@ impl IncludedArtifacts {
 
 pub const MAX_PUBLISH_PACKAGE_SIZE: usize = 60_000;
 
+/// Checks that a package publishing payload fits in a transaction, unless the check is overridden
+fn check_package_size(
+    payload: &TransactionPayload,
+    override_size_check: bool,
+) -> CliTypedResult<()> {
+    let size = bcs::serialized_size(payload)?;
+    println!("package size {} bytes", size);
+    if !override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
+        return Err(CliError::UnexpectedError(format!(
+            "The package is larger than {} bytes ({} bytes)! To lower the size \
+            you may want to include less artifacts via `--included_artifacts`. \
+            You can also override this check with `--override-size-check",
+            MAX_PUBLISH_PACKAGE_SIZE, size
+        )));
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl CliCommand<TransactionSummary> for PublishPackage {
     fn command_name(&self) -> &'static str {
@@ -523,6 +613,7 @@ impl CliCommand<TransactionSummary> for PublishPackage {
             included_artifacts,
         } = self;
         let package_path = move_options.get_package_path()?;
+        check_lockfile(package_path.as_path())?;
         let options = included_artifacts.build_options(move_options.named_addresses());
         let package = BuiltPackage::build(package_path, options)?;
         let compiled_units = package.extract_code();
@@ -541,16 +632,7 @@ impl CliCommand<TransactionSummary> for PublishPackage {
                 bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
                 compiled_units,
             );
-            let size = bcs::serialized_size(&payload)?;
-            println!("package size {} bytes", size);
-            if !override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
-                return Err(CliError::UnexpectedError(format!(
-                    "The package is larger than {} bytes ({} bytes)! To lower the size \
-                you may want to include less artifacts via `--included_artifacts`. \
-                You can also override this check with `--override-size-check",
-                    MAX_PUBLISH_PACKAGE_SIZE, size
-                )));
-            }
+            check_package_size(&payload, override_size_check)?;
             txn_options
                 .submit_transaction(payload)
                 .await
@@ -559,6 +641,89 @@ impl CliCommand<TransactionSummary> for PublishPackage {
     }
 }
 
+/// Publishes a package to a new resource account
+///
+/// The resource account address is derived from the sender and `--seed`, and bound to
+/// `--address-name` when compiling the package, so the package can be published at the
+/// resource account in the same transaction that creates it.
+#[derive(Parser)]
+pub struct CreateResourceAccountAndPublishPackage {
+    /// Resource account seed
+    ///
+    /// Seed used in generation of the AccountId of the resource account
+    /// The seed will be converted to bytes using `BCS`
+    #[clap(long)]
+    pub(crate) seed: String,
+
+    /// Named address of the package to bind to the resource account address
+    #[clap(long)]
+    pub(crate) address_name: String,
+
+    /// Whether to override the check for maximal size of published data.
+    #[clap(long)]
+    pub(crate) override_size_check: bool,
+
+    /// What artifacts to include in the package. See `aptos move publish` for details.
+    #[clap(long, default_value_t = IncludedArtifacts::Sparse)]
+    pub(crate) included_artifacts: IncludedArtifacts,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<CreateResourceAccountSummary> for CreateResourceAccountAndPublishPackage {
+    fn command_name(&self) -> &'static str {
+        "CreateResourceAccountAndPublishPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<CreateResourceAccountSummary> {
+        let CreateResourceAccountAndPublishPackage {
+            seed,
+            address_name,
+            override_size_check,
+            included_artifacts,
+            move_options,
+            txn_options,
+        } = self;
+
+        let seed = bcs::to_bytes(&seed)?;
+        let resource_address = create_resource_address(txn_options.sender_address()?, &seed);
+        let mut named_addresses = move_options.named_addresses();
+        if let Some(address) = named_addresses.insert(address_name.clone(), resource_address) {
+            if address != resource_address {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Named address `{}` is already bound to {}, but the resource account is {}",
+                    address_name, address, resource_address
+                )));
+            }
+        }
+
+        let package_path = move_options.get_package_path()?;
+        check_lockfile(package_path.as_path())?;
+        let options = included_artifacts.build_options(named_addresses);
+        let package = BuiltPackage::build(package_path, options)?;
+        let compiled_units = package.extract_code();
+        let metadata = package.extract_metadata()?;
+        let payload =
+            cached_packages::aptos_stdlib::resource_account_create_resource_account_and_publish_package(
+                seed,
+                bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
+                compiled_units,
+            );
+        check_package_size(&payload, override_size_check)?;
+        txn_options
+            .submit_transaction(payload)
+            .await
+            .map(|transaction| CreateResourceAccountSummary {
+                resource_account: Some(resource_address),
+                transaction_summary: TransactionSummary::from(&transaction),
+            })
+    }
+}
+
 /// Downloads a package and stores it in a directory named after the package
 ///
 /// This lets you retrieve packages directly from the blockchain for inspection
@@ -1032,3 +1197,25 @@ impl FromStr for MemberId {
         parse_member_id(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_size_check() {
+        let payload = |code_size| {
+            cached_packages::aptos_stdlib::code_publish_package_txn(
+                vec![],
+                vec![vec![0u8; code_size]],
+            )
+        };
+
+        check_package_size(&payload(1_000), false).unwrap();
+        assert!(matches!(
+            check_package_size(&payload(MAX_PUBLISH_PACKAGE_SIZE), false),
+            Err(CliError::UnexpectedError(_))
+        ));
+        check_package_size(&payload(MAX_PUBLISH_PACKAGE_SIZE), true).unwrap();
+    }
+}
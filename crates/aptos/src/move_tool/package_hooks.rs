@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::load_account_arg;
+use crate::move_tool::lockfile::LockedDependency;
 use crate::move_tool::CachedPackageRegistry;
+use anyhow::bail;
 use framework::UPGRADE_POLICY_CUSTOM_FIELD;
 use futures::executor::block_on;
 use move_deps::move_package::compilation::package_layout::CompiledPackageLayout;
@@ -10,12 +12,79 @@ use move_deps::move_package::package_hooks::PackageHooks;
 use move_deps::move_package::source_package::parsed_manifest::CustomDepInfo;
 use move_deps::move_symbol_pool::Symbol;
 use reqwest::Url;
+use std::fs;
 
-pub fn register_package_hooks() {
-    move_deps::move_package::package_hooks::register_package_hooks(Box::new(AptosPackageHooks {}))
+/// Name of the file recording the upgrade number and source digest of a downloaded on-chain
+/// package
+const PACKAGE_VERSION_FILE: &str = ".package_version";
+
+/// Registers the package hooks, pinning on-chain dependencies to the given versions so that a
+/// cached download is refreshed if it doesn't match, and a build fails rather than using a
+/// different version
+pub fn register_package_hooks(pinned_dependencies: Vec<LockedDependency>) {
+    move_deps::move_package::package_hooks::register_package_hooks(Box::new(
+        AptosPackageHooks::new(pinned_dependencies),
+    ))
+}
+
+pub struct AptosPackageHooks {
+    pinned_dependencies: Vec<LockedDependency>,
 }
 
-struct AptosPackageHooks {}
+impl AptosPackageHooks {
+    pub fn new(pinned_dependencies: Vec<LockedDependency>) -> Self {
+        Self {
+            pinned_dependencies,
+        }
+    }
+
+    /// Returns the locked version of the on-chain dependency, if it's pinned
+    fn pinned_dependency(&self, info: &CustomDepInfo) -> anyhow::Result<Option<&LockedDependency>> {
+        let package_address = load_account_arg(info.package_address.as_str())?;
+        Ok(self.pinned_dependencies.iter().find(|dependency| {
+            dependency.node_url == info.node_url.as_str()
+                && dependency.package_name == info.package_name.as_str()
+                && load_account_arg(&dependency.address).ok() == Some(package_address)
+        }))
+    }
+
+    async fn maybe_download_package(&self, info: &CustomDepInfo) -> anyhow::Result<()> {
+        let package_address = load_account_arg(info.package_address.as_str())?;
+        let pinned_version = self.pinned_dependency(info)?.map(|dependency| {
+            package_version(dependency.upgrade_number, &dependency.source_digest)
+        });
+
+        let version_path = info.download_to.join(PACKAGE_VERSION_FILE);
+        let is_downloaded = info
+            .download_to
+            .join(CompiledPackageLayout::BuildInfo.path())
+            .exists();
+        let is_up_to_date = match &pinned_version {
+            Some(version) => fs::read_to_string(&version_path).ok().as_ref() == Some(version),
+            None => true,
+        };
+        if is_downloaded && is_up_to_date {
+            return Ok(());
+        }
+
+        let registry =
+            CachedPackageRegistry::create(Url::parse(info.node_url.as_str())?, package_address)
+                .await?;
+        let package = registry.get_package(info.package_name).await?;
+        let version = package_version(package.upgrade_number(), package.source_digest());
+        if let Some(pinned_version) = pinned_version {
+            if version != pinned_version {
+                bail!(
+                    "on-chain package `{}` doesn't match the locked version, run `aptos move update-deps`",
+                    info.package_name
+                )
+            }
+        }
+        package.save_package_to_disk(info.download_to.as_path())?;
+        fs::write(version_path, version)?;
+        Ok(())
+    }
+}
 
 impl PackageHooks for AptosPackageHooks {
     fn custom_package_info_fields(&self) -> Vec<String> {
@@ -31,24 +100,59 @@ impl PackageHooks for AptosPackageHooks {
         _dep_name: Symbol,
         info: &CustomDepInfo,
     ) -> anyhow::Result<()> {
-        block_on(maybe_download_package(info))
+        block_on(self.maybe_download_package(info))
     }
 }
 
-async fn maybe_download_package(info: &CustomDepInfo) -> anyhow::Result<()> {
-    if !info
-        .download_to
-        .join(CompiledPackageLayout::BuildInfo.path())
-        .exists()
-    {
-        let registry = CachedPackageRegistry::create(
-            Url::parse(info.node_url.as_str())?,
-            load_account_arg(info.package_address.as_str())?,
-        )
-        .await?;
-        let package = registry.get_package(info.package_name).await?;
-        package.save_package_to_disk(info.download_to.as_path())
-    } else {
-        Ok(())
+/// Identifies a version of an on-chain package: a package can be republished with the same
+/// source, so the source digest alone doesn't
+fn package_version(upgrade_number: u64, source_digest: &str) -> String {
+    format!("{}:{}", upgrade_number, source_digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn locked(package_name: &str, upgrade_number: u64) -> LockedDependency {
+        LockedDependency {
+            node_url: "http://localhost:8080".to_string(),
+            address: "0xcafe".to_string(),
+            package_name: package_name.to_string(),
+            upgrade_number,
+            source_digest: "ABCD".to_string(),
+        }
+    }
+
+    fn dep_info(package_name: &str) -> CustomDepInfo {
+        CustomDepInfo {
+            node_url: Symbol::from("http://localhost:8080"),
+            package_address: Symbol::from("0x0cafe"),
+            package_name: Symbol::from(package_name),
+            download_to: PathBuf::from("build"),
+        }
+    }
+
+    #[test]
+    fn hooks_only_see_their_own_pins() {
+        let first = AptosPackageHooks::new(vec![locked("Registry", 1)]);
+        let second = AptosPackageHooks::new(vec![locked("Registry", 2), locked("Base", 0)]);
+
+        let pinned = |hooks: &AptosPackageHooks, name| {
+            hooks
+                .pinned_dependency(&dep_info(name))
+                .unwrap()
+                .map(|dependency| dependency.upgrade_number)
+        };
+        assert_eq!(pinned(&first, "Registry"), Some(1));
+        assert_eq!(pinned(&first, "Base"), None);
+        assert_eq!(pinned(&second, "Registry"), Some(2));
+        assert_eq!(pinned(&second, "Base"), Some(0));
+    }
+
+    #[test]
+    fn package_version_includes_upgrade_number() {
+        assert_ne!(package_version(1, "ABCD"), package_version(2, "ABCD"));
     }
 }
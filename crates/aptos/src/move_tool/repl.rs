@@ -0,0 +1,429 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{load_account_arg, CliError, CliTypedResult, MovePackageDir};
+use crate::common::utils::write_to_file;
+use crate::move_tool::aptos_debug_natives::aptos_debug_natives;
+use crate::move_tool::lockfile::check_lockfile;
+use crate::move_tool::manifest::{Dependency, MovePackageManifest, PackageInfo};
+use crate::move_tool::IncludedArtifacts;
+use crate::CliCommand;
+use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::{AccessPath, Path as AccessPathKind},
+    account_address::AccountAddress,
+    state_store::{state_key::StateKey, state_storage_usage::StateStorageUsage},
+    transaction::Version,
+    write_set::WriteOp,
+};
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use aptos_vm::move_vm_ext::{MoveResolverExt, MoveVmExt, SessionId};
+use async_trait::async_trait;
+use clap::Parser;
+use framework::{BuildOptions, BuiltPackage};
+use move_deps::{
+    move_core_types::value::MoveValue, move_package::source_package::layout::SourcePackageLayout,
+    move_vm_types::gas::UnmeteredGasMeter,
+};
+use reqwest::Url;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::task;
+
+const REPL_PACKAGE_NAME: &str = "Repl";
+const REPL_SCRIPT_FILE: &str = "repl.move";
+
+/// Starts an interactive Move session against forked state
+///
+/// Each input is compiled as the body of a script, with the chosen package as a dependency, and
+/// executed against state forked from a fullnode (`--network`) or a local DB (`--db-path`).
+/// State changes are kept between steps, but never leave the session.
+///
+/// Inputs ending without a `;` are treated as expressions, and their value is printed.  Lines
+/// starting with `use` are kept for all later steps.  `:reset` discards all state changes and
+/// `use` declarations, and `:quit` ends the session.
+#[derive(Parser)]
+pub struct Repl {
+    /// URL to a fullnode to fork state from
+    #[clap(long, group = "state")]
+    pub(crate) network: Option<Url>,
+
+    /// Path to a local DB to fork state from
+    #[clap(long, group = "state", parse(from_os_str))]
+    pub(crate) db_path: Option<PathBuf>,
+
+    /// Version to fork state at
+    ///
+    /// Defaults to the latest version
+    #[clap(long)]
+    pub(crate) version: Option<Version>,
+
+    /// Account whose `&signer` is available as `sender` to each step
+    #[clap(long, parse(try_from_str = load_account_arg), default_value = "0x1")]
+    pub(crate) sender: AccountAddress,
+
+    /// The package to compile snippets against, its modules are published to the forked state
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+#[async_trait]
+impl CliCommand<&'static str> for Repl {
+    fn command_name(&self) -> &'static str {
+        "Repl"
+    }
+
+    async fn execute(self) -> CliTypedResult<&'static str> {
+        task::spawn_blocking(move || self.run())
+            .await
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))??;
+        Ok("Success")
+    }
+}
+
+impl Repl {
+    fn run(self) -> CliTypedResult<()> {
+        let debugger: Box<dyn AptosValidatorInterface> = match (&self.network, &self.db_path) {
+            (Some(url), None) => Box::new(RestDebuggerInterface::new(
+                aptos_rest_client::Client::new(url.clone()),
+            )),
+            (None, Some(path)) => Box::new(DBDebuggerInterface::open(path.clone())?),
+            _ => {
+                return Err(CliError::CommandArgumentError(
+                    "Must provide exactly one of --network or --db-path".to_string(),
+                ))
+            }
+        };
+        let version = match self.version {
+            Some(version) => version,
+            None => debugger.get_latest_version()?,
+        };
+
+        let package_path = self.move_options.get_package_path()?;
+        // The hooks stay registered for the builds of the snippets, which depend on the package
+        check_lockfile(package_path.as_path())?;
+        let build_options = BuildOptions {
+            install_dir: self.move_options.output_dir.clone(),
+            ..IncludedArtifacts::None.build_options(self.move_options.named_addresses())
+        };
+        let package = BuiltPackage::build(package_path.clone(), build_options.clone())
+            .map_err(|err| CliError::MoveCompilationError(format!("{:#}", err)))?;
+        let mut package_modules = HashMap::new();
+        for module in package.modules() {
+            let mut bytes = vec![];
+            module
+                .serialize(&mut bytes)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            package_modules.insert(
+                StateKey::AccessPath(AccessPath::code_access_path(module.self_id())),
+                Some(bytes),
+            );
+        }
+
+        let repl_dir = TempDir::new().map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to create temporary directory {}", err))
+        })?;
+        init_repl_package(repl_dir.path(), package_path.as_path())?;
+
+        let vm = MoveVmExt::new_with_natives(
+            aptos_debug_natives(
+                NativeGasParameters::zeros(),
+                AbstractValueSizeGasParameters::zeros(),
+            ),
+            false,
+        )
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let mut session = ReplSession {
+            vm,
+            state: ForkedStateView {
+                base: DebuggerStateView::new(debugger.as_ref(), Some(version)),
+                changes: package_modules.clone(),
+            },
+            uses: vec![],
+            sender: self.sender,
+            repl_dir: repl_dir.path().to_path_buf(),
+            build_options: BuildOptions {
+                install_dir: None,
+                ..build_options
+            },
+        };
+
+        println!(
+            "Forked state at version {}, running as {}. Type `:quit` to exit.",
+            version, self.sender
+        );
+        let stdin = std::io::stdin();
+        let mut lines = stdin.lock().lines();
+        loop {
+            print!("> ");
+            std::io::stdout().flush().ok();
+            let mut input = match lines.next() {
+                Some(line) => line.map_err(|err| CliError::IO("stdin".to_string(), err))?,
+                None => break,
+            };
+            // A trailing backslash continues the input on the next line
+            while input.ends_with('\\') {
+                input.pop();
+                print!(". ");
+                std::io::stdout().flush().ok();
+                match lines.next() {
+                    Some(line) => {
+                        input.push('\n');
+                        input
+                            .push_str(&line.map_err(|err| CliError::IO("stdin".to_string(), err))?);
+                    }
+                    None => break,
+                }
+            }
+
+            let input = input.trim();
+            match input {
+                "" => continue,
+                ":quit" | ":q" => break,
+                ":reset" => {
+                    session.uses.clear();
+                    session.state.changes = package_modules.clone();
+                    println!("State and `use` declarations were reset");
+                }
+                _ if input.starts_with("use ") => {
+                    session.uses.push(input.to_string());
+                }
+                _ => {
+                    if let Err(err) = session.step(input) {
+                        println!("{}", err);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creates the package that snippets are compiled in, with the chosen package as its dependency
+fn init_repl_package(repl_dir: &Path, package_path: &Path) -> CliTypedResult<()> {
+    let manifest = MovePackageManifest::load(package_path)?;
+    let package_path = package_path
+        .canonicalize()
+        .map_err(|err| CliError::IO(package_path.display().to_string(), err))?;
+
+    let mut dependencies = BTreeMap::new();
+    dependencies.insert(
+        manifest.package.name,
+        Dependency {
+            local: Some(package_path.display().to_string()),
+            git: None,
+            rev: None,
+            subdir: None,
+            aptos: None,
+            address: None,
+        },
+    );
+    let repl_manifest = MovePackageManifest {
+        package: PackageInfo {
+            name: REPL_PACKAGE_NAME.to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+        },
+        addresses: BTreeMap::new(),
+        dependencies,
+    };
+
+    std::fs::create_dir_all(repl_dir.join(SourcePackageLayout::Sources.path()))
+        .map_err(|err| CliError::IO(repl_dir.display().to_string(), err))?;
+    write_to_file(
+        repl_dir
+            .join(SourcePackageLayout::Manifest.path())
+            .as_path(),
+        SourcePackageLayout::Manifest.location_str(),
+        toml::to_string_pretty(&repl_manifest)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .as_bytes(),
+    )
+}
+
+struct ReplSession<'a> {
+    vm: MoveVmExt,
+    state: ForkedStateView<'a>,
+    uses: Vec<String>,
+    sender: AccountAddress,
+    repl_dir: PathBuf,
+    build_options: BuildOptions,
+}
+
+impl<'a> ReplSession<'a> {
+    /// Compiles and executes a single input, printing its effects
+    fn step(&mut self, input: &str) -> CliTypedResult<()> {
+        let body = if input.ends_with(';') || input.ends_with('}') {
+            input.to_string()
+        } else {
+            format!("std::debug::print(&({}));", input)
+        };
+        let script = format!(
+            "script {{\n{}\nfun main(sender: &signer) {{\nlet _ = sender;\n{}\n}}\n}}\n",
+            self.uses.join("\n"),
+            body
+        );
+        write_to_file(
+            self.repl_dir
+                .join(SourcePackageLayout::Sources.path())
+                .join(REPL_SCRIPT_FILE)
+                .as_path(),
+            REPL_SCRIPT_FILE,
+            script.as_bytes(),
+        )?;
+        let package = BuiltPackage::build(self.repl_dir.clone(), self.build_options.clone())
+            .map_err(|err| CliError::MoveCompilationError(format!("{:#}", err)))?;
+        let script = package.extract_script_code().pop().ok_or_else(|| {
+            CliError::UnexpectedError("The snippet did not compile to a script".to_string())
+        })?;
+
+        let resolver = self.state.as_move_resolver();
+        let mut session = self.vm.new_session(&resolver, SessionId::void());
+        let signer = MoveValue::Signer(self.sender)
+            .simple_serialize()
+            .expect("signer must serialize");
+        session
+            .execute_script(
+                script.as_slice(),
+                vec![],
+                vec![signer],
+                &mut UnmeteredGasMeter,
+            )
+            .map_err(|err| CliError::UnexpectedError(format!("Execution failed: {:?}", err)))?;
+        let (deltas, change_set) = session
+            .finish()
+            .map_err(|err| CliError::UnexpectedError(format!("{:?}", err)))?
+            .into_change_set(&mut ())
+            .map_err(|status| CliError::UnexpectedError(status.to_string()))?
+            .into_inner();
+        let (write_set, events) = change_set.into_inner();
+        let delta_writes = deltas
+            .try_into_write_set_mut(&self.state)
+            .map_err(|err| CliError::UnexpectedError(format!("{:?}", err)))?
+            .freeze()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        let annotator = AptosValueAnnotator::new(&resolver);
+        for event in &events {
+            match annotator.view_contract_event(event) {
+                Ok(value) => println!("event {}: {}", event.type_tag(), value),
+                Err(_) => println!(
+                    "event {}: 0x{}",
+                    event.type_tag(),
+                    hex::encode(event.event_data())
+                ),
+            }
+        }
+        let mut changes = vec![];
+        for (state_key, write_op) in write_set.iter().chain(delta_writes.iter()) {
+            let value = match write_op {
+                WriteOp::Creation(bytes) | WriteOp::Modification(bytes) => Some(bytes.clone()),
+                WriteOp::Deletion => None,
+            };
+            println!("{}", describe_change(&annotator, state_key, write_op));
+            changes.push((state_key.clone(), value));
+        }
+        drop(annotator);
+        drop(resolver);
+        self.state.changes.extend(changes);
+        Ok(())
+    }
+}
+
+/// Describes a single change of a write set, annotating resources with their field names
+pub(crate) fn describe_change<R: MoveResolverExt>(
+    annotator: &AptosValueAnnotator<'_, R>,
+    state_key: &StateKey,
+    write_op: &WriteOp,
+) -> String {
+    let kind = match write_op {
+        WriteOp::Creation(_) => "created",
+        WriteOp::Modification(_) => "modified",
+        WriteOp::Deletion => "deleted",
+    };
+    match (state_key, write_op) {
+        (StateKey::AccessPath(access_path), WriteOp::Creation(bytes))
+        | (StateKey::AccessPath(access_path), WriteOp::Modification(bytes)) => {
+            match access_path.get_path() {
+                AccessPathKind::Resource(struct_tag) => {
+                    match annotator.view_resource(&struct_tag, bytes) {
+                        Ok(resource) => format!(
+                            "{} {}::{}: {}",
+                            kind, access_path.address, struct_tag, resource
+                        ),
+                        Err(_) => format!("{} {}::{}", kind, access_path.address, struct_tag),
+                    }
+                }
+                AccessPathKind::Code(module_id) => format!("{} module {}", kind, module_id),
+            }
+        }
+        _ => format!("{} {:?}", kind, state_key),
+    }
+}
+
+/// A [`StateView`] on top of forked state, holding the changes made during the session
+struct ForkedStateView<'a> {
+    base: DebuggerStateView<'a>,
+    changes: HashMap<StateKey, Option<Vec<u8>>>,
+}
+
+impl<'a> StateView for ForkedStateView<'a> {
+    fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.changes.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repl_package_depends_on_package_without_dependencies() {
+        let package_dir = TempDir::new().unwrap();
+        std::fs::write(
+            package_dir
+                .path()
+                .join(SourcePackageLayout::Manifest.path()),
+            "[package]\nname = \"Hello\"\nversion = \"0.0.0\"\n",
+        )
+        .unwrap();
+        let repl_dir = TempDir::new().unwrap();
+
+        init_repl_package(repl_dir.path(), package_dir.path()).unwrap();
+
+        let manifest = MovePackageManifest::load(repl_dir.path()).unwrap();
+        assert_eq!(manifest.package.name, REPL_PACKAGE_NAME);
+        assert!(manifest.addresses.is_empty());
+        let dependency = &manifest.dependencies["Hello"];
+        assert_eq!(
+            dependency.local.as_deref(),
+            Some(
+                package_dir
+                    .path()
+                    .canonicalize()
+                    .unwrap()
+                    .display()
+                    .to_string()
+                    .as_str()
+            )
+        );
+    }
+}
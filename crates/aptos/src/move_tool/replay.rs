@@ -0,0 +1,199 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult, MovePackageDir};
+use crate::move_tool::{lockfile::check_lockfile, IncludedArtifacts};
+use crate::CliCommand;
+use aptos_gas::ExecutionTrace;
+use aptos_rest_client::{aptos_api_types::TransactionData, Client};
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    state_store::{state_key::StateKey, state_storage_usage::StateStorageUsage},
+    transaction::Version,
+};
+use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
+use aptos_vm::AptosVM;
+use async_trait::async_trait;
+use clap::Parser;
+use framework::{BuildOptions, BuiltPackage};
+use move_deps::move_core_types::language_storage::ModuleId;
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::task;
+
+/// Replays a committed transaction locally
+///
+/// Fetches the transaction and the state it read from a fullnode's REST API, and re-executes it
+/// locally against the state right before it was committed.  Optionally, modules can be replaced
+/// with locally compiled versions from `--package-dir`, to try out fixes before publishing them.
+///
+/// The output contains an execution trace of every Move call frame entered and the gas charged
+/// for each instruction, which can be used to debug aborts and gas anomalies.
+#[derive(Parser)]
+pub struct ReplayTransaction {
+    /// Version of the transaction to replay
+    #[clap(long)]
+    pub(crate) version: Version,
+
+    /// URL to a fullnode on the network the transaction was committed to
+    ///
+    /// e.g. <https://fullnode.mainnet.aptoslabs.com/v1>
+    #[clap(long)]
+    pub(crate) network: reqwest::Url,
+
+    /// Options for a Move package whose modules replace the on-chain versions during replay
+    ///
+    /// If `--package-dir` is not provided, the transaction is replayed against on-chain code only
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+/// Result of replaying a transaction, alongside what happened on-chain for comparison
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub version: Version,
+    pub vm_status: String,
+    pub status: String,
+    pub on_chain_status: String,
+    pub gas_used: u64,
+    pub on_chain_gas_used: u64,
+    pub write_set_size: usize,
+    pub events: Vec<String>,
+    pub replaced_modules: Vec<String>,
+    pub trace: Option<ExecutionTrace>,
+}
+
+#[async_trait]
+impl CliCommand<ReplaySummary> for ReplayTransaction {
+    fn command_name(&self) -> &'static str {
+        "ReplayTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<ReplaySummary> {
+        if self.version == 0 {
+            return Err(CliError::CommandArgumentError(
+                "The genesis transaction can't be replayed".to_string(),
+            ));
+        }
+
+        let modules = if self.move_options.package_dir.is_some() {
+            compile_replacements(&self.move_options)?
+        } else {
+            vec![]
+        };
+        let replaced_modules = modules
+            .iter()
+            .map(|(module_id, _)| module_id.to_string())
+            .collect();
+        let replacements: HashMap<_, _> = modules
+            .into_iter()
+            .map(|(module_id, bytes)| {
+                (
+                    StateKey::AccessPath(AccessPath::code_access_path(module_id)),
+                    bytes,
+                )
+            })
+            .collect();
+
+        let client = Client::new(self.network.clone());
+        let txn = match client
+            .get_transaction_by_version_bcs(self.version)
+            .await?
+            .into_inner()
+        {
+            TransactionData::OnChain(txn) => txn,
+            TransactionData::Pending(_) => {
+                return Err(CliError::UnexpectedError(format!(
+                    "Transaction at version {} is still pending",
+                    self.version
+                )))
+            }
+        };
+        let signed_txn = txn
+            .transaction
+            .as_signed_user_txn()
+            .map_err(|_| {
+                CliError::CommandArgumentError(format!(
+                    "Transaction at version {} is not a user transaction",
+                    self.version
+                ))
+            })?
+            .clone();
+
+        let version = self.version;
+        let (vm_status, output, trace) = task::spawn_blocking(move || {
+            let debugger = RestDebuggerInterface::new(client);
+            // The transaction must see the state as it was right before it was committed
+            let state_view = ReplayStateView {
+                base: DebuggerStateView::new(&debugger, Some(version - 1)),
+                replacements,
+            };
+            AptosVM::execute_user_transaction_with_trace(signed_txn, &state_view)
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))??;
+
+        Ok(ReplaySummary {
+            version,
+            vm_status: vm_status.to_string(),
+            status: format!("{:?}", output.status()),
+            on_chain_status: format!("{:?}", txn.info.status()),
+            gas_used: output.gas_used(),
+            on_chain_gas_used: txn.info.gas_used(),
+            write_set_size: output.write_set().iter().count(),
+            events: output
+                .events()
+                .iter()
+                .map(|event| event.type_tag().to_string())
+                .collect(),
+            replaced_modules,
+            trace,
+        })
+    }
+}
+
+/// Compiles the package and returns its modules alongside their serialized bytecode
+fn compile_replacements(move_options: &MovePackageDir) -> CliTypedResult<Vec<(ModuleId, Vec<u8>)>> {
+    check_lockfile(move_options.get_package_path()?.as_path())?;
+    let build_options = BuildOptions {
+        install_dir: move_options.output_dir.clone(),
+        ..IncludedArtifacts::None.build_options(move_options.named_addresses())
+    };
+    let package = BuiltPackage::build(move_options.get_package_path()?, build_options)
+        .map_err(|err| CliError::MoveCompilationError(format!("{:#}", err)))?;
+
+    let mut modules = vec![];
+    for module in package.modules() {
+        let mut bytes = vec![];
+        module
+            .serialize(&mut bytes)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        modules.push((module.self_id(), bytes));
+    }
+    Ok(modules)
+}
+
+/// A [`StateView`] that serves replaced state, e.g. locally compiled modules, in place of the
+/// on-chain one
+pub(crate) struct ReplayStateView<'a> {
+    pub(crate) base: DebuggerStateView<'a>,
+    pub(crate) replacements: HashMap<StateKey, Vec<u8>>,
+}
+
+impl<'a> StateView for ReplayStateView<'a> {
+    fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.replacements.get(state_key) {
+            Some(bytes) => Ok(Some(bytes.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
@@ -4,7 +4,9 @@
 use anyhow::bail;
 use aptos_rest_client::Client;
 use aptos_types::account_address::AccountAddress;
-use framework::natives::code::{ModuleMetadata, PackageMetadata, PackageRegistry, UpgradePolicy};
+use framework::natives::code::{
+    ModuleMetadata, PackageDep, PackageMetadata, PackageRegistry, UpgradePolicy,
+};
 use framework::unzip_metadata_str;
 use move_deps::move_package::compilation::package_layout::CompiledPackageLayout;
 use reqwest::Url;
@@ -98,6 +100,11 @@ impl<'a> CachedPackageMetadata<'a> {
         &self.metadata.source_digest
     }
 
+    /// Returns the packages this package depends on, directly or not
+    pub fn deps(&self) -> &[PackageDep] {
+        &self.metadata.deps
+    }
+
     pub fn manifest(&self) -> anyhow::Result<String> {
         unzip_metadata_str(&self.metadata.manifest)
     }
@@ -9,15 +9,17 @@ use aptos_rest_client::VersionedNewBlockEvent;
 use aptos_types::account_address::AccountAddress;
 use aptos_types::account_config::{new_block_event_key, NewBlockEvent};
 use itertools::Itertools;
+use serde::Serialize;
 use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::io::Write;
 use std::ops::Add;
 use storage_interface::{DbReader, Order};
 
 use super::fetch_metadata::ValidatorInfo;
 
 /// Single validator stats
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub struct ValidatorStats {
     /// Number of successful proposals
     pub proposal_successes: u32,
@@ -48,7 +50,7 @@ impl ValidatorStats {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Serialize)]
 pub enum NodeState {
     // Proposal failure < 10%, >30% votes
     Reliable,
@@ -104,7 +106,7 @@ impl Add for ValidatorStats {
 }
 
 /// Statistics for all validators
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct EpochStats {
     /// Statistics for each of the validators
     pub validator_stats: HashMap<AccountAddress, ValidatorStats>,
@@ -189,6 +191,16 @@ impl Add for EpochStats {
     }
 }
 
+/// Statistics of a single epoch, for machine-readable output
+#[derive(Serialize)]
+pub struct EpochReport {
+    pub epoch: u64,
+    /// Whether only part of the epoch was analyzed, e.g. because it is still ongoing
+    pub partial: bool,
+    #[serde(flatten)]
+    pub stats: EpochStats,
+}
+
 /// Analyze validator performance
 pub struct AnalyzeValidators {}
 
@@ -445,6 +457,45 @@ impl AnalyzeValidators {
         }
     }
 
+    /// Write the stats of each validator in each epoch as CSV, one row per validator and epoch,
+    /// alongside the totals of the epoch
+    pub fn write_csv(reports: &[EpochReport], out: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "epoch,partial,validator,state,voting_power,proposal_successes,proposal_failures,\
+            failure_rate,votes,transactions,epoch_total_rounds,epoch_round_failures,\
+            epoch_nil_blocks,epoch_total_transactions"
+        )?;
+        for report in reports {
+            let stats = &report.stats;
+            for (validator, cur_stats) in stats
+                .validator_stats
+                .iter()
+                .sorted_by_key(|(validator, _)| **validator)
+            {
+                writeln!(
+                    out,
+                    "{},{},{},{:?},{},{},{},{},{},{},{},{},{},{}",
+                    report.epoch,
+                    report.partial,
+                    validator,
+                    stats.to_state(validator),
+                    cur_stats.voting_power,
+                    cur_stats.proposal_successes,
+                    cur_stats.proposal_failures,
+                    cur_stats.failure_rate(),
+                    cur_stats.votes,
+                    cur_stats.transactions,
+                    stats.total_rounds,
+                    stats.round_failures,
+                    stats.nil_blocks,
+                    stats.total_transactions,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn print_validator_health_over_time(
         stats: &HashMap<u64, EpochStats>,
         validators: &[AccountAddress],
@@ -545,3 +596,58 @@ impl AnalyzeValidators {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_csv_has_a_row_per_validator_and_epoch() {
+        let first = AccountAddress::from_hex_literal("0x1").unwrap();
+        let second = AccountAddress::from_hex_literal("0x2").unwrap();
+        let stats = |proposal_successes, proposal_failures| ValidatorStats {
+            proposal_successes,
+            proposal_failures,
+            votes: 10,
+            transactions: 5,
+            voting_power: 100,
+        };
+        let reports = vec![EpochReport {
+            epoch: 7,
+            partial: true,
+            stats: EpochStats {
+                validator_stats: [(second, stats(1, 9)), (first, stats(10, 0))]
+                    .into_iter()
+                    .collect(),
+                total_rounds: 20,
+                total_transactions: 10,
+                round_successes: 11,
+                round_failures: 9,
+                nil_blocks: 1,
+                total_voting_power: 200,
+            },
+        }];
+
+        let mut out = vec![];
+        AnalyzeValidators::write_csv(&reports, &mut out).unwrap();
+        let lines: Vec<_> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "epoch,partial,validator,state,voting_power,proposal_successes,proposal_failures,\
+            failure_rate,votes,transactions,epoch_total_rounds,epoch_round_failures,\
+            epoch_nil_blocks,epoch_total_transactions"
+                    .to_string(),
+                format!("7,true,{},Reliable,100,10,0,0,10,5,20,9,1,10", first),
+                format!(
+                    "7,true,{},AliveUnreliable,100,1,9,0.9,10,5,20,9,1,10",
+                    second
+                ),
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@ use aptos_rest_client::{
     aptos_api_types::{IdentifierWrapper, MoveResource, WriteSetChange},
     Client as RestClient, Transaction, VersionedNewBlockEvent,
 };
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, account_config::CORE_CODE_ADDRESS};
 use std::str::FromStr;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
@@ -29,6 +29,18 @@ impl FetchMetadata {
     fn get_validator_addresses(
         data: &MoveResource,
         field_name: &str,
+    ) -> Result<Vec<ValidatorInfo>> {
+        let validators_json = data
+            .data
+            .0
+            .get(&IdentifierWrapper::from_str(field_name).unwrap())
+            .unwrap();
+        FetchMetadata::parse_validators(validators_json, field_name)
+    }
+
+    fn parse_validators(
+        validators_json: &serde_json::Value,
+        field_name: &str,
     ) -> Result<Vec<ValidatorInfo>> {
         fn extract_validator_address(validator: &serde_json::Value) -> Result<ValidatorInfo> {
             Ok(ValidatorInfo {
@@ -55,11 +67,6 @@ impl FetchMetadata {
             })
         }
 
-        let validators_json = data
-            .data
-            .0
-            .get(&IdentifierWrapper::from_str(field_name).unwrap())
-            .unwrap();
         if let serde_json::Value::Array(validators_array) = validators_json {
             let mut validators: Vec<ValidatorInfo> = vec![];
             for validator in validators_array {
@@ -71,6 +78,26 @@ impl FetchMetadata {
         }
     }
 
+    /// Fetches the active validators of the epoch the given version is in, sorted by their
+    /// validator index, which is how `NewBlockEvent`s refer to them.
+    pub async fn get_validators_at_version(
+        client: &RestClient,
+        version: u64,
+    ) -> Result<Vec<ValidatorInfo>> {
+        let validator_set = client
+            .get_account_resource_at_version(CORE_CODE_ADDRESS, "0x1::stake::ValidatorSet", version)
+            .await?
+            .into_inner()
+            .ok_or_else(|| anyhow!("ValidatorSet not found"))?;
+        let validators_json = validator_set
+            .data
+            .get("active_validators")
+            .ok_or_else(|| anyhow!("active_validators not in ValidatorSet"))?;
+        let mut validators = FetchMetadata::parse_validators(validators_json, "active_validators")?;
+        validators.sort_by_key(|v| v.validator_index);
+        Ok(validators)
+    }
+
     async fn get_transactions(
         client: &RestClient,
         start: u64,
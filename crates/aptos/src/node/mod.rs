@@ -8,7 +8,7 @@ use crate::common::types::{
 };
 use crate::common::utils::prompt_yes_with_override;
 use crate::config::GlobalConfig;
-use crate::node::analyze::analyze_validators::AnalyzeValidators;
+use crate::node::analyze::analyze_validators::{AnalyzeValidators, EpochReport};
 use crate::node::analyze::fetch_metadata::FetchMetadata;
 use crate::{
     common::{
@@ -20,10 +20,16 @@ use crate::{
     },
     genesis::git::from_yaml,
 };
-use aptos_config::config::NodeConfig;
-use aptos_crypto::{bls12381, x25519, ValidCryptoMaterialStringExt};
+use aptos_config::config::{Identity, NetworkConfig, NodeConfig, SecureBackend};
+use aptos_config::network_id::NetworkId;
+use aptos_crypto::{
+    bls12381, ed25519::Ed25519PrivateKey, x25519, PrivateKey, ValidCryptoMaterialStringExt,
+};
 use aptos_faucet::FaucetArgs;
 use aptos_genesis::config::{HostAndPort, OperatorConfiguration};
+use aptos_global_constants::consensus_key_for_public_key;
+use aptos_keygen::KeyGen;
+use aptos_secure_storage::{CryptoStorage, KVStorage, Storage};
 use aptos_types::chain_id::ChainId;
 use aptos_types::network_address::NetworkAddress;
 use aptos_types::on_chain_config::{ConsensusScheme, ValidatorSet};
@@ -49,6 +55,7 @@ use reqwest::Url;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::{path::PathBuf, thread, time::Duration};
@@ -70,6 +77,7 @@ pub enum NodeTool {
     RunLocalTestnet(RunLocalTestnet),
     UpdateConsensusKey(UpdateConsensusKey),
     UpdateValidatorNetworkAddresses(UpdateValidatorNetworkAddresses),
+    RotateKeys(RotateKeys),
     AnalyzeValidatorPerformance(AnalyzeValidatorPerformance),
     BootstrapDbFromBackup(BootstrapDbFromBackup),
 }
@@ -88,6 +96,7 @@ impl NodeTool {
             RunLocalTestnet(tool) => tool.execute_serialized_without_logger().await,
             UpdateConsensusKey(tool) => tool.execute_serialized().await,
             UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized().await,
+            RotateKeys(tool) => tool.execute_serialized().await,
             AnalyzeValidatorPerformance(tool) => tool.execute_serialized().await,
             BootstrapDbFromBackup(tool) => tool.execute_serialized().await,
         }
@@ -956,6 +965,253 @@ impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
     }
 }
 
+/// Rotate the consensus and network keys of a validator
+///
+/// Generates new keys, writes them into the secure storage backends of the node config, and
+/// updates the consensus key and network addresses on-chain.  The new consensus key is stored
+/// next to the current one, and the node switches to it at the start of the next epoch.  If
+/// updating the network addresses on-chain fails, the previous network keys are written back into
+/// storage.
+#[derive(Parser)]
+pub struct RotateKeys {
+    /// Node config of the validator, whose secure storage holds the keys
+    #[clap(long, parse(from_os_str))]
+    pub(crate) node_config_file: PathBuf,
+
+    /// Host and port pair for the validator e.g. 127.0.0.1:6180
+    #[clap(long)]
+    pub(crate) validator_host: HostAndPort,
+
+    /// Host and port pair for the fullnode e.g. 127.0.0.1:6180.  Optional
+    ///
+    /// If set, the network key of the node's VFN network is rotated as well
+    #[clap(long)]
+    pub(crate) full_node_host: Option<HostAndPort>,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) operator_args: OperatorArgs,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateKeysSummary {
+    pub consensus_key_transaction: TransactionSummary,
+    pub network_addresses_transaction: TransactionSummary,
+    pub validator_config: ValidatorConfigSummary,
+}
+
+/// A key in secure storage, and its value before the rotation
+struct StoredKey<T> {
+    storage: Storage,
+    name: String,
+    previous: Option<T>,
+}
+
+impl StoredKey<Ed25519PrivateKey> {
+    fn network(network: &NetworkConfig) -> CliTypedResult<Self> {
+        match &network.identity {
+            Identity::FromStorage(identity) => {
+                let storage: Storage = (&identity.backend).into();
+                let previous = storage.export_private_key(&identity.key_name).ok();
+                Ok(StoredKey {
+                    storage,
+                    name: identity.key_name.clone(),
+                    previous,
+                })
+            }
+            _ => Err(CliError::CommandArgumentError(format!(
+                "The identity of the {} network is not in secure storage",
+                network.network_id
+            ))),
+        }
+    }
+
+    /// Network identities are stored as Ed25519 keys, and converted to x25519 when loaded
+    fn write(&mut self, key: Ed25519PrivateKey) -> CliTypedResult<x25519::PublicKey> {
+        let public_key = match self.storage.import_private_key(&self.name, key.clone()) {
+            Ok(()) => x25519::PrivateKey::from_ed25519_private_bytes(&key.to_bytes())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .public_key(),
+            // Vault doesn't overwrite existing keys, instead the key is rotated within Vault
+            Err(aptos_secure_storage::Error::KeyAlreadyExists(_)) => {
+                let public_key = self
+                    .storage
+                    .rotate_key(&self.name)
+                    .map_err(|err| storage_error(&self.name, err))?;
+                // The previous version stays available in Vault, nothing to restore
+                self.previous = None;
+                x25519::PublicKey::from_ed25519_public_bytes(&public_key.to_bytes())
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            }
+            Err(err) => return Err(storage_error(&self.name, err)),
+        };
+        Ok(public_key)
+    }
+
+    fn restore(&mut self) -> CliTypedResult<()> {
+        if let Some(previous) = self.previous.take() {
+            self.storage
+                .import_private_key(&self.name, previous)
+                .map_err(|err| storage_error(&self.name, err))?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores a new consensus key next to the current one, under the name for its public key.
+///
+/// Safety rules keeps signing with the current key until the new one is in the validator set at
+/// the next epoch, so the current key must stay in storage until then.
+fn store_rotated_consensus_key(
+    backend: &SecureBackend,
+    key: bls12381::PrivateKey,
+) -> CliTypedResult<()> {
+    let mut storage: Storage = backend.into();
+    let name = consensus_key_for_public_key(&hex::encode(key.public_key().to_bytes()));
+    storage
+        .set(&name, key)
+        .map_err(|err| storage_error(&name, err))
+}
+
+fn storage_error(name: &str, err: aptos_secure_storage::Error) -> CliError {
+    CliError::UnexpectedError(format!(
+        "Unable to write key '{}' to secure storage: {}",
+        name, err
+    ))
+}
+
+#[async_trait]
+impl CliCommand<RotateKeysSummary> for RotateKeys {
+    fn command_name(&self) -> &'static str {
+        "RotateKeys"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<RotateKeysSummary> {
+        let address = self
+            .operator_args
+            .address_fallback_to_txn(&self.txn_options)?;
+        let node_config = NodeConfig::load(&self.node_config_file).map_err(|err| {
+            CliError::UnableToReadFile(
+                format!("{}", self.node_config_file.display()),
+                err.to_string(),
+            )
+        })?;
+
+        // Find where the keys are stored, before anything is changed
+        let mut validator_network_key =
+            StoredKey::network(node_config.validator_network.as_ref().ok_or_else(|| {
+                CliError::CommandArgumentError(
+                    "The node config has no validator network".to_string(),
+                )
+            })?)?;
+        let mut full_node_network_key = if self.full_node_host.is_some() {
+            let vfn_network = node_config
+                .full_node_networks
+                .iter()
+                .find(|network| network.network_id == NetworkId::Vfn)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(
+                        "The node config has no VFN network for the fullnode host".to_string(),
+                    )
+                })?;
+            Some(StoredKey::network(vfn_network)?)
+        } else {
+            None
+        };
+
+        let mut keygen = KeyGen::from_os_rng();
+        let consensus_private_key = keygen.generate_bls12381_private_key();
+        let consensus_public_key = consensus_private_key.public_key();
+        let consensus_proof_of_possession =
+            bls12381::ProofOfPossession::create(&consensus_private_key);
+
+        // The new key is only used once it's on-chain, so there's nothing to undo if this fails
+        store_rotated_consensus_key(
+            &node_config.consensus.safety_rules.backend,
+            consensus_private_key,
+        )?;
+        let consensus_key_transaction = self
+            .txn_options
+            .submit_transaction(aptos_stdlib::stake_rotate_consensus_key(
+                address,
+                consensus_public_key.to_bytes().to_vec(),
+                consensus_proof_of_possession.to_bytes().to_vec(),
+            ))
+            .await
+            .map(|transaction| TransactionSummary::from(&transaction))?;
+
+        let validator_network_public_key =
+            validator_network_key.write(keygen.generate_ed25519_private_key())?;
+        let full_node_network_public_key = match full_node_network_key.as_mut() {
+            Some(key) => Some(key.write(keygen.generate_ed25519_private_key())?),
+            None => None,
+        };
+        let validator_network_addresses = vec![self
+            .validator_host
+            .as_network_address(validator_network_public_key)?];
+        let full_node_network_addresses =
+            match (self.full_node_host.as_ref(), full_node_network_public_key) {
+                (Some(host), Some(public_key)) => vec![host.as_network_address(public_key)?],
+                _ => vec![],
+            };
+
+        let network_addresses_transaction = match self
+            .txn_options
+            .submit_transaction(aptos_stdlib::stake_update_network_and_fullnode_addresses(
+                address,
+                // BCS encode, so that we can hide the original type
+                bcs::to_bytes(&validator_network_addresses)?,
+                bcs::to_bytes(&full_node_network_addresses)?,
+            ))
+            .await
+        {
+            Ok(transaction) => TransactionSummary::from(&transaction),
+            Err(err) => {
+                // The node keeps advertising its current network keys, so they go back into
+                // storage.  The consensus key stays rotated, it's already on-chain.
+                validator_network_key.restore()?;
+                if let Some(key) = full_node_network_key.as_mut() {
+                    key.restore()?;
+                }
+                return Err(CliError::UnexpectedError(format!(
+                    "The consensus key was rotated in transaction {}, but updating the network \
+                    addresses failed, and the network keys were restored: {}",
+                    consensus_key_transaction.transaction_hash, err
+                )));
+            }
+        };
+
+        // Check that the on-chain config now matches the new keys
+        let validator_config = ShowValidatorConfig {
+            profile_options: self.txn_options.profile_options,
+            rest_options: self.txn_options.rest_options,
+            operator_args: OperatorArgs {
+                pool_address_args: OptionalPoolAddressArgs {
+                    pool_address: Some(address),
+                },
+            },
+        }
+        .execute()
+        .await?;
+        if validator_config.consensus_public_key != consensus_public_key
+            || validator_config.validator_network_addresses != validator_network_addresses
+            || validator_config.fullnode_network_addresses != full_node_network_addresses
+        {
+            return Err(CliError::UnexpectedError(format!(
+                "On-chain validator config of {} does not match the rotated keys: {:?}",
+                address, validator_config
+            )));
+        }
+
+        Ok(RotateKeysSummary {
+            consensus_key_transaction,
+            network_addresses_transaction,
+            validator_config,
+        })
+    }
+}
+
 /// Tool to analyze the performance of an individual validator
 #[derive(Parser)]
 pub struct AnalyzeValidatorPerformance {
@@ -971,6 +1227,26 @@ pub struct AnalyzeValidatorPerformance {
     #[clap(arg_enum, long)]
     pub(crate) analyze_mode: AnalyzeMode,
 
+    /// Format of the output: [Table, Csv, Json]
+    ///
+    /// Csv and Json contain the stats of each validator in each epoch, for further processing
+    #[clap(arg_enum, long, default_value = "table")]
+    pub(crate) output_format: AnalyzeOutputFormat,
+
+    /// File to write the Csv or Json output to, instead of stdout
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: Option<PathBuf>,
+
+    /// After the analysis, keep following new blocks and report proposal failures as they happen
+    ///
+    /// The failures are printed to stdout, so Csv and Json output need `--output-file`
+    #[clap(long)]
+    pub(crate) watch: bool,
+
+    /// Interval in seconds between polls for new blocks in `--watch` mode
+    #[clap(long, default_value = "10")]
+    pub(crate) watch_interval_secs: u64,
+
     #[clap(flatten)]
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
@@ -993,6 +1269,16 @@ pub enum AnalyzeMode {
     NetworkHealthOverTime,
 }
 
+#[derive(PartialEq, Eq, clap::ArgEnum, Clone, Copy)]
+pub enum AnalyzeOutputFormat {
+    /// Human readable tables, as selected by the analyze mode
+    Table,
+    /// One row per validator and epoch
+    Csv,
+    /// A list of the stats of each epoch
+    Json,
+}
+
 #[async_trait]
 impl CliCommand<()> for AnalyzeValidatorPerformance {
     fn command_name(&self) -> &'static str {
@@ -1000,6 +1286,15 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
     }
 
     async fn execute(mut self) -> CliTypedResult<()> {
+        // The proposal failures are printed to stdout as they happen
+        if self.watch
+            && self.output_format != AnalyzeOutputFormat::Table
+            && self.output_file.is_none()
+        {
+            return Err(CliError::CommandArgumentError(
+                "--watch with Csv or Json output requires --output-file".to_string(),
+            ));
+        }
         let client = self.rest_options.client(&self.profile_options.profile)?;
 
         let epochs =
@@ -1007,6 +1302,22 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
                 .await?;
         let mut stats = HashMap::new();
 
+        if self.output_format != AnalyzeOutputFormat::Table {
+            let reports: Vec<_> = epochs
+                .iter()
+                .map(|epoch_info| EpochReport {
+                    epoch: epoch_info.epoch,
+                    partial: epoch_info.partial,
+                    stats: AnalyzeValidators::analyze(&epoch_info.blocks, &epoch_info.validators),
+                })
+                .collect();
+            self.write_reports(&reports)?;
+            if self.watch {
+                self.watch_proposal_failures(&client).await?;
+            }
+            return Ok(());
+        }
+
         let print_detailed = self.analyze_mode == AnalyzeMode::DetailedEpochTable
             || self.analyze_mode == AnalyzeMode::All;
         for epoch_info in epochs {
@@ -1038,6 +1349,9 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
 
         if stats.is_empty() {
             println!("No data found for given input");
+            if self.watch {
+                self.watch_proposal_failures(&client).await?;
+            }
             return Ok(());
         }
         let total_stats = stats
@@ -1074,8 +1388,135 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
             );
             AnalyzeValidators::print_network_health_over_time(&stats, &all_validators);
         }
+        if self.watch {
+            self.watch_proposal_failures(&client).await?;
+        }
+        Ok(())
+    }
+}
+
+impl AnalyzeValidatorPerformance {
+    fn write_reports(&self, reports: &[EpochReport]) -> CliTypedResult<()> {
+        let mut out: Box<dyn Write> = match self.output_file {
+            Some(ref path) => Box::new(
+                std::fs::File::create(path)
+                    .map_err(|err| CliError::IO(path.display().to_string(), err))?,
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+        match self.output_format {
+            AnalyzeOutputFormat::Csv => AnalyzeValidators::write_csv(reports, &mut out)
+                .map_err(|err| CliError::IO("CSV output".to_string(), err))?,
+            AnalyzeOutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, reports)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+                writeln!(out).map_err(|err| CliError::IO("JSON output".to_string(), err))?;
+            }
+            AnalyzeOutputFormat::Table => {}
+        }
         Ok(())
     }
+
+    /// Follows new blocks, printing every failed proposal as it is committed, and a summary of
+    /// the failures of each epoch when it ends.  Runs until interrupted: failed requests are
+    /// retried at the next poll.
+    async fn watch_proposal_failures(
+        &self,
+        client: &aptos_rest_client::Client,
+    ) -> CliTypedResult<()> {
+        let latest = client
+            .get_new_block_events_bcs(None, Some(1))
+            .await?
+            .into_inner();
+        let mut cursor = latest.first().map_or(0, |event| event.sequence_number + 1);
+        let mut current_epoch = None;
+        let mut validators = vec![];
+        let mut failures: HashMap<AccountAddress, u32> = HashMap::new();
+        let mut rounds = 0;
+
+        println!("Watching for proposal failures from block {}", cursor);
+        loop {
+            'poll: loop {
+                let events = match client
+                    .get_new_block_events_bcs(Some(cursor), Some(1000))
+                    .await
+                {
+                    Ok(events) => events.into_inner(),
+                    Err(err) => {
+                        eprintln!("Failed to fetch blocks from {}, retrying: {}", cursor, err);
+                        break;
+                    }
+                };
+                if events.is_empty() {
+                    break;
+                }
+                for event in events {
+                    let epoch = event.event.epoch();
+                    if current_epoch != Some(epoch) {
+                        // Failed proposers are indices into the validator set of the event's epoch
+                        let fetched =
+                            FetchMetadata::get_validators_at_version(client, event.version).await;
+                        validators = match fetched {
+                            Ok(validators) => validators,
+                            Err(err) => {
+                                eprintln!(
+                                    "Failed to fetch the validators of epoch {}, retrying: {}",
+                                    epoch, err
+                                );
+                                break 'poll;
+                            }
+                        };
+                        if let Some(previous_epoch) = current_epoch {
+                            print_epoch_failures(previous_epoch, rounds, &failures);
+                        }
+                        current_epoch = Some(epoch);
+                        failures.clear();
+                        rounds = 0;
+                    }
+                    cursor = event.sequence_number + 1;
+
+                    let failed_proposers = event.event.failed_proposer_indices();
+                    rounds += 1 + failed_proposers.len() as u64;
+                    for index in failed_proposers {
+                        let proposer = validators
+                            .get(*index as usize)
+                            .map(|validator| validator.address);
+                        match proposer {
+                            Some(address) => {
+                                *failures.entry(address).or_default() += 1;
+                                println!(
+                                    "Epoch {} round {}: proposal failure by {}",
+                                    epoch,
+                                    event.event.round(),
+                                    address
+                                );
+                            }
+                            None => println!(
+                                "Epoch {} round {}: proposal failure by unknown validator index {}",
+                                epoch,
+                                event.event.round(),
+                                index
+                            ),
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(self.watch_interval_secs)).await;
+        }
+    }
+}
+
+fn print_epoch_failures(epoch: u64, rounds: u64, failures: &HashMap<AccountAddress, u32>) {
+    let total: u32 = failures.values().sum();
+    println!(
+        "Epoch {} ended: {} proposal failures in {} observed rounds",
+        epoch, total, rounds
+    );
+    let mut failures: Vec<_> = failures.iter().collect();
+    failures.sort_by(|a, b| b.1.cmp(a.1));
+    for (address, count) in failures {
+        println!("  {}: {}", address, count);
+    }
 }
 
 /// Tool to bootstrap DB from backup
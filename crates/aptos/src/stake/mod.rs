@@ -1,6 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod rewards;
+mod vesting;
+
 use crate::common::types::{
     CliCommand, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
 };
@@ -9,6 +12,8 @@ use aptos_types::account_address::{default_stake_pool_address, AccountAddress};
 use async_trait::async_trait;
 use cached_packages::aptos_stdlib;
 use clap::Parser;
+use rewards::ShowRewards;
+use vesting::VestingTool;
 
 /// Tool for manipulating stake
 ///
@@ -22,6 +27,9 @@ pub enum StakeTool {
     InitializeStakeOwner(InitializeStakeOwner),
     SetOperator(SetOperator),
     SetDelegatedVoter(SetDelegatedVoter),
+    ShowRewards(ShowRewards),
+    #[clap(subcommand)]
+    Vesting(VestingTool),
 }
 
 impl StakeTool {
@@ -36,6 +44,8 @@ impl StakeTool {
             InitializeStakeOwner(tool) => tool.execute_serialized().await,
             SetOperator(tool) => tool.execute_serialized().await,
             SetDelegatedVoter(tool) => tool.execute_serialized().await,
+            ShowRewards(tool) => tool.execute_serialized().await,
+            Vesting(tool) => tool.execute().await,
         }
     }
 }
@@ -0,0 +1,467 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use aptos_rest_client::{aptos_api_types::TransactionData, error::RestError, Client};
+use aptos_types::account_address::AccountAddress;
+use aptos_types::stake_pool::{
+    AddStakeEvent, DistributeRewardsEvent, StakePool, WithdrawStakeEvent,
+};
+use aptos_types::staking_conttract::{RequestCommissionEvent, StakingContractStore};
+use aptos_types::transaction::Transaction;
+use aptos_types::vesting::VestingAdminStore;
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const STAKE_POOL: &str = "0x1::stake::StakePool";
+const STAKING_CONTRACT_STORE: &str = "0x1::staking_contract::Store";
+const VESTING_ADMIN_STORE: &str = "0x1::vesting::AdminStore";
+const EVENTS_PAGE_SIZE: u16 = 100;
+
+/// Show the rewards, commission and lockup of stake pools
+///
+/// Finds the stake pools owned by the owner address, either directly, through staking contracts
+/// or through vesting contracts, and walks their event history to report the rewards of each
+/// epoch, the commission paid to the operator, and the current balances and lockup.
+#[derive(Parser)]
+pub struct ShowRewards {
+    /// The owner address that directly or indirectly owns the stake pools
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) owner_address: AccountAddress,
+
+    /// Number of most recent epochs to report rewards for
+    #[clap(long, default_value = "10")]
+    pub(crate) epochs: u64,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// How a stake pool is owned
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum StakePoolType {
+    Direct,
+    StakingContract,
+    Vesting,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpochRewards {
+    pub epoch: u64,
+    pub version: u64,
+    pub rewards: u64,
+    /// Share of the rewards owed to the operator, based on the current commission percentage
+    pub commission: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakePoolRewards {
+    pub pool_address: AccountAddress,
+    pub operator_address: AccountAddress,
+    pub pool_type: StakePoolType,
+    pub commission_percentage: Option<u64>,
+    pub active: u64,
+    pub inactive: u64,
+    pub pending_active: u64,
+    pub pending_inactive: u64,
+    pub locked_until_secs: u64,
+    /// Seconds until the lockup expires, 0 if it already has
+    pub lockup_expires_in_secs: u64,
+    pub total_added: u64,
+    pub total_withdrawn: u64,
+    /// Rewards over all epochs, including the ones not listed in `epoch_rewards`
+    pub total_rewards: u64,
+    /// Commission the operator has requested from the staking contract
+    pub total_commission_paid: u64,
+    pub epoch_rewards: Vec<EpochRewards>,
+}
+
+/// A stake pool, and the account holding the staking contract that manages it, if any
+struct OwnedStakePool {
+    pool_address: AccountAddress,
+    pool_type: StakePoolType,
+    staking_contract: Option<(AccountAddress, u64)>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<StakePoolRewards>> for ShowRewards {
+    fn command_name(&self) -> &'static str {
+        "ShowRewards"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<StakePoolRewards>> {
+        let client = self.rest_options.client(&self.profile_options.profile)?;
+        let now_secs = client
+            .get_ledger_information()
+            .await?
+            .into_inner()
+            .timestamp_usecs
+            / 1_000_000;
+
+        let mut results = vec![];
+        for pool in owned_stake_pools(&client, self.owner_address).await? {
+            results.push(pool_rewards(&client, pool, self.epochs, now_secs).await?);
+        }
+        Ok(results)
+    }
+}
+
+/// Finds all stake pools owned by `owner_address`, like `aptos node get-stake-pool`
+async fn owned_stake_pools(
+    client: &Client,
+    owner_address: AccountAddress,
+) -> CliTypedResult<Vec<OwnedStakePool>> {
+    let mut pools = vec![];
+    if get_resource_if_exists::<StakePool>(client, owner_address, STAKE_POOL)
+        .await?
+        .is_some()
+    {
+        pools.push(OwnedStakePool {
+            pool_address: owner_address,
+            pool_type: StakePoolType::Direct,
+            staking_contract: None,
+        });
+    }
+
+    pools.append(
+        &mut staking_contract_pools(client, owner_address, StakePoolType::StakingContract).await?,
+    );
+
+    // Vesting contracts stake through a staking contract owned by the vesting contract itself
+    if let Some(admin_store) =
+        get_resource_if_exists::<VestingAdminStore>(client, owner_address, VESTING_ADMIN_STORE)
+            .await?
+    {
+        for contract_address in admin_store.vesting_contracts {
+            pools.append(
+                &mut staking_contract_pools(client, contract_address, StakePoolType::Vesting)
+                    .await?,
+            );
+        }
+    }
+    Ok(pools)
+}
+
+/// Fetches a resource, or `None` if the account or the resource doesn't exist
+async fn get_resource_if_exists<T: DeserializeOwned>(
+    client: &Client,
+    address: AccountAddress,
+    resource_type: &str,
+) -> CliTypedResult<Option<T>> {
+    match client
+        .get_account_resource_bcs::<T>(address, resource_type)
+        .await
+    {
+        Ok(resource) => Ok(Some(resource.into_inner())),
+        Err(RestError::Api(err)) if err.status_code == StatusCode::NOT_FOUND => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn staking_contract_pools(
+    client: &Client,
+    store_address: AccountAddress,
+    pool_type: StakePoolType,
+) -> CliTypedResult<Vec<OwnedStakePool>> {
+    let store = get_resource_if_exists::<StakingContractStore>(
+        client,
+        store_address,
+        STAKING_CONTRACT_STORE,
+    )
+    .await?;
+    Ok(store
+        .map(|store| store.staking_contracts)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| OwnedStakePool {
+            pool_address: entry.value.pool_address,
+            pool_type,
+            staking_contract: Some((store_address, entry.value.commission_percentage)),
+        })
+        .collect())
+}
+
+/// Events of a stake pool, and of the staking contract managing it, the report is built from
+struct PoolEvents {
+    added: Vec<AddStakeEvent>,
+    withdrawn: Vec<WithdrawStakeEvent>,
+    /// All rewards distributed to the pool, oldest first
+    rewards: Vec<DistributeRewardsEvent>,
+    /// Epoch and version of the most recent reward distributions, oldest first
+    recent_rewards: Vec<(u64, u64)>,
+    /// Commission requests of every pool of the staking contract
+    commission_requests: Vec<RequestCommissionEvent>,
+}
+
+async fn pool_rewards(
+    client: &Client,
+    pool: OwnedStakePool,
+    epochs: u64,
+    now_secs: u64,
+) -> CliTypedResult<StakePoolRewards> {
+    let pool_address = pool.pool_address;
+    let stake_pool = client
+        .get_account_resource_bcs::<StakePool>(pool_address, STAKE_POOL)
+        .await?
+        .into_inner();
+
+    let added =
+        fetch_events::<AddStakeEvent>(client, pool_address, STAKE_POOL, "add_stake_events").await?;
+    let withdrawn = fetch_events::<WithdrawStakeEvent>(
+        client,
+        pool_address,
+        STAKE_POOL,
+        "withdraw_stake_events",
+    )
+    .await?;
+    let rewards = fetch_events::<DistributeRewardsEvent>(
+        client,
+        pool_address,
+        STAKE_POOL,
+        "distribute_rewards_events",
+    )
+    .await?;
+
+    let skip = rewards.len().saturating_sub(epochs as usize);
+    let mut recent_rewards = vec![];
+    for (version, _) in rewards.iter().skip(skip) {
+        recent_rewards.push((epoch_of_reconfiguration(client, *version).await?, *version));
+    }
+
+    let commission_requests = match pool.staking_contract {
+        Some((store_address, _)) => fetch_events::<RequestCommissionEvent>(
+            client,
+            store_address,
+            STAKING_CONTRACT_STORE,
+            "request_commission_events",
+        )
+        .await?
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect(),
+        None => vec![],
+    };
+
+    let events = PoolEvents {
+        added: added.into_iter().map(|(_, event)| event).collect(),
+        withdrawn: withdrawn.into_iter().map(|(_, event)| event).collect(),
+        rewards: rewards.into_iter().map(|(_, event)| event).collect(),
+        recent_rewards,
+        commission_requests,
+    };
+    Ok(build_report(pool, stake_pool, events, now_secs))
+}
+
+fn build_report(
+    pool: OwnedStakePool,
+    stake_pool: StakePool,
+    events: PoolEvents,
+    now_secs: u64,
+) -> StakePoolRewards {
+    let pool_address = pool.pool_address;
+    let commission_percentage = pool.staking_contract.map(|(_, percentage)| percentage);
+    let skip = events.rewards.len() - events.recent_rewards.len();
+    let epoch_rewards = events.rewards[skip..]
+        .iter()
+        .zip(events.recent_rewards)
+        .map(|(event, (epoch, version))| EpochRewards {
+            epoch,
+            version,
+            rewards: event.rewards_amount,
+            commission: event.rewards_amount * commission_percentage.unwrap_or(0) / 100,
+        })
+        .collect();
+
+    StakePoolRewards {
+        pool_address,
+        operator_address: stake_pool.operator_address,
+        pool_type: pool.pool_type,
+        commission_percentage,
+        active: stake_pool.active,
+        inactive: stake_pool.inactive,
+        pending_active: stake_pool.pending_active,
+        pending_inactive: stake_pool.pending_inactive,
+        locked_until_secs: stake_pool.locked_until_secs,
+        lockup_expires_in_secs: stake_pool.locked_until_secs.saturating_sub(now_secs),
+        total_added: events.added.iter().map(|event| event.amount_added).sum(),
+        total_withdrawn: events
+            .withdrawn
+            .iter()
+            .map(|event| event.amount_withdrawn)
+            .sum(),
+        total_rewards: events
+            .rewards
+            .iter()
+            .map(|event| event.rewards_amount)
+            .sum(),
+        total_commission_paid: events
+            .commission_requests
+            .iter()
+            .filter(|event| event.pool_address == pool_address)
+            .map(|event| event.commission_amount)
+            .sum(),
+        epoch_rewards,
+    }
+}
+
+/// Rewards are distributed by the block that ends an epoch, so the epoch they were earned in is
+/// the epoch of that block
+async fn epoch_of_reconfiguration(client: &Client, version: u64) -> CliTypedResult<u64> {
+    match client
+        .get_transaction_by_version_bcs(version)
+        .await?
+        .into_inner()
+    {
+        TransactionData::OnChain(txn) => match txn.transaction {
+            Transaction::BlockMetadata(block_metadata) => Ok(block_metadata.epoch()),
+            _ => Err(CliError::UnexpectedError(format!(
+                "Rewards were distributed outside of a block prologue at version {}",
+                version
+            ))),
+        },
+        TransactionData::Pending(_) => Err(CliError::UnexpectedError(format!(
+            "Transaction at version {} is still pending",
+            version
+        ))),
+    }
+}
+
+/// Fetches all events of an event handle, alongside the version they were emitted at
+async fn fetch_events<T: DeserializeOwned>(
+    client: &Client,
+    address: AccountAddress,
+    struct_tag: &str,
+    field_name: &str,
+) -> CliTypedResult<Vec<(u64, T)>> {
+    let mut events = vec![];
+    let mut cursor = 0;
+    loop {
+        let page = client
+            .get_account_events_bcs(
+                address,
+                struct_tag,
+                field_name,
+                Some(cursor),
+                Some(EVENTS_PAGE_SIZE),
+            )
+            .await?
+            .into_inner();
+        let page_len = page.len();
+        for event in page {
+            cursor = event.event.sequence_number() + 1;
+            events.push((
+                event.transaction_version,
+                bcs::from_bytes(event.event.event_data())?,
+            ));
+        }
+        if page_len < EVENTS_PAGE_SIZE as usize {
+            return Ok(events);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::event::{EventHandle, EventKey};
+
+    fn stake_pool(operator_address: AccountAddress) -> StakePool {
+        let handle = || EventHandle::new(EventKey::new(0, AccountAddress::ONE), 0);
+        StakePool {
+            active: 1_100,
+            inactive: 0,
+            pending_active: 0,
+            pending_inactive: 100,
+            locked_until_secs: 1_000,
+            operator_address,
+            delegated_voter: AccountAddress::ONE,
+            initialize_validator_events: handle(),
+            set_operator_events: handle(),
+            add_stake_events: handle(),
+            reactivate_stake_events: handle(),
+            rotate_consensus_key_events: handle(),
+            update_network_and_fullnode_addresses_events: handle(),
+            increase_lockup_events: handle(),
+            join_validator_set_events: handle(),
+            distribute_rewards_events: handle(),
+            unlock_stake_events: handle(),
+            withdraw_stake_events: handle(),
+            leave_validator_set_events: handle(),
+        }
+    }
+
+    #[test]
+    fn report_from_events() {
+        let pool_address = AccountAddress::from_hex_literal("0xa").unwrap();
+        let other_pool_address = AccountAddress::from_hex_literal("0xb").unwrap();
+        let operator = AccountAddress::from_hex_literal("0xc").unwrap();
+        let pool = OwnedStakePool {
+            pool_address,
+            pool_type: StakePoolType::StakingContract,
+            staking_contract: Some((AccountAddress::ONE, 10)),
+        };
+        let rewards = [100, 200, 300]
+            .into_iter()
+            .map(|rewards_amount| DistributeRewardsEvent {
+                pool_address,
+                rewards_amount,
+            })
+            .collect();
+        let request_commission = |pool_address, commission_amount| RequestCommissionEvent {
+            operator,
+            pool_address,
+            accumulated_rewards: 0,
+            commission_amount,
+        };
+        let events = PoolEvents {
+            added: vec![
+                AddStakeEvent {
+                    pool_address,
+                    amount_added: 1_000,
+                },
+                AddStakeEvent {
+                    pool_address,
+                    amount_added: 500,
+                },
+            ],
+            withdrawn: vec![WithdrawStakeEvent {
+                pool_address,
+                amount_withdrawn: 800,
+            }],
+            rewards,
+            // Only the last two epochs are reported
+            recent_rewards: vec![(4, 40), (5, 50)],
+            commission_requests: vec![
+                request_commission(pool_address, 30),
+                request_commission(other_pool_address, 1_000),
+            ],
+        };
+
+        let report = build_report(pool, stake_pool(operator), events, 400);
+        assert_eq!(report.operator_address, operator);
+        assert_eq!(report.commission_percentage, Some(10));
+        assert_eq!(report.lockup_expires_in_secs, 600);
+        assert_eq!(report.total_added, 1_500);
+        assert_eq!(report.total_withdrawn, 800);
+        assert_eq!(report.total_rewards, 600);
+        assert_eq!(report.total_commission_paid, 30);
+        let epoch_rewards: Vec<_> = report
+            .epoch_rewards
+            .iter()
+            .map(|rewards| {
+                (
+                    rewards.epoch,
+                    rewards.version,
+                    rewards.rewards,
+                    rewards.commission,
+                )
+            })
+            .collect();
+        assert_eq!(epoch_rewards, vec![(4, 40, 200, 20), (5, 50, 300, 30)]);
+    }
+}
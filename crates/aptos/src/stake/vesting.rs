@@ -0,0 +1,856 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
+    TransactionOptions, TransactionSummary,
+};
+use crate::common::utils::{prompt_yes_with_override, write_to_file};
+use crate::governance::compile_in_temp_dir;
+use crate::move_tool::FrameworkPackageArgs;
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::stake_pool::StakePool;
+use aptos_types::transaction::{Script, TransactionPayload};
+use aptos_types::vesting::{
+    VestingAdminStore, VestingContract, VestingSchedule, VESTING_POOL_ACTIVE,
+};
+use async_trait::async_trait;
+use cached_packages::aptos_stdlib;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use tempfile::TempDir;
+
+const VESTING_CONTRACT: &str = "0x1::vesting::VestingContract";
+const VESTING_ADMIN_STORE: &str = "0x1::vesting::AdminStore";
+const STAKE_POOL: &str = "0x1::stake::StakePool";
+const OCTAS_PER_APT: u64 = 100_000_000;
+/// Bound on the periods listed in the unlock schedule, in case the schedule never runs out
+const MAX_SCHEDULE_PERIODS: u64 = 1000;
+
+/// Tool for managing vesting contracts
+///
+/// Vesting contracts stake a grant on behalf of shareholders, and release it to them over time
+/// according to a vesting schedule.
+#[derive(Subcommand)]
+pub enum VestingTool {
+    Create(CreateVestingContract),
+    Show(ShowVestingContract),
+    UnlockRewards(UnlockRewards),
+    Vest(Vest),
+    Distribute(Distribute),
+    SetBeneficiary(SetBeneficiary),
+    UpdateOperator(UpdateOperator),
+    UpdateVoter(UpdateVoter),
+    Terminate(TerminateVestingContract),
+    AdminWithdraw(AdminWithdraw),
+}
+
+impl VestingTool {
+    pub async fn execute(self) -> CliResult {
+        use VestingTool::*;
+        match self {
+            Create(tool) => tool.execute_serialized().await,
+            Show(tool) => tool.execute_serialized().await,
+            UnlockRewards(tool) => tool.execute_serialized().await,
+            Vest(tool) => tool.execute_serialized().await,
+            Distribute(tool) => tool.execute_serialized().await,
+            SetBeneficiary(tool) => tool.execute_serialized().await,
+            UpdateOperator(tool) => tool.execute_serialized().await,
+            UpdateVoter(tool) => tool.execute_serialized().await,
+            Terminate(tool) => tool.execute_serialized().await,
+            AdminWithdraw(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Status of a vesting contract, with amounts in APT
+#[derive(Debug, Serialize)]
+pub struct VestingContractStatus {
+    pub contract_address: AccountAddress,
+    pub admin: AccountAddress,
+    pub active: bool,
+    pub pool_address: AccountAddress,
+    pub operator: AccountAddress,
+    pub voter: AccountAddress,
+    pub commission_percentage: u64,
+    pub withdrawal_address: AccountAddress,
+    pub total_grant: String,
+    pub remaining_grant: String,
+    pub shareholders: Vec<ShareholderStatus>,
+    pub active_stake: String,
+    pub pending_active_stake: String,
+    pub pending_inactive_stake: String,
+    pub inactive_stake: String,
+    pub lockup_expires_in: String,
+    pub vesting_start_secs: u64,
+    pub period_duration_secs: u64,
+    pub last_vested_period: u64,
+    /// The next period that can be vested, if any of the grant remains
+    pub next_vesting: Option<VestingPeriod>,
+    /// The periods after `next_vesting`, until the grant runs out
+    pub unlock_schedule: Vec<VestingPeriod>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareholderStatus {
+    pub shareholder: AccountAddress,
+    pub beneficiary: AccountAddress,
+    pub grant: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VestingPeriod {
+    pub period: u64,
+    pub vests_at_secs: u64,
+    /// Time until the period can be vested, or "now" if it already can
+    pub vests_in: String,
+    pub amount: String,
+}
+
+/// Result of a vesting transaction, alongside the status of the contract after it
+#[derive(Debug, Serialize)]
+pub struct VestingTransactionSummary {
+    pub transaction: TransactionSummary,
+    pub contract: Option<VestingContractStatus>,
+}
+
+/// Formats an amount of Octas as APT
+fn format_apt(octas: u64) -> String {
+    format!("{}.{:08} APT", octas / OCTAS_PER_APT, octas % OCTAS_PER_APT)
+}
+
+/// Formats a duration in seconds as days, hours and minutes
+fn format_duration(secs: u64) -> String {
+    if secs == 0 {
+        return "now".to_string();
+    }
+    format!(
+        "{}d {}h {}m",
+        secs / 86_400,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// Lists the periods left to vest, by replaying `vesting::vest` for each of them
+fn vesting_periods(
+    schedule: &VestingSchedule,
+    total_grant: u64,
+    remaining_grant: u64,
+    now_secs: u64,
+) -> Vec<VestingPeriod> {
+    let mut periods = vec![];
+    let mut remaining = remaining_grant;
+    let mut period = schedule.last_vested_period + 1;
+    while remaining > 0 && period <= schedule.last_vested_period + MAX_SCHEDULE_PERIODS {
+        let amount = match schedule.fraction(period) {
+            Some(fraction) => fraction.multiply_u64(total_grant).min(remaining),
+            None => break,
+        };
+        if amount == 0 {
+            break;
+        }
+        remaining -= amount;
+        let vests_at_secs = schedule.period_end_secs(period);
+        periods.push(VestingPeriod {
+            period,
+            vests_at_secs,
+            vests_in: format_duration(vests_at_secs.saturating_sub(now_secs)),
+            amount: format_apt(amount),
+        });
+        period += 1;
+    }
+    periods
+}
+
+/// Fetches the vesting contract and its stake pool, and summarizes them
+pub async fn get_vesting_contract_status(
+    client: &Client,
+    contract_address: AccountAddress,
+) -> CliTypedResult<VestingContractStatus> {
+    let now_secs = client
+        .get_ledger_information()
+        .await?
+        .into_inner()
+        .timestamp_usecs
+        / 1_000_000;
+    let contract = client
+        .get_account_resource_bcs::<VestingContract>(contract_address, VESTING_CONTRACT)
+        .await?
+        .into_inner();
+    let stake_pool = client
+        .get_account_resource_bcs::<StakePool>(contract.staking.pool_address, STAKE_POOL)
+        .await?
+        .into_inner();
+
+    let grant_pool = &contract.grant_pool;
+    let total_grant = grant_pool.total_coins;
+    let shareholders = grant_pool
+        .shares
+        .iter()
+        .map(|share| ShareholderStatus {
+            shareholder: share.key,
+            beneficiary: contract.beneficiary(share.key),
+            grant: format_apt(if grant_pool.total_shares == 0 {
+                0
+            } else {
+                (share.value as u128 * total_grant as u128 / grant_pool.total_shares as u128) as u64
+            }),
+        })
+        .collect();
+
+    let schedule = &contract.vesting_schedule;
+    let mut unlock_schedule =
+        vesting_periods(schedule, total_grant, contract.remaining_grant, now_secs);
+    let next_vesting = if contract.state == VESTING_POOL_ACTIVE && !unlock_schedule.is_empty() {
+        Some(unlock_schedule.remove(0))
+    } else {
+        None
+    };
+
+    Ok(VestingContractStatus {
+        contract_address,
+        admin: contract.admin,
+        active: contract.state == VESTING_POOL_ACTIVE,
+        pool_address: contract.staking.pool_address,
+        operator: contract.staking.operator,
+        voter: contract.staking.voter,
+        commission_percentage: contract.staking.commission_percentage,
+        withdrawal_address: contract.withdrawal_address,
+        total_grant: format_apt(total_grant),
+        remaining_grant: format_apt(contract.remaining_grant),
+        shareholders,
+        active_stake: format_apt(stake_pool.active),
+        pending_active_stake: format_apt(stake_pool.pending_active),
+        pending_inactive_stake: format_apt(stake_pool.pending_inactive),
+        inactive_stake: format_apt(stake_pool.inactive),
+        lockup_expires_in: format_duration(stake_pool.locked_until_secs.saturating_sub(now_secs)),
+        vesting_start_secs: schedule.start_timestamp_secs,
+        period_duration_secs: schedule.period_duration,
+        last_vested_period: schedule.last_vested_period,
+        next_vesting,
+        unlock_schedule,
+    })
+}
+
+/// Submits a transaction for a vesting contract, and then fetches the status of the contract
+async fn submit_vesting_transaction(
+    txn_options: &TransactionOptions,
+    contract_address: AccountAddress,
+    payload: TransactionPayload,
+) -> CliTypedResult<VestingTransactionSummary> {
+    let transaction = txn_options.submit_transaction(payload).await?;
+    let client = txn_options
+        .rest_options
+        .client(&txn_options.profile_options.profile)?;
+    Ok(VestingTransactionSummary {
+        transaction: transaction.into(),
+        contract: get_vesting_contract_status(&client, contract_address)
+            .await
+            .ok(),
+    })
+}
+
+#[derive(Parser)]
+pub struct VestingContractArgs {
+    /// Address of the vesting contract
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) contract_address: AccountAddress,
+}
+
+/// Create a vesting contract from the sender's coins
+///
+/// The sender becomes the admin of the contract.  The grant is staked right away with the given
+/// operator, and vests according to the schedule.  For example, `--schedule-numerators 3 3 1
+/// --schedule-denominator 48` vests 3/48 of the grant in each of the first two periods, and 1/48
+/// in every period after that until the grant runs out.
+///
+/// The contract is created by a script calling `vesting::create_vesting_contract`, which is
+/// compiled against the Aptos framework chosen with `--framework-git-rev` or
+/// `--framework-local-dir`.
+#[derive(Parser)]
+pub struct CreateVestingContract {
+    /// Addresses of the shareholders of the grant
+    #[clap(long, multiple_values = true, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) shareholders: Vec<AccountAddress>,
+
+    /// Amounts of Octas (10^-8 APT) granted to each of the shareholders, in the same order
+    #[clap(long, multiple_values = true)]
+    pub(crate) amounts: Vec<u64>,
+
+    /// Numerators of the fraction of the grant vesting in each period
+    #[clap(long, multiple_values = true)]
+    pub(crate) schedule_numerators: Vec<u64>,
+
+    /// Denominator of the fractions of the vesting schedule
+    #[clap(long)]
+    pub(crate) schedule_denominator: u64,
+
+    /// Unix timestamp in seconds at which vesting starts
+    #[clap(long)]
+    pub(crate) start_timestamp_secs: u64,
+
+    /// Duration of a vesting period in seconds
+    #[clap(long)]
+    pub(crate) period_duration_secs: u64,
+
+    /// Address funds are returned to if the contract is terminated
+    ///
+    /// Defaults to the sender
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) withdrawal_address: Option<AccountAddress>,
+
+    /// Address of the operator of the stake pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) operator: AccountAddress,
+
+    /// Address of the delegated voter of the stake pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) voter: AccountAddress,
+
+    /// Percentage of accumulated rewards to pay the operator as commission
+    #[clap(long)]
+    pub(crate) commission_percentage: u64,
+
+    /// Seed for the address of the vesting contract, to create multiple contracts
+    #[clap(long, default_value = "")]
+    pub(crate) seed: String,
+
+    #[clap(flatten)]
+    pub(crate) framework_package_args: FrameworkPackageArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+impl CreateVestingContract {
+    /// Returns the source of a script creating the vesting contract
+    ///
+    /// Script arguments can't be vectors of addresses, so all the arguments are inlined.
+    fn script(&self, withdrawal_address: AccountAddress) -> String {
+        let join = |values: Vec<String>| values.join(", ");
+        format!(
+            r#"script {{
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+    use aptos_framework::vesting;
+    use aptos_std::simple_map;
+    use std::fixed_point32;
+    use std::vector;
+
+    fun main(admin: &signer) {{
+        let shareholders: vector<address> = vector[{shareholders}];
+        let amounts: vector<u64> = vector[{amounts}];
+        let schedule_numerators: vector<u64> = vector[{schedule_numerators}];
+
+        let schedule = vector::empty();
+        let i = 0;
+        while (i < vector::length(&schedule_numerators)) {{
+            let numerator = *vector::borrow(&schedule_numerators, i);
+            vector::push_back(
+                &mut schedule,
+                fixed_point32::create_from_rational(numerator, {schedule_denominator}),
+            );
+            i = i + 1;
+        }};
+
+        let buy_ins = simple_map::create();
+        let i = 0;
+        while (i < vector::length(&shareholders)) {{
+            let coins = coin::withdraw<AptosCoin>(admin, *vector::borrow(&amounts, i));
+            simple_map::add(&mut buy_ins, *vector::borrow(&shareholders, i), coins);
+            i = i + 1;
+        }};
+
+        vesting::create_vesting_contract(
+            admin,
+            &shareholders,
+            buy_ins,
+            vesting::create_vesting_schedule(schedule, {start_timestamp_secs}, {period_duration_secs}),
+            @{withdrawal_address},
+            @{operator},
+            @{voter},
+            {commission_percentage},
+            x"{seed}",
+        );
+    }}
+}}
+"#,
+            shareholders = join(
+                self.shareholders
+                    .iter()
+                    .map(|shareholder| format!("@{}", shareholder.to_hex_literal()))
+                    .collect()
+            ),
+            amounts = join(self.amounts.iter().map(u64::to_string).collect()),
+            schedule_numerators = join(
+                self.schedule_numerators
+                    .iter()
+                    .map(u64::to_string)
+                    .collect()
+            ),
+            schedule_denominator = self.schedule_denominator,
+            start_timestamp_secs = self.start_timestamp_secs,
+            period_duration_secs = self.period_duration_secs,
+            withdrawal_address = withdrawal_address.to_hex_literal(),
+            operator = self.operator.to_hex_literal(),
+            voter = self.voter.to_hex_literal(),
+            commission_percentage = self.commission_percentage,
+            seed = hex::encode(self.seed.as_bytes()),
+        )
+    }
+
+    /// Compiles the script creating the vesting contract
+    fn compile_script(&self, withdrawal_address: AccountAddress) -> CliTypedResult<Vec<u8>> {
+        let temp_dir = TempDir::new().map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to create temporary directory {}", err))
+        })?;
+        let script_path = temp_dir.path().join("create_vesting_contract.move");
+        write_to_file(
+            script_path.as_path(),
+            "create_vesting_contract.move",
+            self.script(withdrawal_address).as_bytes(),
+        )?;
+        let (bytecode, _script_hash) = compile_in_temp_dir(
+            "CreateVestingContract",
+            script_path.as_path(),
+            &self.framework_package_args,
+            self.txn_options.prompt_options,
+        )?;
+        Ok(bytecode)
+    }
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for CreateVestingContract {
+    fn command_name(&self) -> &'static str {
+        "CreateVestingContract"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        if self.shareholders.len() != self.amounts.len() {
+            return Err(CliError::CommandArgumentError(
+                "Every shareholder must have exactly one amount".to_string(),
+            ));
+        }
+        if self.schedule_numerators.is_empty() || self.schedule_denominator == 0 {
+            return Err(CliError::CommandArgumentError(
+                "The vesting schedule must have at least one fraction with a non-zero denominator"
+                    .to_string(),
+            ));
+        }
+
+        let admin = self.txn_options.sender_address()?;
+        let bytecode = self.compile_script(self.withdrawal_address.unwrap_or(admin))?;
+        let transaction = self
+            .txn_options
+            .submit_transaction(TransactionPayload::Script(Script::new(
+                bytecode,
+                vec![],
+                vec![],
+            )))
+            .await?;
+
+        // The new contract is the last one created by the admin
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options.profile)?;
+        let contract = match client
+            .get_account_resource_bcs::<VestingAdminStore>(admin, VESTING_ADMIN_STORE)
+            .await
+        {
+            Ok(admin_store) => match admin_store.into_inner().vesting_contracts.last() {
+                Some(contract_address) => get_vesting_contract_status(&client, *contract_address)
+                    .await
+                    .ok(),
+                None => None,
+            },
+            Err(_) => None,
+        };
+        Ok(VestingTransactionSummary {
+            transaction: transaction.into(),
+            contract,
+        })
+    }
+}
+
+/// Show the status and unlock schedule of a vesting contract
+#[derive(Parser)]
+pub struct ShowVestingContract {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingContractStatus> for ShowVestingContract {
+    fn command_name(&self) -> &'static str {
+        "ShowVestingContract"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingContractStatus> {
+        let client = self.rest_options.client(&self.profile_options.profile)?;
+        get_vesting_contract_status(&client, self.contract_args.contract_address).await
+    }
+}
+
+/// Unlock the rewards accumulated by a vesting contract
+///
+/// Rewards become withdrawable when the lockup of the stake pool expires, and can then be sent to
+/// the shareholders with `distribute`.
+#[derive(Parser)]
+pub struct UnlockRewards {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for UnlockRewards {
+    fn command_name(&self) -> &'static str {
+        "UnlockRewards"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_unlock_rewards(contract_address),
+        )
+        .await
+    }
+}
+
+/// Unlock the vested portion of the grant, alongside any rewards
+///
+/// Vests one period at a time, so it has to be called once for every period that passed.
+#[derive(Parser)]
+pub struct Vest {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for Vest {
+    fn command_name(&self) -> &'static str {
+        "Vest"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_vest(contract_address),
+        )
+        .await
+    }
+}
+
+/// Distribute the withdrawable stake of a vesting contract to its shareholders
+#[derive(Parser)]
+pub struct Distribute {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for Distribute {
+    fn command_name(&self) -> &'static str {
+        "Distribute"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_distribute(contract_address),
+        )
+        .await
+    }
+}
+
+/// Set the beneficiary that distributions of a shareholder are sent to
+///
+/// Can only be called by the admin of the vesting contract.
+#[derive(Parser)]
+pub struct SetBeneficiary {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+
+    /// Address of the shareholder
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) shareholder: AccountAddress,
+
+    /// Address of the new beneficiary
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) beneficiary: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for SetBeneficiary {
+    fn command_name(&self) -> &'static str {
+        "SetBeneficiary"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_set_beneficiary(
+                contract_address,
+                self.shareholder,
+                self.beneficiary,
+            ),
+        )
+        .await
+    }
+}
+
+/// Switch the operator of the stake pool of a vesting contract
+///
+/// Can only be called by the admin of the vesting contract.
+#[derive(Parser)]
+pub struct UpdateOperator {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+
+    /// Address of the new operator
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) operator: AccountAddress,
+
+    /// Percentage of accumulated rewards to pay the new operator as commission
+    ///
+    /// Defaults to the commission of the current operator
+    #[clap(long)]
+    pub(crate) commission_percentage: Option<u64>,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for UpdateOperator {
+    fn command_name(&self) -> &'static str {
+        "UpdateOperator"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        let payload = match self.commission_percentage {
+            Some(commission_percentage) => aptos_stdlib::vesting_update_operator(
+                contract_address,
+                self.operator,
+                commission_percentage,
+            ),
+            None => aptos_stdlib::vesting_update_operator_with_same_commission(
+                contract_address,
+                self.operator,
+            ),
+        };
+        submit_vesting_transaction(&self.txn_options, contract_address, payload).await
+    }
+}
+
+/// Switch the delegated voter of the stake pool of a vesting contract
+///
+/// Can only be called by the admin of the vesting contract.
+#[derive(Parser)]
+pub struct UpdateVoter {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+
+    /// Address of the new voter
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) voter: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for UpdateVoter {
+    fn command_name(&self) -> &'static str {
+        "UpdateVoter"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_update_voter(contract_address, self.voter),
+        )
+        .await
+    }
+}
+
+/// Terminate a vesting contract
+///
+/// Distributes what is already withdrawable, and unlocks all remaining stake.  Once it is
+/// withdrawable, `admin-withdraw` sends it to the withdrawal address of the contract.  Can only
+/// be called by the admin of the vesting contract.
+#[derive(Parser)]
+pub struct TerminateVestingContract {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for TerminateVestingContract {
+    fn command_name(&self) -> &'static str {
+        "TerminateVestingContract"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        prompt_yes_with_override(
+            &format!(
+                "Terminating vesting contract {} stops all further vesting. Confirm?",
+                contract_address
+            ),
+            self.txn_options.prompt_options,
+        )?;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_terminate_vesting_contract(contract_address),
+        )
+        .await
+    }
+}
+
+/// Withdraw the funds of a terminated vesting contract to its withdrawal address
+///
+/// Can only be called by the admin of the vesting contract.
+#[derive(Parser)]
+pub struct AdminWithdraw {
+    #[clap(flatten)]
+    pub(crate) contract_args: VestingContractArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingTransactionSummary> for AdminWithdraw {
+    fn command_name(&self) -> &'static str {
+        "AdminWithdraw"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingTransactionSummary> {
+        let contract_address = self.contract_args.contract_address;
+        submit_vesting_transaction(
+            &self.txn_options,
+            contract_address,
+            aptos_stdlib::vesting_admin_withdraw(contract_address),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::vesting::FixedPoint32;
+
+    /// A schedule vesting `numerators / 16` of the grant, one period of 100 seconds at a time
+    fn schedule(numerators: &[u64], last_vested_period: u64) -> VestingSchedule {
+        VestingSchedule {
+            schedule: numerators
+                .iter()
+                .map(|numerator| FixedPoint32 {
+                    value: (numerator << 32) / 16,
+                })
+                .collect(),
+            start_timestamp_secs: 1_000,
+            period_duration: 100,
+            last_vested_period,
+        }
+    }
+
+    #[test]
+    fn format_amounts_and_durations() {
+        assert_eq!(format_apt(0), "0.00000000 APT");
+        assert_eq!(format_apt(1), "0.00000001 APT");
+        assert_eq!(format_apt(150_000_000), "1.50000000 APT");
+        assert_eq!(format_apt(12 * OCTAS_PER_APT), "12.00000000 APT");
+
+        assert_eq!(format_duration(0), "now");
+        assert_eq!(format_duration(59), "0d 0h 0m");
+        assert_eq!(format_duration(90_061), "1d 1h 1m");
+    }
+
+    #[test]
+    fn vesting_periods_repeat_the_last_fraction() {
+        let total_grant = 16 * OCTAS_PER_APT;
+        let periods = vesting_periods(&schedule(&[3, 3, 1], 0), total_grant, total_grant, 1_150);
+        let amounts: Vec<_> = periods
+            .iter()
+            .map(|period| period.amount.as_str())
+            .collect();
+        // 3 + 3 APT, and then 1 APT until the remaining 10 APT run out
+        assert_eq!(periods.len(), 12);
+        assert_eq!(
+            amounts[..3],
+            ["3.00000000 APT", "3.00000000 APT", "1.00000000 APT"]
+        );
+        assert!(amounts[2..]
+            .iter()
+            .all(|amount| *amount == "1.00000000 APT"));
+
+        assert_eq!(periods[0].period, 1);
+        assert_eq!(periods[0].vests_at_secs, 1_100);
+        assert_eq!(periods[0].vests_in, "now");
+        assert_eq!(periods[1].vests_at_secs, 1_200);
+        assert_eq!(periods[1].vests_in, "0d 0h 0m");
+        assert_eq!(periods[11].vests_at_secs, 2_200);
+    }
+
+    #[test]
+    fn vesting_periods_start_after_the_last_vested_period() {
+        let total_grant = 16 * OCTAS_PER_APT;
+        // Periods 1 and 2 vested 6 APT, and the last period only vests what remains of the 10 APT
+        let periods = vesting_periods(
+            &schedule(&[3, 3, 4], 2),
+            total_grant,
+            total_grant - 6 * OCTAS_PER_APT,
+            0,
+        );
+        let amounts: Vec<_> = periods
+            .iter()
+            .map(|period| period.amount.as_str())
+            .collect();
+        assert_eq!(periods[0].period, 3);
+        assert_eq!(
+            amounts,
+            ["4.00000000 APT", "4.00000000 APT", "2.00000000 APT"]
+        );
+    }
+
+    #[test]
+    fn vesting_periods_stop_when_nothing_vests() {
+        let total_grant = 16 * OCTAS_PER_APT;
+        assert!(vesting_periods(&schedule(&[], 0), total_grant, total_grant, 0).is_empty());
+        assert!(vesting_periods(&schedule(&[0], 0), total_grant, total_grant, 0).is_empty());
+        assert!(vesting_periods(&schedule(&[3], 0), total_grant, 0, 0).is_empty());
+        // A fraction rounding down to nothing would otherwise never end
+        assert!(vesting_periods(&schedule(&[1], 0), 1, 1, 0).is_empty());
+    }
+}
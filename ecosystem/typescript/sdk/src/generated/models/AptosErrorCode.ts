@@ -21,8 +21,11 @@ export enum AptosErrorCode {
     INVALID_TRANSACTION_UPDATE = 'invalid_transaction_update',
     SEQUENCE_NUMBER_TOO_OLD = 'sequence_number_too_old',
     VM_ERROR = 'vm_error',
+    REPLACEMENT_UNDERPRICED = 'replacement_underpriced',
     HEALTH_CHECK_FAILED = 'health_check_failed',
     MEMPOOL_IS_FULL = 'mempool_is_full',
+    EVICTION_UNDERPRICED = 'eviction_underpriced',
+    ACCOUNT_FAIRNESS_CAP_REACHED = 'account_fairness_cap_reached',
     INTERNAL_ERROR = 'internal_error',
     WEB_FRAMEWORK_ERROR = 'web_framework_error',
     BCS_NOT_SUPPORTED = 'bcs_not_supported',
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_vm::AptosVM;
 use criterion::{criterion_group, criterion_main, measurement::Measurement, BatchSize, Criterion};
 use executor_benchmark::{
     init_db_and_executor, transaction_executor::TransactionExecutor,
@@ -23,7 +24,7 @@ pub const INITIAL_BALANCE: u64 = 1000000;
 fn executor_benchmark<M: Measurement + 'static>(c: &mut Criterion<M>) {
     let (config, genesis_key) = aptos_genesis::test_utils::test_config();
 
-    let (db, executor) = init_db_and_executor(&config);
+    let (db, executor) = init_db_and_executor::<AptosVM>(&config);
     let parent_block_id = executor.committed_block_id();
     let executor = Arc::new(executor);
 
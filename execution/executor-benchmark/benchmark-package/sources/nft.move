@@ -0,0 +1,54 @@
+/// Mints the tokens of a collection shared by all the accounts. Each mint bumps the supply of the
+/// same token data, as minting from a popular collection does.
+module benchmark::nft {
+    use std::signer;
+    use std::string::{Self, String};
+    use std::vector;
+
+    use aptos_framework::account::{Self, SignerCapability};
+    use aptos_token::token::{Self, TokenDataId};
+
+    const MAX_U64: u64 = 18446744073709551615;
+
+    struct Minter has key {
+        signer_cap: SignerCapability,
+        token_data_id: TokenDataId,
+    }
+
+    fun init_module(publisher: &signer) {
+        let (collection_signer, signer_cap) = account::create_resource_account(publisher, b"nft");
+        let collection = string::utf8(b"Benchmark collection");
+        token::create_collection(
+            &collection_signer,
+            collection,
+            string::utf8(b""),
+            string::utf8(b""),
+            0,
+            vector<bool>[ false, false, false ],
+        );
+        let token_data_id = token::create_tokendata(
+            &collection_signer,
+            collection,
+            string::utf8(b"Benchmark token"),
+            string::utf8(b""),
+            // a maximum makes the supply tracked
+            MAX_U64,
+            string::utf8(b""),
+            signer::address_of(&collection_signer),
+            0,
+            0,
+            token::create_token_mutability_config(&vector<bool>[ false, false, false, false, false ]),
+            vector::empty<String>(),
+            vector::empty<vector<u8>>(),
+            vector::empty<String>(),
+        );
+        move_to(publisher, Minter { signer_cap, token_data_id });
+    }
+
+    public entry fun mint(receiver: &signer) acquires Minter {
+        let minter = borrow_global<Minter>(@benchmark);
+        let collection_signer = account::create_signer_with_capability(&minter.signer_cap);
+        let token_id = token::mint_token(&collection_signer, minter.token_data_id, 1);
+        token::direct_transfer(&collection_signer, receiver, token_id, 1);
+    }
+}
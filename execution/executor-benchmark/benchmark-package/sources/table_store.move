@@ -0,0 +1,49 @@
+/// Inserts and reads table entries, either in a table of the sender or in a table shared by all
+/// the accounts.
+module benchmark::table_store {
+    use std::signer;
+
+    use aptos_std::table::{Self, Table};
+
+    struct Store has key {
+        entries: Table<u64, u64>,
+    }
+
+    fun init_module(publisher: &signer) {
+        move_to(publisher, Store { entries: table::new() });
+    }
+
+    public entry fun insert(account: &signer, key: u64, value: u64) acquires Store {
+        let addr = signer::address_of(account);
+        if (!exists<Store>(addr)) {
+            move_to(account, Store { entries: table::new() });
+        };
+        upsert(addr, key, value);
+    }
+
+    public entry fun insert_shared(_account: &signer, key: u64, value: u64) acquires Store {
+        upsert(@benchmark, key, value);
+    }
+
+    public entry fun read(account: &signer, key: u64) acquires Store {
+        read_entry(signer::address_of(account), key);
+    }
+
+    public entry fun read_shared(_account: &signer, key: u64) acquires Store {
+        read_entry(@benchmark, key);
+    }
+
+    fun upsert(addr: address, key: u64, value: u64) acquires Store {
+        let entries = &mut borrow_global_mut<Store>(addr).entries;
+        table::upsert(entries, key, value);
+    }
+
+    fun read_entry(addr: address, key: u64) acquires Store {
+        if (exists<Store>(addr)) {
+            let entries = &borrow_global<Store>(addr).entries;
+            if (table::contains(entries, key)) {
+                let _value = *table::borrow(entries, key);
+            };
+        };
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::workloads::read_write_set_analysis;
+use aptos_logger::debug;
+use aptos_state_view::StateView;
+use aptos_types::{
+    transaction::{Transaction, TransactionOutput},
+    vm_status::VMStatus,
+};
+use aptos_vm::{parallel_executor::ParallelAptosVM, AptosVM, VMExecutor};
+use std::fmt;
+
+/// Whether blocks run through the parallel transaction executor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutionMode {
+    /// AptosVM, which uses the parallel executor when the concurrency level is above 1
+    Parallel,
+    /// HintedAptosVM
+    ParallelWithHints,
+    /// SequentialAptosVM
+    Sequential,
+}
+
+impl fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutionMode::Parallel => write!(f, "parallel"),
+            ExecutionMode::ParallelWithHints => write!(f, "parallel-hints"),
+            ExecutionMode::Sequential => write!(f, "sequential"),
+        }
+    }
+}
+
+/// AptosVM scheduling the parallel execution with the read/write sets predicted by the analysis
+/// of the workload entry functions.
+pub struct HintedAptosVM;
+
+impl VMExecutor for HintedAptosVM {
+    fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let concurrency_level = AptosVM::get_concurrency_level();
+        if concurrency_level > 1 {
+            let (result, err) = ParallelAptosVM::execute_block_with_hints(
+                transactions,
+                state_view,
+                concurrency_level,
+                read_write_set_analysis(),
+            )?;
+            debug!("Parallel execution error {:?}", err);
+            Ok(result)
+        } else {
+            SequentialAptosVM::execute_block(transactions, state_view)
+        }
+    }
+}
+
+/// AptosVM executing the transactions one after the other whatever the concurrency level.
+pub struct SequentialAptosVM;
+
+impl VMExecutor for SequentialAptosVM {
+    fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Ok(
+            AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?
+                .into_iter()
+                .map(|(_vm_status, txn_output)| txn_output)
+                .collect(),
+        )
+    }
+}
@@ -3,14 +3,21 @@
 
 mod account_generator;
 pub mod db_generator;
+pub mod execution_mode;
 pub mod pipeline;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
+pub mod workload_report;
+pub mod workloads;
 
 use crate::{
-    transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
+    execution_mode::{ExecutionMode, HintedAptosVM, SequentialAptosVM},
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
+    workload_report::{ConflictCounts, PhaseTimes, WorkloadReport},
+    workloads::WorkloadType,
 };
 use aptos_config::config::{
     NodeConfig, PrunerConfig, RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS,
@@ -22,12 +29,15 @@ use aptos_jellyfish_merkle::metrics::{
 use aptosdb::AptosDB;
 
 use crate::pipeline::Pipeline;
-use aptos_vm::AptosVM;
+use aptos_types::transaction::{ExecutionStatus, Transaction, Version};
+use aptos_vm::{AptosVM, VMExecutor};
 use executor::block_executor::BlockExecutor;
-use std::{fs, path::Path};
+use std::{fs, path::Path, time::Instant};
 use storage_interface::DbReaderWriter;
 
-pub fn init_db_and_executor(config: &NodeConfig) -> (DbReaderWriter, BlockExecutor<AptosVM>) {
+pub fn init_db_and_executor<V: VMExecutor>(
+    config: &NodeConfig,
+) -> (DbReaderWriter, BlockExecutor<V>) {
     let db = DbReaderWriter::new(
         AptosDB::open(
             &config.storage.dir(),
@@ -82,7 +92,7 @@ pub fn run_benchmark(
     config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
     config.storage.storage_pruner_config = pruner_config;
 
-    let (db, executor) = init_db_and_executor(&config);
+    let (db, executor) = init_db_and_executor::<AptosVM>(&config);
     let version = db.reader.get_latest_version().unwrap();
 
     let (pipeline, block_sender) = Pipeline::new(executor, version);
@@ -103,6 +113,168 @@ pub fn run_benchmark(
     }
 }
 
+/// Runs the workload with the parallel executor, then with the parallel executor scheduling by the
+/// predicted read/write sets, then sequentially, each time from a new checkpoint of the source DB.
+/// Reports the throughput of each phase of the pipeline and the conflicts the parallel executor
+/// ran into.
+pub fn run_workload_benchmark(
+    workload_type: WorkloadType,
+    contention_ratio: f64,
+    block_size: usize,
+    num_blocks: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    verify_sequence_numbers: bool,
+    pruner_config: PrunerConfig,
+) -> Vec<WorkloadReport> {
+    if AptosVM::get_concurrency_level() <= 1 {
+        println!("The parallel executor only runs with a concurrency level above 1.");
+    }
+
+    let reports: Vec<_> = [
+        ExecutionMode::Parallel,
+        ExecutionMode::ParallelWithHints,
+        ExecutionMode::Sequential,
+    ]
+    .into_iter()
+    .map(|mode| {
+        // A checkpoint per mode, so that a run never reuses the directory of an open DB.
+        let checkpoint_dir = checkpoint_dir.as_ref().join(mode.to_string());
+        let report = match mode {
+            ExecutionMode::Parallel => run_workload::<AptosVM>(
+                mode,
+                workload_type,
+                contention_ratio,
+                block_size,
+                num_blocks,
+                source_dir.as_ref(),
+                &checkpoint_dir,
+                verify_sequence_numbers,
+                pruner_config,
+            ),
+            ExecutionMode::ParallelWithHints => run_workload::<HintedAptosVM>(
+                mode,
+                workload_type,
+                contention_ratio,
+                block_size,
+                num_blocks,
+                source_dir.as_ref(),
+                &checkpoint_dir,
+                verify_sequence_numbers,
+                pruner_config,
+            ),
+            ExecutionMode::Sequential => run_workload::<SequentialAptosVM>(
+                mode,
+                workload_type,
+                contention_ratio,
+                block_size,
+                num_blocks,
+                source_dir.as_ref(),
+                &checkpoint_dir,
+                verify_sequence_numbers,
+                pruner_config,
+            ),
+        };
+        println!("{}", report);
+        report
+    })
+    .collect();
+
+    let (optimistic, hinted) = (&reports[0].conflicts, &reports[1].conflicts);
+    println!(
+        "Read/write hints took the aborts from {} to {} and the dependency waits from {} to {}.",
+        optimistic.aborts, hinted.aborts, optimistic.dependency_waits, hinted.dependency_waits,
+    );
+    reports
+}
+
+fn run_workload<V: VMExecutor + 'static>(
+    mode: ExecutionMode,
+    workload_type: WorkloadType,
+    contention_ratio: f64,
+    block_size: usize,
+    num_blocks: usize,
+    source_dir: &Path,
+    checkpoint_dir: &Path,
+    verify_sequence_numbers: bool,
+    pruner_config: PrunerConfig,
+) -> WorkloadReport {
+    create_checkpoint(source_dir, checkpoint_dir);
+
+    let (mut config, genesis_key) = aptos_genesis::test_utils::test_config();
+    config.storage.dir = checkpoint_dir.to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+
+    let (db, executor) = init_db_and_executor::<V>(&config);
+    let version = db.reader.get_latest_version().unwrap();
+
+    let (pipeline, block_sender) = Pipeline::new(executor, version);
+
+    let mut generator = TransactionGenerator::new_with_existing_db(
+        db.clone(),
+        genesis_key,
+        block_sender,
+        source_dir,
+        version,
+    );
+    let mut workload = workload_type.create(contention_ratio);
+    generator.run_workload_setup(workload.as_mut());
+    generator.drop_sender();
+    pipeline.join();
+
+    // The setup is not measured, the workload runs through a new pipeline once it is committed.
+    let version = db.reader.get_latest_version().unwrap();
+    let (pipeline, block_sender) = Pipeline::new(BlockExecutor::<V>::new(db.clone()), version);
+    generator.set_block_sender(block_sender);
+
+    let start_phase_times = PhaseTimes::now();
+    let start_conflicts = ConflictCounts::now();
+    let start_time = Instant::now();
+    let num_txns = generator.run_workload(workload.as_mut(), block_size, num_blocks);
+    generator.drop_sender();
+    pipeline.join();
+    let elapsed = start_time.elapsed();
+
+    let report = WorkloadReport {
+        workload: workload_type,
+        mode,
+        num_txns,
+        num_successful_txns: count_successful_user_txns(&db, version + 1),
+        elapsed,
+        phase_times: PhaseTimes::now().since(&start_phase_times),
+        conflicts: ConflictCounts::now().since(&start_conflicts),
+    };
+
+    if verify_sequence_numbers {
+        generator.verify_sequence_numbers(db.reader);
+    }
+    report
+}
+
+/// Counts the user transactions committed since `start_version` that executed successfully.
+fn count_successful_user_txns(db: &DbReaderWriter, start_version: Version) -> usize {
+    const BATCH_SIZE: u64 = 1_000;
+
+    let ledger_version = db.reader.get_latest_version().unwrap();
+    let mut num_successful_txns = 0;
+    for batch_start in (start_version..=ledger_version).step_by(BATCH_SIZE as usize) {
+        let txns = db
+            .reader
+            .get_transactions(batch_start, BATCH_SIZE, ledger_version, false)
+            .unwrap();
+        num_successful_txns += txns
+            .transactions
+            .iter()
+            .zip(txns.proof.transaction_infos.iter())
+            .filter(|(txn, info)| {
+                matches!(txn, Transaction::UserTransaction(_))
+                    && info.status() == &ExecutionStatus::Success
+            })
+            .count();
+    }
+    num_successful_txns
+}
+
 pub fn add_accounts(
     num_new_accounts: usize,
     init_account_balance: u64,
@@ -137,7 +309,7 @@ fn add_accounts_impl(
     let (mut config, genesis_key) = aptos_genesis::test_utils::test_config();
     config.storage.dir = output_dir.as_ref().to_path_buf();
     config.storage.storage_pruner_config = pruner_config;
-    let (db, executor) = init_db_and_executor(&config);
+    let (db, executor) = init_db_and_executor::<AptosVM>(&config);
 
     let version = db.reader.get_latest_version().unwrap();
 
@@ -189,8 +361,10 @@ fn add_accounts_impl(
 
 #[cfg(test)]
 mod tests {
+    use crate::workloads::WorkloadType;
     use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use aptos_temppath::TempPath;
+    use aptos_vm::AptosVM;
 
     #[test]
     fn test_benchmark() {
@@ -216,4 +390,44 @@ mod tests {
             NO_OP_STORAGE_PRUNER_CONFIG,
         );
     }
+
+    #[test]
+    fn test_workloads() {
+        // The parallel modes fall back to sequential execution at a concurrency level of 1.
+        AptosVM::set_concurrency_level_once(4);
+        let storage_dir = TempPath::new();
+
+        crate::db_generator::run(
+            25,        /* num_accounts */
+            1_000_000, /* init_account_balance */
+            5,         /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            true,
+        );
+
+        for workload in WorkloadType::ALL {
+            let checkpoint_dir = TempPath::new();
+            let contention_ratio = if workload.supports_contention() {
+                0.5
+            } else {
+                0.0
+            };
+            let reports = super::run_workload_benchmark(
+                workload,
+                contention_ratio,
+                5, /* block_size */
+                2, /* num_blocks */
+                storage_dir.as_ref(),
+                checkpoint_dir,
+                true,
+                NO_OP_STORAGE_PRUNER_CONFIG,
+            );
+            assert_eq!(reports.len(), 3);
+            for report in reports {
+                assert_eq!(report.num_txns, 10);
+                assert_eq!(report.num_successful_txns, 10, "{}", report);
+            }
+        }
+    }
 }
@@ -6,6 +6,7 @@ use aptos_config::config::{
 };
 use aptos_push_metrics::MetricsPusher;
 use aptos_vm::AptosVM;
+use executor_benchmark::workloads::WorkloadType;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -142,6 +143,30 @@ enum Command {
         #[structopt(long, default_value = "1000000")]
         init_account_balance: u64,
     },
+    RunWorkload {
+        #[structopt(long, about = "one of p2p, nft-mint, table, aggregator or publish")]
+        workload: WorkloadType,
+
+        #[structopt(
+            long,
+            default_value = "0.0",
+            about = "fraction of transactions touching hot state, for p2p, table or aggregator"
+        )]
+        contention_ratio: f64,
+
+        #[structopt(
+            long,
+            default_value = "100",
+            about = "number of workload blocks to run"
+        )]
+        blocks: usize,
+
+        #[structopt(long, parse(from_os_str))]
+        data_dir: PathBuf,
+
+        #[structopt(long, parse(from_os_str))]
+        checkpoint_dir: PathBuf,
+    },
 }
 
 fn main() {
@@ -202,5 +227,23 @@ fn main() {
                 opt.verify_sequence_numbers,
             );
         }
+        Command::RunWorkload {
+            workload,
+            contention_ratio,
+            blocks,
+            data_dir,
+            checkpoint_dir,
+        } => {
+            executor_benchmark::run_workload_benchmark(
+                workload,
+                contention_ratio,
+                opt.block_size,
+                blocks,
+                data_dir,
+                checkpoint_dir,
+                opt.verify_sequence_numbers,
+                opt.pruner_opt.pruner_config(),
+            );
+        }
     }
 }
@@ -4,7 +4,7 @@
 use crate::{TransactionCommitter, TransactionExecutor};
 use aptos_logger::info;
 use aptos_types::transaction::{Transaction, Version};
-use aptos_vm::AptosVM;
+use aptos_vm::VMExecutor;
 use executor::block_executor::BlockExecutor;
 use executor_types::BlockExecutorTrait;
 use std::{
@@ -17,8 +17,8 @@ pub struct Pipeline {
 }
 
 impl Pipeline {
-    pub fn new(
-        executor: BlockExecutor<AptosVM>,
+    pub fn new<V: VMExecutor + 'static>(
+        executor: BlockExecutor<V>,
         version: Version,
     ) -> (Self, mpsc::SyncSender<Vec<Transaction>>) {
         let parent_block_id = executor.committed_block_id();
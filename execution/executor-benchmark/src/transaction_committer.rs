@@ -9,7 +9,7 @@ use aptos_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     transaction::Version,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::VMExecutor;
 use aptosdb::metrics::API_LATENCY_SECONDS;
 use executor::{
     block_executor::BlockExecutor,
@@ -46,15 +46,18 @@ pub(crate) fn gen_li_with_sigs(
     )
 }
 
-pub struct TransactionCommitter {
-    executor: Arc<BlockExecutor<AptosVM>>,
+pub struct TransactionCommitter<V> {
+    executor: Arc<BlockExecutor<V>>,
     version: Version,
     block_receiver: mpsc::Receiver<(HashValue, HashValue, Instant, Instant, Duration, usize)>,
 }
 
-impl TransactionCommitter {
+impl<V> TransactionCommitter<V>
+where
+    V: VMExecutor,
+{
     pub fn new(
-        executor: Arc<BlockExecutor<AptosVM>>,
+        executor: Arc<BlockExecutor<V>>,
         version: Version,
         block_receiver: mpsc::Receiver<(HashValue, HashValue, Instant, Instant, Duration, usize)>,
     ) -> Self {
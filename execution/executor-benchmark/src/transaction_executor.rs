@@ -3,7 +3,7 @@
 
 use aptos_crypto::hash::HashValue;
 use aptos_types::transaction::{Transaction, Version};
-use aptos_vm::AptosVM;
+use aptos_vm::VMExecutor;
 use executor::block_executor::BlockExecutor;
 use executor_types::BlockExecutorTrait;
 use std::{
@@ -11,8 +11,8 @@ use std::{
     time::{Duration, Instant},
 };
 
-pub struct TransactionExecutor {
-    executor: Arc<BlockExecutor<AptosVM>>,
+pub struct TransactionExecutor<V> {
+    executor: Arc<BlockExecutor<V>>,
     parent_block_id: HashValue,
     start_time: Option<Instant>,
     version: Version,
@@ -21,9 +21,12 @@ pub struct TransactionExecutor {
         Option<mpsc::SyncSender<(HashValue, HashValue, Instant, Instant, Duration, usize)>>,
 }
 
-impl TransactionExecutor {
+impl<V> TransactionExecutor<V>
+where
+    V: VMExecutor,
+{
     pub fn new(
-        executor: Arc<BlockExecutor<AptosVM>>,
+        executor: Arc<BlockExecutor<V>>,
         parent_block_id: HashValue,
         version: Version,
         commit_sender: Option<
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_generator::{AccountCache, AccountGenerator};
+use crate::{
+    account_generator::{AccountCache, AccountGenerator},
+    workloads::Workload,
+};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
@@ -10,7 +13,7 @@ use aptos_types::{
     account_config::aptos_test_root_address,
     account_view::AccountView,
     chain_id::ChainId,
-    transaction::{Transaction, Version},
+    transaction::{SignedTransaction, Transaction, Version},
 };
 use chrono::Local;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -381,6 +384,52 @@ impl TransactionGenerator {
         txn_block
     }
 
+    /// Generates the setup blocks of the workload.
+    pub fn run_workload_setup(&mut self, workload: &mut dyn Workload) {
+        assert!(self.block_sender.is_some());
+        for transactions in workload.setup(&mut self.root_account, &self.transaction_factory) {
+            self.send_block(transactions);
+        }
+    }
+
+    /// Generates blocks of the workload, returns the number of user transactions sent.
+    pub fn run_workload(
+        &mut self,
+        workload: &mut dyn Workload,
+        block_size: usize,
+        num_blocks: usize,
+    ) -> usize {
+        assert!(self.block_sender.is_some());
+        (0..num_blocks)
+            .map(|_| {
+                let transactions = workload.gen_block(
+                    self.accounts_cache.as_mut().unwrap(),
+                    &self.transaction_factory,
+                    block_size,
+                );
+                self.send_block(transactions)
+            })
+            .sum()
+    }
+
+    /// Sends the transactions as a block ending with a state checkpoint, returns the number of
+    /// user transactions sent.
+    fn send_block(&mut self, transactions: Vec<SignedTransaction>) -> usize {
+        let num_txns = transactions.len();
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .map(Transaction::UserTransaction)
+            .chain(once(Transaction::StateCheckpoint(HashValue::random())))
+            .collect();
+        self.version += transactions.len() as Version;
+        self.block_sender
+            .as_ref()
+            .unwrap()
+            .send(transactions)
+            .unwrap();
+        num_txns
+    }
+
     /// Verifies the sequence numbers in storage match what we have locally.
     pub fn verify_sequence_numbers(&self, db: Arc<dyn DbReader>) {
         if self.accounts_cache.is_none() {
@@ -422,4 +471,10 @@ impl TransactionGenerator {
     pub fn drop_sender(&mut self) {
         self.block_sender.take().unwrap();
     }
+
+    /// Sends the following blocks to a new channel, e.g. the one of a new pipeline.
+    pub fn set_block_sender(&mut self, block_sender: mpsc::SyncSender<Vec<Transaction>>) {
+        assert!(self.block_sender.is_none());
+        self.block_sender = Some(block_sender);
+    }
 }
@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{execution_mode::ExecutionMode, workloads::WorkloadType};
+use aptos_parallel_executor::counters::{
+    PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_DEFERRALS, PARALLEL_EXECUTION_DEPENDENCY_WAITS,
+};
+use executor::metrics::{
+    APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
+    APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
+};
+use std::{fmt, time::Duration};
+
+/// Time spent in each phase of the pipeline, as accumulated by the executor metrics.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PhaseTimes {
+    /// Running the transactions in the VM
+    pub execution_secs: f64,
+    /// Applying the outputs to the ledger, which computes the state checkpoints
+    pub state_checkpoint_secs: f64,
+    /// Saving the blocks to the DB
+    pub commit_secs: f64,
+}
+
+impl PhaseTimes {
+    pub fn now() -> Self {
+        let execution_secs = APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum();
+        Self {
+            execution_secs,
+            state_checkpoint_secs: APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.get_sample_sum()
+                - execution_secs,
+            commit_secs: APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS.get_sample_sum(),
+        }
+    }
+
+    pub fn since(&self, start: &PhaseTimes) -> PhaseTimes {
+        PhaseTimes {
+            execution_secs: self.execution_secs - start.execution_secs,
+            state_checkpoint_secs: self.state_checkpoint_secs - start.state_checkpoint_secs,
+            commit_secs: self.commit_secs - start.commit_secs,
+        }
+    }
+}
+
+/// Conflicts between transactions handled by the parallel executor, as counted by its metrics.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConflictCounts {
+    /// Incarnations aborted by a failed validation, each followed by a re-execution
+    pub aborts: u64,
+    /// Executions suspended on a read of an estimated write
+    pub dependency_waits: u64,
+    /// First incarnations deferred on a dependency predicted by the read/write hints
+    pub deferrals: u64,
+}
+
+impl ConflictCounts {
+    pub fn now() -> Self {
+        Self {
+            aborts: PARALLEL_EXECUTION_ABORTS.get(),
+            dependency_waits: PARALLEL_EXECUTION_DEPENDENCY_WAITS.get(),
+            deferrals: PARALLEL_EXECUTION_DEFERRALS.get(),
+        }
+    }
+
+    pub fn since(&self, start: &ConflictCounts) -> ConflictCounts {
+        ConflictCounts {
+            aborts: self.aborts - start.aborts,
+            dependency_waits: self.dependency_waits - start.dependency_waits,
+            deferrals: self.deferrals - start.deferrals,
+        }
+    }
+}
+
+/// Throughput of a workload run, overall and for each phase of the pipeline.
+#[derive(Clone, Debug)]
+pub struct WorkloadReport {
+    pub workload: WorkloadType,
+    pub mode: ExecutionMode,
+    /// User transactions, not counting the state checkpoint transactions ending the blocks
+    pub num_txns: usize,
+    /// Committed user transactions that executed successfully, the others were kept with an abort
+    /// or an execution failure
+    pub num_successful_txns: usize,
+    pub elapsed: Duration,
+    pub phase_times: PhaseTimes,
+    pub conflicts: ConflictCounts,
+}
+
+impl WorkloadReport {
+    fn tps(&self, secs: f64) -> f64 {
+        self.num_txns as f64 / secs
+    }
+
+    pub fn execution_tps(&self) -> f64 {
+        self.tps(self.phase_times.execution_secs)
+    }
+
+    pub fn state_checkpoint_tps(&self) -> f64 {
+        self.tps(self.phase_times.state_checkpoint_secs)
+    }
+
+    pub fn commit_tps(&self) -> f64 {
+        self.tps(self.phase_times.commit_secs)
+    }
+}
+
+impl fmt::Display for WorkloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Workload {} ({} execution): {} txns ({} successful) in {:.1} secs, TPS: {:.0}. Execution TPS: {:.0}, state checkpoint TPS: {:.0}, commit TPS: {:.0}. Aborts: {}, dependency waits: {}, deferrals: {}",
+            self.workload,
+            self.mode,
+            self.num_txns,
+            self.num_successful_txns,
+            self.elapsed.as_secs_f64(),
+            self.tps(self.elapsed.as_secs_f64()),
+            self.execution_tps(),
+            self.state_checkpoint_tps(),
+            self.commit_tps(),
+            self.conflicts.aborts,
+            self.conflicts.dependency_waits,
+            self.conflicts.deferrals,
+        )
+    }
+}
@@ -0,0 +1,515 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Workloads reproducing the transaction patterns seen in production, on top of p2p transfers. A
+//! workload first prepares the state it runs on with setup blocks signed by the root account,
+//! e.g. publishing the benchmark package, then generates blocks of transactions.
+//!
+//! The contention ratio is the share of transactions writing to hot state: transfers and mints to
+//! a few hot accounts, or inserts into a table shared by all accounts. NFT minting always
+//! contends on the shared collection, and module publishing on the single publisher.
+
+use crate::account_generator::AccountCache;
+use aptos_sdk::{
+    bcs,
+    move_types::{identifier::Identifier, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::LocalAccount,
+};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+};
+use aptos_vm::read_write_set_analysis::{analyze_modules, NormalizedReadWriteSetAnalysis};
+use cached_packages::aptos_stdlib;
+use framework::{BuildOptions, BuiltPackage};
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{fmt, path::Path, str::FromStr};
+
+/// Gas limit of the workload transactions, which create resources unlike transfers.
+const MAX_GAS_AMOUNT: u64 = 10_000;
+/// Gas limit of the transactions publishing the benchmark package.
+const PUBLISH_MAX_GAS_AMOUNT: u64 = 100_000;
+/// Balance of the accounts created by the workloads.
+const FUNDING_AMOUNT: u64 = 100_000_000_000;
+/// Number of accounts receiving the contended transfers and mints.
+const NUM_HOT_ACCOUNTS: usize = 10;
+/// Number of accounts minting coins in the aggregator workload.
+const NUM_MINTERS: usize = 100;
+/// The keys of the table entries are drawn from a small space so that most reads find an entry.
+const NUM_TABLE_KEYS: u64 = 1_000;
+
+const PUBLISHER_SEED: [u8; 32] = [2; 32];
+const MINTERS_SEED: [u8; 32] = [3; 32];
+const WORKLOAD_SEED: [u8; 32] = [4; 32];
+
+/// Generates the transactions of a workload.
+pub trait Workload {
+    /// Blocks preparing the state the workload runs on.
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>>;
+
+    /// A block of the workload, mostly sent by the accounts of the cache.
+    fn gen_block(
+        &mut self,
+        accounts: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction>;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WorkloadType {
+    /// Transfers between random accounts
+    P2p,
+    /// Mints of a token from a shared collection
+    NftMint,
+    /// Inserts and reads of table entries
+    Table,
+    /// Coin mints, each incrementing the supply aggregator
+    Aggregator,
+    /// Republishing of the benchmark package
+    Publish,
+}
+
+impl WorkloadType {
+    pub const ALL: [WorkloadType; 5] = [
+        WorkloadType::P2p,
+        WorkloadType::NftMint,
+        WorkloadType::Table,
+        WorkloadType::Aggregator,
+        WorkloadType::Publish,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkloadType::P2p => "p2p",
+            WorkloadType::NftMint => "nft-mint",
+            WorkloadType::Table => "table",
+            WorkloadType::Aggregator => "aggregator",
+            WorkloadType::Publish => "publish",
+        }
+    }
+
+    /// Whether the share of contended transactions can be chosen. NFT minting and module
+    /// publishing contend on a single resource whatever the ratio.
+    pub fn supports_contention(&self) -> bool {
+        match self {
+            WorkloadType::P2p | WorkloadType::Table | WorkloadType::Aggregator => true,
+            WorkloadType::NftMint | WorkloadType::Publish => false,
+        }
+    }
+
+    pub fn create(&self, contention_ratio: f64) -> Box<dyn Workload> {
+        assert!(
+            (0.0..=1.0).contains(&contention_ratio),
+            "Contention ratio must be within [0, 1]: {}",
+            contention_ratio
+        );
+        assert!(
+            contention_ratio == 0.0 || self.supports_contention(),
+            "Workload {} does not take a contention ratio",
+            self
+        );
+        let contention = Contention::new(contention_ratio);
+        match self {
+            WorkloadType::P2p => Box::new(P2pWorkload { contention }),
+            WorkloadType::NftMint => Box::new(NftMintWorkload {
+                publisher: Publisher::new(),
+            }),
+            WorkloadType::Table => Box::new(TableWorkload {
+                publisher: Publisher::new(),
+                contention,
+            }),
+            WorkloadType::Aggregator => Box::new(AggregatorWorkload::new(contention)),
+            WorkloadType::Publish => Box::new(PublishWorkload {
+                publisher: Publisher::new(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for WorkloadType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for WorkloadType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|workload| workload.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "Unknown workload {}, expected one of: {}",
+                    s,
+                    Self::ALL
+                        .iter()
+                        .map(WorkloadType::name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Picks whether a transaction writes to hot state.
+struct Contention {
+    ratio: f64,
+    rng: StdRng,
+}
+
+impl Contention {
+    fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            rng: StdRng::from_seed(WORKLOAD_SEED),
+        }
+    }
+
+    fn is_hot(&mut self) -> bool {
+        self.rng.gen_bool(self.ratio)
+    }
+
+    /// One of the first accounts of the cache for a share of the calls given by the ratio.
+    fn hot_account(&mut self, accounts: &AccountCache) -> Option<AccountAddress> {
+        if self.is_hot() {
+            let num_hot_accounts = NUM_HOT_ACCOUNTS.min(accounts.len());
+            let index = self.rng.gen_range(0, num_hot_accounts);
+            Some(accounts.accounts()[index].address())
+        } else {
+            None
+        }
+    }
+}
+
+struct CompiledPackage {
+    metadata: Vec<u8>,
+    code: Vec<Vec<u8>>,
+    /// Read/write set analysis of the framework and of the benchmark package
+    rw_analysis: NormalizedReadWriteSetAnalysis,
+}
+
+/// The benchmark package compiled for the address of the publisher. The publisher key is the same
+/// in every run, so the package is only compiled and analyzed once.
+static BENCHMARK_PACKAGE: Lazy<CompiledPackage> = Lazy::new(|| {
+    let package_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benchmark-package");
+    let build_dir = TempPath::new();
+    build_dir.create_as_dir().unwrap();
+    let mut options = BuildOptions {
+        install_dir: Some(build_dir.path().to_path_buf()),
+        ..BuildOptions::default()
+    };
+    options
+        .named_addresses
+        .insert("benchmark".to_string(), Publisher::new().address());
+
+    let package = BuiltPackage::build(package_dir, options).expect("Failed to build package.");
+    let metadata = package
+        .extract_metadata()
+        .expect("Failed to extract package metadata.");
+    let framework_modules = cached_packages::head_release_bundle().compiled_modules();
+    let rw_analysis = analyze_modules(framework_modules.iter().chain(package.modules()))
+        .expect("Failed to analyze read/write sets.");
+    CompiledPackage {
+        metadata: bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
+        code: package.extract_code(),
+        rw_analysis,
+    }
+});
+
+/// Read/write set analysis of the entry functions the workloads call.
+pub fn read_write_set_analysis() -> &'static NormalizedReadWriteSetAnalysis {
+    &BENCHMARK_PACKAGE.rw_analysis
+}
+
+/// Account the benchmark package is published at.
+struct Publisher {
+    account: LocalAccount,
+}
+
+impl Publisher {
+    fn new() -> Self {
+        Self {
+            account: LocalAccount::generate(&mut StdRng::from_seed(PUBLISHER_SEED)),
+        }
+    }
+
+    fn address(&self) -> AccountAddress {
+        self.account.address()
+    }
+
+    /// Creates and funds the publisher, then publishes the package.
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        let create_and_fund = vec![
+            root_account.sign_with_transaction_builder(
+                transaction_factory.create_user_account(self.account.public_key()),
+            ),
+            root_account.sign_with_transaction_builder(
+                transaction_factory.mint(self.address(), FUNDING_AMOUNT),
+            ),
+        ];
+        vec![create_and_fund, vec![self.publish(transaction_factory)]]
+    }
+
+    fn publish(&mut self, transaction_factory: &TransactionFactory) -> SignedTransaction {
+        let package = &*BENCHMARK_PACKAGE;
+        self.account.sign_with_transaction_builder(
+            transaction_factory
+                .payload(aptos_stdlib::code_publish_package_txn(
+                    package.metadata.clone(),
+                    package.code.clone(),
+                ))
+                .max_gas_amount(PUBLISH_MAX_GAS_AMOUNT),
+        )
+    }
+
+    /// Calls an entry function of the benchmark package.
+    fn entry_function(
+        &self,
+        module: &str,
+        function: &str,
+        args: Vec<Vec<u8>>,
+    ) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(self.address(), Identifier::new(module).unwrap()),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args,
+        ))
+    }
+}
+
+struct P2pWorkload {
+    contention: Contention,
+}
+
+impl Workload for P2pWorkload {
+    fn setup(
+        &mut self,
+        _: &mut LocalAccount,
+        _: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        vec![]
+    }
+
+    fn gen_block(
+        &mut self,
+        accounts: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..block_size)
+            .map(|_| {
+                let hot_account = self.contention.hot_account(accounts);
+                let (sender, receiver) = accounts.get_random_transfer();
+                sender.sign_with_transaction_builder(
+                    transaction_factory.transfer(hot_account.unwrap_or(receiver), 1),
+                )
+            })
+            .collect()
+    }
+}
+
+struct NftMintWorkload {
+    publisher: Publisher,
+}
+
+impl Workload for NftMintWorkload {
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        self.publisher.setup(root_account, transaction_factory)
+    }
+
+    fn gen_block(
+        &mut self,
+        accounts: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..block_size)
+            .map(|_| {
+                accounts.get_random().sign_with_transaction_builder(
+                    transaction_factory
+                        .payload(self.publisher.entry_function("nft", "mint", vec![]))
+                        .max_gas_amount(MAX_GAS_AMOUNT),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Half of the transactions insert an entry, the other half read one. The hot transactions use
+/// the table shared by all accounts, the others the table of their sender.
+struct TableWorkload {
+    publisher: Publisher,
+    contention: Contention,
+}
+
+impl Workload for TableWorkload {
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        self.publisher.setup(root_account, transaction_factory)
+    }
+
+    fn gen_block(
+        &mut self,
+        accounts: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..block_size)
+            .map(|_| {
+                let shared = self.contention.is_hot();
+                let key = self.contention.rng.gen_range(0, NUM_TABLE_KEYS);
+                let payload = if self.contention.rng.gen_bool(0.5) {
+                    let function = if shared { "insert_shared" } else { "insert" };
+                    let value: u64 = self.contention.rng.gen();
+                    self.publisher.entry_function(
+                        "table_store",
+                        function,
+                        vec![bcs::to_bytes(&key).unwrap(), bcs::to_bytes(&value).unwrap()],
+                    )
+                } else {
+                    let function = if shared { "read_shared" } else { "read" };
+                    self.publisher.entry_function(
+                        "table_store",
+                        function,
+                        vec![bcs::to_bytes(&key).unwrap()],
+                    )
+                };
+                accounts.get_random().sign_with_transaction_builder(
+                    transaction_factory
+                        .payload(payload)
+                        .max_gas_amount(MAX_GAS_AMOUNT),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Minters holding a capability delegated by the root account mint coins to the accounts. Every
+/// mint increments the total supply, a parallelizable aggregator read and written by all of them.
+struct AggregatorWorkload {
+    minters: Vec<LocalAccount>,
+    next_minter: usize,
+    contention: Contention,
+}
+
+impl AggregatorWorkload {
+    fn new(contention: Contention) -> Self {
+        let mut rng = StdRng::from_seed(MINTERS_SEED);
+        Self {
+            minters: (0..NUM_MINTERS)
+                .map(|_| LocalAccount::generate(&mut rng))
+                .collect(),
+            next_minter: 0,
+            contention,
+        }
+    }
+}
+
+impl Workload for AggregatorWorkload {
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        let delegate = self
+            .minters
+            .iter()
+            .flat_map(|minter| {
+                vec![
+                    transaction_factory.create_user_account(minter.public_key()),
+                    transaction_factory.mint(minter.address(), FUNDING_AMOUNT),
+                    transaction_factory
+                        .payload(aptos_stdlib::aptos_coin_delegate_mint_capability(
+                            minter.address(),
+                        ))
+                        .max_gas_amount(MAX_GAS_AMOUNT),
+                ]
+            })
+            .map(|builder| root_account.sign_with_transaction_builder(builder))
+            .collect();
+        let claim = self
+            .minters
+            .iter_mut()
+            .map(|minter| {
+                minter.sign_with_transaction_builder(
+                    transaction_factory
+                        .payload(aptos_stdlib::aptos_coin_claim_mint_capability())
+                        .max_gas_amount(MAX_GAS_AMOUNT),
+                )
+            })
+            .collect();
+        vec![delegate, claim]
+    }
+
+    fn gen_block(
+        &mut self,
+        accounts: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..block_size)
+            .map(|_| {
+                let receiver = self
+                    .contention
+                    .hot_account(accounts)
+                    .unwrap_or_else(|| accounts.get_random().address());
+                let minter = &mut self.minters[self.next_minter];
+                self.next_minter = (self.next_minter + 1) % self.minters.len();
+                minter.sign_with_transaction_builder(
+                    transaction_factory
+                        .payload(aptos_stdlib::aptos_coin_mint(receiver, 1))
+                        .max_gas_amount(MAX_GAS_AMOUNT),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The publisher republishes the benchmark package, as an upgrade leaving the code unchanged.
+struct PublishWorkload {
+    publisher: Publisher,
+}
+
+impl Workload for PublishWorkload {
+    fn setup(
+        &mut self,
+        root_account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+    ) -> Vec<Vec<SignedTransaction>> {
+        self.publisher.setup(root_account, transaction_factory)
+    }
+
+    fn gen_block(
+        &mut self,
+        _: &mut AccountCache,
+        transaction_factory: &TransactionFactory,
+        block_size: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..block_size)
+            .map(|_| self.publisher.publish(transaction_factory))
+            .collect()
+    }
+}
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 /// This module provides various indexes used by Mempool.
-use crate::core_mempool::transaction::{MempoolTransaction, SequenceInfo, TimelineState};
+use crate::core_mempool::{
+    inspection::GasPriceBucketCounts,
+    transaction::{MempoolTransaction, SequenceInfo, TimelineState},
+};
 use crate::{
     counters,
     logging::{LogEntry, LogSchema},
@@ -64,6 +67,11 @@ impl PriorityIndex {
         self.data.iter().rev()
     }
 
+    /// Iterates from the lowest priority transaction, i.e. the first candidates for eviction.
+    pub(crate) fn iter_lowest_first(&self) -> Iter<OrderedQueueKey> {
+        self.data.iter()
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
@@ -265,6 +273,8 @@ pub struct ParkingLotIndex {
     data: Vec<(AccountAddress, BTreeSet<u64>)>,
     account_indices: HashMap<AccountAddress, usize>,
     size: usize,
+    // parked transactions by gas price bucket, for inspection
+    gas_price_bucket_counts: GasPriceBucketCounts,
 }
 
 impl ParkingLotIndex {
@@ -273,6 +283,7 @@ impl ParkingLotIndex {
             data: vec![],
             account_indices: HashMap::new(),
             size: 0,
+            gas_price_bucket_counts: GasPriceBucketCounts::default(),
         }
     }
 
@@ -302,6 +313,7 @@ impl ParkingLotIndex {
         };
         if is_new_entry {
             self.size += 1;
+            self.gas_price_bucket_counts.add(txn.get_gas_price());
         }
     }
 
@@ -311,6 +323,7 @@ impl ParkingLotIndex {
             if let Some((_account, txns)) = self.data.get_mut(index) {
                 if txns.remove(&txn.txn.sequence_number()) {
                     self.size -= 1;
+                    self.gas_price_bucket_counts.remove(txn.get_gas_price());
                 }
 
                 // maintain DS invariant
@@ -346,6 +359,10 @@ impl ParkingLotIndex {
     pub(crate) fn size(&self) -> usize {
         self.size
     }
+
+    pub(crate) fn gas_price_bucket_counts(&self) -> &GasPriceBucketCounts {
+        &self.gas_price_bucket_counts
+    }
 }
 
 /// Logical pointer to `MempoolTransaction`.
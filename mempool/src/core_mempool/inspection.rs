@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only views of the core mempool, for operators inspecting it through the API.
+use crate::core_mempool::MempoolTransaction;
+use aptos_crypto::HashValue;
+use aptos_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::UNIX_EPOCH};
+
+/// Whether a transaction in mempool can be pulled into the next block
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionStatus {
+    /// In the priority queue, ready for consensus
+    Ready,
+    /// In the parking lot, waiting for a transaction with a lower sequence number of the sender
+    Parked,
+}
+
+/// A transaction in mempool, with its mempool metadata
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolTransactionInfo {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    pub max_gas_amount: u64,
+    pub expiration_timestamp_secs: u64,
+    pub ranking_score: u64,
+    pub insertion_timestamp_usecs: u64,
+    pub status: MempoolTransactionStatus,
+}
+
+impl MempoolTransactionInfo {
+    pub(crate) fn new(txn: &MempoolTransaction, status: MempoolTransactionStatus) -> Self {
+        Self {
+            hash: txn.get_committed_hash(),
+            sender: txn.get_sender(),
+            sequence_number: txn.sequence_info.transaction_sequence_number,
+            gas_unit_price: txn.get_gas_price(),
+            max_gas_amount: txn.txn.max_gas_amount(),
+            expiration_timestamp_secs: txn.txn.expiration_timestamp_secs(),
+            ranking_score: txn.ranking_score,
+            insertion_timestamp_usecs: txn
+                .insertion_time
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_micros() as u64),
+            status,
+        }
+    }
+}
+
+/// Number of transactions in mempool with a gas unit price between `min_gas_unit_price` and
+/// `max_gas_unit_price`, inclusive.  Buckets are powers of two, i.e. `[0, 0]`, `[1, 1]`,
+/// `[2, 3]`, `[4, 7]`, ...
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasPriceBucket {
+    pub min_gas_unit_price: u64,
+    pub max_gas_unit_price: u64,
+    pub ready: u64,
+    pub parked: u64,
+}
+
+impl GasPriceBucket {
+    /// Returns the empty bucket `gas_unit_price` falls into
+    pub(crate) fn for_gas_unit_price(gas_unit_price: u64) -> Self {
+        let min_gas_unit_price = bucket_min_gas_unit_price(gas_unit_price);
+        Self {
+            min_gas_unit_price,
+            max_gas_unit_price: min_gas_unit_price
+                .saturating_sub(1)
+                .saturating_add(min_gas_unit_price),
+            ready: 0,
+            parked: 0,
+        }
+    }
+}
+
+fn bucket_min_gas_unit_price(gas_unit_price: u64) -> u64 {
+    match gas_unit_price {
+        0 => 0,
+        _ => 1 << (u64::BITS - 1 - gas_unit_price.leading_zeros()),
+    }
+}
+
+/// Number of transactions in each `GasPriceBucket`, kept up to date as transactions are added
+/// and removed, so that reading it doesn't walk the whole mempool.
+#[derive(Debug, Default)]
+pub(crate) struct GasPriceBucketCounts {
+    // number of transactions by the min gas unit price of their bucket, without empty buckets
+    counts: BTreeMap<u64, u64>,
+}
+
+impl GasPriceBucketCounts {
+    pub(crate) fn add(&mut self, gas_unit_price: u64) {
+        *self
+            .counts
+            .entry(bucket_min_gas_unit_price(gas_unit_price))
+            .or_insert(0) += 1;
+    }
+
+    pub(crate) fn remove(&mut self, gas_unit_price: u64) {
+        let bucket = bucket_min_gas_unit_price(gas_unit_price);
+        if let Some(count) = self.counts.get_mut(&bucket) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&bucket);
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, min_gas_unit_price: u64) -> u64 {
+        self.counts.get(&min_gas_unit_price).copied().unwrap_or(0)
+    }
+
+    /// Returns the non-empty buckets and their counts, in increasing gas unit price order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (GasPriceBucket, u64)> + '_ {
+        self.counts.iter().map(|(min_gas_unit_price, count)| {
+            (
+                GasPriceBucket::for_gas_unit_price(*min_gas_unit_price),
+                *count,
+            )
+        })
+    }
+}
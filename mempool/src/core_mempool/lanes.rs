@@ -0,0 +1,99 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lanes for critical operational transactions, configured with `MempoolLaneConfig`s.
+//! Transactions in a lane are bounded by the capacity of the lane instead of the capacity of
+//! mempool, can't be evicted by other transactions, and are pulled first into the block space
+//! reserved for the lane.
+use aptos_config::config::MempoolLaneConfig;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use std::collections::HashSet;
+
+pub(crate) struct MempoolLane {
+    pub name: String,
+    // (module address, module name, function name)
+    entry_functions: HashSet<(AccountAddress, String, String)>,
+    senders: HashSet<AccountAddress>,
+    pub capacity: usize,
+    pub reserved_block_percentage: u64,
+}
+
+impl MempoolLane {
+    fn new(config: &MempoolLaneConfig) -> Self {
+        let entry_functions = config
+            .entry_functions
+            .iter()
+            .map(|entry_function| {
+                parse_entry_function(entry_function).unwrap_or_else(|| {
+                    panic!(
+                        "Invalid entry function {} in mempool lane {}, expected address::module::function",
+                        entry_function, config.name
+                    )
+                })
+            })
+            .collect();
+        Self {
+            name: config.name.clone(),
+            entry_functions,
+            senders: config.senders.iter().cloned().collect(),
+            capacity: config.capacity,
+            reserved_block_percentage: config.reserved_block_percentage.min(100),
+        }
+    }
+
+    fn contains(&self, txn: &SignedTransaction) -> bool {
+        if self.senders.contains(&txn.sender()) {
+            return true;
+        }
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                let module = entry_function.module();
+                self.entry_functions.contains(&(
+                    *module.address(),
+                    module.name().to_string(),
+                    entry_function.function().to_string(),
+                ))
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(crate) struct MempoolLanes {
+    lanes: Vec<MempoolLane>,
+}
+
+impl MempoolLanes {
+    pub(crate) fn new(configs: &[MempoolLaneConfig]) -> Self {
+        Self {
+            lanes: configs.iter().map(MempoolLane::new).collect(),
+        }
+    }
+
+    /// Returns the index of the first lane `txn` is in, if any
+    pub(crate) fn lane_of(&self, txn: &SignedTransaction) -> Option<usize> {
+        self.lanes.iter().position(|lane| lane.contains(txn))
+    }
+
+    pub(crate) fn get(&self, lane: usize) -> &MempoolLane {
+        &self.lanes[lane]
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &MempoolLane> {
+        self.lanes.iter()
+    }
+}
+
+fn parse_entry_function(entry_function: &str) -> Option<(AccountAddress, String, String)> {
+    let mut parts = entry_function.split("::");
+    let address = AccountAddress::from_hex_literal(parts.next()?).ok()?;
+    let module = parts.next()?.to_string();
+    let function = parts.next()?.to_string();
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((address, module, function))
+}
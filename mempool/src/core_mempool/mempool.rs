@@ -6,12 +6,14 @@
 use crate::counters::{CONSENSUS_PULLED_LABEL, E2E_LABEL, INSERT_LABEL, LOCAL_LABEL, REMOVE_LABEL};
 use crate::{
     core_mempool::{
-        index::TxnPointer,
+        index::{OrderedQueueKey, TxnPointer},
+        inspection::{GasPriceBucket, MempoolTransactionInfo},
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
+    mempooldb::{MempoolDB, MempoolDBWriter, PersistedTransaction},
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
@@ -24,6 +26,7 @@ use aptos_types::{
 };
 use std::{
     collections::HashSet,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
@@ -36,14 +39,37 @@ pub struct Mempool {
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let db = if config.mempool.persistence.enabled {
+            Some(MempoolDBWriter::new(Arc::new(MempoolDB::new(
+                config.storage.dir(),
+            ))))
+        } else {
+            None
+        };
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(&config.mempool, db),
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
         }
     }
 
+    /// Returns the transactions persisted before a restart that haven't expired by system TTL.
+    /// They have to be re-validated and added back with `add_txn`, which persists them again,
+    /// and the ones that aren't added back have to be discarded.
+    pub(crate) fn get_persisted_transactions(&self) -> Vec<PersistedTransaction> {
+        self.transactions
+            .get_persisted_transactions(aptos_infallible::duration_since_epoch())
+    }
+
+    /// Deletes the persisted transactions of the given senders and sequence numbers
+    pub(crate) fn discard_persisted_transactions(
+        &self,
+        txns: impl IntoIterator<Item = (AccountAddress, u64)>,
+    ) {
+        self.transactions.discard_persisted_transactions(txns)
+    }
+
     /// This function will be called once the transaction has been stored.
     pub(crate) fn remove_transaction(
         &mut self,
@@ -89,6 +115,31 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Iterates over the committed hashes of the transactions in mempool, in no particular order.
+    pub(crate) fn committed_hashes(&self) -> impl Iterator<Item = &HashValue> {
+        self.transactions.committed_hashes()
+    }
+
+    /// Returns the transactions of `sender` in mempool, in sequence number order.
+    pub(crate) fn get_transaction_infos_by_sender(
+        &self,
+        sender: &AccountAddress,
+    ) -> Vec<MempoolTransactionInfo> {
+        self.transactions.get_transaction_infos_by_sender(sender)
+    }
+
+    pub(crate) fn get_transaction_info_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionInfo> {
+        self.transactions.get_transaction_info_by_hash(hash)
+    }
+
+    /// Counts the transactions in mempool by gas unit price bucket.
+    pub(crate) fn gas_price_buckets(&self) -> Vec<GasPriceBucket> {
+        self.transactions.gas_price_buckets()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -140,7 +191,8 @@ impl Mempool {
     /// `batch_size` - size of requested block.
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet,
     ///  mempool should filter out such transactions.
-    #[allow(clippy::explicit_counter_loop)]
+    /// The share of the block reserved for each lane is filled with transactions of that
+    /// lane first, the rest of the block is filled in gas price order.
     pub(crate) fn get_batch(
         &self,
         max_txns: u64,
@@ -148,50 +200,33 @@ impl Mempool {
         mut seen: HashSet<TxnPointer>,
     ) -> Vec<SignedTransaction> {
         let mut result = vec![];
-        // Helper DS. Helps to mitigate scenarios where account submits several transactions
-        // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
-        // and gas_price 1, 10 respectively)
-        // Later txn has higher gas price and will be observed first in priority index iterator,
-        // but can't be executed before first txn. Once observed, such txn will be saved in
-        // `skipped` DS and rechecked once it's ancestor becomes available
-        let mut skipped = HashSet::new();
         let mut total_bytes = 0;
         let seen_size = seen.len();
         let mut txn_walked = 0usize;
-        // iterate over the queue of transactions based on gas price
-        'main: for txn in self.transactions.iter_queue() {
-            txn_walked += 1;
-            if seen.contains(&TxnPointer::from(txn)) {
-                continue;
-            }
-            let tx_seq = txn.sequence_number.transaction_sequence_number;
-            let account_sequence_number = self.transactions.get_sequence_number(&txn.address);
-            let seen_previous = tx_seq > 0 && seen.contains(&(txn.address, tx_seq - 1));
-            // include transaction if it's "next" for given account or
-            // we've already sent its ancestor to Consensus.
-            if seen_previous || account_sequence_number == Some(&tx_seq) {
-                let ptr = TxnPointer::from(txn);
-                seen.insert(ptr);
-                result.push(ptr);
-                if (result.len() as u64) == max_txns {
-                    break;
-                }
-
-                // check if we can now include some transactions
-                // that were skipped before for given account
-                let mut skipped_txn = (txn.address, tx_seq + 1);
-                while skipped.contains(&skipped_txn) {
-                    seen.insert(skipped_txn);
-                    result.push(skipped_txn);
-                    if (result.len() as u64) == max_txns {
-                        break 'main;
-                    }
-                    skipped_txn = (txn.address, skipped_txn.1 + 1);
-                }
-            } else {
-                skipped.insert(TxnPointer::from(txn));
-            }
+        // fill the space reserved for each lane first, so that lane transactions can't be
+        // crowded out of the block by transactions with a higher gas price
+        for (lane, reserved_percentage) in self
+            .transactions
+            .lane_reserved_block_percentages()
+            .into_iter()
+            .enumerate()
+        {
+            let reserved_txns = max_txns.saturating_mul(reserved_percentage) / 100;
+            let max_lane_txns = max_txns.min(result.len() as u64 + reserved_txns);
+            txn_walked += self.select_transactions(
+                self.transactions.iter_lane_queue(lane),
+                max_lane_txns,
+                &mut seen,
+                &mut result,
+            );
         }
+        // iterate over the queue of transactions based on gas price
+        txn_walked += self.select_transactions(
+            self.transactions.iter_queue(),
+            max_txns,
+            &mut seen,
+            &mut result,
+        );
         let result_size = result.len();
         let mut block = Vec::with_capacity(result_size);
         for (address, seq) in result {
@@ -234,6 +269,63 @@ impl Mempool {
         block
     }
 
+    /// Walks `queue` and moves the transactions that can be executed into `result`, until
+    /// `result` holds `max_txns` transactions. Returns the number of transactions walked.
+    #[allow(clippy::explicit_counter_loop)]
+    fn select_transactions<'a>(
+        &self,
+        queue: impl Iterator<Item = &'a OrderedQueueKey>,
+        max_txns: u64,
+        seen: &mut HashSet<TxnPointer>,
+        result: &mut Vec<TxnPointer>,
+    ) -> usize {
+        let mut txn_walked = 0usize;
+        if (result.len() as u64) >= max_txns {
+            return txn_walked;
+        }
+        // Helper DS. Helps to mitigate scenarios where account submits several transactions
+        // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
+        // and gas_price 1, 10 respectively)
+        // Later txn has higher gas price and will be observed first in priority index iterator,
+        // but can't be executed before first txn. Once observed, such txn will be saved in
+        // `skipped` DS and rechecked once it's ancestor becomes available
+        let mut skipped = HashSet::new();
+        'main: for txn in queue {
+            txn_walked += 1;
+            if seen.contains(&TxnPointer::from(txn)) {
+                continue;
+            }
+            let tx_seq = txn.sequence_number.transaction_sequence_number;
+            let account_sequence_number = self.transactions.get_sequence_number(&txn.address);
+            let seen_previous = tx_seq > 0 && seen.contains(&(txn.address, tx_seq - 1));
+            // include transaction if it's "next" for given account or
+            // we've already sent its ancestor to Consensus.
+            if seen_previous || account_sequence_number == Some(&tx_seq) {
+                let ptr = TxnPointer::from(txn);
+                seen.insert(ptr);
+                result.push(ptr);
+                if (result.len() as u64) == max_txns {
+                    break;
+                }
+
+                // check if we can now include some transactions
+                // that were skipped before for given account
+                let mut skipped_txn = (txn.address, tx_seq + 1);
+                while skipped.contains(&skipped_txn) {
+                    seen.insert(skipped_txn);
+                    result.push(skipped_txn);
+                    if (result.len() as u64) == max_txns {
+                        break 'main;
+                    }
+                    skipped_txn = (txn.address, skipped_txn.1 + 1);
+                }
+            } else {
+                skipped.insert(TxnPointer::from(txn));
+            }
+        }
+        txn_walked
+    }
+
     /// Periodic core mempool garbage collection.
     /// Removes all expired transactions and clears expired entries in metrics
     /// cache and sequence number cache.
@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod inspection;
+mod lanes;
 mod mempool;
 mod transaction;
 mod transaction_store;
 
 pub use self::{
-    index::TxnPointer, mempool::Mempool as CoreMempool, transaction::MempoolTransaction,
-    transaction::TimelineState, transaction_store::TXN_INDEX_ESTIMATED_BYTES,
+    index::TxnPointer,
+    inspection::{GasPriceBucket, MempoolTransactionInfo, MempoolTransactionStatus},
+    mempool::Mempool as CoreMempool,
+    transaction::MempoolTransaction,
+    transaction::TimelineState,
+    transaction_store::TXN_INDEX_ESTIMATED_BYTES,
 };
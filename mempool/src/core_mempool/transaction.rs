@@ -23,6 +23,8 @@ pub struct MempoolTransaction {
     pub timeline_state: TimelineState,
     pub sequence_info: SequenceInfo,
     pub insertion_time: SystemTime,
+    // Index of the mempool lane of the transaction, set when it's inserted in the store
+    pub lane: Option<usize>,
 }
 
 impl MempoolTransaction {
@@ -44,6 +46,7 @@ impl MempoolTransaction {
             ranking_score,
             timeline_state,
             insertion_time,
+            lane: None,
         }
     }
     pub(crate) fn get_sender(&self) -> AccountAddress {
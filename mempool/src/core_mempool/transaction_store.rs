@@ -10,10 +10,15 @@ use crate::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
             TimelineIndex,
         },
+        inspection::{
+            GasPriceBucket, GasPriceBucketCounts, MempoolTransactionInfo, MempoolTransactionStatus,
+        },
+        lanes::MempoolLanes,
         transaction::{MempoolTransaction, TimelineState},
     },
     counters,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
+    mempooldb::{MempoolDBWrite, MempoolDBWriter, PersistedTransaction},
 };
 use aptos_config::config::MempoolConfig;
 use aptos_crypto::HashValue;
@@ -27,7 +32,7 @@ use aptos_types::{
 use std::cmp::max;
 use std::mem::size_of;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     ops::Bound,
     time::{Duration, SystemTime},
 };
@@ -38,6 +43,9 @@ pub const TXN_INDEX_ESTIMATED_BYTES: usize = size_of::<crate::core_mempool::inde
     + (size_of::<u64>() * 3 + size_of::<AccountAddress>()) // timeline_index
     + (size_of::<HashValue>() + size_of::<u64>() + size_of::<AccountAddress>()); // hash_index
 
+/// Max number of transactions looked at to find lower fee transactions to evict when full.
+const MAX_EVICTION_CANDIDATES_SCANNED: usize = 1_000;
+
 /// TransactionStore is in-memory storage for all transactions in mempool.
 pub struct TransactionStore {
     // main DS
@@ -57,6 +65,8 @@ pub struct TransactionStore {
     timeline_index: TimelineIndex,
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
+    // PriorityIndex of the ready txns of each lane
+    lane_priority_indexes: Vec<PriorityIndex>,
 
     // Index for looking up transaction by hash.
     // Transactions are stored by AccountAddress + sequence number.
@@ -65,18 +75,29 @@ pub struct TransactionStore {
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
 
+    // transactions by gas price bucket, for inspection
+    gas_price_bucket_counts: GasPriceBucketCounts,
+
+    // write-ahead store of the transactions, if persistence is enabled
+    db: Option<MempoolDBWriter>,
+
     // estimated size in bytes
     size_bytes: usize,
+    // number of txns in each lane
+    lane_sizes: Vec<usize>,
 
     // configuration
+    lanes: MempoolLanes,
     capacity: usize,
     capacity_bytes: usize,
     capacity_per_user: usize,
+    capacity_per_user_for_eviction: usize,
+    replace_by_fee_min_bump_percentage: u64,
     max_batch_bytes: u64,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, db: Option<MempoolDBWriter>) -> Self {
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -90,15 +111,22 @@ impl TransactionStore {
             priority_index: PriorityIndex::new(),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
+            lane_priority_indexes: config.lanes.iter().map(|_| PriorityIndex::new()).collect(),
             hash_index: HashMap::new(),
+            gas_price_bucket_counts: GasPriceBucketCounts::default(),
+            db,
 
             // estimated size in bytes
             size_bytes: 0,
+            lane_sizes: vec![0; config.lanes.len()],
 
             // configuration
+            lanes: MempoolLanes::new(&config.lanes),
             capacity: config.capacity,
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
+            capacity_per_user_for_eviction: config.capacity_per_user_for_eviction,
+            replace_by_fee_min_bump_percentage: config.replace_by_fee_min_bump_percentage,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
         }
     }
@@ -196,15 +224,19 @@ impl TransactionStore {
     }
 
     /// Insert transaction into TransactionStore. Performs validation checks and updates indexes.
-    pub(crate) fn insert(&mut self, txn: MempoolTransaction) -> MempoolStatus {
+    pub(crate) fn insert(&mut self, mut txn: MempoolTransaction) -> MempoolStatus {
+        txn.lane = self.lanes.lane_of(&txn.txn);
         let address = txn.get_sender();
         let sequence_number = txn.sequence_info;
 
         // If the transaction is already in Mempool, we only allow the user to
         // increase the gas unit price to speed up a transaction, but not the max gas.
+        // The increase has to be at least `replace_by_fee_min_bump_percentage`, so that
+        // replacements can't be used to cheaply spam the network.
         //
         // Transactions with all the same inputs (but possibly signed differently) are idempotent
         // since the raw transaction is the same
+        let min_bump_percentage = self.replace_by_fee_min_bump_percentage;
         if let Some(txns) = self.transactions.get_mut(&address) {
             if let Some(current_version) =
                 txns.get_mut(&sequence_number.transaction_sequence_number)
@@ -226,14 +258,27 @@ impl TransactionStore {
                             .to_string(),
                     );
                 } else if current_version.txn.gas_unit_price() < txn.get_gas_price() {
+                    let min_gas_price = min_replacement_gas_price(
+                        current_version.get_gas_price(),
+                        min_bump_percentage,
+                    );
+                    if txn.get_gas_price() < min_gas_price {
+                        return MempoolStatus::new(MempoolStatusCode::ReplacementUnderpriced)
+                            .with_message(format!(
+                                "Replacing the transaction in mempool requires a gas unit price of at least {}, got {}",
+                                min_gas_price,
+                                txn.get_gas_price(),
+                            ));
+                    }
                     // Update txn if gas unit price is a larger value than before
                     if let Some(txn) = txns.remove(&sequence_number.transaction_sequence_number) {
                         self.index_remove(&txn);
                     };
                 } else if current_version.get_gas_price() > txn.get_gas_price() {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
-                        "Transaction already in mempool with a higher gas price".to_string(),
-                    );
+                    return MempoolStatus::new(MempoolStatusCode::ReplacementUnderpriced)
+                        .with_message(
+                            "Transaction already in mempool with a higher gas price".to_string(),
+                        );
                 } else {
                     // If the transaction is the same, it's an idempotent call
                     // Updating signers is not supported, the previous submission must fail
@@ -243,15 +288,22 @@ impl TransactionStore {
             }
         }
 
-        if self.check_is_full_after_eviction(
+        // Transactions in a lane are only bounded by the capacity of the lane
+        if let Some(lane) = txn.lane {
+            let lane_capacity = self.lanes.get(lane).capacity;
+            if self.lane_sizes[lane] >= lane_capacity {
+                return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
+                    "Mempool lane {} is full. Lane size: {}, Capacity: {}",
+                    self.lanes.get(lane).name,
+                    self.lane_sizes[lane],
+                    lane_capacity,
+                ));
+            }
+        } else if let Some(status) = self.check_is_full_after_eviction(
             &txn,
             sequence_number.account_sequence_number_type.min_seq(),
         ) {
-            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
-                "Mempool is full. Mempool size: {}, Capacity: {}",
-                self.system_ttl_index.size(),
-                self.capacity,
-            ));
+            return status;
         }
 
         self.clean_committed_transactions(
@@ -283,7 +335,14 @@ impl TransactionStore {
                 txn.get_committed_hash(),
                 (sender, sequence_number.transaction_sequence_number),
             );
+            self.gas_price_bucket_counts.add(txn.get_gas_price());
             let txn_size_bytes = txn.get_estimated_bytes();
+            if let Some(db) = &self.db {
+                db.send(MempoolDBWrite::Save(persisted_transaction(&txn)));
+            }
+            if let Some(lane) = txn.lane {
+                self.lane_sizes[lane] += 1;
+            }
             txns.insert(sequence_number.transaction_sequence_number, txn);
             self.sequence_numbers.insert(
                 sender,
@@ -322,24 +381,32 @@ impl TransactionStore {
             self.hash_index.len(),
         );
         counters::core_mempool_index_size(counters::SIZE_BYTES_LABEL, self.size_bytes);
+        for (lane, size) in self.lanes.iter().zip(self.lane_sizes.iter()) {
+            counters::CORE_MEMPOOL_LANE_SIZE
+                .with_label_values(&[&lane.name])
+                .set(*size as i64);
+        }
     }
 
-    /// Checks if Mempool is full.
-    /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
+    /// Checks if Mempool is full, and returns the status to reject the transaction with if it is.
+    /// If it's full, tries to free some space by evicting transactions from the ParkingLot, and
+    /// then ready transactions with a lower ranking score than `txn`.
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
         txn: &MempoolTransaction,
         curr_sequence_number: u64,
-    ) -> bool {
+    ) -> Option<MempoolStatus> {
         if self.is_full() && self.check_txn_ready(txn, curr_sequence_number) {
-            // try to free some space in Mempool from ParkingLot by evicting a non-ready txn
+            // try to free some space in Mempool from ParkingLot by evicting a non-ready txn,
+            // transactions in a lane can't be evicted
             if let Some((address, sequence_number)) = self.parking_lot_index.get_poppable() {
-                if let Some(txn) = self
-                    .transactions
-                    .get_mut(&address)
-                    .and_then(|txns| txns.remove(&sequence_number))
-                {
+                if let Some(txn) = self.transactions.get_mut(&address).and_then(|txns| {
+                    match txns.get(&sequence_number) {
+                        Some(txn) if txn.lane.is_none() => txns.remove(&sequence_number),
+                        _ => None,
+                    }
+                }) {
                     debug!(
                         LogSchema::new(LogEntry::MempoolFullEvictedTxn).txns(TxnsLog::new_txn(
                             txn.get_sender(),
@@ -349,12 +416,114 @@ impl TransactionStore {
                     self.index_remove(&txn);
                 }
             }
+            if self.is_full() {
+                if let Err(status) = self.evict_lower_ranked_transactions(txn) {
+                    return Some(status);
+                }
+            }
+        }
+        if self.is_full() {
+            return Some(
+                MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
+                    "Mempool is full. Mempool size: {}, Capacity: {}",
+                    self.system_ttl_index.size(),
+                    self.capacity,
+                )),
+            );
+        }
+        None
+    }
+
+    /// Frees space for `txn` by evicting ready transactions of other accounts with a lower
+    /// ranking score.  Only the last ready transaction of an account is evicted, so that the
+    /// account's remaining transactions stay ready, and at most one per account.
+    /// Nothing is evicted unless enough space can be freed.
+    fn evict_lower_ranked_transactions(
+        &mut self,
+        txn: &MempoolTransaction,
+    ) -> Result<(), MempoolStatus> {
+        let sender = txn.get_sender();
+        let num_sender_txns = self.transactions.get(&sender).map_or(0, |txns| txns.len());
+        if num_sender_txns >= self.capacity_per_user_for_eviction {
+            return Err(
+                MempoolStatus::new(MempoolStatusCode::AccountFairnessCapReached).with_message(
+                    format!(
+                        "Mempool is full, and accounts with {} or more transactions in mempool can't evict other transactions. Number of transactions from account: {}",
+                        self.capacity_per_user_for_eviction, num_sender_txns,
+                    ),
+                ),
+            );
+        }
+
+        let mut evicted = vec![];
+        let mut evicted_accounts = HashSet::new();
+        let mut size = self.size_outside_lanes();
+        let mut size_bytes = self.size_bytes;
+        for key in self
+            .priority_index
+            .iter_lowest_first()
+            .take(MAX_EVICTION_CANDIDATES_SCANNED)
+        {
+            if size < self.capacity && size_bytes < self.capacity_bytes {
+                break;
+            }
+            if key.gas_ranking_score >= txn.ranking_score {
+                break;
+            }
+            if key.address == sender || evicted_accounts.contains(&key.address) {
+                continue;
+            }
+            let sequence_number = key.sequence_number.transaction_sequence_number;
+            if let Some(txns) = self.transactions.get(&key.address) {
+                if txns.contains_key(&(sequence_number + 1)) {
+                    continue;
+                }
+                if let Some(candidate) = txns.get(&sequence_number) {
+                    if candidate.lane.is_some() {
+                        continue;
+                    }
+                    size -= 1;
+                    size_bytes -= candidate.get_estimated_bytes();
+                    evicted_accounts.insert(key.address);
+                    evicted.push((key.address, sequence_number));
+                }
+            }
         }
-        self.is_full()
+        if size >= self.capacity || size_bytes >= self.capacity_bytes {
+            return Err(
+                MempoolStatus::new(MempoolStatusCode::EvictionUnderpriced).with_message(format!(
+                    "Mempool is full, and there are not enough transactions with a lower gas unit price to evict. Mempool size: {}, Capacity: {}",
+                    self.system_ttl_index.size(),
+                    self.capacity,
+                )),
+            );
+        }
+
+        for (address, sequence_number) in evicted {
+            if let Some(evicted_txn) = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number))
+            {
+                debug!(
+                    LogSchema::new(LogEntry::MempoolFullEvictedTxn)
+                        .txns(TxnsLog::new_txn(address, sequence_number)),
+                    ranking_score = evicted_txn.ranking_score,
+                );
+                counters::CORE_MEMPOOL_FEE_EVICTED_TXNS.inc();
+                self.index_remove(&evicted_txn);
+            }
+        }
+        Ok(())
     }
 
     fn is_full(&self) -> bool {
-        self.system_ttl_index.size() >= self.capacity || self.size_bytes >= self.capacity_bytes
+        self.size_outside_lanes() >= self.capacity || self.size_bytes >= self.capacity_bytes
+    }
+
+    /// Number of transactions that count towards `capacity`, i.e. that are not in a lane
+    fn size_outside_lanes(&self) -> usize {
+        self.system_ttl_index.size() - self.lane_sizes.iter().sum::<usize>()
     }
 
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
@@ -400,6 +569,9 @@ impl TransactionStore {
                 AccountSequenceInfo::Sequential(_) => {
                     while let Some(txn) = txns.get_mut(&min_seq) {
                         self.priority_index.insert(txn);
+                        if let Some(lane) = txn.lane {
+                            self.lane_priority_indexes[lane].insert(txn);
+                        }
 
                         let mut broadcast_ready = false;
                         if txn.timeline_state == TimelineState::NotReady {
@@ -523,10 +695,21 @@ impl TransactionStore {
         self.system_ttl_index.remove(txn);
         self.expiration_time_index.remove(txn);
         self.priority_index.remove(txn);
+        if let Some(lane) = txn.lane {
+            self.lane_priority_indexes[lane].remove(txn);
+            self.lane_sizes[lane] -= 1;
+        }
         self.timeline_index.remove(txn);
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
+        self.gas_price_bucket_counts.remove(txn.get_gas_price());
         self.size_bytes -= txn.get_estimated_bytes();
+        if let Some(db) = &self.db {
+            db.send(MempoolDBWrite::Delete(
+                txn.get_sender(),
+                txn.sequence_info.transaction_sequence_number,
+            ));
+        }
 
         // Remove account datastructures if there are no more transactions for the account.
         let address = &txn.get_sender();
@@ -645,6 +828,9 @@ impl TransactionStore {
                 for (_, t) in txns.range_mut((park_range_start, park_range_end)) {
                     self.parking_lot_index.insert(t);
                     self.priority_index.remove(t);
+                    if let Some(lane) = t.lane {
+                        self.lane_priority_indexes[lane].remove(t);
+                    }
                     self.timeline_index.remove(t);
                     if let TimelineState::Ready(_) = t.timeline_state {
                         t.timeline_state = TimelineState::NotReady;
@@ -680,10 +866,27 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    pub(crate) fn committed_hashes(&self) -> impl Iterator<Item = &HashValue> {
+        self.hash_index.keys()
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
 
+    /// Iterates over the ready transactions of `lane`, in priority order
+    pub(crate) fn iter_lane_queue(&self, lane: usize) -> PriorityQueueIter {
+        self.lane_priority_indexes[lane].iter()
+    }
+
+    /// Returns the percentage of block space reserved for each lane, in lane order
+    pub(crate) fn lane_reserved_block_percentages(&self) -> Vec<u64> {
+        self.lanes
+            .iter()
+            .map(|lane| lane.reserved_block_percentage)
+            .collect()
+    }
+
     pub(crate) fn gen_snapshot(&self) -> TxnsLog {
         let mut txns_log = TxnsLog::new();
         for (account, txns) in self.transactions.iter() {
@@ -699,13 +902,118 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Returns the transactions of `address` in sequence number order, with their status
+    pub(crate) fn get_transaction_infos_by_sender(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<MempoolTransactionInfo> {
+        self.transactions
+            .get(address)
+            .map(|txns| {
+                txns.values()
+                    .map(|txn| MempoolTransactionInfo::new(txn, self.get_status(txn)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_transaction_info_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionInfo> {
+        let (address, sequence_number) = self.hash_index.get(&hash)?;
+        self.get_mempool_txn(address, *sequence_number)
+            .map(|txn| MempoolTransactionInfo::new(txn, self.get_status(txn)))
+    }
+
+    /// Counts the transactions in mempool by gas unit price.  Only non-empty buckets are
+    /// returned, in increasing gas unit price order.
+    pub(crate) fn gas_price_buckets(&self) -> Vec<GasPriceBucket> {
+        let parked_counts = self.parking_lot_index.gas_price_bucket_counts();
+        self.gas_price_bucket_counts
+            .iter()
+            .map(|(mut bucket, count)| {
+                bucket.parked = parked_counts.get(bucket.min_gas_unit_price);
+                bucket.ready = count - bucket.parked;
+                bucket
+            })
+            .collect()
+    }
+
+    fn get_status(&self, txn: &MempoolTransaction) -> MempoolTransactionStatus {
+        if self.parking_lot_index.contains(
+            &txn.get_sender(),
+            &txn.sequence_info.transaction_sequence_number,
+        ) {
+            MempoolTransactionStatus::Parked
+        } else {
+            MempoolTransactionStatus::Ready
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
     }
 
+    /// Returns the transactions persisted before a restart, deleting the ones already expired
+    /// by system TTL at `now` from the db.
+    /// The rest stay in the db until they are added back to mempool, which persists them again,
+    /// or discarded with `discard_persisted_transactions`.
+    pub(crate) fn get_persisted_transactions(&self, now: Duration) -> Vec<PersistedTransaction> {
+        let db = match &self.db {
+            Some(db) => db,
+            None => return vec![],
+        };
+        match db.db().get_transactions() {
+            Ok(txns) => {
+                let (txns, expired_txns): (Vec<_>, Vec<_>) =
+                    txns.into_iter().partition(|txn| txn.expiration_time > now);
+                counters::CORE_MEMPOOL_PERSISTED_TXNS_EXPIRED.inc_by(expired_txns.len() as u64);
+                for txn in expired_txns {
+                    db.send(MempoolDBWrite::Delete(
+                        txn.txn.sender(),
+                        txn.txn.sequence_number(),
+                    ));
+                }
+                txns
+            }
+            Err(e) => {
+                error!(LogSchema::new(LogEntry::DBError).error(&e));
+                counters::DB_ERROR.inc();
+                vec![]
+            }
+        }
+    }
+
+    /// Deletes persisted transactions that were not added back to mempool from the db
+    pub(crate) fn discard_persisted_transactions(
+        &self,
+        txns: impl IntoIterator<Item = (AccountAddress, u64)>,
+    ) {
+        if let Some(db) = &self.db {
+            for (address, sequence_number) in txns {
+                db.send(MempoolDBWrite::Delete(address, sequence_number));
+            }
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get_transactions(&self) -> &HashMap<AccountAddress, AccountTransactions> {
         &self.transactions
     }
 }
+
+/// The minimum gas unit price for a transaction to replace one with `gas_unit_price`.
+fn min_replacement_gas_price(gas_unit_price: u64, min_bump_percentage: u64) -> u64 {
+    let bump = (gas_unit_price as u128 * min_bump_percentage as u128 + 99) / 100;
+    gas_unit_price.saturating_add(u64::try_from(bump).unwrap_or(u64::MAX).max(1))
+}
+
+fn persisted_transaction(txn: &MempoolTransaction) -> PersistedTransaction {
+    PersistedTransaction {
+        txn: txn.txn.clone(),
+        expiration_time: txn.expiration_time,
+        non_qualified: txn.timeline_state == TimelineState::NonQualified,
+    }
+}
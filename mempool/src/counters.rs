@@ -52,6 +52,7 @@ pub const VM_VALIDATION_LABEL: &str = "vm_validation";
 // Txn process result labels
 pub const CLIENT_LABEL: &str = "client";
 pub const SUCCESS_LABEL: &str = "success";
+pub const DISCARDED_LABEL: &str = "discarded";
 
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
@@ -66,6 +67,7 @@ pub const START_LABEL: &str = "start";
 // Mempool network msg failure type labels:
 pub const BROADCAST_TXNS: &str = "broadcast_txns";
 pub const ACK_TXNS: &str = "ack_txns";
+pub const SEEN_FILTER: &str = "seen_filter";
 
 // Broadcast/ACK type labels
 pub const EXPIRED_BROADCAST_LABEL: &str = "expired";
@@ -103,6 +105,16 @@ pub fn core_mempool_index_size(label: &'static str, size: usize) {
         .set(size as i64)
 }
 
+/// Counter tracking number of txns in each core mempool lane
+pub static CORE_MEMPOOL_LANE_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_core_mempool_lane_size",
+        "Number of txns in a core mempool lane",
+        &["lane"]
+    )
+    .unwrap()
+});
+
 /// Counter tracking number of txns removed from core mempool
 pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -112,6 +124,34 @@ pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns evicted from a full core mempool for higher fee txns
+pub static CORE_MEMPOOL_FEE_EVICTED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_fee_evicted_txns_count",
+        "Number of txns evicted from a full core mempool for txns with a higher gas unit price"
+    )
+    .unwrap()
+});
+
+/// Counter tracking number of txns persisted before a restart, that expired before being reloaded
+pub static CORE_MEMPOOL_PERSISTED_TXNS_EXPIRED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_persisted_txns_expired_count",
+        "Number of txns persisted before a restart that expired by system TTL before being reloaded"
+    )
+    .unwrap()
+});
+
+/// Counter tracking number of txns persisted before a restart, by result of adding them back
+pub static CORE_MEMPOOL_PERSISTED_TXNS_RELOADED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_persisted_txns_reloaded_count",
+        "Number of txns persisted before a restart that were added back to mempool or discarded",
+        &["status"]
+    )
+    .unwrap()
+});
+
 /// Counter tracking number of txns received that are idempotent duplicates
 pub static CORE_MEMPOOL_IDEMPOTENT_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -384,6 +424,45 @@ pub fn shared_mempool_broadcast_type_inc(network_id: NetworkId, label: &str) {
         .inc();
 }
 
+/// Counter tracking txns and bytes not broadcast to a peer because its seen filter shows it
+/// already holds them, i.e. the bandwidth saved by the seen filter exchange
+pub static SHARED_MEMPOOL_BROADCAST_DEDUP_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_broadcast_dedup_txns",
+        "Number of txns not broadcast to a peer that already holds them",
+        &["network"]
+    )
+    .unwrap()
+});
+
+pub static SHARED_MEMPOOL_BROADCAST_DEDUP_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_broadcast_dedup_bytes",
+        "Number of txn bytes not broadcast to a peer that already holds them",
+        &["network"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_broadcast_dedup(network_id: NetworkId, num_txns: usize, num_bytes: usize) {
+    SHARED_MEMPOOL_BROADCAST_DEDUP_TXNS
+        .with_label_values(&[network_id.as_str()])
+        .inc_by(num_txns as u64);
+    SHARED_MEMPOOL_BROADCAST_DEDUP_BYTES
+        .with_label_values(&[network_id.as_str()])
+        .inc_by(num_bytes as u64);
+}
+
+/// Counter tracking bytes of seen filters sent to peers, i.e. the cost of the seen filter exchange
+pub static SHARED_MEMPOOL_SEEN_FILTER_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_seen_filter_bytes",
+        "Number of bytes of seen filters sent to peers",
+        &["network"]
+    )
+    .unwrap()
+});
+
 static SHARED_MEMPOOL_ACK_TYPE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_shared_mempool_ack_count",
@@ -57,11 +57,12 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+pub use core_mempool::{GasPriceBucket, MempoolTransactionInfo, MempoolTransactionStatus};
 pub use shared_mempool::{
     bootstrap, network,
     types::{
-        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
-        QuorumStoreResponse, SubmissionStatus,
+        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, MempoolInspectionRequest,
+        PeerBroadcastState, QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
@@ -70,5 +71,6 @@ pub use tests::{fuzzing, mocks};
 mod core_mempool;
 pub mod counters;
 mod logging;
+mod mempooldb;
 mod shared_mempool;
 pub(crate) mod thread_pool;
@@ -155,12 +155,14 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    InspectMempool,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
     BroadcastTransaction,
     BroadcastACK,
     ReceiveACK,
+    SeenFilter,
     InvariantViolated,
     AddTxn,
     RemoveTxn,
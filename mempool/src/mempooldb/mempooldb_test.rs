@@ -0,0 +1,98 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+};
+use std::{sync::Arc, time::Duration};
+
+fn persisted_transaction(sender: AccountAddress, sequence_number: u64) -> PersistedTransaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let raw_transaction = RawTransaction::new(
+        sender,
+        sequence_number,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        0,
+        0,
+        0,
+        ChainId::new(10),
+    );
+    PersistedTransaction {
+        txn: SignedTransaction::new(
+            raw_transaction.clone(),
+            private_key.public_key(),
+            private_key.sign(&raw_transaction).unwrap(),
+        ),
+        expiration_time: Duration::from_secs(sequence_number),
+        non_qualified: false,
+    }
+}
+
+#[test]
+fn test_write_get() {
+    let tmp_dir = TempPath::new();
+    let db = MempoolDB::new(&tmp_dir);
+    assert!(db.get_transactions().unwrap().is_empty());
+
+    let sender = AccountAddress::random();
+    let txns: Vec<_> = (0..3)
+        .map(|seq| persisted_transaction(sender, seq))
+        .collect();
+    // Saved out of order, returned in sequence number order. Writes apply in order, so the
+    // deleted transaction can be saved again
+    let mut writes: Vec<_> = txns
+        .iter()
+        .rev()
+        .cloned()
+        .map(MempoolDBWrite::Save)
+        .collect();
+    writes.push(MempoolDBWrite::Delete(sender, 1));
+    writes.push(MempoolDBWrite::Delete(sender, 2));
+    writes.push(MempoolDBWrite::Save(txns[2].clone()));
+    db.write(writes).unwrap();
+    assert_eq!(
+        db.get_transactions().unwrap(),
+        vec![txns[0].clone(), txns[2].clone()]
+    );
+
+    // Reading the transactions doesn't remove them from the db
+    assert_eq!(db.get_transactions().unwrap().len(), 2);
+}
+
+#[test]
+fn test_writer_applies_writes_in_order() {
+    let tmp_dir = TempPath::new();
+    let sender = AccountAddress::random();
+    let txns: Vec<_> = (0..10)
+        .map(|seq| persisted_transaction(sender, seq))
+        .collect();
+    {
+        let writer = MempoolDBWriter::new(Arc::new(MempoolDB::new(&tmp_dir)));
+        for txn in &txns {
+            writer.send(MempoolDBWrite::Save(txn.clone()));
+            writer.send(MempoolDBWrite::Delete(sender, txn.txn.sequence_number()));
+            writer.send(MempoolDBWrite::Save(txn.clone()));
+        }
+        writer.send(MempoolDBWrite::Delete(sender, 0));
+        // Dropping the writer waits for the queued writes
+    }
+
+    let db = MempoolDB::new(&tmp_dir);
+    assert_eq!(db.get_transactions().unwrap(), txns[1..].to_vec());
+}
+
+#[test]
+fn test_reopen() {
+    let tmp_dir = TempPath::new();
+    let txn = persisted_transaction(AccountAddress::random(), 0);
+    MempoolDB::new(&tmp_dir)
+        .write(vec![MempoolDBWrite::Save(txn.clone())])
+        .unwrap();
+
+    let db = MempoolDB::new(&tmp_dir);
+    assert_eq!(db.get_transactions().unwrap(), vec![txn]);
+}
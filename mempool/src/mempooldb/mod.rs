@@ -0,0 +1,144 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod mempooldb_test;
+mod schema;
+
+pub use schema::transaction::PersistedTransaction;
+
+use crate::{
+    counters,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::Result;
+use aptos_logger::prelude::*;
+use aptos_types::account_address::AccountAddress;
+use schema::{transaction::TransactionSchema, TRANSACTION_CF_NAME};
+use schemadb::{Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
+use std::{
+    path::Path,
+    sync::{mpsc, Arc},
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+/// The name of the mempool db file
+pub const MEMPOOL_DB_NAME: &str = "mempool_db";
+
+/// Write-ahead store of the transactions in mempool, so they can be reloaded after a restart.
+pub struct MempoolDB {
+    db: DB,
+}
+
+impl MempoolDB {
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        let column_families = vec![
+            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+            TRANSACTION_CF_NAME,
+        ];
+
+        let path = db_root_path.as_ref().join(MEMPOOL_DB_NAME);
+        let instant = Instant::now();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open(path.clone(), "mempool", column_families, &opts)
+            .expect("MempoolDB open failed; unable to continue");
+
+        info!(
+            "Opened MempoolDB at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    /// Applies the writes in order, as a single batch that isn't synced to disk.
+    pub fn write(&self, writes: Vec<MempoolDBWrite>) -> Result<()> {
+        let batch = SchemaBatch::new();
+        for write in writes {
+            match write {
+                MempoolDBWrite::Save(txn) => batch.put::<TransactionSchema>(
+                    &(txn.txn.sender(), txn.txn.sequence_number()),
+                    &txn,
+                )?,
+                MempoolDBWrite::Delete(address, sequence_number) => {
+                    batch.delete::<TransactionSchema>(&(address, sequence_number))?
+                }
+            }
+        }
+        self.db.write_schemas_relaxed(batch)
+    }
+
+    /// Returns all transactions in the db, ordered by sender and sequence number. They stay in
+    /// the db until they are deleted, or overwritten when added back to mempool.
+    pub fn get_transactions(&self) -> Result<Vec<PersistedTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|row| row.map(|(_, txn)| txn)).collect()
+    }
+}
+
+/// A change to the transactions in the `MempoolDB`
+#[derive(Debug)]
+pub enum MempoolDBWrite {
+    Save(PersistedTransaction),
+    Delete(AccountAddress, u64),
+}
+
+/// Applies writes to the `MempoolDB` on a dedicated thread, in the order they are sent, so that
+/// mempool never waits for the disk while it holds its lock. The writes queued up while the
+/// previous batch was being written go into the next batch.
+pub struct MempoolDBWriter {
+    db: Arc<MempoolDB>,
+    sender: Option<mpsc::Sender<MempoolDBWrite>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MempoolDBWriter {
+    pub fn new(db: Arc<MempoolDB>) -> Self {
+        let (sender, receiver) = mpsc::channel::<MempoolDBWrite>();
+        let writer_db = db.clone();
+        let handle = thread::Builder::new()
+            .name("mempool-db-writer".to_string())
+            .spawn(move || {
+                while let Ok(write) = receiver.recv() {
+                    let mut writes = vec![write];
+                    writes.extend(receiver.try_iter());
+                    if let Err(e) = writer_db.write(writes) {
+                        error!(LogSchema::new(LogEntry::DBError).error(&e));
+                        counters::DB_ERROR.inc();
+                    }
+                }
+            })
+            .expect("Failed to spawn the mempool db writer thread");
+        Self {
+            db,
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+
+    pub fn db(&self) -> &MempoolDB {
+        &self.db
+    }
+
+    pub fn send(&self, write: MempoolDBWrite) {
+        if let Some(sender) = &self.sender {
+            // The writer thread only stops once the sender is dropped
+            let _ = sender.send(write);
+        }
+    }
+}
+
+impl Drop for MempoolDBWriter {
+    /// Waits for the queued writes to be applied
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
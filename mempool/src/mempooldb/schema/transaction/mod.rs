@@ -0,0 +1,66 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for transactions accepted into mempool.
+//!
+//! Serialized transaction bytes identified by sender and sequence number, so that the
+//! transactions of an account are iterated in sequence number order.
+//! ```text
+//! |<---------key--------->|<-----value----->|
+//! | sender | sequence_num | persisted txn   |
+//! ```
+
+use super::{ensure_slice_len_eq, TRANSACTION_CF_NAME};
+use anyhow::Result;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::schema::{KeyCodec, Schema, ValueCodec};
+use serde::{Deserialize, Serialize};
+use std::{mem::size_of, time::Duration};
+
+/// A transaction accepted into mempool, with the state needed to add it back after a restart
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PersistedTransaction {
+    pub txn: SignedTransaction,
+    // System expiration time of the transaction, as a duration since the unix epoch
+    pub expiration_time: Duration,
+    // Whether the transaction will never be broadcast, e.g. because it was received from a peer
+    pub non_qualified: bool,
+}
+
+#[derive(Debug)]
+pub struct TransactionSchema;
+
+impl Schema for TransactionSchema {
+    const COLUMN_FAMILY_NAME: schemadb::ColumnFamilyName = TRANSACTION_CF_NAME;
+    type Key = (AccountAddress, u64);
+    type Value = PersistedTransaction;
+}
+
+impl KeyCodec<TransactionSchema> for (AccountAddress, u64) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = self.0.to_vec();
+        encoded.write_u64::<BigEndian>(self.1)?;
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let sequence_number = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+        Ok((address, sequence_number))
+    }
+}
+
+impl ValueCodec<TransactionSchema> for PersistedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
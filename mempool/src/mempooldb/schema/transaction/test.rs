@@ -0,0 +1,28 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        seq_num in any::<u64>(),
+        txn in any::<SignedTransaction>(),
+        expiration_secs in any::<u64>(),
+        non_qualified in any::<bool>(),
+    ) {
+        assert_encode_decode::<TransactionSchema>(
+            &(address, seq_num),
+            &PersistedTransaction {
+                txn,
+                expiration_time: Duration::from_secs(expiration_secs),
+                non_qualified,
+            },
+        );
+    }
+}
+
+test_no_panic_decoding!(TransactionSchema);
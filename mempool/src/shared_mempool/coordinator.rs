@@ -148,6 +148,11 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::Inspect(request) => {
+            bounded_executor
+                .spawn(tasks::process_client_inspection(smp.clone(), request))
+                .await;
+        }
     }
 }
 
@@ -263,6 +268,7 @@ async fn handle_network_event<V>(
                         .is_upstream_peer(&peer, Some(&metadata))
                 ));
             smp.network_interface.disable_peer(peer);
+            smp.seen_filter_schedule.lock().remove_peer(&peer);
             notify_subscribers(SharedMempoolNotification::PeerStateChange, &smp.subscribers);
         }
         Event::Message(peer_id, msg) => {
@@ -319,6 +325,10 @@ async fn handle_network_event<V>(
                         ack_timestamp,
                     );
                 }
+                MempoolSyncMsg::SeenTransactions { filter } => {
+                    smp.network_interface
+                        .process_seen_filter(PeerNetworkId::new(network_id, peer_id), filter);
+                }
             }
         }
         Event::RpcRequest(peer_id, _msg, _, _res_tx) => {
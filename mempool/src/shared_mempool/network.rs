@@ -7,6 +7,7 @@ use crate::{
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     shared_mempool::{
+        seen_filter::SeenTransactionsFilter,
         tasks,
         types::{
             notify_subscribers, BatchId, PeerSyncState, SharedMempool, SharedMempoolNotification,
@@ -72,6 +73,9 @@ pub enum MempoolSyncMsg {
         /// A backpressure signal from the recipient when it is overwhelmed (e.g., mempool is full).
         backoff: bool,
     },
+    /// Filter of the transactions held by the sender, so that the recipient skips them in its
+    /// broadcasts. Only sent when `shared_mempool_seen_filter_enabled`.
+    SeenTransactions { filter: SeenTransactionsFilter },
 }
 
 /// The interface from Network to Mempool layer.
@@ -224,7 +228,7 @@ impl MempoolNetworkInterface {
             let peer_states = self.sync_states.read_all();
             peer_states
                 .iter()
-                .map(|(peer, state)| (*peer, state.metadata.role))
+                .map(|(peer, state)| (*peer, state.metadata.role, state.score.rank()))
                 .collect()
         };
 
@@ -235,7 +239,7 @@ impl MempoolNetworkInterface {
         let peers: Vec<_> = peers
             .iter()
             .sorted_by(|peer_a, peer_b| self.prioritized_peers_comparator.compare(peer_a, peer_b))
-            .map(|(peer, _)| *peer)
+            .map(|(peer, _, _)| *peer)
             .collect();
         let _ = std::mem::replace(&mut *prioritized_peers, peers);
     }
@@ -279,7 +283,9 @@ impl MempoolNetworkInterface {
             return;
         };
 
-        if let Some(sent_timestamp) = sync_state.broadcast_info.sent_batches.remove(&batch_id) {
+        let peer_rank_changed = if let Some(sent_timestamp) =
+            sync_state.broadcast_info.sent_batches.remove(&batch_id)
+        {
             let rtt = timestamp
                 .duration_since(sent_timestamp)
                 .expect("failed to calculate mempool broadcast RTT");
@@ -290,6 +296,7 @@ impl MempoolNetworkInterface {
                 .observe(rtt.as_secs_f64());
 
             counters::shared_mempool_pending_broadcasts(&peer).dec();
+            sync_state.score.record_ack(rtt)
         } else {
             trace!(
                 LogSchema::new(LogEntry::ReceiveACK)
@@ -298,7 +305,7 @@ impl MempoolNetworkInterface {
                 "batch ID does not exist or expired"
             );
             return;
-        }
+        };
 
         trace!(
             LogSchema::new(LogEntry::ReceiveACK)
@@ -319,6 +326,29 @@ impl MempoolNetworkInterface {
         if backoff {
             sync_state.broadcast_info.backoff_mode = true;
         }
+        drop(sync_states);
+
+        // Prefer the peers that ack the fastest and most reliably
+        if peer_rank_changed {
+            self.update_prioritized_peers();
+        }
+    }
+
+    /// Stores the filter of the transactions `peer` holds, to skip them in broadcasts to `peer`
+    pub fn process_seen_filter(&self, peer: PeerNetworkId, filter: SeenTransactionsFilter) {
+        if !filter.is_valid() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(60)),
+                warn!(
+                    LogSchema::new(LogEntry::SeenFilter).peer(&peer),
+                    "invalid seen filter"
+                )
+            );
+            return;
+        }
+        if let Some(state) = self.sync_states.write_lock().get_mut(&peer) {
+            state.seen_filter = Some(filter);
+        }
     }
 
     pub fn is_backoff_mode(&self, peer: &PeerNetworkId) -> bool {
@@ -433,19 +463,48 @@ impl MempoolNetworkInterface {
                         Some(counters::RETRY_BROADCAST_LABEL)
                     };
 
-                    let txns = mempool.timeline_range(id.0, id.1);
+                    let txns = remove_seen_transactions(
+                        peer,
+                        state.seen_filter.as_ref(),
+                        mempool.timeline_range(id.0, id.1),
+                    );
                     (*id, txns, metric_label)
                 }
                 None => {
-                    // Fresh broadcast
-                    let (txns, new_timeline_id) = mempool.read_timeline(
-                        state.timeline_id,
-                        self.mempool_config.shared_mempool_batch_size,
-                    );
-                    (BatchId(state.timeline_id, new_timeline_id), txns, None)
+                    // Fresh broadcast, skipping over the batches the peer already holds
+                    loop {
+                        let (txns, new_timeline_id) = mempool.read_timeline(
+                            state.timeline_id,
+                            self.mempool_config.shared_mempool_batch_size,
+                        );
+                        let batch_id = BatchId(state.timeline_id, new_timeline_id);
+                        if txns.is_empty() {
+                            break (batch_id, txns, None);
+                        }
+                        let txns = remove_seen_transactions(peer, state.seen_filter.as_ref(), txns);
+                        if !txns.is_empty() {
+                            break (batch_id, txns, None);
+                        }
+                        state.timeline_id = new_timeline_id;
+                    }
                 }
             };
 
+        let peer_rank_changed =
+            metric_label == Some(counters::EXPIRED_BROADCAST_LABEL) && state.score.record_timeout();
+        if transactions.is_empty() {
+            // The peer may hold all the transactions of a rebroadcast by now
+            state.broadcast_info.sent_batches.remove(&batch_id);
+            state.broadcast_info.retry_batches.remove(&batch_id);
+        }
+        drop(mempool);
+        drop(sync_states);
+
+        // Prefer the peers that ack the fastest and most reliably
+        if peer_rank_changed {
+            self.update_prioritized_peers();
+        }
+
         if transactions.is_empty() {
             return Err(BroadcastError::NoTransactions(peer));
         }
@@ -560,6 +619,29 @@ impl NetworkInterface<MempoolSyncMsg, MempoolMultiNetworkSender> for MempoolNetw
     }
 }
 
+/// Removes the transactions that the seen filter of `peer` shows it already holds
+fn remove_seen_transactions(
+    peer: PeerNetworkId,
+    seen_filter: Option<&SeenTransactionsFilter>,
+    transactions: Vec<SignedTransaction>,
+) -> Vec<SignedTransaction> {
+    let seen_filter = match seen_filter {
+        Some(seen_filter) => seen_filter,
+        None => return transactions,
+    };
+    let (seen, unseen): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .partition(|txn| seen_filter.contains(&txn.clone().committed_hash()));
+    if !seen.is_empty() {
+        counters::shared_mempool_broadcast_dedup(
+            peer.network_id(),
+            seen.len(),
+            seen.iter().map(SignedTransaction::raw_txn_bytes_len).sum(),
+        );
+    }
+    unseen
+}
+
 #[derive(Clone, Debug)]
 struct PrioritizedPeersComparator {
     random_state: RandomState,
@@ -575,8 +657,8 @@ impl PrioritizedPeersComparator {
     /// Provides ordering for peers to send transactions to
     fn compare(
         &self,
-        peer_a: &(PeerNetworkId, PeerRole),
-        peer_b: &(PeerNetworkId, PeerRole),
+        peer_a: &(PeerNetworkId, PeerRole, u64),
+        peer_b: &(PeerNetworkId, PeerRole, u64),
     ) -> Ordering {
         let peer_network_id_a = peer_a.0;
         let peer_network_id_b = peer_b.0;
@@ -591,13 +673,17 @@ impl PrioritizedPeersComparator {
                 let role_a = peer_a.1;
                 let role_b = peer_b.1;
                 match role_a.cmp(&role_b) {
-                    // Tiebreak by hash_peer_id.
-                    Ordering::Equal => {
-                        let hash_a = self.hash_peer_id(&peer_network_id_a.peer_id());
-                        let hash_b = self.hash_peer_id(&peer_network_id_b.peer_id());
-
-                        hash_a.cmp(&hash_b)
-                    }
+                    // Then sort by PeerScore rank, higher first
+                    Ordering::Equal => match peer_b.2.cmp(&peer_a.2) {
+                        // Tiebreak by hash_peer_id.
+                        Ordering::Equal => {
+                            let hash_a = self.hash_peer_id(&peer_network_id_a.peer_id());
+                            let hash_b = self.hash_peer_id(&peer_network_id_b.peer_id());
+
+                            hash_a.cmp(&hash_b)
+                        }
+                        ordering => ordering,
+                    },
                     ordering => ordering,
                 }
             }
@@ -616,6 +702,7 @@ impl PrioritizedPeersComparator {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::shared_mempool::types::PeerScore;
     use aptos_config::network_id::NetworkId;
     use aptos_types::PeerId;
 
@@ -628,18 +715,22 @@ mod test {
         let val_1 = (
             PeerNetworkId::new(NetworkId::Vfn, peer_id_1),
             PeerRole::Validator,
+            0,
         );
         let val_2 = (
             PeerNetworkId::new(NetworkId::Vfn, peer_id_2),
             PeerRole::Validator,
+            0,
         );
         let vfn_1 = (
             PeerNetworkId::new(NetworkId::Public, peer_id_1),
             PeerRole::ValidatorFullNode,
+            0,
         );
         let preferred_1 = (
             PeerNetworkId::new(NetworkId::Public, peer_id_1),
             PeerRole::PreferredUpstream,
+            0,
         );
 
         // NetworkId ordering
@@ -650,6 +741,15 @@ mod test {
         assert_eq!(Ordering::Greater, comparator.compare(&vfn_1, &preferred_1));
         assert_eq!(Ordering::Less, comparator.compare(&preferred_1, &vfn_1));
 
+        // PeerScore rank ordering
+        let fast_val_2 = (val_2.0, val_2.1, 1);
+        assert_eq!(Ordering::Less, comparator.compare(&fast_val_2, &val_1));
+        assert_eq!(Ordering::Greater, comparator.compare(&val_1, &fast_val_2));
+        assert_eq!(
+            Ordering::Greater,
+            comparator.compare(&preferred_1, &fast_val_2)
+        );
+
         // Tiebreaker on peer_id
         let hash_1 = comparator.hash_peer_id(&val_1.0.peer_id());
         let hash_2 = comparator.hash_peer_id(&val_2.0.peer_id());
@@ -660,4 +760,21 @@ mod test {
         // Same the only equal case
         assert_eq!(Ordering::Equal, comparator.compare(&val_1, &val_1));
     }
+
+    #[test]
+    fn check_peer_score_rank() {
+        let mut score = PeerScore::new();
+        let rank = score.rank();
+
+        // Small changes in round trip time don't change the rank
+        assert!(!score.record_ack(Duration::from_micros(100)));
+        assert_eq!(rank, score.rank());
+
+        // Slow acks lower the rank, and timeouts even more
+        while !score.record_ack(Duration::from_secs(1)) {}
+        let slow_rank = score.rank();
+        assert!(slow_rank < rank);
+        while !score.record_timeout() {}
+        assert!(score.rank() < slow_rank);
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        tasks,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -70,6 +71,9 @@ pub(crate) fn start_shared_mempool<V>(
         peer_metadata_storage,
     );
 
+    // Add back the transactions persisted before a restart, before serving any request
+    tasks::reload_persisted_transactions(&smp);
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
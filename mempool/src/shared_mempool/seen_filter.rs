@@ -0,0 +1,183 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bloom filter of the transactions held in a node's mempool. It is sent to the peers broadcasting
+//! to the node, so that they skip the transactions the node already holds. A false positive makes
+//! a peer skip a transaction the node doesn't hold, so the filter is sized to make that unlikely
+//! (less than one in a million with `SEEN_FILTER_MAX_TXNS` transactions).
+
+use aptos_config::network_id::PeerNetworkId;
+use aptos_crypto::HashValue;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    time::{Duration, Instant},
+};
+
+/// Max number of transactions in a filter, beyond that the filter only covers part of mempool
+pub(crate) const SEEN_FILTER_MAX_TXNS: usize = 4_096;
+const SEEN_FILTER_NUM_BITS: usize = 1 << 18;
+const SEEN_FILTER_NUM_HASHES: u32 = 7;
+
+// Bounds on the filters received from peers, so that a peer can't make us do unbounded work
+const MAX_SEEN_FILTER_NUM_BITS: usize = 1 << 20;
+const MAX_SEEN_FILTER_NUM_HASHES: u32 = 16;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SeenTransactionsFilter {
+    num_hashes: u32,
+    bits: Vec<u64>,
+}
+
+impl SeenTransactionsFilter {
+    /// Builds a filter of the given committed transaction hashes
+    pub(crate) fn new<'a>(hashes: impl Iterator<Item = &'a HashValue>) -> Self {
+        let mut filter = Self {
+            num_hashes: SEEN_FILTER_NUM_HASHES,
+            bits: vec![0; SEEN_FILTER_NUM_BITS / 64],
+        };
+        for hash in hashes.take(SEEN_FILTER_MAX_TXNS) {
+            filter.insert(hash);
+        }
+        filter
+    }
+
+    fn insert(&mut self, hash: &HashValue) {
+        for bit in self.bit_indexes(hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `true` if the transaction with the given committed hash is (probably) held by the
+    /// peer that sent the filter
+    pub(crate) fn contains(&self, hash: &HashValue) -> bool {
+        self.bit_indexes(hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Filters received from peers are only used when they are within bounds
+    pub(crate) fn is_valid(&self) -> bool {
+        !self.bits.is_empty()
+            && self.bits.len() * 64 <= MAX_SEEN_FILTER_NUM_BITS
+            && (1..=MAX_SEEN_FILTER_NUM_HASHES).contains(&self.num_hashes)
+    }
+
+    pub(crate) fn size_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    // Double hashing on the bytes of the hash, which are already uniformly distributed
+    fn bit_indexes(&self, hash: &HashValue) -> impl Iterator<Item = usize> {
+        let bytes = hash.as_ref();
+        let h1 = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) | 1;
+        let num_bits = (self.bits.len() * 64) as u64;
+        (0..self.num_hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+/// Decides when the seen filter is due for each of the peers broadcasting to this node. The
+/// filter is rebuilt at most once per interval and shared by all the peers.
+pub(crate) struct SeenFilterSchedule {
+    interval: Duration,
+    last_sent: HashMap<PeerNetworkId, Instant>,
+    filter: Option<(Instant, SeenTransactionsFilter)>,
+}
+
+impl SeenFilterSchedule {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sent: HashMap::new(),
+            filter: None,
+        }
+    }
+
+    /// Returns the filter to send to `peer` if it is due, `build` is only called when the last
+    /// filter built is older than the interval
+    pub(crate) fn next_filter(
+        &mut self,
+        peer: PeerNetworkId,
+        now: Instant,
+        build: impl FnOnce() -> SeenTransactionsFilter,
+    ) -> Option<SeenTransactionsFilter> {
+        if let Some(last_sent) = self.last_sent.get(&peer) {
+            if now.duration_since(*last_sent) < self.interval {
+                return None;
+            }
+        }
+        let is_stale = match &self.filter {
+            Some((built, _)) => now.duration_since(*built) >= self.interval,
+            None => true,
+        };
+        if is_stale {
+            self.filter = Some((now, build()));
+        }
+        self.last_sent.insert(peer, now);
+        self.filter.as_ref().map(|(_, filter)| filter.clone())
+    }
+
+    pub(crate) fn remove_peer(&mut self, peer: &PeerNetworkId) {
+        self.last_sent.remove(peer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_seen_filter() {
+        let hashes: Vec<_> = (0..SEEN_FILTER_MAX_TXNS)
+            .map(|_| HashValue::random())
+            .collect();
+        let filter = SeenTransactionsFilter::new(hashes.iter());
+        assert!(filter.is_valid());
+        assert!(hashes.iter().all(|hash| filter.contains(hash)));
+
+        // False positives are rare
+        let false_positives = (0..10_000)
+            .filter(|_| filter.contains(&HashValue::random()))
+            .count();
+        assert!(false_positives <= 1);
+
+        let invalid_filter = SeenTransactionsFilter {
+            num_hashes: 1,
+            bits: vec![],
+        };
+        assert!(!invalid_filter.is_valid());
+    }
+
+    #[test]
+    fn test_seen_filter_schedule() {
+        let mut schedule = SeenFilterSchedule::new(Duration::from_secs(1));
+        let peer_1 = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        let peer_2 = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        let hash = HashValue::random();
+        let now = Instant::now();
+
+        assert!(schedule
+            .next_filter(peer_1, now, || SeenTransactionsFilter::new([hash].iter()))
+            .unwrap()
+            .contains(&hash));
+        // Not due yet for the same peer, and the filter is shared with other peers
+        assert!(schedule
+            .next_filter(peer_1, now, || unreachable!())
+            .is_none());
+        assert!(schedule
+            .next_filter(peer_2, now, || unreachable!())
+            .unwrap()
+            .contains(&hash));
+
+        // Rebuilt after the interval
+        let later = now + Duration::from_secs(1);
+        let filter = schedule
+            .next_filter(peer_1, later, || SeenTransactionsFilter::new([].iter()))
+            .unwrap();
+        assert!(!filter.contains(&hash));
+    }
+}
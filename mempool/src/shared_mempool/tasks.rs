@@ -7,9 +7,12 @@ use crate::{
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, MempoolSyncMsg},
-    shared_mempool::types::{
-        notify_subscribers, BatchId, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-        SubmissionStatusBundle,
+    shared_mempool::{
+        seen_filter::SeenTransactionsFilter,
+        types::{
+            notify_subscribers, BatchId, MempoolInspectionRequest, PeerBroadcastState,
+            ScheduledBroadcast, SharedMempool, SharedMempoolNotification, SubmissionStatusBundle,
+        },
     },
     thread_pool::IO_POOL,
     QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
@@ -151,6 +154,48 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes read-only mempool inspection request by client.
+pub(crate) async fn process_client_inspection<V>(
+    smp: SharedMempool<V>,
+    request: MempoolInspectionRequest,
+) where
+    V: TransactionValidation,
+{
+    let callback_sent = match request {
+        MempoolInspectionRequest::GetTransactionInfosBySender(sender, callback) => {
+            let txns = smp.mempool.lock().get_transaction_infos_by_sender(&sender);
+            callback.send(txns).is_ok()
+        }
+        MempoolInspectionRequest::GetTransactionInfoByHash(hash, callback) => {
+            let txn = smp.mempool.lock().get_transaction_info_by_hash(hash);
+            callback.send(txn).is_ok()
+        }
+        MempoolInspectionRequest::GetGasPriceBuckets(callback) => {
+            let buckets = smp.mempool.lock().gas_price_buckets();
+            callback.send(buckets).is_ok()
+        }
+        MempoolInspectionRequest::GetPeerBroadcastStates(callback) => {
+            let mut states: Vec<_> = smp
+                .network_interface
+                .app_data()
+                .read_all()
+                .iter()
+                .map(|(peer, state)| PeerBroadcastState::new(*peer, state))
+                .collect();
+            states.sort_by_key(|state| state.peer);
+            callback.send(states).is_ok()
+        }
+    };
+
+    if !callback_sent {
+        error!(LogSchema::event_log(
+            LogEntry::InspectMempool,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -167,6 +212,10 @@ pub(crate) async fn process_transaction_broadcast<V>(
     let results = process_incoming_transactions(&smp, transactions, timeline_state);
     log_txn_process_results(&results, Some(peer));
 
+    if smp.config.shared_mempool_seen_filter_enabled {
+        send_seen_filter(&smp, peer);
+    }
+
     let ack_response = gen_ack_response(request_id, results, &peer);
     let network_sender = smp.network_interface.sender();
 
@@ -185,7 +234,38 @@ pub(crate) async fn process_transaction_broadcast<V>(
     notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
 }
 
-/// If `MempoolIsFull` on any of the transactions, provide backpressure to the downstream peer.
+/// Sends the filter of the transactions in mempool to `peer` if it is due, so that `peer` skips
+/// them in its broadcasts.
+fn send_seen_filter<V>(smp: &SharedMempool<V>, peer: PeerNetworkId)
+where
+    V: TransactionValidation,
+{
+    let filter = smp
+        .seen_filter_schedule
+        .lock()
+        .next_filter(peer, Instant::now(), || {
+            let mempool = smp.mempool.lock();
+            SeenTransactionsFilter::new(mempool.committed_hashes())
+        });
+    if let Some(filter) = filter {
+        let filter_bytes = filter.size_bytes();
+        let message = MempoolSyncMsg::SeenTransactions { filter };
+        if let Err(e) = smp.network_interface.sender().send_to(peer, message) {
+            counters::network_send_fail_inc(counters::SEEN_FILTER);
+            error!(
+                LogSchema::event_log(LogEntry::SeenFilter, LogEvent::NetworkSendFail)
+                    .peer(&peer)
+                    .error(&e.into())
+            );
+        } else {
+            counters::SHARED_MEMPOOL_SEEN_FILTER_BYTES
+                .with_label_values(&[peer.network_id().as_str()])
+                .inc_by(filter_bytes as u64);
+        }
+    }
+}
+
+/// If mempool is full on any of the transactions, provide backpressure to the downstream peer.
 fn gen_ack_response(
     request_id: BatchId,
     results: Vec<SubmissionStatusBundle>,
@@ -193,7 +273,12 @@ fn gen_ack_response(
 ) -> MempoolSyncMsg {
     let mut backoff_and_retry = false;
     for (_, (mempool_status, _)) in results.into_iter() {
-        if mempool_status.code == MempoolStatusCode::MempoolIsFull {
+        if matches!(
+            mempool_status.code,
+            MempoolStatusCode::MempoolIsFull
+                | MempoolStatusCode::EvictionUnderpriced
+                | MempoolStatusCode::AccountFairnessCapReached
+        ) {
             backoff_and_retry = true;
             break;
         }
@@ -350,6 +435,61 @@ where
     statuses
 }
 
+/// Adds back the transactions persisted before a restart. They go through the same checks as
+/// new transactions, so the ones committed in the meantime are discarded and the rest are
+/// re-validated against the latest state. Their system TTL restarts from now. They are only
+/// deleted from the db once they are discarded, so a crash while reloading doesn't lose them.
+pub(crate) fn reload_persisted_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let persisted_txns = smp.mempool.lock().get_persisted_transactions();
+    if persisted_txns.is_empty() {
+        return;
+    }
+
+    let (non_qualified_txns, txns): (Vec<_>, Vec<_>) = persisted_txns
+        .into_iter()
+        .partition(|persisted_txn| persisted_txn.non_qualified);
+    let mut statuses = vec![];
+    for (txns, timeline_state) in [
+        (txns, TimelineState::NotReady),
+        (non_qualified_txns, TimelineState::NonQualified),
+    ] {
+        if !txns.is_empty() {
+            statuses.extend(process_incoming_transactions(
+                smp,
+                txns.into_iter()
+                    .map(|persisted_txn| persisted_txn.txn)
+                    .collect(),
+                timeline_state,
+            ));
+        }
+    }
+
+    // The transactions added back were persisted again, the rest are removed from the db
+    let discarded_txns: Vec<_> = statuses
+        .iter()
+        .filter(|(_, (mempool_status, _))| mempool_status.code != MempoolStatusCode::Accepted)
+        .map(|(txn, _)| (txn.sender(), txn.sequence_number()))
+        .collect();
+    let num_reloaded = statuses.len() - discarded_txns.len();
+    smp.mempool
+        .lock()
+        .discard_persisted_transactions(discarded_txns);
+    counters::CORE_MEMPOOL_PERSISTED_TXNS_RELOADED
+        .with_label_values(&[counters::SUCCESS_LABEL])
+        .inc_by(num_reloaded as u64);
+    counters::CORE_MEMPOOL_PERSISTED_TXNS_RELOADED
+        .with_label_values(&[counters::DISCARDED_LABEL])
+        .inc_by((statuses.len() - num_reloaded) as u64);
+    info!(
+        "Reloaded {} persisted transactions into mempool, discarded {}",
+        num_reloaded,
+        statuses.len() - num_reloaded
+    );
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
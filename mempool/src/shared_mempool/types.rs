@@ -3,8 +3,12 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
-    shared_mempool::network::MempoolNetworkSender,
+    core_mempool::{CoreMempool, GasPriceBucket, MempoolTransactionInfo},
+    network::MempoolNetworkInterface,
+    shared_mempool::{
+        network::MempoolNetworkSender,
+        seen_filter::{SeenFilterSchedule, SeenTransactionsFilter},
+    },
 };
 use anyhow::Result;
 use aptos_config::{
@@ -14,7 +18,8 @@ use aptos_config::{
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_types::{
-    mempool_status::MempoolStatus, transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress, mempool_status::MempoolStatus, transaction::SignedTransaction,
+    vm_status::DiscardedVMStatus,
 };
 use consensus_types::common::TransactionSummary;
 use futures::{
@@ -30,7 +35,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::Waker,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use storage_interface::DbReader;
 use tokio::runtime::Handle;
@@ -48,6 +53,7 @@ where
     pub db: Arc<dyn DbReader>,
     pub validator: Arc<RwLock<V>>,
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
+    pub(crate) seen_filter_schedule: Arc<Mutex<SeenFilterSchedule>>,
 }
 
 impl<V: TransactionValidation + 'static> SharedMempool<V> {
@@ -67,6 +73,9 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             role,
             config.clone(),
         );
+        let seen_filter_schedule = Arc::new(Mutex::new(SeenFilterSchedule::new(
+            Duration::from_millis(config.shared_mempool_seen_filter_interval_ms),
+        )));
         SharedMempool {
             mempool,
             config,
@@ -74,6 +83,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             db,
             validator,
             subscribers,
+            seen_filter_schedule,
         }
     }
 
@@ -207,6 +217,15 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Read-only inspection of mempool state, for operators debugging stuck transactions
+    Inspect(MempoolInspectionRequest),
+}
+
+pub enum MempoolInspectionRequest {
+    GetTransactionInfosBySender(AccountAddress, oneshot::Sender<Vec<MempoolTransactionInfo>>),
+    GetTransactionInfoByHash(HashValue, oneshot::Sender<Option<MempoolTransactionInfo>>),
+    GetGasPriceBuckets(oneshot::Sender<Vec<GasPriceBucket>>),
+    GetPeerBroadcastStates(oneshot::Sender<Vec<PeerBroadcastState>>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -220,6 +239,9 @@ pub(crate) struct PeerSyncState {
     pub timeline_id: u64,
     pub broadcast_info: BroadcastInfo,
     pub metadata: ConnectionMetadata,
+    pub score: PeerScore,
+    // Latest filter of the transactions the peer holds, sent by the peer
+    pub seen_filter: Option<SeenTransactionsFilter>,
 }
 
 impl PeerSyncState {
@@ -228,10 +250,60 @@ impl PeerSyncState {
             timeline_id: 0,
             broadcast_info: BroadcastInfo::new(),
             metadata,
+            score: PeerScore::new(),
+            seen_filter: None,
         }
     }
 }
 
+/// Weight of the latest sample in the moving averages of `PeerScore`
+const PEER_SCORE_SAMPLE_WEIGHT: f64 = 0.1;
+
+/// How fast and reliably an upstream peer acks broadcasts, used to prefer the peers that get
+/// transactions on their way to validators fastest
+#[derive(Clone, Debug)]
+pub(crate) struct PeerScore {
+    // Exponential moving average of the broadcast ack round trip time, in seconds
+    ack_rtt_secs: f64,
+    // Exponential moving average of the share of broadcasts acked before the ack timeout
+    ack_success_rate: f64,
+}
+
+impl PeerScore {
+    pub(crate) fn new() -> Self {
+        Self {
+            ack_rtt_secs: 0.0,
+            ack_success_rate: 1.0,
+        }
+    }
+
+    /// Records an ack received after `rtt`, and returns whether the rank of the peer changed
+    pub fn record_ack(&mut self, rtt: Duration) -> bool {
+        let rank = self.rank();
+        self.ack_rtt_secs += PEER_SCORE_SAMPLE_WEIGHT * (rtt.as_secs_f64() - self.ack_rtt_secs);
+        self.ack_success_rate += PEER_SCORE_SAMPLE_WEIGHT * (1.0 - self.ack_success_rate);
+        rank != self.rank()
+    }
+
+    /// Records a broadcast that timed out waiting for an ack, and returns whether the rank of the
+    /// peer changed
+    pub fn record_timeout(&mut self) -> bool {
+        let rank = self.rank();
+        self.ack_success_rate -= PEER_SCORE_SAMPLE_WEIGHT * self.ack_success_rate;
+        rank != self.rank()
+    }
+
+    /// Coarse rank of the peer, higher is better. The ack success rate is rounded to tenths and
+    /// the round trip time to a power of two milliseconds, so that small fluctuations don't
+    /// reorder peers.
+    pub fn rank(&self) -> u64 {
+        let success_rate_bucket = (self.ack_success_rate * 10.0).round() as u64;
+        let rtt_ms = (self.ack_rtt_secs * 1000.0) as u64;
+        let rtt_bucket = 64 - u64::from(rtt_ms.leading_zeros());
+        success_rate_bucket * 100 + (64 - rtt_bucket)
+    }
+}
+
 /// Identifier for a broadcasted batch of txns.
 /// For BatchId(`start_id`, `end_id`), (`start_id`, `end_id`) is the range of timeline IDs read from
 /// the core mempool timeline index that produced the txns in this batch.
@@ -261,6 +333,31 @@ pub struct BroadcastInfo {
     pub backoff_mode: bool,
 }
 
+/// Broadcast state of a peer, as reported by `MempoolInspectionRequest::GetPeerBroadcastStates`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerBroadcastState {
+    pub peer: PeerNetworkId,
+    /// Position in the timeline of ready transactions broadcast up to
+    pub timeline_id: u64,
+    /// Broadcasts that have not yet received an ack
+    pub sent_batches: usize,
+    /// Broadcasts that have received a retry ack and are pending a resend
+    pub retry_batches: usize,
+    pub backoff_mode: bool,
+}
+
+impl PeerBroadcastState {
+    pub(crate) fn new(peer: PeerNetworkId, state: &PeerSyncState) -> Self {
+        Self {
+            peer,
+            timeline_id: state.timeline_id,
+            sent_batches: state.broadcast_info.sent_batches.len(),
+            retry_batches: state.broadcast_info.retry_batches.len(),
+            backoff_mode: state.broadcast_info.backoff_mode,
+        }
+    }
+}
+
 impl BroadcastInfo {
     fn new() -> Self {
         Self {
@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, MempoolTransaction, TimelineState},
+    core_mempool::{
+        CoreMempool, GasPriceBucket, MempoolTransaction, MempoolTransactionStatus, TimelineState,
+    },
     tests::common::{add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool, TestTransaction},
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{MempoolLaneConfig, NodeConfig};
 use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
 use aptos_types::mempool_status::MempoolStatusCode;
-use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
+use aptos_types::{
+    account_address::AccountAddress, account_config::AccountSequenceInfo,
+    transaction::SignedTransaction,
+};
 use std::time::SystemTime;
 use std::{collections::HashSet, time::Duration};
 
@@ -599,3 +605,376 @@ fn test_sequence_number_behavior_at_capacity() {
     let batch = pool.get_batch(10, 10240, HashSet::new());
     assert_eq!(batch.len(), 1);
 }
+
+fn add_txn_with_status(pool: &mut CoreMempool, transaction: TestTransaction) -> MempoolStatusCode {
+    let txn = transaction.make_signed_transaction();
+    pool.add_txn(
+        txn.clone(),
+        txn.gas_unit_price(),
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    )
+    .code
+}
+
+#[test]
+fn test_replace_by_fee_min_bump() {
+    let mut config = NodeConfig::random();
+    config.mempool.replace_by_fee_min_bump_percentage = 10;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 100)).unwrap();
+
+    // The gas unit price has to increase by at least 10%
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(0, 0, 105)),
+        MempoolStatusCode::ReplacementUnderpriced
+    );
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(0, 0, 110)),
+        MempoolStatusCode::Accepted
+    );
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(0, 0, 50)),
+        MempoolStatusCode::ReplacementUnderpriced
+    );
+
+    let batch = pool.get_batch(10, 10240, HashSet::new());
+    assert_eq!(batch.len(), 1);
+    assert_eq!(batch[0].gas_unit_price(), 110);
+}
+
+#[test]
+fn test_fee_eviction_when_full() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 5)).unwrap();
+
+    // The lowest gas unit price transaction is evicted for a higher one
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(2, 0, 3)),
+        MempoolStatusCode::Accepted
+    );
+    let mut gas_prices: Vec<_> = pool
+        .get_batch(10, 10240, HashSet::new())
+        .iter()
+        .map(SignedTransaction::gas_unit_price)
+        .collect();
+    gas_prices.sort_unstable();
+    assert_eq!(gas_prices, vec![3, 5]);
+
+    // Nothing has a lower gas unit price left
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(3, 0, 2)),
+        MempoolStatusCode::EvictionUnderpriced
+    );
+}
+
+#[test]
+fn test_fee_eviction_keeps_remaining_txns_ready() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 5)).unwrap();
+
+    // Only the last transaction of account 0 can be evicted
+    add_txn(&mut pool, TestTransaction::new(2, 0, 3)).unwrap();
+    let mut txns: Vec<_> = pool
+        .get_batch(10, 10240, HashSet::new())
+        .iter()
+        .map(|txn| (txn.gas_unit_price(), txn.sequence_number()))
+        .collect();
+    txns.sort_unstable();
+    assert_eq!(txns, vec![(1, 0), (3, 0), (5, 0)]);
+}
+
+#[test]
+fn test_fee_eviction_fairness_cap() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    config.mempool.capacity_per_user_for_eviction = 2;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 10)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 1, 10)).unwrap();
+
+    // Account 1 already has its share of the full mempool, even though it pays more
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(1, 2, 10)),
+        MempoolStatusCode::AccountFairnessCapReached
+    );
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(2, 0, 10)),
+        MempoolStatusCode::Accepted
+    );
+}
+
+fn persistent_mempool_config(path: &TempPath) -> NodeConfig {
+    let mut config = NodeConfig::random();
+    config.mempool.persistence.enabled = true;
+    config.storage.dir = path.path().to_path_buf();
+    config
+}
+
+fn persisted_transactions(pool: &CoreMempool) -> HashSet<(AccountAddress, u64)> {
+    pool.get_persisted_transactions()
+        .into_iter()
+        .map(|persisted_txn| {
+            (
+                persisted_txn.txn.sender(),
+                persisted_txn.txn.sequence_number(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_persisted_transactions_reload() {
+    let path = TempPath::new();
+    let config = persistent_mempool_config(&path);
+    let txns = {
+        let mut pool = CoreMempool::new(&config);
+        let txns = add_txns_to_mempool(
+            &mut pool,
+            vec![
+                TestTransaction::new(0, 0, 1),
+                TestTransaction::new(0, 1, 1),
+                TestTransaction::new(1, 0, 1),
+            ],
+        );
+        // Committed transactions are removed from the db
+        pool.remove_transaction(&txns[2].sender(), 0, false);
+        txns
+    };
+    let keys: Vec<_> = txns
+        .iter()
+        .map(|txn| (txn.sender(), txn.sequence_number()))
+        .collect();
+
+    // Reading the persisted transactions doesn't remove them, so they survive another restart
+    {
+        let pool = CoreMempool::new(&config);
+        assert_eq!(
+            persisted_transactions(&pool),
+            keys[..2].iter().cloned().collect()
+        );
+    }
+
+    // They are removed once they are discarded, and kept when they are added back
+    {
+        let mut pool = CoreMempool::new(&config);
+        assert_eq!(
+            persisted_transactions(&pool),
+            keys[..2].iter().cloned().collect()
+        );
+        add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+        pool.discard_persisted_transactions(vec![keys[1]]);
+    }
+
+    let pool = CoreMempool::new(&config);
+    assert_eq!(persisted_transactions(&pool), HashSet::from([keys[0]]));
+}
+
+#[test]
+fn test_persisted_transactions_expired_by_system_ttl() {
+    let path = TempPath::new();
+    let mut config = persistent_mempool_config(&path);
+    config.mempool.system_transaction_timeout_secs = 0;
+    {
+        let mut pool = CoreMempool::new(&config);
+        add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    }
+    {
+        let pool = CoreMempool::new(&config);
+        assert!(pool.get_persisted_transactions().is_empty());
+    }
+
+    // Expired transactions are removed from the db
+    config.mempool.system_transaction_timeout_secs = 600;
+    let pool = CoreMempool::new(&config);
+    assert!(pool.get_persisted_transactions().is_empty());
+}
+
+#[test]
+fn test_inspection() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 2, 1),
+            TestTransaction::new(1, 0, 5),
+        ],
+    );
+
+    // The second transaction of account 0 waits for sequence number 1
+    let infos = pool.get_transaction_infos_by_sender(&TestTransaction::get_address(0));
+    assert_eq!(
+        infos
+            .iter()
+            .map(|info| (info.sequence_number, info.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, MempoolTransactionStatus::Ready),
+            (2, MempoolTransactionStatus::Parked)
+        ]
+    );
+    assert!(pool
+        .get_transaction_infos_by_sender(&TestTransaction::get_address(2))
+        .is_empty());
+
+    let info = pool
+        .get_transaction_info_by_hash(txns[2].clone().committed_hash())
+        .unwrap();
+    assert_eq!(info.sender, TestTransaction::get_address(1));
+    assert_eq!(info.gas_unit_price, 5);
+    assert_eq!(info.status, MempoolTransactionStatus::Ready);
+    assert!(pool
+        .get_transaction_info_by_hash(HashValue::random())
+        .is_none());
+
+    assert_eq!(
+        pool.gas_price_buckets(),
+        vec![
+            GasPriceBucket {
+                min_gas_unit_price: 1,
+                max_gas_unit_price: 1,
+                ready: 1,
+                parked: 1,
+            },
+            GasPriceBucket {
+                min_gas_unit_price: 4,
+                max_gas_unit_price: 7,
+                ready: 1,
+                parked: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_gas_price_buckets_follow_mempool_changes() {
+    let (mut pool, _) = setup_mempool();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 2, 1),
+            TestTransaction::new(1, 0, 5),
+        ],
+    );
+    let bucket = |min_gas_unit_price, max_gas_unit_price, ready, parked| GasPriceBucket {
+        min_gas_unit_price,
+        max_gas_unit_price,
+        ready,
+        parked,
+    };
+
+    // Filling the sequence number gap makes the parked transaction ready
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 1, 1)]);
+    assert_eq!(
+        pool.gas_price_buckets(),
+        vec![bucket(1, 1, 3, 0), bucket(4, 7, 1, 0)]
+    );
+
+    // Committed transactions leave their bucket, and empty buckets are dropped
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    assert_eq!(pool.gas_price_buckets(), vec![bucket(1, 1, 2, 0)]);
+
+    // A higher gas price replaces the transaction in its bucket
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 2, 100)]);
+    assert_eq!(
+        pool.gas_price_buckets(),
+        vec![bucket(1, 1, 1, 0), bucket(64, 127, 1, 0)]
+    );
+
+    pool.remove_transaction(&TestTransaction::get_address(0), 2, false);
+    assert!(pool.gas_price_buckets().is_empty());
+}
+
+fn lane_mempool_config(
+    capacity: usize,
+    lane_capacity: usize,
+    reserved_percentage: u64,
+) -> NodeConfig {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = capacity;
+    config.mempool.lanes = vec![MempoolLaneConfig {
+        name: "operators".to_string(),
+        senders: vec![TestTransaction::get_address(5)],
+        capacity: lane_capacity,
+        reserved_block_percentage: reserved_percentage,
+        ..MempoolLaneConfig::default()
+    }];
+    config
+}
+
+#[test]
+fn test_lane_capacity() {
+    let mut pool = CoreMempool::new(&lane_mempool_config(1, 2, 0));
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 1)).is_err());
+
+    // The lane has its own capacity
+    add_txn(&mut pool, TestTransaction::new(5, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(5, 1, 1)).unwrap();
+    assert_eq!(
+        add_txn_with_status(&mut pool, TestTransaction::new(5, 2, 1)),
+        MempoolStatusCode::MempoolIsFull
+    );
+
+    // Committing a lane transaction frees space in the lane
+    pool.remove_transaction(&TestTransaction::get_address(5), 0, false);
+    add_txn(&mut pool, TestTransaction::new(5, 2, 1)).unwrap();
+}
+
+#[test]
+fn test_lane_transactions_not_evicted() {
+    let mut pool = CoreMempool::new(&lane_mempool_config(2, 10, 0));
+    add_txn(&mut pool, TestTransaction::new(5, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 0, 2)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 3)).unwrap();
+
+    // The transaction of account 0 is evicted, even though the lane transaction pays less
+    add_txn(&mut pool, TestTransaction::new(2, 0, 10)).unwrap();
+    let mut gas_prices: Vec<_> = pool
+        .get_batch(10, 10240, HashSet::new())
+        .iter()
+        .map(SignedTransaction::gas_unit_price)
+        .collect();
+    gas_prices.sort_unstable();
+    assert_eq!(gas_prices, vec![1, 3, 10]);
+}
+
+#[test]
+fn test_lane_reserved_block_space() {
+    let mut pool = CoreMempool::new(&lane_mempool_config(100, 10, 50));
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 100),
+            TestTransaction::new(1, 0, 100),
+            TestTransaction::new(2, 0, 100),
+            TestTransaction::new(5, 0, 1),
+            TestTransaction::new(5, 1, 1),
+            TestTransaction::new(5, 2, 1),
+        ],
+    );
+
+    // Half of the block is reserved for the lane, the rest goes by gas price
+    let batch = pool.get_batch(4, 10240, HashSet::new());
+    let lane_txns = batch
+        .iter()
+        .filter(|txn| txn.sender() == TestTransaction::get_address(5))
+        .count();
+    assert_eq!(batch.len(), 4);
+    assert_eq!(lane_txns, 2);
+
+    // Without competition, the lane can use the rest of the block
+    let batch = pool.get_batch(10, 10240, HashSet::new());
+    assert_eq!(batch.len(), 6);
+}
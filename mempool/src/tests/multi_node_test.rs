@@ -3,6 +3,8 @@
 
 use crate::tests::common;
 use crate::{
+    core_mempool::TimelineState,
+    counters,
     network::MempoolSyncMsg,
     shared_mempool::types::SharedMempoolNotification,
     tests::{
@@ -17,7 +19,7 @@ use aptos_config::{
     config::{NodeConfig, PeerRole},
     network_id::{NetworkId, PeerNetworkId},
 };
-use aptos_types::{transaction::SignedTransaction, PeerId};
+use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction, PeerId};
 use netcore::transport::ConnectionOrigin;
 use network::{
     peer_manager::{PeerManagerNotification, PeerManagerRequest},
@@ -36,6 +38,7 @@ struct MempoolOverrideConfig {
     ack_timeout_ms: Option<u64>,
     backoff_interval_ms: Option<u64>,
     tick_interval_ms: Option<u64>,
+    seen_filter_interval_ms: Option<u64>,
 }
 
 impl MempoolOverrideConfig {
@@ -48,6 +51,7 @@ impl MempoolOverrideConfig {
             ack_timeout_ms: None,
             backoff_interval_ms: None,
             tick_interval_ms: None,
+            seen_filter_interval_ms: None,
         }
     }
 }
@@ -196,6 +200,11 @@ impl TestHarness {
             if let Some(tick_interval_ms) = mempool_config.tick_interval_ms {
                 config.mempool.shared_mempool_tick_interval_ms = tick_interval_ms;
             }
+
+            if let Some(seen_filter_interval_ms) = mempool_config.seen_filter_interval_ms {
+                config.mempool.shared_mempool_seen_filter_enabled = true;
+                config.mempool.shared_mempool_seen_filter_interval_ms = seen_filter_interval_ms;
+            }
         }
     }
 
@@ -393,10 +402,15 @@ impl TestHarness {
         }
     }
 
-    /// Delivers broadcast ACK from `peer`.
+    /// Delivers broadcast ACK from `peer`, and the seen filter sent before it, if any.
     fn deliver_response(&mut self, sender_id: &NodeId, network_id: NetworkId) {
         // Wait for an ACK to come in on the events
         self.wait_for_event(sender_id, SharedMempoolNotification::ACK);
+        while !self.deliver_next_message(sender_id, network_id) {}
+    }
+
+    /// Delivers the next message from `peer`, and returns whether it was a broadcast ACK.
+    fn deliver_next_message(&mut self, sender_id: &NodeId, network_id: NetworkId) -> bool {
         let sender = self.mut_node(sender_id);
         let sender_peer_id = sender.peer_id(network_id);
         let network_req = sender.get_next_network_req(network_id);
@@ -404,8 +418,13 @@ impl TestHarness {
         match network_req {
             PeerManagerRequest::SendDirectSend(remote_peer_id, msg) => {
                 let mempool_message = common::decompress_and_deserialize(&msg.mdata.to_vec());
+                let is_ack = matches!(
+                    mempool_message,
+                    MempoolSyncMsg::BroadcastTransactionsResponse { .. }
+                );
                 match mempool_message {
-                    MempoolSyncMsg::BroadcastTransactionsResponse { .. } => {
+                    MempoolSyncMsg::BroadcastTransactionsResponse { .. }
+                    | MempoolSyncMsg::SeenTransactions { .. } => {
                         // send it to peer
                         let lookup_peer_network_id = match network_id {
                             NetworkId::Vfn => {
@@ -433,6 +452,7 @@ impl TestHarness {
                         request
                     ),
                 }
+                is_ack
             }
             request => panic!("Node did not ACK broadcast, instead got {:?}", request),
         }
@@ -626,3 +646,67 @@ fn test_max_network_byte_size() {
         }
     }
 }
+
+#[test]
+fn test_seen_filter_dedup() {
+    let mut validator_mempool_config = MempoolOverrideConfig::new();
+    validator_mempool_config.max_broadcasts_per_peer = Some(1);
+    validator_mempool_config.ack_timeout_ms = Some(u64::MAX);
+    validator_mempool_config.seen_filter_interval_ms = Some(0);
+
+    let (mut harness, validators) =
+        TestHarness::bootstrap_validator_network(2, Some(validator_mempool_config));
+    let (v_a, v_b) = (validators.first().unwrap(), validators.get(1).unwrap());
+
+    // B already holds the transactions, but doesn't broadcast them
+    harness.add_txns(v_a, test_transactions(0, 5));
+    {
+        let mut mempool = harness.node(v_b).mempool();
+        for txn in test_transactions(0, 5) {
+            let txn = txn.make_signed_transaction_with_max_gas_amount(5);
+            mempool.add_txn(
+                txn.clone(),
+                txn.gas_unit_price(),
+                AccountSequenceInfo::Sequential(0),
+                TimelineState::NonQualified,
+            );
+        }
+    }
+    let dedup_txns = || {
+        counters::SHARED_MEMPOOL_BROADCAST_DEDUP_TXNS
+            .with_label_values(&[NetworkId::Validator.as_str()])
+            .get()
+    };
+    let dedup_txns_before = dedup_txns();
+
+    // A and B discover each other
+    harness.connect(v_b, v_a);
+
+    // B sends its seen filter along with the ACK of the first broadcast
+    let (txns, _) = harness.broadcast_txns(
+        v_a,
+        NetworkId::Validator,
+        1,
+        Some(1),
+        None,
+        true,
+        true,
+        false,
+    );
+    assert_eq!(0, txns.first().unwrap().sequence_number());
+
+    // A skips the transactions B already holds
+    harness.add_txns(v_a, test_transactions(5, 1));
+    let (txns, _) = harness.broadcast_txns(
+        v_a,
+        NetworkId::Validator,
+        1,
+        Some(1),
+        None,
+        false,
+        false,
+        false,
+    );
+    assert_eq!(5, txns.first().unwrap().sequence_number());
+    assert!(dedup_txns() - dedup_txns_before >= 4);
+}
@@ -382,6 +382,9 @@ impl MempoolNode {
             MempoolSyncMsg::BroadcastTransactionsResponse { .. } => {
                 panic!("We aren't supposed to be getting as response here");
             }
+            MempoolSyncMsg::SeenTransactions { .. } => {
+                panic!("We aren't supposed to be getting a seen filter here");
+            }
         };
         let response = MempoolSyncMsg::BroadcastTransactionsResponse {
             request_id,
@@ -14,12 +14,21 @@
 //!
 //! Internally both the client and server leverage a NetworkStream that communications in blocks
 //! where a block is a length prefixed array of bytes.
+//!
+//! Optionally, the client and server authenticate each other with pinned x25519 static keys during
+//! a Noise IK handshake performed on every new connection. The blocks that follow are then
+//! encrypted with the resulting session.
 
+use aptos_crypto::{
+    noise::{self, NoiseConfig, NoiseError, NoiseSession},
+    x25519,
+};
 use aptos_logger::{info, trace, warn, Schema};
 use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
 use once_cell::sync::Lazy;
 use serde::Serialize;
 use std::{
+    collections::{HashMap, HashSet},
     io::{Read, Write},
     net::{Shutdown, SocketAddr, TcpListener, TcpStream},
     thread, time,
@@ -57,6 +66,7 @@ enum LogEvent {
     ConnectionFailed,
     DisconnectedPeerOnRead,
     DisconnectedPeerOnWrite,
+    HandshakeFailed,
     Shutdown,
 }
 
@@ -138,14 +148,43 @@ pub enum Error {
     AlreadyShutdown,
     #[error("Found data that is too large to decode: {0}")]
     DataTooLarge(usize),
+    #[error("Received an invalid message: {0}")]
+    InvalidMessage(String),
     #[error("Internal network error:")]
     NetworkError(#[from] std::io::Error),
     #[error("No active stream")]
     NoActiveStream,
+    #[error("Noise error: {0}")]
+    NoiseError(#[from] NoiseError),
     #[error("Overflow error: {0}")]
     OverflowError(String),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Replayed handshake from {0} with timestamp {1}")]
+    ReplayedHandshake(x25519::PublicKey, u64),
+    #[error("Remote peer is not trusted: {0}")]
+    UntrustedPeer(x25519::PublicKey),
+}
+
+/// Largest plaintext fitting in a Noise message along with its authentication tag
+const MAX_NOISE_PAYLOAD: usize = noise::MAX_SIZE_NOISE_MSG - noise::AES_GCM_TAGLEN;
+
+/// The client sends the current time in milliseconds as the payload of the handshake, so that a
+/// recorded handshake can't be replayed.
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Static key of the client and pinned static key of the server of a Noise IK handshake
+struct ClientNoise {
+    config: NoiseConfig,
+    server_public_key: x25519::PublicKey,
+}
+
+/// Static key of the server and pinned static keys of the clients allowed to connect
+struct ServerNoise {
+    config: NoiseConfig,
+    trusted_clients: HashSet<x25519::PublicKey>,
+    /// Latest handshake timestamp of each client, the next ones must be strictly greater
+    client_timestamps: HashMap<x25519::PublicKey, u64>,
 }
 
 pub struct NetworkClient {
@@ -154,6 +193,7 @@ pub struct NetworkClient {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    noise: Option<ClientNoise>,
 }
 
 impl NetworkClient {
@@ -163,6 +203,25 @@ impl NetworkClient {
             server,
             stream: None,
             timeout_ms,
+            noise: None,
+        }
+    }
+
+    /// Creates a client authenticating with its private key to a server holding the private key of
+    /// server_public_key. The connections fail if the server does not prove ownership of that key.
+    pub fn new_with_noise(
+        service: &'static str,
+        server: SocketAddr,
+        timeout_ms: u64,
+        private_key: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+    ) -> Self {
+        Self {
+            noise: Some(ClientNoise {
+                config: NoiseConfig::new(private_key),
+                server_public_key,
+            }),
+            ..Self::new(service, server, timeout_ms)
         }
     }
 
@@ -257,7 +316,25 @@ impl NetworkClient {
 
             let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.server, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.server, self.timeout_ms);
+            if let Some(noise) = &self.noise {
+                if let Err(err) = stream.initiate_handshake(
+                    &noise.config,
+                    self.service.as_bytes(),
+                    noise.server_public_key,
+                ) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        self.service,
+                        NetworkMode::Client,
+                        LogEvent::HandshakeFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&self.server));
+                    return Err(err);
+                }
+            }
+            self.stream = Some(stream);
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 self.service,
@@ -277,6 +354,7 @@ pub struct NetworkServer {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    noise: Option<ServerNoise>,
 }
 
 impl NetworkServer {
@@ -287,6 +365,26 @@ impl NetworkServer {
             listener: Some(listener.unwrap()),
             stream: None,
             timeout_ms,
+            noise: None,
+        }
+    }
+
+    /// Creates a server authenticating with its private key, which only accepts the clients proving
+    /// ownership of one of the trusted_clients keys.
+    pub fn new_with_noise(
+        service: &'static str,
+        listen: SocketAddr,
+        timeout_ms: u64,
+        private_key: x25519::PrivateKey,
+        trusted_clients: HashSet<x25519::PublicKey>,
+    ) -> Self {
+        Self {
+            noise: Some(ServerNoise {
+                config: NoiseConfig::new(private_key),
+                trusted_clients,
+                client_timestamps: HashMap::new(),
+            }),
+            ..Self::new(service, listen, timeout_ms)
         }
     }
 
@@ -390,6 +488,28 @@ impl NetworkServer {
                 }
             };
 
+            stream.set_nodelay(true)?;
+            let mut stream = NetworkStream::new(stream, stream_addr, self.timeout_ms);
+            if let Some(noise) = &mut self.noise {
+                if let Err(err) = stream.respond_to_handshake(
+                    &noise.config,
+                    self.service.as_bytes(),
+                    &noise.trusted_clients,
+                    &mut noise.client_timestamps,
+                ) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        self.service,
+                        NetworkMode::Server,
+                        LogEvent::HandshakeFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&stream_addr));
+                    // Dropping the stream closes the connection
+                    return Err(err);
+                }
+            }
+
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 self.service,
@@ -398,8 +518,7 @@ impl NetworkServer {
             )
             .remote_peer(&stream_addr));
 
-            stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, stream_addr, self.timeout_ms));
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or(Error::NoActiveStream)
@@ -411,6 +530,8 @@ struct NetworkStream {
     remote: SocketAddr,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    /// Encrypts the blocks once a Noise handshake completed
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -425,11 +546,158 @@ impl NetworkStream {
             remote,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
         }
     }
 
-    /// Blocking read until able to successfully read an entire message
+    /// Runs the initiator side of a Noise IK handshake with a remote owning remote_public_key.
+    /// The following messages are encrypted.
+    pub fn initiate_handshake(
+        &mut self,
+        config: &NoiseConfig,
+        prologue: &[u8],
+        remote_public_key: x25519::PublicKey,
+    ) -> Result<(), Error> {
+        let timestamp = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)
+            .expect("System time is before the UNIX epoch")
+            .as_millis() as u64;
+
+        let mut rng = rand::rngs::OsRng;
+        let mut init_message = vec![0; noise::handshake_init_msg_len(TIMESTAMP_SIZE)];
+        let state = config.initiate_connection(
+            &mut rng,
+            prologue,
+            remote_public_key,
+            Some(&timestamp.to_le_bytes()),
+            &mut init_message,
+        )?;
+        self.write_block(&init_message)?;
+
+        let response = self.read_handshake_block(noise::handshake_resp_msg_len(0))?;
+        let (_, session) = config.finalize_connection(state, &response)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Runs the responder side of a Noise IK handshake, rejecting remotes whose static key is not
+    /// part of trusted_keys, or whose timestamp is not greater than the one of their previous
+    /// handshake in timestamps. The following messages are encrypted.
+    pub fn respond_to_handshake(
+        &mut self,
+        config: &NoiseConfig,
+        prologue: &[u8],
+        trusted_keys: &HashSet<x25519::PublicKey>,
+        timestamps: &mut HashMap<x25519::PublicKey, u64>,
+    ) -> Result<(), Error> {
+        let init_message =
+            self.read_handshake_block(noise::handshake_init_msg_len(TIMESTAMP_SIZE))?;
+        let (remote_public_key, state, payload) =
+            config.parse_client_init_message(prologue, &init_message)?;
+        if !trusted_keys.contains(&remote_public_key) {
+            return Err(Error::UntrustedPeer(remote_public_key));
+        }
+
+        let timestamp: [u8; TIMESTAMP_SIZE] = payload
+            .try_into()
+            .map_err(|_| Error::InvalidMessage("Invalid handshake timestamp".into()))?;
+        let timestamp = u64::from_le_bytes(timestamp);
+        if let Some(last_timestamp) = timestamps.get(&remote_public_key) {
+            if timestamp <= *last_timestamp {
+                return Err(Error::ReplayedHandshake(remote_public_key, timestamp));
+            }
+        }
+        timestamps.insert(remote_public_key, timestamp);
+
+        let mut rng = rand::rngs::OsRng;
+        let mut response = vec![0; noise::handshake_resp_msg_len(0)];
+        let session = config.respond_to_client(&mut rng, state, None, &mut response)?;
+        self.write_block(&response)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Blocking read until able to successfully read an entire message. Encrypted messages are
+    /// made of an encrypted length followed by encrypted chunks of at most MAX_NOISE_PAYLOAD bytes.
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        if self.session.is_none() {
+            return self.read_block();
+        }
+
+        let header = self.read_encrypted_block()?;
+        let header: [u8; 4] = header
+            .try_into()
+            .map_err(|_| Error::InvalidMessage("Invalid message length".into()))?;
+        let data_size = u32::from_le_bytes(header) as usize;
+
+        let mut data = Vec::with_capacity(data_size);
+        while data.len() < data_size {
+            let chunk = self.read_encrypted_block()?;
+            if chunk.is_empty() || data.len() + chunk.len() > data_size {
+                return Err(Error::InvalidMessage(format!(
+                    "Unexpected chunk of {} bytes",
+                    chunk.len()
+                )));
+            }
+            data.extend(chunk);
+        }
+        Ok(data)
+    }
+
+    /// Blocking write until able to successfully send an entire message
+    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        if self.session.is_none() {
+            return self.write_block(data);
+        }
+
+        let u32_max = u32::max_value() as usize;
+        if u32_max <= data.len() {
+            return Err(Error::DataTooLarge(data.len()));
+        }
+        let data_len = data.len() as u32;
+        self.write_encrypted_block(&data_len.to_le_bytes())?;
+        for chunk in data.chunks(MAX_NOISE_PAYLOAD) {
+            self.write_encrypted_block(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn read_encrypted_block(&mut self) -> Result<Vec<u8>, Error> {
+        let mut message = self.read_block()?;
+        let session = self.session.as_mut().ok_or(Error::NoActiveStream)?;
+        let data = session.read_message_in_place(&mut message)?;
+        Ok(data.to_vec())
+    }
+
+    fn write_encrypted_block(&mut self, data: &[u8]) -> Result<(), Error> {
+        let session = self.session.as_mut().ok_or(Error::NoActiveStream)?;
+        let mut message = data.to_vec();
+        let tag = session.write_message_in_place(&mut message)?;
+        message.extend(tag);
+        self.write_block(&message)
+    }
+
+    /// Blocking read of a handshake message, which must be exactly expected_len bytes long. The
+    /// length is checked before reading the message, so that a peer can't make us buffer more.
+    fn read_handshake_block(&mut self, expected_len: usize) -> Result<Vec<u8>, Error> {
+        // Nothing is buffered before the handshake
+        let mut u32_bytes = [0; 4];
+        self.stream.read_exact(&mut u32_bytes)?;
+        let data_size = u32::from_le_bytes(u32_bytes) as usize;
+        if data_size != expected_len {
+            return Err(Error::InvalidMessage(format!(
+                "Handshake message of {} bytes, expected {}",
+                data_size, expected_len
+            )));
+        }
+
+        let mut message = vec![0; expected_len];
+        self.stream.read_exact(&mut message)?;
+        Ok(message)
+    }
+
+    /// Blocking read until able to successfully read an entire block
+    fn read_block(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -457,8 +725,8 @@ impl NetworkStream {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
-    /// Blocking write until able to successfully send an entire message
-    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+    /// Blocking write until able to successfully send an entire block
+    fn write_block(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -518,6 +786,8 @@ impl NetworkStream {
 mod test {
     use super::*;
     use aptos_config::utils;
+    use aptos_crypto::Uniform;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     /// Read, Write, Connect timeout in milliseconds.
@@ -656,4 +926,155 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    #[test]
+    fn test_noise_ping() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let trusted_clients = vec![client_key.public_key()].into_iter().collect();
+        let mut server = NetworkServer::new_with_noise(
+            "test",
+            server_addr,
+            TIMEOUT,
+            server_key,
+            trusted_clients,
+        );
+
+        // The handshake needs the server to respond, so the client runs in its own thread
+        let client = thread::spawn(move || {
+            let mut client = NetworkClient::new_with_noise(
+                "test",
+                server_addr,
+                TIMEOUT,
+                client_key,
+                server_public_key,
+            );
+            client.write(&[0, 1, 2, 3]).unwrap();
+            client.read().unwrap()
+        });
+
+        let result = server.read().unwrap();
+        assert_eq!(vec![0, 1, 2, 3], result);
+
+        // Spans several Noise messages
+        let data: Vec<u8> = (0..3 * MAX_NOISE_PAYLOAD).map(|i| i as u8).collect();
+        server.write(&data).unwrap();
+        assert_eq!(data, client.join().unwrap());
+    }
+
+    #[test]
+    fn test_noise_untrusted_client() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let trusted_key = x25519::PrivateKey::generate(&mut rng);
+        let trusted_clients = vec![trusted_key.public_key()].into_iter().collect();
+        let mut server = NetworkServer::new_with_noise(
+            "test",
+            server_addr,
+            TIMEOUT,
+            server_key,
+            trusted_clients,
+        );
+
+        let client = thread::spawn(move || {
+            let mut client = NetworkClient::new_with_noise(
+                "test",
+                server_addr,
+                TIMEOUT,
+                client_key,
+                server_public_key,
+            );
+            client.write(&[0, 1, 2, 3])
+        });
+
+        assert!(matches!(server.read(), Err(Error::UntrustedPeer(_))));
+        client.join().unwrap().unwrap_err();
+    }
+
+    fn write_raw_block(stream: &mut TcpStream, data: &[u8]) {
+        stream
+            .write_all(&(data.len() as u32).to_le_bytes())
+            .unwrap();
+        stream.write_all(data).unwrap();
+    }
+
+    #[test]
+    fn test_noise_oversized_init() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let trusted_clients = vec![client_key.public_key()].into_iter().collect();
+        let mut server = NetworkServer::new_with_noise(
+            "test",
+            server_addr,
+            TIMEOUT,
+            server_key,
+            trusted_clients,
+        );
+
+        // Only the length is sent, the server must reject it without waiting for the message
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        assert!(matches!(server.read(), Err(Error::InvalidMessage(_))));
+
+        // A message one byte longer than an init message is rejected too
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        write_raw_block(
+            &mut stream,
+            &vec![0; noise::handshake_init_msg_len(TIMESTAMP_SIZE) + 1],
+        );
+        assert!(matches!(server.read(), Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn test_noise_replayed_init() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let trusted_clients = vec![client_key.public_key()].into_iter().collect();
+        let mut server = NetworkServer::new_with_noise(
+            "test",
+            server_addr,
+            TIMEOUT,
+            server_key,
+            trusted_clients,
+        );
+
+        // Record an init message, as an attacker observing the connection would
+        let client_config = NoiseConfig::new(client_key);
+        let mut init_message = vec![0; noise::handshake_init_msg_len(TIMESTAMP_SIZE)];
+        client_config
+            .initiate_connection(
+                &mut rng,
+                b"test",
+                server_public_key,
+                Some(&1u64.to_le_bytes()),
+                &mut init_message,
+            )
+            .unwrap();
+
+        // The first handshake succeeds, then the client hangs up
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        write_raw_block(&mut stream, &init_message);
+        stream.shutdown(Shutdown::Write).unwrap();
+        assert!(matches!(server.read(), Err(Error::RemoteStreamClosed)));
+
+        // Replaying it fails
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        write_raw_block(&mut stream, &init_message);
+        assert!(matches!(server.read(), Err(Error::ReplayedHandshake(_, 1))));
+    }
 }
@@ -44,6 +44,11 @@ pub trait ConsensusNotificationSender: Send + Sync {
 
     /// Notify state sync to synchronize storage to the specified target.
     async fn sync_to_target(&self, target: LedgerInfoWithSignatures) -> Result<(), Error>;
+
+    /// Notify state sync that the consensus observer starts (or stops) executing and
+    /// committing blocks. State sync stops syncing before handing over to the observer,
+    /// and resumes once the observer hands back.
+    async fn notify_observer_executing(&self, executing: bool) -> Result<(), Error>;
 }
 
 /// This method returns a (ConsensusNotifier, ConsensusNotificationListener) pair that can be used
@@ -159,6 +164,35 @@ impl ConsensusNotificationSender for ConsensusNotifier {
             Err(error) => Err(Error::UnexpectedErrorEncountered(format!("{:?}", error))),
         }
     }
+
+    async fn notify_observer_executing(&self, executing: bool) -> Result<(), Error> {
+        // Construct a oneshot channel to receive a state sync response
+        let (callback, callback_receiver) = oneshot::channel();
+        let observer_notification =
+            ConsensusNotification::ObserverExecuting(ConsensusObserverNotification {
+                executing,
+                callback,
+            });
+
+        // Send the notification to state sync
+        if let Err(error) = self
+            .notification_sender
+            .clone()
+            .send(observer_notification)
+            .await
+        {
+            return Err(Error::NotificationError(format!(
+                "Failed to notify state sync of the consensus observer execution! Error: {:?}",
+                error
+            )));
+        }
+
+        // Process the response
+        match callback_receiver.await {
+            Ok(response) => response.result,
+            Err(error) => Err(Error::UnexpectedErrorEncountered(format!("{:?}", error))),
+        }
+    }
 }
 
 /// The state sync component responsible for handling consensus requests and
@@ -198,6 +232,18 @@ impl ConsensusNotificationListener {
             .send(ConsensusNotificationResponse { result })
             .map_err(|error| Error::UnexpectedErrorEncountered(format!("{:?}", error)))
     }
+
+    /// Respond to the observer notification previously sent by the consensus observer.
+    pub async fn respond_to_observer_notification(
+        &mut self,
+        consensus_observer_notification: ConsensusObserverNotification,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        consensus_observer_notification
+            .callback
+            .send(ConsensusNotificationResponse { result })
+            .map_err(|error| Error::UnexpectedErrorEncountered(format!("{:?}", error)))
+    }
 }
 
 impl Stream for ConsensusNotificationListener {
@@ -218,6 +264,7 @@ impl FusedStream for ConsensusNotificationListener {
 pub enum ConsensusNotification {
     NotifyCommit(ConsensusCommitNotification),
     SyncToTarget(ConsensusSyncNotification),
+    ObserverExecuting(ConsensusObserverNotification),
 }
 
 /// A commit notification to notify state sync of new commits.
@@ -268,6 +315,25 @@ impl ConsensusSyncNotification {
     }
 }
 
+/// A notification from the consensus observer that it starts (or stops) executing blocks.
+#[derive(Debug)]
+pub struct ConsensusObserverNotification {
+    pub executing: bool,
+    pub(crate) callback: oneshot::Sender<ConsensusNotificationResponse>,
+}
+
+impl ConsensusObserverNotification {
+    pub fn new(executing: bool) -> (Self, oneshot::Receiver<ConsensusNotificationResponse>) {
+        let (callback, callback_receiver) = oneshot::channel();
+        let observer_notification = ConsensusObserverNotification {
+            executing,
+            callback,
+        };
+
+        (observer_notification, callback_receiver)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{ConsensusNotification, ConsensusNotificationSender, Error};
@@ -400,6 +466,17 @@ mod tests {
                         Err(Error::UnexpectedErrorEncountered("Oops?".into())),
                     ));
                 }
+                Some(ConsensusNotification::ObserverExecuting(observer_notification)) => {
+                    let result = if observer_notification.executing {
+                        Ok(())
+                    } else {
+                        Err(Error::UnexpectedErrorEncountered("Oops?".into()))
+                    };
+                    let _result = block_on(
+                        consensus_listener
+                            .respond_to_observer_notification(observer_notification, result),
+                    );
+                }
                 _ => { /* Do nothing */ }
             }
         });
@@ -412,6 +489,12 @@ mod tests {
         // Send a sync notification and very an error response
         let notify_result = block_on(consensus_notifier.sync_to_target(create_ledger_info()));
         assert_err!(notify_result);
+
+        // Send observer notifications and verify the responses
+        let notify_result = block_on(consensus_notifier.notify_observer_executing(true));
+        assert_ok!(notify_result);
+        let notify_result = block_on(consensus_notifier.notify_observer_executing(false));
+        assert_err!(notify_result);
     }
 
     fn create_user_transaction() -> Transaction {
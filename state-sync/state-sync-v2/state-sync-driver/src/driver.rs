@@ -19,13 +19,14 @@ use crate::{
     utils,
     utils::PENDING_DATA_LOG_FREQ_SECS,
 };
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
 use aptos_data_client::AptosDataClient;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::waypoint::Waypoint;
 use consensus_notifications::{
-    ConsensusCommitNotification, ConsensusNotification, ConsensusSyncNotification,
+    ConsensusCommitNotification, ConsensusNotification, ConsensusObserverNotification,
+    ConsensusSyncNotification,
 };
 use data_streaming_service::streaming_client::{
     DataStreamingClient, NotificationAndFeedback, NotificationFeedback,
@@ -53,14 +54,23 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // The config of the consensus observer (full nodes only)
+    pub consensus_observer: ConsensusObserverConfig,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+        consensus_observer: ConsensusObserverConfig,
+    ) -> Self {
         Self {
             config,
             role,
             waypoint,
+            consensus_observer,
         }
     }
 }
@@ -85,6 +95,9 @@ pub struct StateSyncDriver<
     // The handler for notifications from consensus
     consensus_notification_handler: ConsensusNotificationHandler,
 
+    // Whether the consensus observer is executing blocks (full nodes only)
+    consensus_observer_executing: bool,
+
     // The component that manages the continuous syncing of the node
     continuous_syncer: ContinuousSyncer<StorageSyncer, StreamingClient>,
 
@@ -155,6 +168,7 @@ impl<
             client_notification_listener,
             commit_notification_listener,
             consensus_notification_handler,
+            consensus_observer_executing: false,
             continuous_syncer,
             aptos_data_client,
             driver_configuration,
@@ -200,13 +214,24 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes shouldn't receive notifications (unless
+        // they run a consensus observer), only the consensus observer should send
+        // observer notifications and consensus should only send notifications after
+        // bootstrapping!
+        let result = if self.driver_configuration.role == RoleType::FullNode
+            && !self.is_consensus_observer()
+        {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
             )))
+        } else if matches!(notification, ConsensusNotification::ObserverExecuting(_))
+            && !self.is_consensus_observer()
+        {
+            Err(Error::UnexpectedError(format!(
+                "Received consensus observer notification without a consensus observer: {:?}",
+                notification
+            )))
         } else if !self.bootstrapper.is_bootstrapped() {
             Err(Error::BootstrapNotComplete(format!(
                 "Received consensus notification: {:?}",
@@ -231,6 +256,12 @@ impl<
                         .respond_to_sync_notification(sync_notification, Err(error.clone()))
                         .await;
                 }
+                ConsensusNotification::ObserverExecuting(observer_notification) => {
+                    let _ = self
+                        .consensus_notification_handler
+                        .respond_to_observer_notification(observer_notification, Err(error.clone()))
+                        .await;
+                }
             }
             error!(LogSchema::new(LogEntry::ConsensusNotification)
                 .error(&error)
@@ -248,6 +279,10 @@ impl<
                 self.handle_consensus_sync_notification(sync_notification)
                     .await
             }
+            ConsensusNotification::ObserverExecuting(observer_notification) => {
+                self.handle_consensus_observer_notification(observer_notification)
+                    .await
+            }
         };
 
         // Log any errors from notification handling
@@ -356,6 +391,39 @@ impl<
             .await
     }
 
+    /// Handles a notification from the consensus observer that it starts (or stops)
+    /// executing blocks
+    async fn handle_consensus_observer_notification(
+        &mut self,
+        observer_notification: ConsensusObserverNotification,
+    ) -> Result<(), Error> {
+        info!(
+            LogSchema::new(LogEntry::ConsensusNotification).message(&format!(
+                "Received a consensus observer notification! Executing: {:?}",
+                observer_notification.executing
+            ))
+        );
+
+        // Before handing over to the observer, stop syncing and wait for the data
+        // already in flight to be committed, as we do before responding to consensus.
+        // The observer waits for its own commits before handing back.
+        if observer_notification.executing && !self.consensus_observer_executing {
+            if let Err(error) = self.continuous_syncer.reset_active_stream(None).await {
+                self.consensus_notification_handler
+                    .respond_to_observer_notification(observer_notification, Err(error.clone()))
+                    .await?;
+                return Err(error);
+            }
+            self.wait_for_storage_synchronizer_to_drain().await;
+            self.storage_synchronizer.finish_chunk_executor(); // The observer is now in control
+        }
+        self.consensus_observer_executing = observer_notification.executing;
+
+        self.consensus_notification_handler
+            .respond_to_observer_notification(observer_notification, Ok(()))
+            .await
+    }
+
     /// Handles a client notification sent by the driver client
     async fn handle_client_notification(&mut self, notification: DriverNotification) {
         debug!(LogSchema::new(LogEntry::ClientNotification)
@@ -462,15 +530,7 @@ impl<
 
         // Wait for the storage synchronizer to drain (if it hasn't already).
         // This prevents notifying consensus prematurely.
-        while self.storage_synchronizer.pending_storage_data() {
-            sample!(
-                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
-                info!("Waiting for the storage synchronizer to handle pending data!")
-            );
-
-            // Yield to avoid starving the storage synchronizer threads.
-            yield_now().await;
-        }
+        self.wait_for_storage_synchronizer_to_drain().await;
 
         // Refresh the latest synced ledger info and handle the sync request
         let latest_synced_ledger_info =
@@ -488,6 +548,19 @@ impl<
         Ok(())
     }
 
+    /// Waits until the storage synchronizer has handled all pending data
+    async fn wait_for_storage_synchronizer_to_drain(&self) {
+        while self.storage_synchronizer.pending_storage_data() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                info!("Waiting for the storage synchronizer to handle pending data!")
+            );
+
+            // Yield to avoid starving the storage synchronizer threads.
+            yield_now().await;
+        }
+    }
+
     /// Returns true iff there's an active sync request from consensus
     fn active_sync_request(&self) -> bool {
         self.consensus_notification_handler.active_sync_request()
@@ -498,9 +571,26 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff this node is a full node running a consensus observer
+    fn is_consensus_observer(&self) -> bool {
+        self.driver_configuration.role == RoleType::FullNode
+            && self
+                .driver_configuration
+                .consensus_observer
+                .observer_enabled
+    }
+
+    /// Returns true iff consensus is currently executing. For full nodes, this
+    /// is the consensus observer, between the notifications that it starts and
+    /// stops executing.
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        if !self.bootstrapper.is_bootstrapped() || self.active_sync_request() {
+            return false;
+        }
+        if self.is_consensus_observer() {
+            return self.consensus_observer_executing;
+        }
+        self.is_validator()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -14,7 +14,7 @@ use aptos_types::{
 };
 use consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusNotificationListener,
-    ConsensusSyncNotification,
+    ConsensusObserverNotification, ConsensusSyncNotification,
 };
 use data_streaming_service::data_notification::NotificationId;
 use event_notifications::{EventNotificationSender, EventSubscriptionService};
@@ -331,6 +331,33 @@ impl ConsensusNotificationHandler {
                 Error::CallbackSendFailed(format!("Consensus commit response error: {:?}", error))
             })
     }
+
+    /// Responds to the consensus observer for an observer notification
+    pub async fn respond_to_observer_notification(
+        &mut self,
+        observer_notification: ConsensusObserverNotification,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        // Wrap the result in an error that the consensus observer can process
+        let message = result.map_err(|error| {
+            consensus_notifications::Error::UnexpectedErrorEncountered(format!("{:?}", error))
+        });
+
+        info!(
+            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "Responding to consensus observer notification with message: {:?}",
+                message
+            ))
+        );
+
+        // Send the result
+        self.consensus_listener
+            .respond_to_observer_notification(observer_notification, message)
+            .await
+            .map_err(|error| {
+                Error::CallbackSendFailed(format!("Consensus observer response error: {:?}", error))
+            })
+    }
 }
 
 impl Stream for ConsensusNotificationHandler {
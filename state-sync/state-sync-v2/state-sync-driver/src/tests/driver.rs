@@ -69,6 +69,49 @@ async fn test_consensus_commit_notification() {
     assert_err!(result);
 }
 
+#[tokio::test]
+async fn test_consensus_observer_notifications() {
+    // Create a driver for a full node running a consensus observer
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    node_config.consensus_observer.observer_enabled = true;
+    let (_full_node_driver, consensus_notifier, _, _, _) =
+        create_driver_for_tests(node_config, Waypoint::default(), None).await;
+
+    // Verify the notifications are accepted, but the node isn't bootstrapped
+    let error = consensus_notifier
+        .notify_new_commit(vec![create_transaction()], vec![])
+        .await
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("BootstrapNotComplete"));
+    let error = consensus_notifier
+        .sync_to_target(create_ledger_info_at_version(0))
+        .await
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("BootstrapNotComplete"));
+    for executing in [true, false] {
+        let error = consensus_notifier
+            .notify_observer_executing(executing)
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", error).contains("BootstrapNotComplete"));
+    }
+
+    // Verify that nodes without a consensus observer reject observer notifications
+    let (_validator_driver, consensus_notifier, _, _, _) = create_validator_driver(None).await;
+    let error = consensus_notifier
+        .notify_observer_executing(true)
+        .await
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("without a consensus observer"));
+    let (_full_node_driver, consensus_notifier, _, _, _) = create_full_node_driver(None).await;
+    let error = consensus_notifier
+        .notify_observer_executing(true)
+        .await
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("FullNodeConsensusNotification"));
+}
+
 #[tokio::test]
 async fn test_mempool_commit_notifications() {
     // Create a driver for a validator with a waypoint at version 0
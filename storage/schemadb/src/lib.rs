@@ -221,6 +221,20 @@ impl DB {
 
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: SchemaBatch) -> Result<()> {
+        self.write_schemas_inner(batch, &default_write_options())
+    }
+
+    /// Writes a group of records wrapped in a [`SchemaBatch`], without waiting for them to be
+    /// synced to disk. They survive a process crash, but not a machine crash.
+    pub fn write_schemas_relaxed(&self, batch: SchemaBatch) -> Result<()> {
+        self.write_schemas_inner(batch, &rocksdb::WriteOptions::default())
+    }
+
+    fn write_schemas_inner(
+        &self,
+        batch: SchemaBatch,
+        write_opts: &rocksdb::WriteOptions,
+    ) -> Result<()> {
         let _timer = APTOS_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS
             .with_label_values(&[self.name])
             .start_timer();
@@ -238,7 +252,7 @@ impl DB {
         }
         let serialized_size = db_batch.size_in_bytes();
 
-        self.inner.write_opt(db_batch, &default_write_options())?;
+        self.inner.write_opt(db_batch, write_opts)?;
 
         // Bump counters only after DB write succeeds.
         for (cf_name, rows) in rows_locked.iter() {
@@ -63,6 +63,12 @@ pub enum MempoolStatusCode {
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Replacing a transaction requires a minimum increase of the gas unit price
+    ReplacementUnderpriced = 7,
+    // Mempool is full, and the gas unit price is too low to evict any other transaction
+    EvictionUnderpriced = 8,
+    // Mempool is full, and the account has too many transactions to evict other accounts'
+    AccountFairnessCapReached = 9,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -77,6 +83,9 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::ReplacementUnderpriced),
+            8 => Ok(MempoolStatusCode::EvictionUnderpriced),
+            9 => Ok(MempoolStatusCode::AccountFairnessCapReached),
             _ => Err("invalid StatusCode"),
         }
     }
@@ -142,6 +142,9 @@ pub enum LeaderReputationType {
     // Proposer election based on whether nodes succeeded or failed
     // their proposer election rounds, and whether they voted.
     ProposerAndVoter(ProposerAndVoterConfig),
+    // Same statistics as ProposerAndVoter, but the contribution of each block
+    // decays exponentially with its age, so recent rounds matter the most.
+    RecencyWeighted(RecencyWeightedConfig),
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -169,6 +172,32 @@ pub struct ProposerAndVoterConfig {
     pub use_history_from_previous_epoch_max_count: u32,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecencyWeightedConfig {
+    // Selection weight for active validators with proposer failures below threshold
+    pub active_weight: u64,
+    // Selection weight for inactive validators with proposer failures below threshold
+    pub inactive_weight: u64,
+    // Selection weight for validators with proposer failures above threshold
+    pub failed_weight: u64,
+    // Threshold of the decayed failures in the rounds validator was selected to be proposer
+    // integer values representing percentages, i.e. 12 is 12%.
+    pub failure_threshold_percent: u32,
+    // Window into history considered for proposer statistics, multiplier
+    // on top of number of validators
+    pub proposer_window_num_validators_multiplier: usize,
+    // Window into history considered for voter statistics, multiplier
+    // on top of number of validators
+    pub voter_window_num_validators_multiplier: usize,
+    // Number of blocks after which the contribution of a block halves, multiplier
+    // on top of number of validators
+    pub half_life_num_validators_multiplier: usize,
+    // Flag whether to use voting power as multiplier to the weights
+    pub weight_by_voting_power: bool,
+    // Number of historical epochs (beyond the current one) to consider, 0 if none.
+    pub use_history_from_previous_epoch_max_count: u32,
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
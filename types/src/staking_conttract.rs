@@ -6,26 +6,26 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DistributionPoolShare {
-    key: AccountAddress,
-    value: u64,
+    pub key: AccountAddress,
+    pub value: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DistributionPool {
     shareholders_limit: u64,
-    total_coins: u64,
-    total_shares: u64,
-    shares: Vec<DistributionPoolShare>,
-    shareholders: Vec<AccountAddress>,
+    pub total_coins: u64,
+    pub total_shares: u64,
+    pub shares: Vec<DistributionPoolShare>,
+    pub shareholders: Vec<AccountAddress>,
     scaling_factor: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StakingContract {
-    principal: u64,
+    pub principal: u64,
     pub pool_address: AccountAddress,
     owner_cap: AccountAddress,
-    commission_percentage: u64,
+    pub commission_percentage: u64,
     distribution_pool: DistributionPool,
     signer_cap: AccountAddress,
 }
@@ -51,3 +51,11 @@ pub struct StakingContractStore {
     add_distribution_events: EventHandle,
     distribute_events: EventHandle,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestCommissionEvent {
+    pub operator: AccountAddress,
+    pub pool_address: AccountAddress,
+    pub accumulated_rewards: u64,
+    pub commission_amount: u64,
+}
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{account_address::AccountAddress, event::EventHandle};
+use crate::{
+    account_address::AccountAddress, event::EventHandle, staking_conttract::DistributionPool,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -10,3 +12,89 @@ pub struct VestingAdminStore {
     nonce: u64,
     create_events: EventHandle,
 }
+
+pub const VESTING_POOL_ACTIVE: u64 = 1;
+pub const VESTING_POOL_TERMINATED: u64 = 2;
+
+/// A `std::fixed_point32::FixedPoint32`, with 32 fractional bits
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FixedPoint32 {
+    pub value: u64,
+}
+
+impl FixedPoint32 {
+    /// Multiplies `amount` by the fraction, rounding down like `fixed_point32::multiply_u64`
+    pub fn multiply_u64(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.value as u128) >> 32) as u64
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VestingSchedule {
+    pub schedule: Vec<FixedPoint32>,
+    pub start_timestamp_secs: u64,
+    pub period_duration: u64,
+    pub last_vested_period: u64,
+}
+
+impl VestingSchedule {
+    /// The fraction of the grant that vests in the given 1-indexed period. The last fraction of
+    /// the schedule repeats until the grant runs out.
+    pub fn fraction(&self, period: u64) -> Option<FixedPoint32> {
+        let index = (period.checked_sub(1)? as usize).min(self.schedule.len().checked_sub(1)?);
+        self.schedule.get(index).copied()
+    }
+
+    /// The time at which the given 1-indexed period ends, and can be vested
+    pub fn period_end_secs(&self, period: u64) -> u64 {
+        self.start_timestamp_secs + period * self.period_duration
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StakingInfo {
+    pub pool_address: AccountAddress,
+    pub operator: AccountAddress,
+    pub voter: AccountAddress,
+    pub commission_percentage: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BeneficiaryEntry {
+    pub key: AccountAddress,
+    pub value: AccountAddress,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VestingContract {
+    pub state: u64,
+    pub admin: AccountAddress,
+    pub grant_pool: DistributionPool,
+    pub beneficiaries: Vec<BeneficiaryEntry>,
+    pub vesting_schedule: VestingSchedule,
+    pub withdrawal_address: AccountAddress,
+    pub staking: StakingInfo,
+    pub remaining_grant: u64,
+    signer_cap: AccountAddress,
+
+    // Events.
+    update_operator_events: EventHandle,
+    update_voter_events: EventHandle,
+    reset_lockup_events: EventHandle,
+    set_beneficiary_events: EventHandle,
+    unlock_rewards_events: EventHandle,
+    vest_events: EventHandle,
+    distribute_events: EventHandle,
+    terminate_events: EventHandle,
+    admin_withdraw_events: EventHandle,
+}
+
+impl VestingContract {
+    /// The beneficiary that distributions of the shareholder are sent to
+    pub fn beneficiary(&self, shareholder: AccountAddress) -> AccountAddress {
+        self.beneficiaries
+            .iter()
+            .find(|entry| entry.key == shareholder)
+            .map_or(shareholder, |entry| entry.value)
+    }
+}